@@ -1,5 +1,7 @@
-use criterion::{criterion_group, criterion_main, Criterion};
-use munkres::{solve_assignment, WeightMatrix};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use munkres::{
+    generators, solve_assignment, solve_assignment_lapjv, solve_from_vec, Solver, WeightMatrix,
+};
 
 fn gen_matrix(n: usize) -> Vec<i32> {
     (0..n * n)
@@ -12,18 +14,65 @@ fn gen_matrix(n: usize) -> Vec<i32> {
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
-    c.bench_function_over_inputs(
-        "solve_assignment",
-        |b: &mut criterion::Bencher, n: &usize| {
-            let n = *n;
-            let matrix = gen_matrix(n);
+    let mut group = c.benchmark_group("solve_assignment");
+    for n in [50] {
+        let matrix = gen_matrix(n);
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, &n| {
             b.iter(|| {
                 let mut weights: WeightMatrix<i32> = WeightMatrix::from_row_vec(n, matrix.clone());
                 let _matching = solve_assignment(&mut weights);
             })
-        },
-        vec![50],
-    );
+        });
+    }
+    group.finish();
+
+    // reuses the buffer instead of cloning the matrix.
+    let mut group = c.benchmark_group("solve_from_vec");
+    for n in [50] {
+        let matrix = gen_matrix(n);
+        let mut buffer = Some(matrix.clone());
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, &n| {
+            b.iter(|| {
+                let mut data = buffer.take().unwrap();
+                data.copy_from_slice(&matrix);
+                let (_matching, data) = solve_from_vec(n, data);
+                buffer = Some(data);
+            })
+        });
+    }
+    group.finish();
+
+    // reuses the buffers of the algorithm and the matching; only the weights
+    // are copied.
+    let mut group = c.benchmark_group("solver");
+    for n in [50] {
+        let matrix = gen_matrix(n);
+        let mut buffer = Some(matrix.clone());
+        let mut solver = Solver::with_capacity(n);
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, &n| {
+            b.iter(|| {
+                let mut data = buffer.take().unwrap();
+                data.copy_from_slice(&matrix);
+                let mut weights = WeightMatrix::from_row_vec(n, data);
+                let _matching = solver.solve(&mut weights);
+                buffer = Some(weights.into_vec());
+            })
+        });
+    }
+    group.finish();
+
+    // the step-based algorithm against LAPJV on random costs.
+    let mut group = c.benchmark_group("munkres_vs_lapjv");
+    for n in [100, 300] {
+        let weights = generators::uniform(n, 0..1000, 1);
+        group.bench_with_input(BenchmarkId::new("munkres", n), &weights, |b, weights| {
+            b.iter(|| solve_assignment(&mut weights.clone()))
+        });
+        group.bench_with_input(BenchmarkId::new("lapjv", n), &weights, |b, weights| {
+            b.iter(|| solve_assignment_lapjv(weights))
+        });
+    }
+    group.finish();
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,162 @@
+//! Statistics of an instance, to predict how hard it is before solving it.
+//!
+//! Solve times of the Kuhn-Munkres algorithm depend less on `n` alone than
+//! on how many zeros the initial reduction leaves: with few zeros, most of
+//! the work is spent in step 6 creating new ones. `analyze` reports this
+//! together with the value range and ties without modifying the weights,
+//! and `InstanceStats::backend`
+//! routes instances that look expensive to the auction backend.
+use crate::{Backend, Position, WeightNum, Weights};
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+/// Above this `hardness`, `InstanceStats::backend` suggests the auction.
+const AUCTION_HARDNESS: f64 = 1e10;
+
+/// The result of `analyze`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstanceStats {
+    pub n: usize,
+    /// Number of valid cells.
+    pub valid: usize,
+    /// Fraction of valid cells that are zero after subtracting the row and
+    /// then the column minima.
+    pub zero_density: f64,
+    /// The smallest and largest valid values, if convertible to `f64`.
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// Number of distinct valid values convertible to `f64`.
+    pub distinct: usize,
+    /// Number of rows whose minimum is attained in more than one cell.
+    pub tied_rows: usize,
+    /// A rough estimate of the cells visited by `solve_assignment`, only
+    /// meaningful for comparing instances.
+    pub hardness: f64,
+}
+
+impl InstanceStats {
+    /// `max - min`, or `None` if there are no valid values.
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.max? - self.min?)
+    }
+
+    /// `Backend::Auction` for instances harder than a fixed threshold,
+    /// otherwise `Backend::Auto`.
+    pub fn backend(&self) -> Backend {
+        if self.hardness > AUCTION_HARDNESS {
+            Backend::Auction
+        } else {
+            Backend::Auto
+        }
+    }
+}
+
+/// Computes the statistics of `weights` in O(n^2), without modifying it.
+pub fn analyze<W>(weights: &W) -> InstanceStats
+where
+    W: Weights,
+{
+    let n = weights.n();
+    let cell = |row, column| {
+        let pos = Position { row, column };
+        if weights.is_valid_at(pos) {
+            Some(weights.element_at(pos))
+        } else {
+            None
+        }
+    };
+    let min_of = |values: &mut dyn Iterator<Item = W::T>| {
+        values.fold(None, |min: Option<W::T>, value| match min {
+            Some(m) if m <= value => Some(m),
+            _ => Some(value),
+        })
+    };
+
+    let mut valid = 0;
+    let mut tied_rows = 0;
+    let mut min: Option<f64> = None;
+    let mut max: Option<f64> = None;
+    let mut distinct = BTreeSet::new();
+    let mut row_min = Vec::with_capacity(n);
+    for row in 0..n {
+        let row_minimum = min_of(&mut (0..n).filter_map(|column| cell(row, column)));
+        let mut ties = 0;
+        for value in (0..n).filter_map(|column| cell(row, column)) {
+            valid += 1;
+            if Some(value) == row_minimum {
+                ties += 1;
+            }
+            if let Some(value) = value.to_f64() {
+                min = Some(min.map_or(value, |m| m.min(value)));
+                max = Some(max.map_or(value, |m| m.max(value)));
+                distinct.insert(value.to_bits());
+            }
+        }
+        if ties > 1 {
+            tied_rows += 1;
+        }
+        row_min.push(row_minimum);
+    }
+
+    let reduced = |row: usize, column| Some(cell(row, column)? - row_min[row]?);
+    let mut zeros = 0;
+    for column in 0..n {
+        if let Some(column_min) = min_of(&mut (0..n).filter_map(|row| reduced(row, column))) {
+            zeros += (0..n)
+                .filter_map(|row| reduced(row, column))
+                .filter(|&value| (value - column_min).is_zero())
+                .count();
+        }
+    }
+
+    let zero_density = if valid == 0 {
+        0.0
+    } else {
+        zeros as f64 / valid as f64
+    };
+    // each step 6 visits the valid cells, and the fewer zeros the reduction
+    // leaves, the more steps 6 are needed, up to one per row.
+    let hardness = valid as f64 * n as f64 * (1.0 - zero_density);
+
+    InstanceStats {
+        n,
+        valid,
+        zero_density,
+        min,
+        max,
+        distinct: distinct.len(),
+        tied_rows,
+        hardness,
+    }
+}
+
+#[test]
+fn test_analyze() {
+    use crate::{generators, WeightMatrix};
+
+    let inf = f64::INFINITY;
+    let weights = WeightMatrix::from_row_vec(
+        3,
+        vec![
+            1.0, 1.0, 3.0, //
+            2.0, inf, 4.0, //
+            5.0, 6.0, 5.0, //
+        ],
+    );
+    let stats = analyze(&weights);
+    assert_eq!(8, stats.valid);
+    assert_eq!(2, stats.tied_rows);
+    assert_eq!(6, stats.distinct);
+    assert_eq!(Some(5.0), stats.spread());
+    // reduced: [0, 0, 2], [0, _, 2], [0, 1, 0], all column minima are zero.
+    assert_eq!(5.0 / 8.0, stats.zero_density);
+    assert_eq!(Backend::Auto, stats.backend());
+
+    // every cell of a constant matrix is a zero.
+    let stats = analyze(&WeightMatrix::from_fn(50, |_| 7u32));
+    assert_eq!(
+        (1.0, 0.0, 50),
+        (stats.zero_density, stats.hardness, stats.tied_rows)
+    );
+    assert!(analyze(&generators::uniform(50, 0..1000, 1)).hardness > 1e5);
+}
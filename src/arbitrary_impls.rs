@@ -0,0 +1,35 @@
+use crate::{WeightMatrix, WeightNum};
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+/// Upper bound for the size of generated matrices, keeping fuzz inputs cheap
+/// to solve.
+const MAX_N: usize = 32;
+
+impl<'a, T> Arbitrary<'a> for WeightMatrix<T>
+where
+    T: WeightNum + Arbitrary<'a>,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let n = u.int_in_range(1..=MAX_N)?;
+        let data = (0..n * n)
+            .map(|_| T::arbitrary(u))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(WeightMatrix::from_row_vec(n, data))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        let (lo, _) = T::size_hint(depth);
+        (1 + lo, None)
+    }
+}
+
+#[test]
+fn test_arbitrary_weight_matrix() {
+    use crate::{solve_assignment, Weights};
+
+    let bytes: Vec<u8> = (0..=255).collect();
+    let mut u = Unstructured::new(&bytes);
+    let mut m = WeightMatrix::<u8>::arbitrary(&mut u).unwrap();
+    assert!(m.n() >= 1 && m.n() <= MAX_N);
+    assert_eq!(m.n(), solve_assignment(&mut m).unwrap().len());
+}
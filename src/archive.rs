@@ -0,0 +1,384 @@
+//! Self-contained records of a solve, for bug reports and regression
+//! corpora.
+//!
+//! An `Archive` bundles an `Instance`, i.e. the costs together with the
+//! constraints and options it was solved with, and what the solve produced:
+//! the matching, its total, the backend used, the time taken and the version
+//! of this crate. `to_text` writes it in a line-based text format, which
+//! `parse` reads back without further dependencies, and `replay` solves the
+//! instance again and compares the outcome with the recorded one.
+//!
+//! ```text
+//! munkres-archive 1
+//! version 0.5.2
+//! n 2
+//! maximize false
+//! backend auto
+//! forbid 0 1
+//! row 1.5 -
+//! row 3 2
+//! assign 0 0
+//! assign 1 1
+//! backend-used munkres
+//! total 3.5
+//! elapsed-ns 2100
+//! ```
+//!
+//! Invalid cells are written as `-`, and an unsolvable instance records
+//! `error matrix-not-solvable` instead of its assignments. Costs are stored
+//! as `f64`, which represents integers up to `2^53` exactly.
+use crate::builder::ConfiguredSolver;
+use crate::{Backend, Error, Munkres, Position, WeightMatrix, WeightNum, Weights};
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+const HEADER: &str = "munkres-archive 1";
+
+/// An instance with the constraints and options to solve it with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instance {
+    pub n: usize,
+    /// The costs in row-major order, `None` for invalid cells.
+    pub costs: Vec<Option<f64>>,
+    pub forbidden: Vec<Position>,
+    pub pinned: Vec<Position>,
+    pub maximize: bool,
+    pub backend: Backend,
+}
+
+impl Instance {
+    /// The instance of the valid costs of `weights`, without constraints.
+    ///
+    /// # Panics
+    ///
+    /// If a valid cost can't be converted to `f64`.
+    pub fn new<W>(weights: &W) -> Instance
+    where
+        W: Weights,
+    {
+        let n = weights.n();
+        let costs = (0..n * n)
+            .map(|index| {
+                let pos = Position {
+                    row: index / n,
+                    column: index % n,
+                };
+                weights.is_valid_at(pos).then(|| {
+                    weights
+                        .element_at(pos)
+                        .to_f64()
+                        .expect("cost not representable as f64")
+                })
+            })
+            .collect();
+        Instance {
+            n,
+            costs,
+            forbidden: Vec::new(),
+            pinned: Vec::new(),
+            maximize: false,
+            backend: Backend::Auto,
+        }
+    }
+
+    pub fn weights(&self) -> WeightMatrix<f64> {
+        let valid: Vec<bool> = self.costs.iter().map(Option::is_some).collect();
+        let data = self.costs.iter().map(|cost| cost.unwrap_or(0.0)).collect();
+        WeightMatrix::from_row_vec_with_validity(self.n, data, &valid)
+    }
+
+    fn solver(&self) -> ConfiguredSolver {
+        let mut builder = Munkres::builder().backend(self.backend);
+        if self.maximize {
+            builder = builder.maximize();
+        }
+        for &pos in &self.forbidden {
+            builder = builder.forbid(pos);
+        }
+        for &pos in &self.pinned {
+            builder = builder.pin(pos);
+        }
+        builder.build()
+    }
+}
+
+/// A solved instance with the outcome of the solve.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Archive {
+    /// The version of this crate that solved the instance.
+    pub version: String,
+    pub instance: Instance,
+    pub result: Result<Vec<Position>, Error>,
+    /// The original cost of the matching, 0 if there is none.
+    pub total: f64,
+    /// `instance.backend`, resolved for the costs.
+    pub backend_used: Backend,
+    pub elapsed: Duration,
+}
+
+/// The outcome of replaying an archive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    /// The archive of the new solve.
+    pub archive: Archive,
+    pub same_result: bool,
+    pub same_total: bool,
+}
+
+impl Replay {
+    pub fn is_reproduced(&self) -> bool {
+        self.same_result && self.same_total
+    }
+}
+
+/// Why an archive couldn't be parsed, with its 1-based line number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Archive {
+    /// Solves `instance` and records the outcome.
+    ///
+    /// # Panics
+    ///
+    /// If a forbidden or pinned cell is out of range.
+    pub fn record(instance: Instance) -> Archive {
+        let mut weights = instance.weights();
+        let backend_used = instance.backend.resolve(&weights);
+        let start = Instant::now();
+        let result = instance
+            .solver()
+            .solve(&mut weights)
+            .map(|assignment| assignment.into_positions());
+        let elapsed = start.elapsed();
+        let total = match &result {
+            Ok(matching) => matching
+                .iter()
+                .map(|pos| instance.costs[pos.row * instance.n + pos.column].unwrap())
+                .sum(),
+            Err(_) => 0.0,
+        };
+        Archive {
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            instance,
+            result,
+            total,
+            backend_used,
+            elapsed,
+        }
+    }
+
+    /// Solves the instance again with this version of the crate.
+    pub fn replay(&self) -> Replay {
+        let archive = Archive::record(self.instance.clone());
+        Replay {
+            same_result: archive.result == self.result,
+            same_total: archive.total == self.total,
+            archive,
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let instance = &self.instance;
+        let mut text = format!("{}\nversion {}\n", HEADER, self.version);
+        writeln!(text, "n {}", instance.n).unwrap();
+        writeln!(text, "maximize {}", instance.maximize).unwrap();
+        writeln!(text, "backend {}", backend_name(instance.backend)).unwrap();
+        for pos in &instance.forbidden {
+            writeln!(text, "forbid {} {}", pos.row, pos.column).unwrap();
+        }
+        for pos in &instance.pinned {
+            writeln!(text, "pin {} {}", pos.row, pos.column).unwrap();
+        }
+        for row in instance.costs.chunks(instance.n.max(1)) {
+            text.push_str("row");
+            for cost in row {
+                match cost {
+                    Some(cost) => write!(text, " {}", cost).unwrap(),
+                    None => text.push_str(" -"),
+                }
+            }
+            text.push('\n');
+        }
+        match &self.result {
+            Ok(matching) => {
+                for pos in matching {
+                    writeln!(text, "assign {} {}", pos.row, pos.column).unwrap();
+                }
+            }
+            Err(Error::MatrixNotSolvable) => text.push_str("error matrix-not-solvable\n"),
+            Err(Error::NoPrimeInRow) => text.push_str("error no-prime-in-row\n"),
+            Err(Error::LimitExceeded) => text.push_str("error limit-exceeded\n"),
+        }
+        writeln!(text, "backend-used {}", backend_name(self.backend_used)).unwrap();
+        writeln!(text, "total {}", self.total).unwrap();
+        writeln!(text, "elapsed-ns {}", self.elapsed.as_nanos()).unwrap();
+        text
+    }
+
+    /// Parses the text written by `to_text`.
+    pub fn parse(text: &str) -> Result<Archive, ParseError> {
+        let mut lines = text.lines().enumerate();
+        match lines.next() {
+            Some((_, line)) if line.trim() == HEADER => {}
+            _ => return Err(parse_error(0, "missing header")),
+        }
+
+        let mut version = None;
+        let mut n = None;
+        let mut instance = Instance {
+            n: 0,
+            costs: Vec::new(),
+            forbidden: Vec::new(),
+            pinned: Vec::new(),
+            maximize: false,
+            backend: Backend::Auto,
+        };
+        let mut matching = Vec::new();
+        let mut error = None;
+        let mut backend_used = Backend::Auto;
+        let mut total = 0.0;
+        let mut elapsed = Duration::default();
+        for (index, line) in lines {
+            let mut tokens = line.split_whitespace();
+            let key = match tokens.next() {
+                Some(key) => key,
+                None => continue,
+            };
+            let values: Vec<&str> = tokens.collect();
+            let value = || match values[..] {
+                [value] => Ok(value),
+                _ => Err(parse_error(index, "expected one value")),
+            };
+            let position = || match values[..] {
+                [row, column] => match (row.parse(), column.parse()) {
+                    (Ok(row), Ok(column)) => Ok(Position { row, column }),
+                    _ => Err(parse_error(index, "invalid position")),
+                },
+                _ => Err(parse_error(index, "expected a row and a column")),
+            };
+            match key {
+                "version" => version = Some(value()?.to_owned()),
+                "n" => n = Some(parse_value(index, value()?)?),
+                "maximize" => instance.maximize = parse_value(index, value()?)?,
+                "backend" => instance.backend = parse_backend(index, value()?)?,
+                "forbid" => instance.forbidden.push(position()?),
+                "pin" => instance.pinned.push(position()?),
+                "row" => {
+                    for &value in &values {
+                        instance.costs.push(match value {
+                            "-" => None,
+                            value => Some(parse_value(index, value)?),
+                        });
+                    }
+                }
+                "assign" => matching.push(position()?),
+                "error" => {
+                    error = Some(match value()? {
+                        "matrix-not-solvable" => Error::MatrixNotSolvable,
+                        "no-prime-in-row" => Error::NoPrimeInRow,
+                        "limit-exceeded" => Error::LimitExceeded,
+                        _ => return Err(parse_error(index, "unknown error")),
+                    })
+                }
+                "backend-used" => backend_used = parse_backend(index, value()?)?,
+                "total" => total = parse_value(index, value()?)?,
+                "elapsed-ns" => elapsed = Duration::from_nanos(parse_value(index, value()?)?),
+                _ => return Err(parse_error(index, "unknown key")),
+            }
+        }
+
+        let last = text.lines().count().saturating_sub(1);
+        instance.n = n.ok_or_else(|| parse_error(last, "missing n"))?;
+        if instance.costs.len() != instance.n * instance.n {
+            return Err(parse_error(last, "expected n rows of n costs"));
+        }
+        Ok(Archive {
+            version: version.ok_or_else(|| parse_error(last, "missing version"))?,
+            instance,
+            result: error.map_or(Ok(matching), Err),
+            total,
+            backend_used,
+            elapsed,
+        })
+    }
+}
+
+fn parse_error(index: usize, message: &str) -> ParseError {
+    ParseError {
+        line: index + 1,
+        message: message.to_owned(),
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(index: usize, value: &str) -> Result<T, ParseError> {
+    value
+        .parse()
+        .map_err(|_| parse_error(index, &format!("invalid value `{}`", value)))
+}
+
+fn backend_name(backend: Backend) -> &'static str {
+    match backend {
+        Backend::Auto => "auto",
+        Backend::Munkres => "munkres",
+        Backend::CostScaling => "cost-scaling",
+        Backend::Auction => "auction",
+        Backend::Lapjv => "lapjv",
+    }
+}
+
+fn parse_backend(index: usize, name: &str) -> Result<Backend, ParseError> {
+    match name {
+        "auto" => Ok(Backend::Auto),
+        "munkres" => Ok(Backend::Munkres),
+        "cost-scaling" => Ok(Backend::CostScaling),
+        "auction" => Ok(Backend::Auction),
+        "lapjv" => Ok(Backend::Lapjv),
+        _ => Err(parse_error(index, "unknown backend")),
+    }
+}
+
+#[test]
+fn test_archive() {
+    use crate::generators;
+
+    let mut instance = Instance::new(&generators::uniform(8, 0..100, 3));
+    instance.forbidden.push(Position { row: 0, column: 0 });
+    instance.pinned.push(Position { row: 1, column: 2 });
+    instance.costs[3 * 8 + 4] = None;
+    let archive = Archive::record(instance);
+    assert_eq!(
+        Some(&Position { row: 1, column: 2 }),
+        archive.result.as_ref().unwrap().get(1)
+    );
+
+    let parsed = Archive::parse(&archive.to_text()).unwrap();
+    assert_eq!(archive, parsed);
+    assert!(parsed.replay().is_reproduced());
+
+    let inf = f64::INFINITY;
+    let weights = WeightMatrix::from_row_vec(2, vec![1.5, inf, 3.0, inf]);
+    let mut instance = Instance::new(&weights);
+    instance.maximize = true;
+    instance.backend = Backend::Auction;
+    let archive = Archive::record(instance);
+    assert_eq!(Err(Error::MatrixNotSolvable), archive.result);
+    assert!(archive
+        .to_text()
+        .contains("\nrow 1.5 -\nrow 3 -\nerror matrix-not-solvable\n"));
+    assert_eq!(archive, Archive::parse(&archive.to_text()).unwrap());
+
+    let text = "munkres-archive 1\nversion 0.1.0\nn 1\nrow 2\nassign 0 0\ntotal 3\n";
+    let replay = Archive::parse(text).unwrap().replay();
+    assert!(replay.same_result && !replay.same_total);
+    assert_eq!(
+        Err(parse_error(1, "invalid value `x`")),
+        Archive::parse("munkres-archive 1\nn x\n")
+    );
+    assert_eq!(
+        Err(parse_error(0, "missing header")),
+        Archive::parse("n 1\n")
+    );
+}
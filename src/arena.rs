@@ -0,0 +1,114 @@
+//! Solver memory owned by the caller, e.g. per frame in games and real-time
+//! applications.
+//!
+//! `SolverArena` keeps the buffers of the algorithm (marks, coverage and
+//! path) for every size it solved, and hands them out again instead of
+//! allocating. After a warm-up frame, solving with `solve_into` doesn't
+//! allocate at all, and `reset` releases all memory at once. Stable Rust
+//! can't parameterize `Vec` over an allocator, so the arena owns its memory
+//! rather than borrowing it from a bump allocator.
+use crate::memory::MemoryUsage;
+use crate::workspace::Workspace;
+use crate::{run_in, Error, Position, Weights};
+use alloc::vec::Vec;
+
+#[derive(Debug, Default)]
+pub struct SolverArena {
+    workspaces: Vec<Workspace>,
+}
+
+impl SolverArena {
+    pub fn new() -> SolverArena {
+        SolverArena::default()
+    }
+
+    /// An arena with the buffers for instances of the given sizes allocated
+    /// up front.
+    pub fn with_sizes(sizes: &[usize]) -> SolverArena {
+        let mut arena = SolverArena::new();
+        for &n in sizes {
+            arena.workspace(n);
+        }
+        arena
+    }
+
+    fn workspace(&mut self, n: usize) -> &mut Workspace {
+        let index = match self.workspaces.iter().position(|w| w.n() == n) {
+            Some(index) => index,
+            None => {
+                self.workspaces.push(Workspace::new(n));
+                self.workspaces.len() - 1
+            }
+        };
+        &mut self.workspaces[index]
+    }
+
+    /// Like `solve_assignment_into`, using the buffers of the arena.
+    pub fn solve_into<W>(
+        &mut self,
+        weights: &mut W,
+        matching: &mut Vec<Position>,
+    ) -> Result<(), Error>
+    where
+        W: Weights,
+    {
+        matching.clear();
+        let n = weights.n();
+        if n == 0 {
+            return Ok(());
+        }
+        let workspace = self.workspace(n);
+        run_in(weights, workspace)?;
+        workspace.marks.each_star(|pos| matching.push(pos));
+        Ok(())
+    }
+
+    /// Like `solve_assignment`, using the buffers of the arena.
+    pub fn solve<W>(&mut self, weights: &mut W) -> Result<Vec<Position>, Error>
+    where
+        W: Weights,
+    {
+        let mut matching = Vec::with_capacity(weights.n());
+        self.solve_into(weights, &mut matching)?;
+        Ok(matching)
+    }
+
+    /// The sizes the arena holds buffers for.
+    pub fn sizes(&self) -> Vec<usize> {
+        self.workspaces.iter().map(Workspace::n).collect()
+    }
+
+    /// The memory of all buffers held by the arena.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        self.workspaces
+            .iter()
+            .map(Workspace::memory_usage)
+            .fold(MemoryUsage::default(), |total, usage| total + usage)
+    }
+
+    /// Releases all buffers.
+    pub fn reset(&mut self) {
+        self.workspaces = Vec::new();
+    }
+}
+
+#[test]
+fn test_solver_arena() {
+    use crate::{solve_assignment, WeightMatrix};
+
+    let c = vec![250, 400, 350, 400, 600, 350, 200, 400, 250];
+    let expected = solve_assignment(&mut WeightMatrix::from_row_vec(3, c.clone())).unwrap();
+    let mut arena = SolverArena::with_sizes(&[3]);
+    let mut matching = Vec::with_capacity(3);
+    for _ in 0..2 {
+        let mut weights = WeightMatrix::from_row_vec(3, c.clone());
+        arena.solve_into(&mut weights, &mut matching).unwrap();
+        assert_eq!(expected, matching);
+    }
+    assert_eq!(vec![3], arena.sizes());
+    let mut weights = WeightMatrix::from_row_vec(2, vec![1, 2, 3, 4]);
+    assert!(arena.solve(&mut weights).is_ok());
+    assert_eq!(vec![3, 2], arena.sizes());
+    arena.reset();
+    assert!(arena.sizes().is_empty());
+}
@@ -0,0 +1,228 @@
+use crate::{solve_assignment, Error, Position, Weights};
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::iter::FromIterator;
+use core::ops::Index;
+use core::slice;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+/// A complete matching of rows to columns, ordered by row.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Assignment {
+    positions: Vec<Position>,
+}
+
+impl Assignment {
+    pub fn from_positions(mut positions: Vec<Position>) -> Assignment {
+        positions.sort_by_key(|pos| pos.row);
+        Assignment { positions }
+    }
+
+    #[inline]
+    pub fn positions(&self) -> &[Position] {
+        &self.positions
+    }
+
+    pub fn into_positions(self) -> Vec<Position> {
+        self.positions
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, Position> {
+        self.positions.iter()
+    }
+
+    /// The column assigned to `row`, if any.
+    pub fn column_of(&self, row: usize) -> Option<usize> {
+        self.positions
+            .binary_search_by_key(&row, |pos| pos.row)
+            .ok()
+            .map(|i| self.positions[i].column)
+    }
+
+    /// Pairs the item of each assigned row with the item of its column.
+    /// Positions beyond either slice are skipped, e.g. the padding rows or
+    /// columns of a rectangular problem solved as a square one.
+    pub fn zip<'a, R, C>(
+        &'a self,
+        row_items: &'a [R],
+        column_items: &'a [C],
+    ) -> impl Iterator<Item = (&'a R, &'a C)> + 'a {
+        self.iter()
+            .filter_map(move |pos| Some((row_items.get(pos.row)?, column_items.get(pos.column)?)))
+    }
+
+    /// The column of each assigned row, keyed by row.
+    #[cfg(feature = "std")]
+    pub fn to_hashmap(&self) -> HashMap<usize, usize> {
+        self.iter().map(|pos| (pos.row, pos.column)).collect()
+    }
+
+    /// Like `to_hashmap`, ordered by row.
+    pub fn to_btreemap(&self) -> BTreeMap<usize, usize> {
+        self.iter().map(|pos| (pos.row, pos.column)).collect()
+    }
+
+    /// The matrix with ones at the assigned positions and zeros elsewhere.
+    /// It is square, sized by the largest row or column assigned, so rows
+    /// and columns beyond it are left out.
+    #[cfg(feature = "ndarray")]
+    pub fn to_permutation_matrix(&self) -> ndarray::Array2<u8> {
+        let n = self
+            .iter()
+            .map(|pos| pos.row.max(pos.column) + 1)
+            .max()
+            .unwrap_or(0);
+        let mut matrix = ndarray::Array2::zeros((n, n));
+        for pos in self {
+            matrix[(pos.row, pos.column)] = 1;
+        }
+        matrix
+    }
+}
+
+/// The column assigned to a row.
+///
+/// # Panics
+///
+/// If the row is not assigned.
+impl Index<usize> for Assignment {
+    type Output = usize;
+
+    fn index(&self, row: usize) -> &usize {
+        let i = self
+            .positions
+            .binary_search_by_key(&row, |pos| pos.row)
+            .unwrap_or_else(|_| panic!("row {} is not assigned", row));
+        &self.positions[i].column
+    }
+}
+
+impl FromIterator<Position> for Assignment {
+    fn from_iter<I: IntoIterator<Item = Position>>(iter: I) -> Assignment {
+        Assignment::from_positions(iter.into_iter().collect())
+    }
+}
+
+impl Extend<Position> for Assignment {
+    fn extend<I: IntoIterator<Item = Position>>(&mut self, iter: I) {
+        self.positions.extend(iter);
+        self.positions.sort_by_key(|pos| pos.row);
+    }
+}
+
+impl From<Vec<Position>> for Assignment {
+    fn from(positions: Vec<Position>) -> Assignment {
+        Assignment::from_positions(positions)
+    }
+}
+
+impl From<Assignment> for Vec<Position> {
+    fn from(assignment: Assignment) -> Vec<Position> {
+        assignment.positions
+    }
+}
+
+impl<'a> IntoIterator for &'a Assignment {
+    type Item = &'a Position;
+    type IntoIter = slice::Iter<'a, Position>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An algorithm solving the assignment problem on `W`.
+///
+/// Implemented by the built-in backends so applications can swap solvers via
+/// generics or `dyn AssignmentSolver<W>`, and by third party algorithms that
+/// want to reuse `Weights`, `Position` and `Assignment`. Like
+/// `solve_assignment`, solvers may modify `weights`.
+pub trait AssignmentSolver<W: Weights> {
+    fn solve(&mut self, weights: &mut W) -> Result<Assignment, Error>;
+}
+
+/// The step-based Kuhn-Munkres algorithm of `solve_assignment`.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Munkres;
+
+impl<W: Weights> AssignmentSolver<W> for Munkres {
+    fn solve(&mut self, weights: &mut W) -> Result<Assignment, Error> {
+        solve_assignment(weights).map(Assignment::from)
+    }
+}
+
+#[test]
+fn test_assignment_solver() {
+    use crate::{Backend, WeightMatrix};
+
+    let c = vec![250, 400, 350, 400, 600, 350, 200, 400, 250];
+    let mut solvers: Vec<Box<dyn AssignmentSolver<WeightMatrix<i32>>>> =
+        vec![Box::new(Munkres), Box::new(Backend::Auto)];
+
+    for solver in solvers.iter_mut() {
+        let mut weights = WeightMatrix::from_row_vec(3, c.clone());
+        let assignment = solver.solve(&mut weights).unwrap();
+        assert_eq!(3, assignment.len());
+        assert_eq!(Some(1), assignment.column_of(0));
+        assert_eq!(Some(2), assignment.column_of(1));
+        assert_eq!(Some(0), assignment.column_of(2));
+        assert_eq!(None, assignment.column_of(3));
+    }
+}
+
+#[test]
+fn test_assignment_conversions() {
+    let pos = |row, column| Position { row, column };
+    let mut assignment: Assignment = vec![pos(2, 0), pos(0, 1)].into_iter().collect();
+    assert_eq!(1, assignment[0]);
+    assert_eq!(0, assignment[2]);
+    assignment.extend(vec![pos(1, 2)]);
+    assert_eq!(&[pos(0, 1), pos(1, 2), pos(2, 0)], assignment.positions());
+    #[cfg(feature = "std")]
+    assert_eq!(Some(&2), assignment.to_hashmap().get(&1));
+    assert_eq!(
+        vec![(0, 1), (1, 2), (2, 0)],
+        assignment.to_btreemap().into_iter().collect::<Vec<_>>()
+    );
+}
+
+#[cfg(feature = "ndarray")]
+#[test]
+fn test_to_permutation_matrix() {
+    let pos = |row, column| Position { row, column };
+    let assignment = Assignment::from_positions(vec![pos(0, 2), pos(1, 0), pos(2, 1)]);
+    let matrix = assignment.to_permutation_matrix();
+    assert_eq!(ndarray::arr2(&[[0, 0, 1], [1, 0, 0], [0, 1, 0]]), matrix);
+
+    // applies the permutation to the rows of a feature matrix.
+    let features = ndarray::arr2(&[[1.0], [2.0], [3.0]]);
+    assert_eq!(
+        ndarray::arr2(&[[3.0], [1.0], [2.0]]),
+        matrix.mapv(f64::from).dot(&features)
+    );
+    assert_eq!((0, 0), Assignment::default().to_permutation_matrix().dim());
+}
+
+#[test]
+fn test_assignment_zip() {
+    let pos = |row, column| Position { row, column };
+    // 3 workers padded with a dummy row to the 4 tasks.
+    let assignment = Assignment::from_positions(vec![pos(0, 2), pos(1, 0), pos(2, 3), pos(3, 1)]);
+    let workers = ["ann", "bob", "cy"];
+    let tasks = [10, 20, 30, 40];
+    let pairs: Vec<_> = assignment.zip(&workers, &tasks).collect();
+    assert_eq!(vec![(&"ann", &30), (&"bob", &10), (&"cy", &40)], pairs);
+    // a dummy column, too.
+    assert_eq!(2, assignment.zip(&workers, &tasks[..3]).count());
+}
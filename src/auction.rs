@@ -0,0 +1,387 @@
+//! Auction algorithm, structured for decentralized task allocation.
+//!
+//! Each row is an agent bidding for columns (tasks). Bidding happens in
+//! synchronous rounds: every unassigned agent bids for its best column given
+//! the current prices, all bids of the round are exchanged between all
+//! agents, and every agent applies the same deterministic rule (highest bid
+//! wins a column, ties go to the lower row). As all agents see the same
+//! messages, they all keep an identical view of prices and owners without a
+//! central coordinator.
+//!
+//! `AuctionAgent` contains the per-agent logic and `Channel` abstracts the
+//! message exchange, so every agent of a multi-robot system can run
+//! `run_agent` over its own transport. `solve_assignment_auction` is the
+//! synchronous in-process version of the same protocol.
+//!
+//! Prices are refined by epsilon scaling. The result is optimal within
+//! `n * final_epsilon`, which with the default options means exactly optimal
+//! for integer weights, and optimal up to the rounding error of the costs
+//! otherwise.
+use crate::graph::Graph;
+use crate::{Assignment, AssignmentSolver, Error, Position, WeightNum, Weights};
+use alloc::{vec, vec::Vec};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AuctionOptions {
+    /// The epsilon of the last scaling phase. `solve_assignment_auction`
+    /// defaults to `1 / (n + 1)` if all costs are integers, and otherwise to
+    /// `n * max_cost * f64::EPSILON`, the rounding error of a sum of costs.
+    /// `AuctionAgent::new` only sees a single row, so it always defaults to
+    /// the latter.
+    pub final_epsilon: Option<f64>,
+    /// Factor by which epsilon shrinks between scaling phases.
+    pub scaling_factor: f64,
+}
+
+impl Default for AuctionOptions {
+    fn default() -> Self {
+        AuctionOptions {
+            final_epsilon: None,
+            scaling_factor: 5.0,
+        }
+    }
+}
+
+/// The epsilon of the last scaling phase if none is given, see
+/// `AuctionOptions::final_epsilon`.
+fn default_final_epsilon(n: usize, max_cost: f64, integral: bool) -> f64 {
+    if integral {
+        1.0 / (n + 1) as f64
+    } else if max_cost > 0.0 {
+        n as f64 * max_cost * f64::EPSILON
+    } else {
+        // all costs are zero, so any assignment is optimal.
+        1.0
+    }
+}
+
+/// A bid of agent `row` raising the price of `column` to `price`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Bid {
+    pub row: usize,
+    pub column: usize,
+    pub price: f64,
+}
+
+/// Message transport between the agents.
+pub trait Channel {
+    /// Submits the bid of this agent for the current round (if any) and
+    /// returns the bids of all agents for this round, including its own.
+    /// Blocks until all agents have submitted.
+    fn exchange(&mut self, bid: Option<Bid>) -> Vec<Bid>;
+}
+
+/// The auction logic of a single agent (row).
+#[derive(Debug, Clone)]
+pub struct AuctionAgent {
+    row: usize,
+    /// Costs of this agent's row, `None` for forbidden columns.
+    costs: Vec<Option<f64>>,
+    prices: Vec<f64>,
+    owners: Vec<Option<usize>>,
+    assigned: usize,
+    epsilon: f64,
+    final_epsilon: f64,
+    scaling_factor: f64,
+}
+
+impl AuctionAgent {
+    /// Creates agent `row` from its row of costs. `max_cost` must be the same
+    /// bound on the absolute costs for all agents, as it determines the
+    /// epsilon schedule.
+    pub fn new(
+        row: usize,
+        costs: Vec<Option<f64>>,
+        max_cost: f64,
+        options: &AuctionOptions,
+    ) -> Self {
+        let n = costs.len();
+        let final_epsilon = options
+            .final_epsilon
+            .unwrap_or_else(|| default_final_epsilon(n, max_cost, false));
+        let mut agent = AuctionAgent {
+            row,
+            costs,
+            prices: vec![0.0; n],
+            owners: vec![None; n],
+            assigned: 0,
+            epsilon: max_cost.max(final_epsilon),
+            final_epsilon,
+            scaling_factor: options.scaling_factor,
+        };
+        agent.next_phase();
+        agent
+    }
+
+    fn next_phase(&mut self) {
+        self.epsilon = (self.epsilon / self.scaling_factor).max(self.final_epsilon);
+        self.owners.iter_mut().for_each(|owner| *owner = None);
+        self.assigned = 0;
+    }
+
+    /// The column currently held by this agent.
+    pub fn column(&self) -> Option<usize> {
+        self.owners
+            .iter()
+            .position(|&owner| owner == Some(self.row))
+    }
+
+    /// Whether the auction has terminated.
+    pub fn is_done(&self) -> bool {
+        self.assigned == self.owners.len() && self.epsilon <= self.final_epsilon
+    }
+
+    /// The bid of this agent for the current round, if it is unassigned.
+    pub fn bid(&self) -> Option<Bid> {
+        if self.column().is_some() {
+            return None;
+        }
+        let mut best: Option<(usize, f64)> = None;
+        let mut second = f64::INFINITY;
+        for (column, cost) in self.costs.iter().enumerate() {
+            if let Some(cost) = cost {
+                let value = cost + self.prices[column];
+                match best {
+                    Some((_, b)) if value >= b => second = second.min(value),
+                    Some((_, b)) => {
+                        second = b;
+                        best = Some((column, value));
+                    }
+                    None => best = Some((column, value)),
+                }
+            }
+        }
+        let (column, best) = best?;
+        let increment = if second.is_finite() {
+            second - best
+        } else {
+            0.0
+        };
+        Some(Bid {
+            row: self.row,
+            column,
+            price: self.prices[column] + increment + self.epsilon,
+        })
+    }
+
+    /// Applies the bids of a round. Starts the next scaling phase once all
+    /// columns are assigned.
+    pub fn apply(&mut self, bids: &[Bid]) {
+        let mut winners: Vec<Option<Bid>> = vec![None; self.owners.len()];
+        for &bid in bids {
+            let winner = &mut winners[bid.column];
+            match winner {
+                Some(w)
+                    if (w.price, core::cmp::Reverse(w.row))
+                        >= (bid.price, core::cmp::Reverse(bid.row)) => {}
+                _ => *winner = Some(bid),
+            }
+        }
+        for bid in winners.into_iter().flatten() {
+            self.prices[bid.column] = bid.price;
+            if self.owners[bid.column].is_none() {
+                self.assigned += 1;
+            }
+            self.owners[bid.column] = Some(bid.row);
+        }
+        if self.assigned == self.owners.len() && self.epsilon > self.final_epsilon {
+            self.next_phase();
+        }
+    }
+}
+
+/// Runs `agent` until the auction terminates, exchanging bids over
+/// `channel`, and returns the column assigned to it. The instance must have
+/// a complete assignment, otherwise the auction doesn't terminate.
+pub fn run_agent<C: Channel>(mut agent: AuctionAgent, channel: &mut C) -> usize {
+    while !agent.is_done() {
+        let bids = channel.exchange(agent.bid());
+        agent.apply(&bids);
+    }
+    agent.column().unwrap()
+}
+
+/// Solves the assignment problem with the synchronous in-process auction.
+/// `weights` is not modified.
+///
+/// # Panics
+///
+/// If a valid cell can't be converted to `f64`.
+pub fn solve_assignment_auction<W>(
+    weights: &W,
+    options: &AuctionOptions,
+) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+{
+    let mut integral = true;
+    let graph = Graph::from_weights(weights, |c| {
+        integral = integral && c.to_i64().is_some();
+        c.to_f64()
+            .expect("auction requires weights convertible to f64")
+    });
+    if !graph.has_perfect_matching() {
+        return Err(Error::MatrixNotSolvable);
+    }
+    let n = graph.n();
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+    let max_cost = graph
+        .costs()
+        .iter()
+        .fold(0.0, |max: f64, c| max.max(c.abs()));
+    let options = AuctionOptions {
+        final_epsilon: Some(
+            options
+                .final_epsilon
+                .unwrap_or_else(|| default_final_epsilon(n, max_cost, integral)),
+        ),
+        ..*options
+    };
+    let mut agents: Vec<AuctionAgent> = (0..n)
+        .map(|row| {
+            let mut costs = vec![None; n];
+            for (column, cost) in graph.edges(row) {
+                costs[column] = Some(cost);
+            }
+            AuctionAgent::new(row, costs, max_cost, &options)
+        })
+        .collect();
+
+    // All agents share the same view, so the first one tells when to stop.
+    let mut bids = Vec::with_capacity(n);
+    while !agents[0].is_done() {
+        bids.clear();
+        bids.extend(agents.iter().filter_map(|agent| agent.bid()));
+        agents.iter_mut().for_each(|agent| agent.apply(&bids));
+    }
+    Ok(agents
+        .iter()
+        .map(|agent| Position {
+            row: agent.row,
+            column: agent.column().unwrap(),
+        })
+        .collect())
+}
+
+/// The in-process auction backend with default options.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Auction;
+
+impl<W: Weights> AssignmentSolver<W> for Auction {
+    fn solve(&mut self, weights: &mut W) -> Result<Assignment, Error> {
+        solve_assignment_auction(weights, &AuctionOptions::default()).map(Assignment::from)
+    }
+}
+
+#[test]
+fn test_auction_matches_munkres() {
+    use crate::{generators, solve_assignment, WeightMatrix};
+
+    fn cost(weights: &WeightMatrix<u32>, matching: &[Position]) -> u32 {
+        matching.iter().map(|&pos| weights.element_at(pos)).sum()
+    }
+
+    for seed in 0..10 {
+        let weights = generators::uniform(1 + seed as usize * 3, 0..100, seed);
+        let matching = solve_assignment_auction(&weights, &AuctionOptions::default()).unwrap();
+        let expected = solve_assignment(&mut weights.clone()).unwrap();
+        assert_eq!(cost(&weights, &expected), cost(&weights, &matching));
+    }
+
+    let inf = f32::INFINITY;
+    let weights = WeightMatrix::from_row_vec(2, vec![inf, 1.0, inf, 2.0]);
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_assignment_auction(&weights, &AuctionOptions::default())
+    );
+}
+
+#[test]
+fn test_auction_float_costs() {
+    use crate::{generators, solve_assignment, WeightMatrix};
+
+    fn cost(weights: &WeightMatrix<f64>, matching: &[Position]) -> f64 {
+        matching.iter().map(|&pos| weights.element_at(pos)).sum()
+    }
+
+    // costs in [0, 1), where an epsilon of 1 / (n + 1) is far too coarse.
+    for seed in 0..5 {
+        let mut rng = generators::Rng::new(seed);
+        let weights = WeightMatrix::from_fn(30, |_| rng.next_f64());
+        let matching = solve_assignment_auction(&weights, &AuctionOptions::default()).unwrap();
+        let expected = solve_assignment(&mut weights.clone()).unwrap();
+        assert!((cost(&weights, &expected) - cost(&weights, &matching)).abs() < 1e-9);
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    for seed in 0..5 {
+        let weights = generators::geometric(30, seed);
+        let matching = solve_assignment_auction(&weights, &AuctionOptions::default()).unwrap();
+        let expected = solve_assignment(&mut weights.clone()).unwrap();
+        assert!((cost(&weights, &expected) - cost(&weights, &matching)).abs() < 1e-9);
+    }
+
+    // all costs equal.
+    let weights = WeightMatrix::from_row_vec(3, vec![0.0; 9]);
+    assert_eq!(
+        3,
+        solve_assignment_auction(&weights, &AuctionOptions::default())
+            .unwrap()
+            .len()
+    );
+}
+
+#[test]
+fn test_auction_agents_over_threads() {
+    use crate::{solve_assignment, WeightMatrix};
+    use std::sync::{Arc, Barrier, Mutex};
+    use std::thread;
+
+    /// All agents share a board of bids, synchronized by a barrier.
+    struct SharedBoard {
+        bids: Arc<Mutex<Vec<Bid>>>,
+        barrier: Arc<Barrier>,
+    }
+
+    impl Channel for SharedBoard {
+        fn exchange(&mut self, bid: Option<Bid>) -> Vec<Bid> {
+            self.bids.lock().unwrap().extend(bid);
+            self.barrier.wait();
+            let mut bids = self.bids.lock().unwrap().clone();
+            bids.sort_by_key(|bid| bid.row);
+            self.barrier.wait();
+            if self.barrier.wait().is_leader() {
+                self.bids.lock().unwrap().clear();
+            }
+            self.barrier.wait();
+            bids
+        }
+    }
+
+    let c = vec![250, 400, 350, 400, 600, 350, 200, 400, 250];
+    let weights: WeightMatrix<i32> = WeightMatrix::from_row_vec(3, c.clone());
+    let bids = Arc::new(Mutex::new(Vec::new()));
+    let barrier = Arc::new(Barrier::new(3));
+    let handles: Vec<_> = (0..3)
+        .map(|row| {
+            let costs = c[row * 3..(row + 1) * 3]
+                .iter()
+                .map(|&c| Some(c as f64))
+                .collect();
+            let agent = AuctionAgent::new(row, costs, 600.0, &AuctionOptions::default());
+            let mut channel = SharedBoard {
+                bids: bids.clone(),
+                barrier: barrier.clone(),
+            };
+            thread::spawn(move || run_agent(agent, &mut channel))
+        })
+        .collect();
+    let columns: Vec<usize> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+    let expected = solve_assignment(&mut weights.clone()).unwrap();
+    assert_eq!(
+        expected.iter().map(|pos| pos.column).collect::<Vec<_>>(),
+        columns
+    );
+}
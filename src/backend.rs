@@ -0,0 +1,248 @@
+//! Solver backend selection.
+//!
+//! `Backend::Auto` picks a concrete backend from the size, density and weight
+//! type of the instance: cost scaling for large sparse integer instances,
+//! LAPJV for large dense ones and Munkres for the rest. The decision is
+//! reported in the returned `Stats`.
+use crate::auction::AuctionOptions;
+use crate::{
+    solve_assignment_auction, solve_assignment_cost_scaling, solve_assignment_lapjv, Assignment,
+    AssignmentSolver, Error, Position, WeightNum, Weights,
+};
+use alloc::vec::Vec;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum Backend {
+    /// Choose a backend based on the instance.
+    #[default]
+    Auto,
+    /// The step-based Kuhn-Munkres algorithm.
+    Munkres,
+    /// Cost-scaling push-relabel. Only applicable to integer weights,
+    /// otherwise `Munkres` is used instead.
+    CostScaling,
+    /// The epsilon-scaling auction algorithm. Exact for integer weights and
+    /// optimal up to the rounding error of the costs otherwise.
+    Auction,
+    /// Jonker-Volgenant shortest augmenting paths, in O(n^3).
+    Lapjv,
+}
+
+impl Backend {
+    /// Resolves `Backend::Auto` to a concrete backend for `weights`. Concrete
+    /// backends are returned unchanged.
+    pub fn resolve<W>(self, weights: &W) -> Backend
+    where
+        W: Weights,
+    {
+        match self {
+            Backend::Auto | Backend::CostScaling => self.resolve_for(&InstanceProfile::of(weights)),
+            backend => backend,
+        }
+    }
+
+    fn resolve_for(self, profile: &InstanceProfile) -> Backend {
+        match self {
+            Backend::Auto => select(profile),
+            Backend::CostScaling if !profile.integral => Backend::Munkres,
+            backend => backend,
+        }
+    }
+}
+
+/// The properties of an instance `Backend::Auto` bases its decision on.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct InstanceProfile {
+    pub n: usize,
+    /// Fraction of valid cells, in `[0, 1]`, and 1 for an empty instance.
+    pub density: f64,
+    /// Whether all valid cells are integers representable as `i64`.
+    pub integral: bool,
+}
+
+impl InstanceProfile {
+    pub fn of<W>(weights: &W) -> InstanceProfile
+    where
+        W: Weights,
+    {
+        let n = weights.n();
+        let mut valid = 0usize;
+        let mut integral = true;
+        for row in 0..n {
+            for column in 0..n {
+                let pos = Position { row, column };
+                if weights.is_valid_at(pos) {
+                    let elm = weights.element_at(pos);
+                    valid += 1;
+                    integral = integral && elm.to_i64().is_some();
+                }
+            }
+        }
+        // an empty instance has no invalid cells.
+        let density = if n == 0 {
+            1.0
+        } else {
+            valid as f64 / (n * n) as f64
+        };
+        InstanceProfile {
+            n,
+            density,
+            integral,
+        }
+    }
+}
+
+fn select(profile: &InstanceProfile) -> Backend {
+    if profile.integral && profile.n >= 256 && profile.density <= 0.1 {
+        Backend::CostScaling
+    } else if profile.n >= 64 && profile.density >= 0.5 {
+        Backend::Lapjv
+    } else {
+        Backend::Munkres
+    }
+}
+
+/// Statistics about a solve.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    /// The concrete backend that solved the instance.
+    pub backend: Backend,
+    pub profile: InstanceProfile,
+    /// Time spent in the phases of the algorithm, if the backend is
+    /// `Munkres`.
+    #[cfg(feature = "profile")]
+    pub timings: Option<crate::Timings>,
+}
+
+/// Solves the assignment problem with `backend`, resolving `Backend::Auto`
+/// first.
+pub fn solve_assignment_with_backend<W>(
+    weights: &mut W,
+    backend: Backend,
+) -> Result<(Vec<Position>, Stats), Error>
+where
+    W: Weights,
+{
+    let profile = InstanceProfile::of(weights);
+    let backend = backend.resolve_for(&profile);
+    #[cfg(feature = "profile")]
+    let mut timings = None;
+    let matching = match backend {
+        #[cfg(feature = "profile")]
+        Backend::Munkres | Backend::Auto => {
+            let mut workspace = crate::workspace::Workspace::new(weights.n());
+            let matching = crate::solve_assignment_in(weights, &mut workspace)?;
+            timings = Some(workspace.timings);
+            matching
+        }
+        #[cfg(not(feature = "profile"))]
+        Backend::Munkres | Backend::Auto => crate::solve_assignment(weights)?,
+        Backend::CostScaling => solve_assignment_cost_scaling(weights)?,
+        Backend::Auction => solve_assignment_auction(weights, &AuctionOptions::default())?,
+        Backend::Lapjv => solve_assignment_lapjv(weights)?,
+    };
+    Ok((
+        matching,
+        Stats {
+            backend,
+            profile,
+            #[cfg(feature = "profile")]
+            timings,
+        },
+    ))
+}
+
+impl<W: Weights> AssignmentSolver<W> for Backend {
+    fn solve(&mut self, weights: &mut W) -> Result<Assignment, Error> {
+        solve_assignment_with_backend(weights, *self)
+            .map(|(matching, _)| Assignment::from(matching))
+    }
+}
+
+#[test]
+fn test_auto_backend() {
+    use crate::WeightMatrix;
+
+    let mut weights = WeightMatrix::from_row_vec(2, vec![1.0, f32::INFINITY, 2.0, 3.0]);
+    assert_eq!(Backend::Munkres, Backend::Auto.resolve(&weights));
+
+    let (matching, stats) = solve_assignment_with_backend(&mut weights, Backend::Auto).unwrap();
+    assert_eq!(2, matching.len());
+    assert_eq!(Backend::Munkres, stats.backend);
+    assert_eq!(2, stats.profile.n);
+    assert_eq!(0.75, stats.profile.density);
+    assert!(!stats.profile.integral);
+
+    assert_eq!(Backend::Munkres, Backend::CostScaling.resolve(&weights));
+    let weights = WeightMatrix::from_row_vec(2, vec![1, 2, 3, 4]);
+    assert_eq!(Backend::CostScaling, Backend::CostScaling.resolve(&weights));
+    assert_eq!(Backend::Lapjv, Backend::Lapjv.resolve(&weights));
+    let (matching, stats) =
+        solve_assignment_with_backend(&mut weights.clone(), Backend::Lapjv).unwrap();
+    assert_eq!(Backend::Lapjv, stats.backend);
+    // both diagonals cost 5, so only the costs must agree.
+    let cost = |matching: &[Position]| -> i32 {
+        matching.iter().map(|&pos| weights.element_at(pos)).sum()
+    };
+    let expected = crate::solve_assignment(&mut weights.clone()).unwrap();
+    assert_eq!(cost(&expected), cost(&matching));
+}
+
+#[test]
+fn test_auto_backend_large_instances() {
+    use crate::{generators, WeightMatrix};
+
+    let weights = generators::uniform(64, 0..1000, 1);
+    assert_eq!(Backend::Lapjv, Backend::Auto.resolve(&weights));
+    let (matching, stats) =
+        solve_assignment_with_backend(&mut weights.clone(), Backend::Auto).unwrap();
+    assert_eq!(Backend::Lapjv, stats.backend);
+    let cost = |matching: &[Position]| -> u64 {
+        matching
+            .iter()
+            .map(|&pos| u64::from(weights.element_at(pos)))
+            .sum()
+    };
+    let expected = crate::solve_assignment(&mut weights.clone()).unwrap();
+    assert_eq!(cost(&expected), cost(&matching));
+
+    // a smaller dense instance stays with Munkres.
+    let weights = generators::uniform(63, 0..1000, 1);
+    assert_eq!(Backend::Munkres, Backend::Auto.resolve(&weights));
+
+    // a large sparse integer instance goes to cost scaling.
+    let n = 256;
+    let valid: Vec<bool> = (0..n * n).map(|i| i % n == i / n).collect();
+    let weights = WeightMatrix::from_row_vec_with_validity(n, vec![1u32; n * n], &valid);
+    assert_eq!(Backend::CostScaling, Backend::Auto.resolve(&weights));
+}
+
+#[test]
+fn test_empty_instance_profile() {
+    use crate::WeightMatrix;
+
+    let weights = WeightMatrix::<u32>::from_row_vec(0, vec![]);
+    let profile = InstanceProfile::of(&weights);
+    assert_eq!(0, profile.n);
+    assert_eq!(1.0, profile.density);
+    assert!(profile.integral);
+    assert_eq!(Backend::Munkres, Backend::Auto.resolve(&weights));
+    assert_eq!(Backend::CostScaling, Backend::CostScaling.resolve(&weights));
+}
+
+#[cfg(feature = "profile")]
+#[test]
+fn test_stats_timings() {
+    use crate::generators;
+
+    let mut weights = generators::uniform(30, 0..1000, 1);
+    let (_, stats) = solve_assignment_with_backend(&mut weights, Backend::Munkres).unwrap();
+    let timings = stats.timings.unwrap();
+    assert!(timings.reduction > core::time::Duration::from_secs(0));
+    assert!(timings.total() >= timings.zero_search);
+
+    let mut weights = generators::uniform(3, 0..10, 1);
+    let (_, stats) = solve_assignment_with_backend(&mut weights, Backend::CostScaling).unwrap();
+    assert_eq!(None, stats.timings);
+}
@@ -0,0 +1,178 @@
+//! Load balancing by repeated assignment.
+//!
+//! Placing shards on workers, or jobs on machines, is often solved as a
+//! sequence of assignment problems: solve, compute the load each column takes
+//! on under the resulting matching, price that load into the costs and solve
+//! again, until the matching stops changing. `LoadBalancer` runs this loop.
+//! The cost of a cell in each round is `base + demand[row] * load[column]`.
+//!
+//! Consecutive rounds differ only in their loads, so each round starts from
+//! the column potentials of the previous one and reuses its buffers, which
+//! usually takes a fraction of the steps of a cold solve. Loads that only
+//! depend on the matching can cycle; `damping` mixes the new loads into the
+//! old ones to dampen such oscillations.
+use crate::dual::DualWeights;
+use crate::workspace::Workspace;
+use crate::{solve_assignment_warm_in, Error, Position, WeightMatrix, WeightNum, Weights};
+use alloc::{vec, vec::Vec};
+
+#[derive(Debug, Clone, Copy)]
+pub struct LoadBalancer {
+    max_iterations: usize,
+    damping: f64,
+    tolerance: f64,
+}
+
+impl Default for LoadBalancer {
+    fn default() -> LoadBalancer {
+        LoadBalancer {
+            max_iterations: 100,
+            damping: 1.0,
+            tolerance: 1e-9,
+        }
+    }
+}
+
+impl LoadBalancer {
+    pub fn new() -> LoadBalancer {
+        LoadBalancer::default()
+    }
+
+    /// The maximum number of solves, 100 by default.
+    pub fn max_iterations(mut self, max_iterations: usize) -> LoadBalancer {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// The weight of the new loads in each round, in `(0, 1]`. 1 by
+    /// default, which replaces the loads.
+    ///
+    /// # Panics
+    ///
+    /// If `damping` isn't in `(0, 1]`.
+    pub fn damping(mut self, damping: f64) -> LoadBalancer {
+        assert!(
+            damping > 0.0 && damping <= 1.0,
+            "the damping must be in (0, 1]"
+        );
+        self.damping = damping;
+        self
+    }
+
+    /// How much a load may still change in a round that counts as stable,
+    /// `1e-9` by default.
+    pub fn tolerance(mut self, tolerance: f64) -> LoadBalancer {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Solves `base` with all loads 0, then repeatedly computes the loads of
+    /// the columns with `load` from the latest matching and solves again,
+    /// until the matching is the same as in the previous round and no load
+    /// changed by more than the tolerance.
+    ///
+    /// # Panics
+    ///
+    /// If `demands` or a result of `load` doesn't have `n` elements.
+    pub fn balance<W, F>(&self, base: &W, demands: &[f64], mut load: F) -> Result<Balanced, Error>
+    where
+        W: Weights<T = f64>,
+        F: FnMut(&[Position]) -> Vec<f64>,
+    {
+        let n = base.n();
+        assert!(demands.len() == n, "demands must have n elements");
+        let mut loads = vec![0.0; n];
+        let mut potentials = vec![0.0; n];
+        let mut workspace = Workspace::new(n);
+        let mut previous: Option<Vec<Position>> = None;
+        let mut iterations = 0;
+        loop {
+            let costs = WeightMatrix::from_fn(n, |(row, column)| {
+                let cost = base.element_at(Position { row, column });
+                cost.add_if_valid(demands[row] * loads[column])
+            });
+            let mut duals = DualWeights::new(&costs);
+            let matching = solve_assignment_warm_in(&mut duals, &potentials, &mut workspace)?;
+            potentials.copy_from_slice(duals.column_duals());
+            iterations += 1;
+
+            let target = load(&matching);
+            assert!(target.len() == n, "loads must have n elements");
+            let mut change: f64 = 0.0;
+            for (old, new) in loads.iter_mut().zip(target) {
+                let mixed = *old + self.damping * (new - *old);
+                change = change.max((mixed - *old).abs());
+                *old = mixed;
+            }
+
+            let converged = change <= self.tolerance && previous.as_ref() == Some(&matching);
+            if converged || iterations >= self.max_iterations {
+                return Ok(Balanced {
+                    matching,
+                    loads,
+                    iterations,
+                    converged,
+                });
+            }
+            previous = Some(matching);
+        }
+    }
+}
+
+/// The result of `LoadBalancer::balance`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Balanced {
+    /// The matching of the last round.
+    pub matching: Vec<Position>,
+    /// The loads computed from `matching`, after damping.
+    pub loads: Vec<f64>,
+    /// The number of solves.
+    pub iterations: usize,
+    /// Whether the matching and loads became stable before the maximum
+    /// number of iterations.
+    pub converged: bool,
+}
+
+#[test]
+fn test_load_balancer() {
+    use crate::solve_assignment;
+
+    let base = WeightMatrix::from_row_vec(3, vec![1.0, 2.0, 3.0, 2.0, 1.0, 3.0, 3.0, 1.0, 2.0]);
+    let demands = [1.0, 2.0, 3.0];
+    let pos = |row, column| Position { row, column };
+
+    // loads that don't depend on the matching are stable after two rounds.
+    let balanced = LoadBalancer::new()
+        .balance(&base, &demands, |_| vec![0.0; 3])
+        .unwrap();
+    assert!(balanced.converged);
+    assert_eq!(2, balanced.iterations);
+    assert_eq!(
+        solve_assignment(&mut base.clone()).unwrap(),
+        balanced.matching
+    );
+
+    // column 1 is congested: the damped loads approach it, and the final
+    // matching is optimal for them.
+    let balanced = LoadBalancer::new()
+        .damping(0.5)
+        .tolerance(1e-6)
+        .balance(&base, &demands, |_| vec![0.0, 3.0, 0.0])
+        .unwrap();
+    assert!(balanced.converged);
+    assert!((balanced.loads[1] - 3.0).abs() < 1e-6);
+    assert_eq!(vec![pos(0, 1), pos(1, 0), pos(2, 2)], balanced.matching);
+
+    // a load that always drives the heaviest row away from its column never
+    // settles.
+    let balanced = LoadBalancer::new()
+        .max_iterations(10)
+        .balance(&base, &demands, |matching| {
+            let mut loads = vec![0.0; 3];
+            loads[matching[2].column] = 10.0;
+            loads
+        })
+        .unwrap();
+    assert!(!balanced.converged);
+    assert_eq!(10, balanced.iterations);
+}
@@ -0,0 +1,183 @@
+//! Banded cost matrices, where only the cells with `|row - column| <= k` are
+//! valid, as in sequence-alignment-style matchings.
+//!
+//! `BandedMatrix` stores the `2k + 1` diagonals of the band, O(nk) values,
+//! and its row and column operations only touch the band.
+//! `solve_assignment_banded` solves on the band alone instead of scanning
+//! all `n^2` cells.
+use crate::sparse::solve_rows;
+use crate::{Error, Position, WeightNum, Weights};
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone)]
+pub struct BandedMatrix<T> {
+    n: usize,
+    k: usize,
+    /// Row `i` holds columns `i - k ..= i + k`, padded at the borders.
+    values: Vec<T>,
+}
+
+impl<T: WeightNum + Default> BandedMatrix<T> {
+    /// Calls `f` for the cells of the band only.
+    pub fn from_fn<F: FnMut((usize, usize)) -> T>(n: usize, k: usize, mut f: F) -> BandedMatrix<T> {
+        let width = 2 * k + 1;
+        let mut values = Vec::with_capacity(n * width);
+        for row in 0..n {
+            for offset in 0..width {
+                match (row + offset).checked_sub(k) {
+                    Some(column) if column < n => values.push(f((row, column))),
+                    _ => values.push(T::default()),
+                }
+            }
+        }
+        BandedMatrix { n, k, values }
+    }
+
+    /// The half-width of the band.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// The columns of the band in `row`.
+    #[inline]
+    pub fn band(&self, row: usize) -> core::ops::Range<usize> {
+        row.saturating_sub(self.k)..(row + self.k + 1).min(self.n)
+    }
+
+    #[inline]
+    fn index(&self, pos: Position) -> Option<usize> {
+        if pos.row.max(pos.column) - pos.row.min(pos.column) <= self.k {
+            Some(pos.row * (2 * self.k + 1) + pos.column + self.k - pos.row)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: WeightNum + Default> Weights for BandedMatrix<T> {
+    type T = T;
+
+    #[inline(always)]
+    fn n(&self) -> usize {
+        self.n
+    }
+
+    /// `T::default()` outside the band.
+    #[inline]
+    fn element_at(&self, pos: Position) -> T {
+        self.index(pos).map_or_else(T::default, |i| self.values[i])
+    }
+
+    #[inline]
+    fn is_element_zero(&self, pos: Position) -> bool {
+        self.index(pos).is_some_and(|i| self.values[i].is_zero())
+    }
+
+    #[inline]
+    fn is_valid_at(&self, pos: Position) -> bool {
+        self.index(pos).is_some_and(|i| self.values[i].is_valid())
+    }
+
+    fn sub_min_of_each_row(&mut self) {
+        for row in 0..self.n {
+            let min = self
+                .band(row)
+                .map(|column| Position { row, column })
+                .filter(|&pos| self.is_valid_at(pos))
+                .map(|pos| self.element_at(pos))
+                .fold(None, |min: Option<T>, value| match min {
+                    Some(m) if m <= value => Some(m),
+                    _ => Some(value),
+                });
+            if let Some(min) = min {
+                for column in self.band(row) {
+                    let i = self.index(Position { row, column }).unwrap();
+                    self.values[i] = self.values[i].sub_if_valid(min);
+                }
+            }
+        }
+    }
+
+    fn add_row(&mut self, row: usize, val: T) {
+        for column in self.band(row) {
+            let i = self.index(Position { row, column }).unwrap();
+            self.values[i] = self.values[i].add_if_valid(val);
+        }
+    }
+
+    fn sub_column(&mut self, col: usize, val: T) {
+        for row in self.band(col) {
+            let i = self.index(Position { row, column: col }).unwrap();
+            self.values[i] = self.values[i].sub_if_valid(val);
+        }
+    }
+
+    fn is_solvable(&self) -> bool {
+        (0..self.n).all(|row| {
+            self.band(row)
+                .any(|column| self.is_valid_at(Position { row, column }))
+        })
+    }
+}
+
+/// Finds the assignment with minimum total cost using only the cells of the
+/// band. `weights` is not modified.
+///
+/// # Panics
+///
+/// If a valid cell can't be converted to `f64`.
+pub fn solve_assignment_banded<T>(weights: &BandedMatrix<T>) -> Result<Vec<Position>, Error>
+where
+    T: WeightNum + Default,
+{
+    let rows: Vec<Vec<(usize, f64)>> = (0..weights.n)
+        .map(|row| {
+            weights
+                .band(row)
+                .map(|column| Position { row, column })
+                .filter(|&pos| weights.is_valid_at(pos))
+                .map(|pos| {
+                    let cost = weights.element_at(pos).to_f64();
+                    (
+                        pos.column,
+                        cost.expect("banded solve requires weights convertible to f64"),
+                    )
+                })
+                .collect()
+        })
+        .collect();
+    solve_rows(weights.n, &rows)
+}
+
+#[test]
+fn test_banded_matrix() {
+    use crate::{generators, solve_assignment, WeightMatrix};
+
+    let weights = BandedMatrix::from_fn(4, 1, |(row, column)| 10 * row + column);
+    assert_eq!(12, weights.element_at(Position { row: 1, column: 2 }));
+    assert!(!weights.is_valid_at(Position { row: 0, column: 2 }));
+    assert_eq!(0..3, weights.band(1));
+
+    let cost =
+        |m: &[Position], w: &WeightMatrix<f64>| m.iter().map(|&pos| w.element_at(pos)).sum::<f64>();
+    for seed in 0..10 {
+        let base = generators::uniform(20, 0..100, seed);
+        let value = |(row, column)| base.element_at(Position { row, column }) as f64;
+        let mut banded = BandedMatrix::from_fn(20, 2, value);
+        let dense = WeightMatrix::from_fn(20, |(row, column): (usize, usize)| {
+            if row.max(column) - row.min(column) <= 2 {
+                value((row, column))
+            } else {
+                f64::INFINITY
+            }
+        });
+        let expected = cost(&solve_assignment(&mut dense.clone()).unwrap(), &dense);
+        let found = solve_assignment_banded(&banded).unwrap();
+        assert!(found.iter().all(|&pos| banded.is_valid_at(pos)));
+        assert_eq!(expected, cost(&found, &dense));
+        assert_eq!(
+            expected,
+            cost(&solve_assignment(&mut banded).unwrap(), &dense)
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Solving many tiny instances at once.
+//!
+//! Tracking and auction simulations solve thousands of matrices of at most
+//! 16 x 16 each, where the setup of the general solver (allocating the
+//! workspace, checking solvability, ...) costs more than the solve itself.
+//! `solve_batch` takes the matrices packed one after another in a single
+//! slice and writes the column of each row packed into a single output.
+//! Each instance is solved by the shortest augmenting path method on `f64`
+//! with all of its state on the stack.
+use crate::WeightNum;
+use alloc::{vec, vec::Vec};
+
+/// The largest size of the instances of a batch.
+pub const MAX_BATCH_N: usize = 16;
+
+/// The column written for every row of an unsolvable instance.
+pub const UNSOLVABLE: u8 = u8::MAX;
+
+/// Solves the `n x n` instance in the top left corner of `c`, whose rows
+/// have a stride of `MAX_BATCH_N`, into `out`, one column per row.
+fn solve_small(n: usize, c: &[f64; MAX_BATCH_N * MAX_BATCH_N], out: &mut [u8]) {
+    const N: usize = MAX_BATCH_N + 1;
+
+    // 1-based: row and column 0 are the virtual start of each search.
+    // `row_of[j]` is the row assigned to column `j`, 0 if none.
+    let mut u = [0.0f64; N];
+    let mut v = [0.0f64; N];
+    let mut row_of = [0usize; N];
+    let mut way = [0usize; N];
+    for row in 1..=n {
+        row_of[0] = row;
+        let mut j0 = 0;
+        let mut min_slack = [f64::INFINITY; N];
+        let mut used = [false; N];
+        loop {
+            used[j0] = true;
+            let i0 = row_of[j0];
+            let mut delta = f64::INFINITY;
+            let mut j1 = 0;
+            for j in 1..=n {
+                if !used[j] {
+                    let slack = c[(i0 - 1) * MAX_BATCH_N + j - 1] - u[i0] - v[j];
+                    if slack < min_slack[j] {
+                        min_slack[j] = slack;
+                        way[j] = j0;
+                    }
+                    if min_slack[j] < delta {
+                        delta = min_slack[j];
+                        j1 = j;
+                    }
+                }
+            }
+            if delta == f64::INFINITY {
+                out.iter_mut().for_each(|column| *column = UNSOLVABLE);
+                return;
+            }
+            for j in 0..=n {
+                if used[j] {
+                    u[row_of[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    min_slack[j] -= delta;
+                }
+            }
+            j0 = j1;
+            if row_of[j0] == 0 {
+                break;
+            }
+        }
+        // augment along the path back to the virtual column.
+        while j0 != 0 {
+            let j1 = way[j0];
+            row_of[j0] = row_of[j1];
+            j0 = j1;
+        }
+    }
+    for j in 1..=n {
+        out[row_of[j] - 1] = (j - 1) as u8;
+    }
+}
+
+fn solve_packed<T: WeightNum>(n: usize, matrix: &[T], out: &mut [u8]) {
+    // invalid cells are infinite.
+    let mut c = [f64::INFINITY; MAX_BATCH_N * MAX_BATCH_N];
+    for (row, values) in matrix.chunks(n).enumerate() {
+        for (column, value) in values.iter().enumerate() {
+            if value.is_valid() {
+                c[row * MAX_BATCH_N + column] = value
+                    .to_f64()
+                    .expect("batch solves require weights convertible to f64");
+            }
+        }
+    }
+    solve_small(n, &c, out);
+}
+
+fn check_batch<T>(n: usize, costs: &[T], out: &[u8]) {
+    assert!(
+        n > 0 && n <= MAX_BATCH_N,
+        "batch instances must have 1 to 16 rows"
+    );
+    assert!(costs.len() % (n * n) == 0);
+    assert!(out.len() == costs.len() / n);
+}
+
+/// Solves the `n x n` instances packed in `costs` in row-major order, one
+/// after another, writing the column of each row to `out`, one instance
+/// after another. All rows of an unsolvable instance are `UNSOLVABLE`.
+///
+/// # Panics
+///
+/// If `n` is 0 or larger than `MAX_BATCH_N`, `costs` isn't made of whole
+/// instances, `out` doesn't have `n` entries per instance, or a valid cell
+/// can't be converted to `f64`.
+pub fn solve_batch_into<T: WeightNum>(n: usize, costs: &[T], out: &mut [u8]) {
+    check_batch(n, costs, out);
+    for (matrix, columns) in costs.chunks(n * n).zip(out.chunks_mut(n)) {
+        solve_packed(n, matrix, columns);
+    }
+}
+
+/// Like `solve_batch_into`, allocating the output.
+pub fn solve_batch<T: WeightNum>(n: usize, costs: &[T]) -> Vec<u8> {
+    let mut out = vec![0; costs.len() / n.max(1)];
+    solve_batch_into(n, costs, &mut out);
+    out
+}
+
+/// Like `solve_batch`, solving the instances in parallel.
+#[cfg(feature = "rayon")]
+pub fn solve_batch_parallel<T: WeightNum + Sync>(n: usize, costs: &[T]) -> Vec<u8> {
+    use rayon::prelude::*;
+
+    let mut out = vec![0; costs.len() / n.max(1)];
+    check_batch(n, costs, &out);
+    costs
+        .par_chunks(n * n)
+        .zip(out.par_chunks_mut(n))
+        .for_each(|(matrix, columns)| solve_packed(n, matrix, columns));
+    out
+}
+
+#[test]
+fn test_solve_batch() {
+    use crate::{generators, solve_assignment, Position, Weights};
+
+    for n in 1..=MAX_BATCH_N {
+        let mut costs = Vec::new();
+        let mut matrices = Vec::new();
+        for seed in 0..5 {
+            let weights = generators::uniform(n, 0..100, seed + 10 * n as u64);
+            costs.extend_from_slice(weights.as_slice());
+            matrices.push(weights);
+        }
+        let out = solve_batch(n, &costs);
+        #[cfg(feature = "rayon")]
+        assert_eq!(out, solve_batch_parallel(n, &costs));
+        for (weights, columns) in matrices.iter().zip(out.chunks(n)) {
+            let expected: u32 = solve_assignment(&mut weights.clone())
+                .unwrap()
+                .iter()
+                .map(|&pos| weights.element_at(pos))
+                .sum();
+            let cost: u32 = columns
+                .iter()
+                .enumerate()
+                .map(|(row, &column)| {
+                    weights.element_at(Position {
+                        row,
+                        column: column as usize,
+                    })
+                })
+                .sum();
+            assert_eq!(expected, cost);
+        }
+    }
+
+    let inf = f64::INFINITY;
+    let costs = [1.0, inf, 2.0, inf, 1.0, 2.0, 1.0, 4.0];
+    assert_eq!(vec![UNSOLVABLE, UNSOLVABLE, 1, 0], solve_batch(2, &costs));
+}
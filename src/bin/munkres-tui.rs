@@ -0,0 +1,174 @@
+//! Terminal UI stepping through the Kuhn-Munkres algorithm on a matrix.
+//!
+//! Usage: `munkres-tui [FILE]`
+//!
+//! Reads a square matrix from `FILE` (or standard input), one row per line
+//! with the values separated by whitespace or commas. `inf` forbids a cell.
+//! The arrow keys step through the snapshots of the algorithm, Home and End
+//! jump to the first and last one, and `q` quits.
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::style::{
+    Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor,
+};
+use crossterm::{cursor, execute, queue, terminal};
+use munkres::visualize::{solve_assignment_recorded, Snapshot};
+use munkres::{Position, WeightMatrix};
+use std::io::{self, Read, Write};
+use std::{env, fs, process};
+
+fn parse_matrix(input: &str) -> Result<WeightMatrix<f64>, String> {
+    let rows: Vec<Vec<f64>> = input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| {
+            line.split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|value| !value.is_empty())
+                .map(|value| {
+                    value
+                        .parse()
+                        .map_err(|_| format!("line {}: invalid value {:?}", i + 1, value))
+                })
+                .collect()
+        })
+        .collect::<Result<_, _>>()?;
+    let n = rows.len();
+    if n == 0 {
+        return Err("empty matrix".to_owned());
+    }
+    if let Some(i) = rows.iter().position(|row| row.len() != n) {
+        return Err(format!("line {}: expected {} values", i + 1, n));
+    }
+    Ok(WeightMatrix::from_row_vec(n, rows.concat()))
+}
+
+fn draw<W: Write>(out: &mut W, snapshots: &[Snapshot], index: usize) -> io::Result<()> {
+    let snapshot = &snapshots[index];
+    let n = snapshot.n;
+    let width = snapshot.values.iter().map(|v| v.len()).max().unwrap_or(1) + 2;
+    queue!(
+        out,
+        terminal::Clear(terminal::ClearType::All),
+        cursor::MoveTo(0, 0),
+        SetAttribute(Attribute::Bold),
+        Print(format!(
+            "{}/{}  {}",
+            index + 1,
+            snapshots.len(),
+            snapshot.description()
+        )),
+        SetAttribute(Attribute::Reset),
+        cursor::MoveTo(0, 1),
+        Print("left/right: step, home/end: first/last, q: quit"),
+    )?;
+    for row in 0..n {
+        queue!(out, cursor::MoveTo(0, row as u16 + 3))?;
+        for column in 0..n {
+            let pos = Position { row, column };
+            let covered =
+                snapshot.covered_rows.contains(&row) || snapshot.covered_columns.contains(&column);
+            let (mark, color) = if snapshot.stars.contains(&pos) {
+                ("*", Color::Yellow)
+            } else if snapshot.primes.contains(&pos) {
+                ("'", Color::Cyan)
+            } else {
+                (" ", Color::Reset)
+            };
+            if covered {
+                queue!(out, SetBackgroundColor(Color::DarkGrey))?;
+            }
+            queue!(
+                out,
+                SetForegroundColor(color),
+                Print(format!(
+                    "{:>width$}{}",
+                    snapshot.values[row * n + column],
+                    mark,
+                    width = width
+                )),
+                ResetColor,
+            )?;
+        }
+    }
+    if index + 1 == snapshots.len() {
+        let columns: Vec<String> = snapshot
+            .stars
+            .iter()
+            .map(|pos| format!("{}->{}", pos.row, pos.column))
+            .collect();
+        queue!(
+            out,
+            cursor::MoveTo(0, n as u16 + 4),
+            Print(format!("matching: {}", columns.join(" "))),
+        )?;
+    }
+    out.flush()
+}
+
+fn run(snapshots: &[Snapshot]) -> io::Result<()> {
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+    let mut index = 0;
+    let result = loop {
+        if let Err(err) = draw(&mut out, snapshots, index) {
+            break Err(err);
+        }
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind != KeyEventKind::Release => match key.code {
+                KeyCode::Left | KeyCode::Up => index = index.saturating_sub(1),
+                KeyCode::Right | KeyCode::Down | KeyCode::Char(' ') => {
+                    index = (index + 1).min(snapshots.len() - 1)
+                }
+                KeyCode::Home => index = 0,
+                KeyCode::End => index = snapshots.len() - 1,
+                KeyCode::Char('q') | KeyCode::Esc => break Ok(()),
+                _ => {}
+            },
+            Ok(_) => {}
+            Err(err) => break Err(err),
+        }
+    };
+    execute!(out, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
+
+fn main() {
+    let input = match env::args().nth(1) {
+        Some(path) => fs::read_to_string(&path).map_err(|err| format!("{}: {}", path, err)),
+        None => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map(|_| input)
+                .map_err(|err| err.to_string())
+        }
+    };
+    let mut weights = match input.and_then(|input| parse_matrix(&input)) {
+        Ok(weights) => weights,
+        Err(err) => {
+            eprintln!("munkres-tui: {}", err);
+            process::exit(2);
+        }
+    };
+    let (result, snapshots) = solve_assignment_recorded(&mut weights);
+    if let Err(err) = result {
+        eprintln!("munkres-tui: {:?}, showing the steps up to the error", err);
+    }
+    if snapshots.is_empty() {
+        process::exit(1);
+    }
+    if let Err(err) = run(&snapshots) {
+        eprintln!("munkres-tui: {}", err);
+        process::exit(1);
+    }
+}
+
+#[test]
+fn test_parse_matrix() {
+    let weights = parse_matrix("1 2\n3,inf\n\n").unwrap();
+    assert_eq!(&[1.0, 2.0, 3.0, f64::INFINITY], weights.as_slice());
+    assert!(parse_matrix("1 2\n3").is_err());
+    assert!(parse_matrix("1 x\n3 4").is_err());
+}
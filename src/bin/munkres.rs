@@ -0,0 +1,99 @@
+//! Command line solver for sparse instances.
+//!
+//! Usage: `munkres [FILE]`
+//!
+//! Reads `row,column,cost` triples from `FILE` (or standard input), one per
+//! line with the values separated by commas or whitespace. Empty lines and
+//! lines starting with `#` are ignored. Cells not given are forbidden. The
+//! input is streamed, so no dense matrix is ever built. Prints the assigned
+//! `row,column,cost` triples followed by the total cost.
+use munkres::sparse::TripletBuilder;
+use std::io::{self, BufRead, BufReader, Write};
+use std::{env, fs, process};
+
+fn parse_triple(line: &str) -> Result<Option<(usize, usize, f64)>, String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+    let fields: Vec<&str> = line
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|field| !field.is_empty())
+        .collect();
+    if fields.len() != 3 {
+        return Err(format!("expected row,column,cost, got {:?}", line));
+    }
+    let index = |field: &str| {
+        field
+            .parse()
+            .map_err(|_| format!("invalid index {:?}", field))
+    };
+    let cost = fields[2]
+        .parse()
+        .map_err(|_| format!("invalid cost {:?}", fields[2]))?;
+    Ok(Some((index(fields[0])?, index(fields[1])?, cost)))
+}
+
+fn read_triples<R: BufRead>(input: R) -> Result<TripletBuilder, String> {
+    let mut builder = TripletBuilder::new();
+    for (i, line) in input.lines().enumerate() {
+        let line = line.map_err(|err| err.to_string())?;
+        if let Some((row, column, cost)) =
+            parse_triple(&line).map_err(|err| format!("line {}: {}", i + 1, err))?
+        {
+            builder.push(row, column, cost);
+        }
+    }
+    Ok(builder)
+}
+
+fn main() {
+    let builder = match env::args().nth(1) {
+        Some(path) => fs::File::open(&path)
+            .map_err(|err| format!("{}: {}", path, err))
+            .and_then(|file| read_triples(BufReader::new(file))),
+        None => read_triples(io::stdin().lock()),
+    };
+    let mut builder = match builder {
+        Ok(builder) => builder,
+        Err(err) => {
+            eprintln!("munkres: {}", err);
+            process::exit(2);
+        }
+    };
+    let matching = match builder.solve() {
+        Ok(matching) => matching,
+        Err(err) => {
+            eprintln!("munkres: {:?}", err);
+            process::exit(1);
+        }
+    };
+
+    let stdout = io::stdout();
+    let mut out = io::BufWriter::new(stdout.lock());
+    let mut total = 0.0;
+    for pos in matching {
+        let cost = builder.cost(pos).unwrap();
+        total += cost;
+        writeln!(out, "{},{},{}", pos.row, pos.column, cost).unwrap();
+    }
+    writeln!(out, "# total {}", total).unwrap();
+}
+
+#[test]
+fn test_read_triples() {
+    let input = "# row,column,cost\n0,0,1\n\n1 0 2.5\n0,1,inf\n1, 1, 3\n";
+    let mut builder = read_triples(input.as_bytes()).unwrap();
+    assert_eq!((2, 3), (builder.n(), builder.len()));
+    let matching = builder.solve().unwrap();
+    assert_eq!(
+        vec![0, 1],
+        matching.iter().map(|pos| pos.column).collect::<Vec<_>>()
+    );
+
+    assert_eq!(
+        Err("line 2: invalid cost \"x\"".to_owned()),
+        read_triples("0,0,1\n0,1,x\n".as_bytes()).map(|_| ())
+    );
+    assert!(read_triples("0,0\n".as_bytes()).is_err());
+}
@@ -0,0 +1,217 @@
+#[cfg(not(feature = "smallvec"))]
+use alloc::vec::Vec;
+use core::{iter, mem};
+#[cfg(feature = "smallvec")]
+use smallvec::SmallVec;
+
+#[cfg(feature = "smallvec")]
+type Words = SmallVec<[u64; 2]>;
+#[cfg(feature = "smallvec")]
+type Summary = SmallVec<[u64; 1]>;
+#[cfg(not(feature = "smallvec"))]
+type Words = Vec<u64>;
+#[cfg(not(feature = "smallvec"))]
+type Summary = Vec<u64>;
+
+const BITS: usize = 64;
+
+/// A fixed size set of bits, stored inline for up to 128 bits with the
+/// `smallvec` feature.
+///
+/// A second level of summary bits, one per word, tells which words have any
+/// bit set, so iterating over the set bits skips empty regions a word at a
+/// time. This keeps iteration cheap for very large sets with few bits set.
+#[derive(Debug, Clone)]
+pub(crate) struct BitSet {
+    len: usize,
+    words: Words,
+    summary: Summary,
+}
+
+impl BitSet {
+    /// A set of `len` bits, all cleared.
+    pub(crate) fn with_capacity(len: usize) -> BitSet {
+        let words = len.div_ceil(BITS);
+        BitSet {
+            len,
+            words: iter::repeat_n(0, words).collect(),
+            summary: iter::repeat_n(0, words.div_ceil(BITS)).collect(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn contains(&self, bit: usize) -> bool {
+        debug_assert!(bit < self.len);
+        self.words[bit / BITS] & (1 << (bit % BITS)) != 0
+    }
+
+    /// Like `contains`, without bounds checks with the `unchecked` feature.
+    ///
+    /// # Safety
+    ///
+    /// `bit` must be less than the length of the set.
+    #[inline]
+    pub(crate) unsafe fn contains_unchecked(&self, bit: usize) -> bool {
+        debug_assert!(bit < self.len);
+        #[cfg(feature = "unchecked")]
+        let word = *self.words.get_unchecked(bit / BITS);
+        #[cfg(not(feature = "unchecked"))]
+        let word = self.words[bit / BITS];
+        word & (1 << (bit % BITS)) != 0
+    }
+
+    #[inline]
+    pub(crate) fn set(&mut self, bit: usize, enabled: bool) {
+        debug_assert!(bit < self.len);
+        let (index, mask) = (bit / BITS, 1 << (bit % BITS));
+        let word = &mut self.words[index];
+        if enabled {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+        let nonempty = *word != 0;
+        let summary_mask = 1 << (index % BITS);
+        if nonempty {
+            self.summary[index / BITS] |= summary_mask;
+        } else {
+            self.summary[index / BITS] &= !summary_mask;
+        }
+    }
+
+    /// Sets or clears all bits.
+    pub(crate) fn set_all(&mut self, enabled: bool) {
+        let fill = if enabled { !0 } else { 0 };
+        self.words.iter_mut().for_each(|word| *word = fill);
+        // keep the bits past `len` cleared, for `count_ones` and `ones`.
+        if let Some(last) = self.words.last_mut() {
+            if self.len % BITS != 0 {
+                *last &= (1 << (self.len % BITS)) - 1;
+            }
+        }
+        let words = self.words.len();
+        for (index, summary) in self.summary.iter_mut().enumerate() {
+            *summary = if !enabled {
+                0
+            } else if words - index * BITS >= BITS {
+                !0
+            } else {
+                (1 << (words - index * BITS)) - 1
+            };
+        }
+    }
+
+    /// Clears the bits set in `other`, which must have the same length.
+    pub(crate) fn remove_all(&mut self, other: &BitSet) {
+        debug_assert!(self.len == other.len);
+        self.summary.iter_mut().for_each(|summary| *summary = 0);
+        for (index, (word, other)) in self.words.iter_mut().zip(&other.words).enumerate() {
+            *word &= !other;
+            if *word != 0 {
+                self.summary[index / BITS] |= 1 << (index % BITS);
+            }
+        }
+    }
+
+    /// The bytes of the words, see `estimated_bytes`.
+    pub(crate) fn memory_usage(&self) -> usize {
+        (self.words.len() + self.summary.len()) * mem::size_of::<u64>()
+    }
+
+    /// The bytes of the words of a set of `len` bits.
+    pub(crate) fn estimated_bytes(len: usize) -> usize {
+        let words = len.div_ceil(BITS);
+        (words + words.div_ceil(BITS)) * mem::size_of::<u64>()
+    }
+
+    pub(crate) fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Iterates over the set bits in ascending order.
+    #[inline]
+    pub(crate) fn ones(&self) -> Ones<'_> {
+        Ones {
+            words: &self.words,
+            summary: &self.summary,
+            summary_index: 0,
+            summary_current: self.summary.first().copied().unwrap_or(0),
+            index: 0,
+            current: 0,
+        }
+    }
+}
+
+pub(crate) struct Ones<'a> {
+    words: &'a [u64],
+    summary: &'a [u64],
+    summary_index: usize,
+    /// The summary bits of the words not visited yet.
+    summary_current: u64,
+    index: usize,
+    current: u64,
+}
+
+impl<'a> Iterator for Ones<'a> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        while self.current == 0 {
+            while self.summary_current == 0 {
+                self.summary_index += 1;
+                self.summary_current = *self.summary.get(self.summary_index)?;
+            }
+            let word = self.summary_current.trailing_zeros() as usize;
+            self.summary_current &= self.summary_current - 1;
+            self.index = self.summary_index * BITS + word;
+            self.current = self.words[self.index];
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        self.current &= self.current - 1;
+        Some(self.index * BITS + bit)
+    }
+}
+
+#[test]
+fn test_bitset() {
+    let mut bits = BitSet::with_capacity(130);
+    bits.set_all(true);
+    assert_eq!(130, bits.count_ones());
+    bits.set(3, false);
+    bits.set(64, false);
+    assert!(!bits.contains(3));
+    assert!(bits.contains(129));
+    assert_eq!(128, bits.ones().count());
+    bits.set_all(false);
+    bits.set(0, true);
+    bits.set(127, true);
+    assert_eq!(vec![0, 127], bits.ones().collect::<Vec<_>>());
+
+    let mut all = BitSet::with_capacity(130);
+    all.set_all(true);
+    all.remove_all(&bits);
+    assert_eq!(128, all.count_ones());
+    assert_eq!(Some(1), all.ones().next());
+    assert_eq!(Some(129), all.ones().last());
+}
+
+#[test]
+fn test_bitset_sparse() {
+    let n = 100_000;
+    let mut bits = BitSet::with_capacity(n);
+    bits.set_all(true);
+    for bit in 0..n {
+        if bit != 5 && bit != 70_000 && bit != n - 1 {
+            bits.set(bit, false);
+        }
+    }
+    assert_eq!(vec![5, 70_000, n - 1], bits.ones().collect::<Vec<_>>());
+    bits.set(70_000, false);
+    bits.set(64 * 64 * 3, true);
+    assert_eq!(vec![5, 64 * 64 * 3, n - 1], bits.ones().collect::<Vec<_>>());
+    assert_eq!(3, bits.count_ones());
+}
@@ -0,0 +1,160 @@
+//! Block decomposition: solving independent subproblems separately.
+//!
+//! The valid cells form a bipartite graph between rows and columns. If it
+//! falls apart into several connected components, no assignment ever mixes
+//! them, so each block of rows and columns is solved on its own. Gated
+//! matrices, e.g. from tracking, are often close to block-diagonal, and
+//! solving `k` blocks of size `n / k` takes a `k^2`-th of the time of the
+//! whole matrix.
+use crate::restricted::Restricted;
+use crate::{solve_assignment, Error, Position, Weights};
+use alloc::{vec, vec::Vec};
+
+/// A connected component of the valid cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    /// The rows of the block, ascending.
+    pub rows: Vec<usize>,
+    /// The columns of the block, ascending.
+    pub columns: Vec<usize>,
+}
+
+fn find(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// Splits the rows and columns of `weights` into the connected components of
+/// its valid cells, ordered by their first row (or column, for blocks
+/// without rows). A row or column without valid cells is a block of its own.
+pub fn blocks<W>(weights: &W) -> Vec<Block>
+where
+    W: Weights,
+{
+    let n = weights.n();
+    // rows are nodes `0..n`, columns `n..2n`.
+    let mut parent: Vec<usize> = (0..2 * n).collect();
+    for row in 0..n {
+        for column in 0..n {
+            if weights.is_valid_at(Position { row, column }) {
+                let (a, b) = (find(&mut parent, row), find(&mut parent, n + column));
+                if a != b {
+                    parent[a.max(b)] = a.min(b);
+                }
+            }
+        }
+    }
+
+    let mut block_of_root = vec![usize::MAX; 2 * n];
+    let mut blocks: Vec<Block> = Vec::new();
+    for node in 0..2 * n {
+        let root = find(&mut parent, node);
+        if block_of_root[root] == usize::MAX {
+            block_of_root[root] = blocks.len();
+            blocks.push(Block {
+                rows: Vec::new(),
+                columns: Vec::new(),
+            });
+        }
+        let block = &mut blocks[block_of_root[root]];
+        if node < n {
+            block.rows.push(node);
+        } else {
+            block.columns.push(node - n);
+        }
+    }
+    blocks
+}
+
+fn solve_block<W>(weights: &W, block: &Block) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+{
+    if block.rows.len() != block.columns.len() {
+        return Err(Error::MatrixNotSolvable);
+    }
+    let mut sub = Restricted::submatrix(weights, &block.rows, &block.columns);
+    Ok(solve_assignment(&mut sub)?
+        .into_iter()
+        .map(|pos| Position {
+            row: block.rows[pos.row],
+            column: block.columns[pos.column],
+        })
+        .collect())
+}
+
+/// Solves the assignment problem block by block. `weights` is not modified.
+/// Returns `Error::MatrixNotSolvable` if a block has more rows than columns
+/// or vice versa.
+pub fn solve_assignment_blocks<W>(weights: &W) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+{
+    let mut matching = Vec::with_capacity(weights.n());
+    for block in blocks(weights) {
+        matching.extend(solve_block(weights, &block)?);
+    }
+    matching.sort_by_key(|pos| pos.row);
+    Ok(matching)
+}
+
+/// Like `solve_assignment_blocks`, but solves the blocks in parallel.
+#[cfg(feature = "rayon")]
+pub fn solve_assignment_blocks_parallel<W>(weights: &W) -> Result<Vec<Position>, Error>
+where
+    W: Weights + Sync,
+{
+    use rayon::prelude::*;
+
+    let blocks = blocks(weights);
+    let matchings: Vec<Vec<Position>> = blocks
+        .par_iter()
+        .map(|block| solve_block(weights, block))
+        .collect::<Result<_, _>>()?;
+    let mut matching: Vec<Position> = matchings.into_iter().flatten().collect();
+    matching.sort_by_key(|pos| pos.row);
+    Ok(matching)
+}
+
+#[test]
+fn test_solve_assignment_blocks() {
+    use crate::WeightMatrix;
+
+    let inf = f64::INFINITY;
+    // rows 0 and 2 only use columns 1 and 3, rows 1 and 3 columns 0 and 2.
+    let weights = WeightMatrix::from_row_vec(
+        4,
+        vec![
+            inf, 1.0, inf, 2.0, //
+            3.0, inf, 1.0, inf, //
+            inf, 2.0, inf, 5.0, //
+            1.0, inf, 4.0, inf, //
+        ],
+    );
+    let found = blocks(&weights);
+    assert_eq!(2, found.len());
+    assert_eq!(
+        Block {
+            rows: vec![0, 2],
+            columns: vec![1, 3]
+        },
+        found[0]
+    );
+    assert_eq!(vec![0, 2], found[1].columns);
+
+    let expected = solve_assignment(&mut weights.clone());
+    assert_eq!(expected, solve_assignment_blocks(&weights));
+    #[cfg(feature = "rayon")]
+    assert_eq!(expected, solve_assignment_blocks_parallel(&weights));
+
+    // a block with two rows but a single column.
+    let weights = WeightMatrix::from_row_vec(2, vec![1.0, inf, 2.0, inf]);
+    assert_eq!(2, blocks(&weights).len());
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_assignment_blocks(&weights)
+    );
+}
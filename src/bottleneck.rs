@@ -0,0 +1,151 @@
+//! Bottleneck objectives, which minimize the largest assigned weight
+//! instead of (or before) the sum.
+//!
+//! The smallest achievable maximum, the bottleneck value, is found by a
+//! binary search over the distinct valid weights, checking at each threshold
+//! whether the cells up to it contain a perfect matching.
+//!
+//! `solve_bottleneck_assignment` returns any matching attaining it, found by
+//! augmenting paths, and `solve_assignment_bottleneck_sum` the cheapest one.
+use crate::graph::Graph;
+use crate::restricted::Restricted;
+use crate::{solve_assignment_feasible, Error, Position, Weights};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// Solves the bottleneck assignment problem: minimizes the largest assigned
+/// weight, e.g. the makespan of workers doing tasks in parallel. Returns a
+/// matching, sorted by row, and its bottleneck value. The sum isn't
+/// minimized. `weights` is not modified.
+pub fn solve_bottleneck_assignment<W>(weights: &W) -> Result<(Vec<Position>, W::T), Error>
+where
+    W: Weights,
+    W::T: Default,
+{
+    if weights.n() == 0 {
+        return Ok((Vec::new(), W::T::default()));
+    }
+    let threshold = bottleneck_value(weights)?;
+    let below = Restricted::from_weights_where(weights, |pos| weights.element_at(pos) <= threshold);
+    let columns = Graph::from_weights(&below, |_| ())
+        .perfect_matching()
+        .ok_or(Error::MatrixNotSolvable)?;
+    let matching = columns
+        .into_iter()
+        .enumerate()
+        .map(|(row, column)| Position { row, column })
+        .collect();
+    Ok((matching, threshold))
+}
+
+/// Solves the assignment problem lexicographically: first minimizes the
+/// largest assigned weight, then the sum among the matchings attaining that
+/// maximum. Fair in that no row gets a worse cell than necessary, and as
+/// efficient as possible otherwise. `weights` is not modified.
+pub fn solve_assignment_bottleneck_sum<W>(weights: &W) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+{
+    if weights.n() == 0 {
+        return Ok(Vec::new());
+    }
+    let threshold = bottleneck_value(weights)?;
+    solve_assignment_feasible(weights, |pos| weights.element_at(pos) <= threshold)
+}
+
+/// The smallest value such that the valid cells not above it contain a
+/// perfect matching.
+pub(crate) fn bottleneck_value<W>(weights: &W) -> Result<W::T, Error>
+where
+    W: Weights,
+{
+    let n = weights.n();
+    let mut values = Vec::with_capacity(n * n);
+    for row in 0..n {
+        for column in 0..n {
+            let pos = Position { row, column };
+            if weights.is_valid_at(pos) {
+                values.push(weights.element_at(pos));
+            }
+        }
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    values.dedup();
+
+    let has_matching = |threshold: W::T| {
+        let below =
+            Restricted::from_weights_where(weights, |pos| weights.element_at(pos) <= threshold);
+        Graph::from_weights(&below, |_| ()).has_perfect_matching()
+    };
+    match values.last() {
+        Some(&max) if has_matching(max) => {}
+        _ => return Err(Error::MatrixNotSolvable),
+    }
+    // the first value with a matching.
+    let (mut lo, mut hi) = (0, values.len() - 1);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if has_matching(values[mid]) {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+    Ok(values[lo])
+}
+
+#[test]
+fn test_solve_assignment_bottleneck_sum() {
+    use crate::{solve_assignment, WeightMatrix};
+
+    let pos = |row, column| Position { row, column };
+    // the optimal sum 9 + 4 + 1 needs a 9, the bottleneck is 7.
+    let weights = WeightMatrix::from_row_vec(3, vec![9, 7, 6, 9, 7, 4, 6, 1, 5]);
+    let optimal = solve_assignment(&mut weights.clone()).unwrap();
+    assert_eq!(vec![pos(0, 0), pos(1, 2), pos(2, 1)], optimal);
+    assert_eq!(Ok(7), bottleneck_value(&weights));
+    // of the matchings with maximum 7, this one has the smaller sum 17.
+    assert_eq!(
+        Ok(vec![pos(0, 1), pos(1, 2), pos(2, 0)]),
+        solve_assignment_bottleneck_sum(&weights)
+    );
+
+    let inf = f32::INFINITY;
+    let weights = WeightMatrix::from_row_vec(2, vec![inf, 1.0, inf, 2.0]);
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_assignment_bottleneck_sum(&weights)
+    );
+}
+
+#[test]
+fn test_solve_bottleneck_assignment() {
+    use crate::{generators, WeightMatrix};
+
+    let weights = WeightMatrix::from_row_vec(3, vec![9, 7, 6, 9, 7, 4, 6, 1, 5]);
+    let (matching, value) = solve_bottleneck_assignment(&weights).unwrap();
+    assert_eq!(7, value);
+    assert_eq!(
+        Some(7),
+        matching.iter().map(|&pos| weights.element_at(pos)).max()
+    );
+    assert!((0..3).all(|row| matching.iter().any(|pos| pos.row == row)));
+    assert!((0..3).all(|column| matching.iter().any(|pos| pos.column == column)));
+
+    for seed in 0..5 {
+        let weights = generators::uniform(12, 0..1000, seed);
+        let (matching, value) = solve_bottleneck_assignment(&weights).unwrap();
+        assert_eq!(Ok(value), bottleneck_value(&weights));
+        let max = matching.iter().map(|&pos| weights.element_at(pos)).max();
+        assert_eq!(Some(value), max);
+    }
+
+    let inf = f32::INFINITY;
+    let weights = WeightMatrix::from_row_vec(2, vec![inf, 1.0, inf, 2.0]);
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_bottleneck_assignment(&weights)
+    );
+    let empty = WeightMatrix::<u32>::from_row_vec(0, vec![]);
+    assert_eq!(Ok((vec![], 0)), solve_bottleneck_assignment(&empty));
+}
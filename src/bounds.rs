@@ -0,0 +1,183 @@
+use crate::{Error, Position, WeightNum, Weights};
+use alloc::{vec, vec::Vec};
+
+/// A lower bound on the cost of any assignment of `weights`: the sum of the
+/// row minima plus the column minima of the matrix after subtracting the row
+/// minima. Takes `O(n^2)` time and doesn't modify `weights`. Fails if a row
+/// or column has no valid cell.
+///
+/// # Panics
+///
+/// If `weights` is empty.
+pub fn lower_bound<W>(weights: &W) -> Result<W::T, Error>
+where
+    W: Weights,
+{
+    let n = weights.n();
+    assert!(n > 0);
+    let mut row_minima = Vec::with_capacity(n);
+    for row in 0..n {
+        let min = (0..n)
+            .map(|column| Position { row, column })
+            .filter(|&pos| weights.is_valid_at(pos))
+            .map(|pos| weights.element_at(pos))
+            .fold(None, |min: Option<W::T>, elm| match min {
+                Some(m) if m < elm => Some(m),
+                _ => Some(elm),
+            });
+        row_minima.push(min.ok_or(Error::MatrixNotSolvable)?);
+    }
+    let mut bound = row_minima
+        .iter()
+        .skip(1)
+        .fold(row_minima[0], |sum, &m| sum + m);
+    for column in 0..n {
+        let min = (0..n)
+            .map(|row| Position { row, column })
+            .filter(|&pos| weights.is_valid_at(pos))
+            .map(|pos| weights.element_at(pos) - row_minima[pos.row])
+            .fold(None, |min: Option<W::T>, elm| match min {
+                Some(m) if m < elm => Some(m),
+                _ => Some(elm),
+            });
+        bound = bound + min.ok_or(Error::MatrixNotSolvable)?;
+    }
+    Ok(bound)
+}
+
+/// The minimum valid value of each column, `None` for columns without one.
+pub(crate) fn column_minima<W>(weights: &W) -> Vec<Option<W::T>>
+where
+    W: Weights,
+{
+    let n = weights.n();
+    let mut minima: Vec<Option<W::T>> = vec![None; n];
+    for row in 0..n {
+        for (column, min) in minima.iter_mut().enumerate() {
+            let pos = Position { row, column };
+            if weights.is_valid_at(pos) {
+                let elm = weights.element_at(pos);
+                *min = Some(match *min {
+                    Some(m) if m < elm => m,
+                    _ => elm,
+                });
+            }
+        }
+    }
+    minima
+}
+
+/// Greedily assigns each row to the first free column in which it attains
+/// the column minimum. Such an assignment reaches the lower bound of the sum
+/// of row minima plus column minima of the row-reduced matrix, so it is
+/// optimal if `weights` is row-reduced.
+pub(crate) fn tight_permutation<W>(weights: &W) -> Option<Vec<Position>>
+where
+    W: Weights,
+{
+    let n = weights.n();
+    let minima = column_minima(weights);
+    let mut used = vec![false; n];
+    let mut matching = Vec::with_capacity(n);
+    for row in 0..n {
+        let column = (0..n).find(|&column| {
+            let pos = Position { row, column };
+            !used[column]
+                && weights.is_valid_at(pos)
+                && minima[column].is_some_and(|min| (weights.element_at(pos) - min).is_zero())
+        })?;
+        used[column] = true;
+        matching.push(Position { row, column });
+    }
+    Some(matching)
+}
+
+#[test]
+fn test_lower_bound() {
+    use crate::{solve_assignment, WeightMatrix};
+
+    // row minima 1 + 1 + 2, then column 2 still needs 2.
+    let c = vec![1, 5, 4, 3, 1, 5, 2, 9, 4];
+    let weights = WeightMatrix::from_row_vec(3, c);
+    assert_eq!(Ok(6), lower_bound(&weights));
+
+    let weights = WeightMatrix::from_row_vec(3, vec![250, 400, 350, 400, 600, 350, 200, 400, 250]);
+    let bound = lower_bound(&weights).unwrap();
+    let matching = solve_assignment(&mut weights.clone()).unwrap();
+    let cost: i32 = matching.iter().map(|&pos| weights.element_at(pos)).sum();
+    assert!(bound <= cost);
+
+    let inf = f32::INFINITY;
+    let weights = WeightMatrix::from_row_vec(2, vec![1.0, inf, 2.0, inf]);
+    assert_eq!(Err(Error::MatrixNotSolvable), lower_bound(&weights));
+}
+
+#[test]
+#[should_panic]
+fn test_lower_bound_empty() {
+    let _ = lower_bound(&crate::WeightMatrix::<u32>::from_row_vec(0, vec![]));
+}
+
+#[test]
+fn test_lower_bound_invalid_cells() {
+    use crate::WeightMatrix;
+
+    assert_eq!(Ok(7), lower_bound(&WeightMatrix::from_row_vec(1, vec![7])));
+
+    // the masked placeholders are smaller than any valid cell.
+    let valid = [false, true, true, true];
+    let weights = WeightMatrix::from_row_vec_with_validity(2, vec![0, 4, 3, 9], &valid);
+    assert_eq!(Ok(7), lower_bound(&weights));
+
+    // every row has a valid cell, but column 0 doesn't.
+    let weights = WeightMatrix::from_row_options(2, vec![None, Some(1), None, Some(2)]);
+    assert_eq!(Err(Error::MatrixNotSolvable), lower_bound(&weights));
+}
+
+#[test]
+fn test_tight_permutation() {
+    use crate::{solve_assignment, WeightMatrix};
+
+    let pos = |row, column| Position { row, column };
+    assert_eq!(
+        Some(vec![]),
+        tight_permutation(&WeightMatrix::<u32>::from_row_vec(0, vec![]))
+    );
+    assert_eq!(
+        Some(vec![pos(0, 0)]),
+        tight_permutation(&WeightMatrix::from_row_vec(1, vec![5]))
+    );
+
+    // the zeros of the row-reduced matrix lie in a single column, so they
+    // can be covered by fewer than n lines and the augmenting loop runs.
+    let c = vec![0, 1, 2, 0, 3, 4, 0, 5, 6];
+    assert_eq!(
+        None,
+        tight_permutation(&WeightMatrix::from_row_vec(3, c.clone()))
+    );
+    let matching = solve_assignment(&mut WeightMatrix::from_row_vec(3, c.clone())).unwrap();
+    let cost: i32 = matching.iter().map(|&p| c[p.row * 3 + p.column]).sum();
+    assert_eq!(5, cost);
+
+    // a tight permutation exists, but the greedy choice of row 0 blocks it.
+    assert_eq!(
+        None,
+        tight_permutation(&WeightMatrix::from_row_vec(2, vec![0, 0, 0, 1]))
+    );
+
+    // invalid cells are skipped even if their placeholder is a zero.
+    let valid = [false, true, true, true];
+    let weights = WeightMatrix::from_row_vec_with_validity(2, vec![0, 0, 0, 1], &valid);
+    assert_eq!(
+        Some(vec![pos(0, 1), pos(1, 0)]),
+        tight_permutation(&weights)
+    );
+    let inf = f64::INFINITY;
+    let weights = WeightMatrix::from_row_vec(2, vec![inf, 0.0, 0.0, inf]);
+    assert_eq!(
+        Some(vec![pos(0, 1), pos(1, 0)]),
+        tight_permutation(&weights)
+    );
+    let weights = WeightMatrix::from_row_vec(2, vec![inf, 0.0, inf, 0.0]);
+    assert_eq!(None, tight_permutation(&weights));
+}
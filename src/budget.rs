@@ -0,0 +1,144 @@
+//! Assignment under a budget on a second weight: minimizes the total cost,
+//! subject to the total weight of the assigned cells being at most a budget.
+//!
+//! The budget constraint makes the problem NP-hard.
+//! `solve_assignment_with_budget` relaxes it in a Lagrangian fashion,
+//! minimizing `cost + multiplier * weight` by repeated solves, and searches
+//! for the multiplier giving the best lower bound, like a dichotomic search
+//! between a cheap assignment exceeding the budget and one within it. The
+//! result is the cheapest assignment within the budget found on the way,
+//! together with the lower bound, so the duality gap tells how far from
+//! optimal it may be.
+use crate::pareto::{point, to_f64, ParetoPoint};
+use crate::{solve_assignment, Assignment, Error, Position, WeightMatrix, Weights};
+
+/// Upper limit on the number of multipliers tried. The search converges after
+/// at most as many steps as there are supported Pareto-optimal assignments,
+/// which is much less in practice.
+const MAX_ITERATIONS: usize = 100;
+
+/// An assignment within the budget, with a lower bound on the cost of any
+/// assignment within the budget.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetSolution {
+    pub assignment: Assignment,
+    pub cost: f64,
+    pub weight: f64,
+    /// The best lower bound of the Lagrangian relaxation.
+    pub lower_bound: f64,
+    /// The multiplier of the weight giving `lower_bound`.
+    pub multiplier: f64,
+}
+
+impl BudgetSolution {
+    /// The difference between the cost and the lower bound. Zero if the
+    /// assignment is proven optimal.
+    pub fn duality_gap(&self) -> f64 {
+        self.cost - self.lower_bound
+    }
+}
+
+/// Minimizes the total of `costs`, subject to the total of `weights` being
+/// at most `budget`. A cell is valid if it is valid in both. Returns
+/// `Error::MatrixNotSolvable` if no assignment is within the budget.
+///
+/// # Panics
+///
+/// If the sizes differ, or a valid cell can't be converted to `f64`.
+pub fn solve_assignment_with_budget<W1, W2>(
+    costs: &W1,
+    weights: &W2,
+    budget: f64,
+) -> Result<BudgetSolution, Error>
+where
+    W1: Weights,
+    W2: Weights,
+{
+    assert!(costs.n() == weights.n());
+    let costs = to_f64(costs, weights);
+    let weights = to_f64(weights, &costs);
+    let solve = |multiplier: f64| -> Result<ParetoPoint, Error> {
+        let mut combined = WeightMatrix::from_fn(costs.n(), |(row, column)| {
+            let pos = Position { row, column };
+            costs.element_at(pos) + multiplier * weights.element_at(pos)
+        });
+        Ok(point(&costs, &weights, solve_assignment(&mut combined)?))
+    };
+    let solution = |p: ParetoPoint, lower_bound: f64, multiplier: f64| BudgetSolution {
+        assignment: p.assignment,
+        cost: p.first,
+        weight: p.second,
+        lower_bound,
+        multiplier,
+    };
+
+    let cheapest = solve(0.0)?;
+    if cheapest.second <= budget {
+        let cost = cheapest.first;
+        return Ok(solution(cheapest, cost, 0.0));
+    }
+    let lightest = point(&costs, &weights, solve_assignment(&mut weights.clone())?);
+    if lightest.second > budget {
+        return Err(Error::MatrixNotSolvable);
+    }
+
+    // `over` exceeds the budget, `within` doesn't. The best multiplier lies
+    // where their Lagrangian values are equal.
+    let (mut over, mut within) = (cheapest, lightest);
+    let mut best = within.clone();
+    let (mut lower_bound, mut best_multiplier) = (over.first, 0.0);
+    for _ in 0..MAX_ITERATIONS {
+        if over.second <= within.second {
+            break;
+        }
+        let multiplier = (within.first - over.first) / (over.second - within.second);
+        let candidate = solve(multiplier)?;
+        let line = over.first + multiplier * (over.second - budget);
+        let value = candidate.first + multiplier * (candidate.second - budget);
+        if value > lower_bound {
+            lower_bound = value;
+            best_multiplier = multiplier;
+        }
+        if value >= line - 1e-9 * line.abs().max(1.0) {
+            break;
+        }
+        if candidate.second <= budget {
+            if candidate.first < best.first {
+                best = candidate.clone();
+            }
+            within = candidate;
+        } else {
+            over = candidate;
+        }
+    }
+    Ok(solution(best, lower_bound, best_multiplier))
+}
+
+#[test]
+fn test_solve_assignment_with_budget() {
+    // the assignments have (cost, weight) (3, 24), (19, 27), (11, 13),
+    // (23, 12), (23, 12) and (19, 8).
+    let costs = WeightMatrix::from_row_vec(3, vec![1, 5, 9, 5, 1, 9, 9, 9, 1]);
+    let weights = WeightMatrix::from_row_vec(3, vec![9, 2, 1, 2, 6, 9, 1, 9, 9]);
+
+    let solution = solve_assignment_with_budget(&costs, &weights, 30.0).unwrap();
+    assert_eq!(
+        (3.0, 24.0, 0.0),
+        (solution.cost, solution.weight, solution.duality_gap())
+    );
+
+    let solution = solve_assignment_with_budget(&costs, &weights, 15.0).unwrap();
+    assert_eq!((11.0, 13.0), (solution.cost, solution.weight));
+    // the best multiplier is 8/11, with the lower bound 3 + 8/11 * (24 - 15).
+    assert!((solution.multiplier - 8.0 / 11.0).abs() < 1e-9);
+    assert!((solution.lower_bound - (3.0 + 72.0 / 11.0)).abs() < 1e-9);
+
+    let solution = solve_assignment_with_budget(&costs, &weights, 10.0).unwrap();
+    assert_eq!((19.0, 8.0), (solution.cost, solution.weight));
+    assert!(solution.duality_gap() > 0.0);
+
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_assignment_with_budget(&costs, &weights, 7.0)
+    );
+}
@@ -0,0 +1,275 @@
+//! A single entry point for the options of the solvers.
+//!
+//! `Munkres::builder()` collects the options that otherwise each come with
+//! their own `solve_*` function, and `build` turns them into a
+//! `ConfiguredSolver`, which can be kept around and used like any other
+//! `AssignmentSolver`:
+//!
+//! ```
+//! use munkres::{Backend, Munkres, WeightMatrix};
+//!
+//! let solver = Munkres::builder()
+//!     .maximize()
+//!     .backend(Backend::Auction)
+//!     .epsilon(1e-9)
+//!     .build();
+//! let mut weights = WeightMatrix::from_row_vec(2, vec![1.0, 2.0, 3.0, 5.0]);
+//! let assignment = solver.solve(&mut weights).unwrap();
+//! assert_eq!(Some(1), assignment.column_of(1));
+//! ```
+use crate::auction::AuctionOptions;
+#[cfg(feature = "std")]
+use crate::deadline::{Fallback, Strategy};
+use crate::restricted::Restricted;
+use crate::{
+    solve_assignment_auction, solve_assignment_cost_scaling, solve_assignment_lapjv,
+    solve_assignment_with_scan_order, Assignment, AssignmentSolver, Backend, Error, Munkres,
+    Position, ScanOrder, WeightMatrix, Weights,
+};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SolverBuilder {
+    solver: ConfiguredSolver,
+}
+
+impl SolverBuilder {
+    pub fn new() -> SolverBuilder {
+        SolverBuilder::default()
+    }
+
+    /// Maximizes the total instead of minimizing it.
+    pub fn maximize(mut self) -> SolverBuilder {
+        self.solver.maximize = true;
+        self
+    }
+
+    /// The backend to solve with, `Backend::Auto` by default.
+    pub fn backend(mut self, backend: Backend) -> SolverBuilder {
+        self.solver.backend = backend;
+        self
+    }
+
+    /// The final epsilon of the auction backend, see `AuctionOptions`.
+    pub fn epsilon(mut self, epsilon: f64) -> SolverBuilder {
+        self.solver.auction.final_epsilon = Some(epsilon);
+        self
+    }
+
+    /// The order in which the Munkres backend searches for zeros, which
+    /// decides between several optimal matchings.
+    pub fn scan_order(mut self, order: ScanOrder) -> SolverBuilder {
+        self.solver.scan_order = order;
+        self
+    }
+
+    /// Falls back to an approximation if the exact solve takes longer than
+    /// `time_budget`, see `deadline::Strategy`. Ignores the backend.
+    #[cfg(feature = "std")]
+    pub fn time_budget(mut self, time_budget: Duration) -> SolverBuilder {
+        self.solver.time_budget = Some(time_budget);
+        self
+    }
+
+    /// The approximation used once the time budget is spent.
+    #[cfg(feature = "std")]
+    pub fn fallback(mut self, fallback: Fallback) -> SolverBuilder {
+        self.solver.fallback = fallback;
+        self
+    }
+
+    /// Never assigns the cell at `pos`.
+    pub fn forbid(mut self, pos: Position) -> SolverBuilder {
+        self.solver.forbidden.push(pos);
+        self
+    }
+
+    /// Always assigns the cell at `pos`.
+    pub fn pin(mut self, pos: Position) -> SolverBuilder {
+        self.solver.pinned.push(pos);
+        self
+    }
+
+    pub fn build(self) -> ConfiguredSolver {
+        self.solver
+    }
+}
+
+/// A solver configured by `SolverBuilder`. Solving doesn't modify
+/// `weights`, unless all options but the backend and the scan order are
+/// left at their defaults.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ConfiguredSolver {
+    maximize: bool,
+    backend: Backend,
+    auction: AuctionOptions,
+    scan_order: ScanOrder,
+    #[cfg(feature = "std")]
+    time_budget: Option<Duration>,
+    #[cfg(feature = "std")]
+    fallback: Fallback,
+    forbidden: Vec<Position>,
+    pinned: Vec<Position>,
+}
+
+impl ConfiguredSolver {
+    /// Returns `Error::MatrixNotSolvable` if the valid cells that are not
+    /// forbidden admit no complete matching containing the pinned cells.
+    ///
+    /// # Panics
+    ///
+    /// If a forbidden or pinned cell is out of range.
+    pub fn solve<W>(&self, weights: &mut W) -> Result<Assignment, Error>
+    where
+        W: Weights,
+    {
+        #[cfg(feature = "std")]
+        let timed = self.time_budget.is_some();
+        #[cfg(not(feature = "std"))]
+        let timed = false;
+        let copy = !self.forbidden.is_empty() || !self.pinned.is_empty() || timed;
+        let matching = match (self.maximize, copy) {
+            (false, false) => self.dispatch(weights),
+            (false, true) => self.solve_copy(weights),
+            (true, false) => self.dispatch(&mut maximization(weights)),
+            (true, true) => self.solve_copy(&maximization(weights)),
+        }?;
+        Ok(Assignment::from(matching))
+    }
+
+    /// Solves a copy of `weights` with the forbidden and pinned cells.
+    fn solve_copy<W>(&self, weights: &W) -> Result<Vec<Position>, Error>
+    where
+        W: Weights,
+    {
+        let n = weights.n();
+        for pos in self.forbidden.iter().chain(&self.pinned) {
+            assert!(pos.row < n && pos.column < n, "cell out of range");
+        }
+        let mut restricted =
+            Restricted::from_weights_where(weights, |pos| !self.forbidden.contains(&pos));
+        for &pos in &self.pinned {
+            restricted.pin(pos);
+        }
+        #[cfg(feature = "std")]
+        if let Some(time_budget) = self.time_budget {
+            return Strategy::new(time_budget)
+                .fallback(self.fallback)
+                .solve(&restricted)
+                .map(|result| result.matching);
+        }
+        self.dispatch(&mut restricted)
+    }
+
+    fn dispatch<W>(&self, weights: &mut W) -> Result<Vec<Position>, Error>
+    where
+        W: Weights,
+    {
+        match self.backend.resolve(weights) {
+            Backend::CostScaling => solve_assignment_cost_scaling(weights),
+            Backend::Auction => solve_assignment_auction(weights, &self.auction),
+            Backend::Lapjv => solve_assignment_lapjv(weights),
+            _ => solve_assignment_with_scan_order(weights, self.scan_order),
+        }
+    }
+}
+
+impl<W: Weights> AssignmentSolver<W> for ConfiguredSolver {
+    fn solve(&mut self, weights: &mut W) -> Result<Assignment, Error> {
+        ConfiguredSolver::solve(self, weights)
+    }
+}
+
+impl Munkres {
+    pub fn builder() -> SolverBuilder {
+        SolverBuilder::new()
+    }
+}
+
+/// A copy of `weights` in which minimizing maximizes the original total:
+/// each valid value `x` becomes `max - x`, with `max` the largest valid
+/// value. Unlike negating, this keeps the values non-negative, so unsigned
+/// types work too.
+pub(crate) fn maximization<W>(weights: &W) -> WeightMatrix<W::T>
+where
+    W: Weights,
+{
+    let n = weights.n();
+    let positions = || {
+        (0..n * n).map(|index| Position {
+            row: index / n,
+            column: index % n,
+        })
+    };
+    let valid: Vec<bool> = positions().map(|pos| weights.is_valid_at(pos)).collect();
+    let max = positions()
+        .filter(|&pos| weights.is_valid_at(pos))
+        .map(|pos| weights.element_at(pos))
+        .reduce(|max, value| if value > max { value } else { max });
+    let data = positions()
+        .map(|pos| match max {
+            Some(max) if weights.is_valid_at(pos) => max - weights.element_at(pos),
+            _ => weights.element_at(pos),
+        })
+        .collect();
+    WeightMatrix::from_row_vec_with_validity(n, data, &valid)
+}
+
+#[test]
+fn test_solver_builder() {
+    use crate::{generators, solve_assignment};
+
+    let pos = |row, column| Position { row, column };
+    let weights = generators::uniform(12, 0..1000, 5);
+    let cost = |a: &Assignment| a.iter().map(|&pos| weights.element_at(pos)).sum::<u32>();
+    let optimum = solve_assignment(&mut weights.clone()).map(Assignment::from);
+    assert_eq!(
+        optimum,
+        Munkres::builder().build().solve(&mut weights.clone())
+    );
+    #[cfg(feature = "std")]
+    assert_eq!(
+        optimum,
+        Munkres::builder()
+            .time_budget(Duration::from_secs(3600))
+            .build()
+            .solve(&mut weights.clone())
+    );
+
+    // unsigned weights, maximized over the largest valid value.
+    let max = Munkres::builder()
+        .maximize()
+        .build()
+        .solve(&mut weights.clone())
+        .unwrap();
+    let negated = WeightMatrix::from_fn(12, |(row, column)| {
+        -(weights.element_at(pos(row, column)) as i64)
+    });
+    let expected = solve_assignment(&mut negated.clone()).map(Assignment::from);
+    assert_eq!(cost(&expected.unwrap()), cost(&max));
+
+    let solver = Munkres::builder()
+        .backend(Backend::Auction)
+        .epsilon(1e-3)
+        .pin(pos(0, 0))
+        .forbid(pos(1, 1))
+        .build();
+    let assignment = solver.solve(&mut weights.clone()).unwrap();
+    assert_eq!(Some(0), assignment.column_of(0));
+    assert_ne!(Some(1), assignment.column_of(1));
+    assert_eq!(12, assignment.len());
+
+    let mut solver: Box<dyn AssignmentSolver<WeightMatrix<u32>>> = Box::new(
+        Munkres::builder()
+            .pin(pos(0, 0))
+            .forbid(pos(1, 0))
+            .pin(pos(1, 0))
+            .build(),
+    );
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solver.solve(&mut weights.clone())
+    );
+}
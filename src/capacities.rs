@@ -0,0 +1,375 @@
+//! Assignment with degree bounds, where rows and columns may be assigned
+//! more than once.
+//!
+//! `solve_assignment_with_capacities` lets row `i` take up to
+//! `capacities[i]` columns, and assigns every column to exactly one row.
+//! This covers the common case of fewer workers (rows) than jobs (columns).
+//! It is solved by expanding each row into one row per unit of capacity,
+//! padded with dummy columns to a square matrix.
+//!
+//! `solve_b_matching` generalizes this to bounds on the degrees of both rows
+//! and columns, e.g. ads with budgets shown in slots, or students taking a
+//! number of courses with limited seats. It shares the min-cost flow solver
+//! of `transport::transport_exact`.
+//!
+//! `solve_assignment_with_group_bounds` assigns every column to a different
+//! row, with rows in groups that must receive a bounded number of columns,
+//! e.g. each team between 2 and 5 of the slots. It is solved by the same
+//! flow formulation, with a node per group between the source and the rows.
+use crate::flow::FlowNetwork;
+use crate::{solve_assignment, Error, Position, WeightMatrix, WeightNum};
+use alloc::{vec, vec::Vec};
+
+/// Assigns each column of the `capacities.len() x columns` matrix `costs`,
+/// given in row-major order, to a row, such that row `i` gets at most
+/// `capacities[i]` columns, minimizing the total cost. Invalid cells are
+/// forbidden.
+///
+/// Returns one position per column, sorted by row and column.
+///
+/// # Panics
+///
+/// If `costs.len()` is not a multiple of the number of rows.
+pub fn solve_assignment_with_capacities<T>(
+    costs: &[T],
+    capacities: &[usize],
+) -> Result<Vec<Position>, Error>
+where
+    T: WeightNum,
+{
+    let rows = capacities.len();
+    if rows == 0 {
+        assert!(costs.is_empty());
+        return Ok(Vec::new());
+    }
+    assert!(costs.len() % rows == 0);
+    let columns = costs.len() / rows;
+    if columns == 0 {
+        return Ok(Vec::new());
+    }
+
+    // a row never takes more than all columns.
+    let slots: Vec<usize> = capacities
+        .iter()
+        .enumerate()
+        .flat_map(|(row, &capacity)| core::iter::repeat_n(row, capacity.min(columns)))
+        .collect();
+    if slots.len() < columns {
+        return Err(Error::MatrixNotSolvable);
+    }
+    // all dummy columns are assigned, so any fixed valid cost will do.
+    let dummy = match costs.iter().find(|c| c.is_valid()) {
+        Some(&c) => c,
+        None => return Err(Error::MatrixNotSolvable),
+    };
+
+    let n = slots.len();
+    let mut expanded = WeightMatrix::from_fn(n, |(slot, column)| {
+        if column < columns {
+            costs[slots[slot] * columns + column]
+        } else {
+            dummy
+        }
+    });
+    let mut matching: Vec<Position> = solve_assignment(&mut expanded)?
+        .into_iter()
+        .filter(|pos| pos.column < columns)
+        .map(|pos| Position {
+            row: slots[pos.row],
+            column: pos.column,
+        })
+        .collect();
+    matching.sort_by_key(|pos| (pos.row, pos.column));
+    Ok(matching)
+}
+
+/// Bounds on the number of cells assigned in a row or column.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Degree {
+    pub min: usize,
+    pub max: usize,
+}
+
+impl Degree {
+    pub fn exactly(degree: usize) -> Degree {
+        Degree {
+            min: degree,
+            max: degree,
+        }
+    }
+
+    pub fn at_most(degree: usize) -> Degree {
+        Degree {
+            min: 0,
+            max: degree,
+        }
+    }
+}
+
+/// Finds a set of cells of the `rows.len() x columns.len()` matrix `costs`,
+/// given in row-major order, with minimum total cost, such that the number
+/// of cells in each row and column lies within its `Degree` bounds. Each
+/// cell is used at most once, invalid cells never.
+///
+/// Returns the cells sorted by row and column, or
+/// `Error::MatrixNotSolvable` if the bounds can't be met.
+///
+/// # Panics
+///
+/// If `costs.len()` doesn't match, a lower bound exceeds its upper bound, or
+/// a valid cell can't be converted to `f64`.
+pub fn solve_b_matching<T>(
+    costs: &[T],
+    rows: &[Degree],
+    columns: &[Degree],
+) -> Result<Vec<Position>, Error>
+where
+    T: WeightNum,
+{
+    assert!(costs.len() == rows.len() * columns.len());
+    assert!(rows.iter().chain(columns).all(|d| d.min <= d.max));
+    let costs: Vec<Option<f64>> = costs
+        .iter()
+        .map(|c| {
+            if c.is_valid() {
+                Some(
+                    c.to_f64()
+                        .expect("b-matching requires weights convertible to f64"),
+                )
+            } else {
+                None
+            }
+        })
+        .collect();
+    // the lower bounds are met first, by rewarding each unit of flow through
+    // them with more than the cost of any set of cells.
+    let reward = 1.0 + costs.iter().flatten().map(|c| c.abs()).sum::<f64>();
+
+    // nodes: source, sink, rows, columns.
+    let (source, sink) = (0, 1);
+    let column_node = |column: usize| 2 + rows.len() + column;
+    let mut network = FlowNetwork::new(2 + rows.len() + columns.len(), 1e-9);
+    for (row, degree) in rows.iter().enumerate() {
+        network.add_edge(source, 2 + row, degree.min as f64, -reward);
+        network.add_edge(source, 2 + row, (degree.max - degree.min) as f64, 0.0);
+    }
+    for (column, degree) in columns.iter().enumerate() {
+        network.add_edge(column_node(column), sink, degree.min as f64, -reward);
+        network.add_edge(
+            column_node(column),
+            sink,
+            (degree.max - degree.min) as f64,
+            0.0,
+        );
+    }
+    let mut edges = Vec::new();
+    for row in 0..rows.len() {
+        for column in 0..columns.len() {
+            if let Some(cost) = costs[row * columns.len() + column] {
+                let edge = network.add_edge(2 + row, column_node(column), 1.0, cost);
+                edges.push((Position { row, column }, edge));
+            }
+        }
+    }
+    network.min_cost_flow_any_amount(source, sink);
+
+    let matching: Vec<Position> = edges
+        .into_iter()
+        .filter(|&(_, edge)| network.flow(edge) > 0.5)
+        .map(|(pos, _)| pos)
+        .collect();
+    let mut row_degrees = vec![0; rows.len()];
+    let mut column_degrees = vec![0; columns.len()];
+    for pos in &matching {
+        row_degrees[pos.row] += 1;
+        column_degrees[pos.column] += 1;
+    }
+    let met =
+        |degrees: &[usize], bounds: &[Degree]| degrees.iter().zip(bounds).all(|(&d, b)| d >= b.min);
+    if met(&row_degrees, rows) && met(&column_degrees, columns) {
+        Ok(matching)
+    } else {
+        Err(Error::MatrixNotSolvable)
+    }
+}
+
+/// Assigns each column of the `groups.len() x columns` matrix `costs`, given
+/// in row-major order, to a different row, minimizing the total cost, such
+/// that the number of columns assigned to the rows of group `g` lies within
+/// `bounds[g]`. `groups[row]` is the group of `row`. Rows may stay
+/// unassigned, invalid cells are forbidden.
+///
+/// Returns one position per column, sorted by row, or
+/// `Error::MatrixNotSolvable` if the bounds can't be met.
+///
+/// # Panics
+///
+/// If `costs.len()` doesn't match, a group is out of range, a lower bound
+/// exceeds its upper bound, or a valid cell can't be converted to `f64`.
+pub fn solve_assignment_with_group_bounds<T>(
+    costs: &[T],
+    groups: &[usize],
+    bounds: &[Degree],
+) -> Result<Vec<Position>, Error>
+where
+    T: WeightNum,
+{
+    let rows = groups.len();
+    if rows == 0 {
+        assert!(costs.is_empty());
+        return Ok(Vec::new());
+    }
+    assert!(costs.len() % rows == 0);
+    assert!(groups.iter().all(|&group| group < bounds.len()));
+    assert!(bounds.iter().all(|d| d.min <= d.max));
+    let columns = costs.len() / rows;
+    let costs: Vec<Option<f64>> = costs
+        .iter()
+        .map(|c| {
+            if c.is_valid() {
+                Some(
+                    c.to_f64()
+                        .expect("group bounds require weights convertible to f64"),
+                )
+            } else {
+                None
+            }
+        })
+        .collect();
+    // as in `solve_b_matching`, the lower bounds and the columns, which must
+    // all be assigned, are met first.
+    let reward = 1.0 + costs.iter().flatten().map(|c| c.abs()).sum::<f64>();
+
+    // nodes: source, sink, groups, rows, columns.
+    let (source, sink) = (0, 1);
+    let row_node = |row: usize| 2 + bounds.len() + row;
+    let column_node = |column: usize| 2 + bounds.len() + rows + column;
+    let mut network = FlowNetwork::new(2 + bounds.len() + rows + columns, 1e-9);
+    for (group, degree) in bounds.iter().enumerate() {
+        network.add_edge(source, 2 + group, degree.min as f64, -reward);
+        network.add_edge(source, 2 + group, (degree.max - degree.min) as f64, 0.0);
+    }
+    for (row, &group) in groups.iter().enumerate() {
+        network.add_edge(2 + group, row_node(row), 1.0, 0.0);
+    }
+    for column in 0..columns {
+        network.add_edge(column_node(column), sink, 1.0, -reward);
+    }
+    let mut edges = Vec::new();
+    for row in 0..rows {
+        for column in 0..columns {
+            if let Some(cost) = costs[row * columns + column] {
+                let edge = network.add_edge(row_node(row), column_node(column), 1.0, cost);
+                edges.push((Position { row, column }, edge));
+            }
+        }
+    }
+    network.min_cost_flow_any_amount(source, sink);
+
+    let matching: Vec<Position> = edges
+        .into_iter()
+        .filter(|&(_, edge)| network.flow(edge) > 0.5)
+        .map(|(pos, _)| pos)
+        .collect();
+    let mut group_degrees = vec![0; bounds.len()];
+    for pos in &matching {
+        group_degrees[groups[pos.row]] += 1;
+    }
+    let met = group_degrees.iter().zip(bounds).all(|(&d, b)| d >= b.min);
+    if met && matching.len() == columns {
+        Ok(matching)
+    } else {
+        Err(Error::MatrixNotSolvable)
+    }
+}
+
+#[test]
+fn test_solve_assignment_with_capacities() {
+    let pos = |row, column| Position { row, column };
+    // two workers, three jobs, the first worker can take two jobs.
+    let costs = [1, 2, 3, 2, 4, 1];
+    assert_eq!(
+        Ok(vec![pos(0, 0), pos(0, 1), pos(1, 2)]),
+        solve_assignment_with_capacities(&costs, &[2, 1])
+    );
+    assert_eq!(
+        Ok(vec![pos(0, 1), pos(1, 0), pos(1, 2)]),
+        solve_assignment_with_capacities(&costs, &[1, 5])
+    );
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_assignment_with_capacities(&costs, &[1, 1])
+    );
+
+    let inf = f64::INFINITY;
+    let costs = [1.0, inf, 2.0, 3.0];
+    assert_eq!(
+        Ok(vec![pos(0, 0), pos(1, 1)]),
+        solve_assignment_with_capacities(&costs, &[2, 2])
+    );
+}
+
+#[test]
+fn test_solve_b_matching() {
+    let pos = |row, column| Position { row, column };
+    // the capacity problem as a b-matching.
+    let costs = [1, 2, 3, 2, 4, 1];
+    let columns = [Degree::exactly(1); 3];
+    assert_eq!(
+        solve_assignment_with_capacities(&costs, &[2, 1]),
+        solve_b_matching(&costs, &[Degree::at_most(2), Degree::at_most(1)], &columns)
+    );
+
+    // only the lower bounds force cells to be used.
+    assert_eq!(
+        Ok(vec![]),
+        solve_b_matching(&costs, &[Degree::at_most(3); 2], &[Degree::at_most(1); 3])
+    );
+    let rows = [Degree::exactly(2), Degree { min: 1, max: 2 }];
+    assert_eq!(
+        Ok(vec![pos(0, 0), pos(0, 1), pos(1, 2)]),
+        solve_b_matching(&costs, &rows, &[Degree::at_most(1); 3])
+    );
+    // each cell is used at most once.
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_b_matching(
+            &costs,
+            &[Degree::exactly(4), Degree::at_most(1)],
+            &[Degree::at_most(4); 3]
+        )
+    );
+    let inf = f64::INFINITY;
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_b_matching(&[1.0, inf], &[Degree::exactly(2)], &[Degree::at_most(1); 2])
+    );
+}
+
+#[test]
+fn test_solve_assignment_with_group_bounds() {
+    let pos = |row, column| Position { row, column };
+    // rows 0 and 1 form group 0, rows 2 and 3 group 1, which is more
+    // expensive for both columns.
+    let costs = [1, 2, 2, 1, 5, 6, 6, 6];
+    let groups = [0, 0, 1, 1];
+    assert_eq!(
+        Ok(vec![pos(0, 0), pos(1, 1)]),
+        solve_assignment_with_group_bounds(&costs, &groups, &[Degree::at_most(2); 2])
+    );
+    let bounds = [Degree::at_most(2), Degree { min: 1, max: 2 }];
+    assert_eq!(
+        Ok(vec![pos(1, 1), pos(2, 0)]),
+        solve_assignment_with_group_bounds(&costs, &groups, &bounds)
+    );
+    let bounds = [Degree::at_most(1), Degree::at_most(0)];
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_assignment_with_group_bounds(&costs, &groups, &bounds)
+    );
+    let bounds = [Degree::at_most(2), Degree::exactly(3)];
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_assignment_with_group_bounds(&costs, &groups, &bounds)
+    );
+}
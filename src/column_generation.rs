@@ -0,0 +1,216 @@
+//! Column generation for instances with too many cells to enumerate, e.g.
+//! crew scheduling where each cell is a generated pairing.
+//!
+//! `RestrictedMaster` holds the cells priced in so far. Each `solve` returns
+//! the optimal matching over these cells together with the dual potentials
+//! `u` of the rows and `v` of the columns. A cell not yet included improves
+//! the matching only if its reduced cost `c - u[row] - v[column]` is
+//! negative, so the caller prices candidate cells against the duals, adds
+//! those with a negative reduced cost and solves again. Once no candidate is
+//! negative, the matching is optimal for the full instance.
+//!
+//! Each solve starts from the column potentials of the previous one, so
+//! adding a few cells only takes a few steps. The cells must admit a complete
+//! matching from the start, e.g. by including a costly fallback cell per row.
+use crate::dual::DualWeights;
+use crate::{solve_assignment_warm, Error, Position, Weights};
+use alloc::{vec, vec::Vec};
+
+/// The cells of each row as `(column, cost)`, sorted by column.
+#[derive(Debug, Clone)]
+struct Rows {
+    n: usize,
+    rows: Vec<Vec<(usize, f64)>>,
+}
+
+impl Rows {
+    #[inline]
+    fn find(&self, pos: Position) -> Result<usize, usize> {
+        self.rows[pos.row].binary_search_by_key(&pos.column, |&(column, _)| column)
+    }
+}
+
+impl Weights for Rows {
+    type T = f64;
+
+    #[inline(always)]
+    fn n(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    fn element_at(&self, pos: Position) -> f64 {
+        match self.find(pos) {
+            Ok(index) => self.rows[pos.row][index].1,
+            Err(_) => f64::INFINITY,
+        }
+    }
+
+    #[inline]
+    fn is_valid_at(&self, pos: Position) -> bool {
+        self.find(pos).is_ok()
+    }
+
+    fn sub_min_of_each_row(&mut self) {
+        for row in &mut self.rows {
+            let min = row
+                .iter()
+                .map(|&(_, cost)| cost)
+                .fold(f64::INFINITY, f64::min);
+            row.iter_mut().for_each(|(_, cost)| *cost -= min);
+        }
+    }
+
+    fn add_row(&mut self, row: usize, val: f64) {
+        self.rows[row].iter_mut().for_each(|(_, cost)| *cost += val);
+    }
+
+    fn sub_column(&mut self, col: usize, val: f64) {
+        for row in 0..self.n {
+            if let Ok(index) = self.find(Position { row, column: col }) {
+                self.rows[row][index].1 -= val;
+            }
+        }
+    }
+
+    fn is_solvable(&self) -> bool {
+        self.rows.iter().all(|row| !row.is_empty())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RestrictedMaster {
+    cells: Rows,
+    row_duals: Vec<f64>,
+    column_duals: Vec<f64>,
+}
+
+impl RestrictedMaster {
+    /// An `n x n` instance without any cells.
+    pub fn new(n: usize) -> RestrictedMaster {
+        RestrictedMaster {
+            cells: Rows {
+                n,
+                rows: vec![Vec::new(); n],
+            },
+            row_duals: vec![0.0; n],
+            column_duals: vec![0.0; n],
+        }
+    }
+
+    #[inline]
+    pub fn n(&self) -> usize {
+        self.cells.n
+    }
+
+    /// The number of cells added.
+    pub fn len(&self) -> usize {
+        self.cells.rows.iter().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds a cell. If the cell is already included, its smaller cost is
+    /// kept. Non-finite costs are skipped. Returns whether the cell is new.
+    ///
+    /// # Panics
+    ///
+    /// If `pos` is out of range.
+    pub fn add_cell(&mut self, pos: Position, cost: f64) -> bool {
+        assert!(pos.row < self.n() && pos.column < self.n());
+        if !cost.is_finite() {
+            return false;
+        }
+        match self.cells.find(pos) {
+            Ok(index) => {
+                let cell = &mut self.cells.rows[pos.row][index].1;
+                *cell = cell.min(cost);
+                false
+            }
+            Err(index) => {
+                self.cells.rows[pos.row].insert(index, (pos.column, cost));
+                true
+            }
+        }
+    }
+
+    /// Finds the optimal matching over the cells added, sorted by row, and
+    /// updates the duals. Returns `Error::MatrixNotSolvable` if the cells
+    /// admit no complete matching.
+    pub fn solve(&mut self) -> Result<Vec<Position>, Error> {
+        let mut duals = DualWeights::new(&self.cells);
+        let matching = solve_assignment_warm(&mut duals, &self.column_duals)?;
+        self.row_duals.copy_from_slice(duals.row_duals());
+        self.column_duals.copy_from_slice(duals.column_duals());
+        Ok(matching)
+    }
+
+    /// The potential `u` of each row after the last solve.
+    pub fn row_duals(&self) -> &[f64] {
+        &self.row_duals
+    }
+
+    /// The potential `v` of each column after the last solve.
+    pub fn column_duals(&self) -> &[f64] {
+        &self.column_duals
+    }
+
+    /// The reduced cost of a cell of cost `cost` under the current duals.
+    /// Cells with a negative reduced cost can improve the matching.
+    #[inline]
+    pub fn reduced_cost(&self, pos: Position, cost: f64) -> f64 {
+        cost - self.row_duals[pos.row] - self.column_duals[pos.column]
+    }
+}
+
+#[test]
+fn test_restricted_master() {
+    use crate::{generators, solve_assignment};
+
+    let n = 12;
+    for seed in 0..5 {
+        let weights = generators::uniform(n, 0..1000, seed);
+        let cost = |pos: Position| weights.element_at(pos) as f64;
+        let expected: f64 = solve_assignment(&mut weights.clone())
+            .unwrap()
+            .into_iter()
+            .map(cost)
+            .sum();
+
+        // start from the diagonal, and price in all improving cells.
+        let mut master = RestrictedMaster::new(n);
+        for row in 0..n {
+            master.add_cell(
+                Position { row, column: row },
+                cost(Position { row, column: row }),
+            );
+        }
+        let mut rounds = 0;
+        let matching = loop {
+            let matching = master.solve().unwrap();
+            rounds += 1;
+            let mut added = false;
+            for row in 0..n {
+                for column in 0..n {
+                    let pos = Position { row, column };
+                    if master.reduced_cost(pos, cost(pos)) < -1e-9 {
+                        added |= master.add_cell(pos, cost(pos));
+                    }
+                }
+            }
+            if !added {
+                break matching;
+            }
+        };
+        assert!(rounds > 1);
+        assert!(master.len() < n * n);
+        assert_eq!(expected, matching.into_iter().map(cost).sum::<f64>());
+    }
+
+    let mut master = RestrictedMaster::new(2);
+    master.add_cell(Position { row: 0, column: 0 }, 1.0);
+    master.add_cell(Position { row: 1, column: 0 }, 1.0);
+    assert_eq!(Err(Error::MatrixNotSolvable), master.solve());
+}
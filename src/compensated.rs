@@ -0,0 +1,166 @@
+//! Compensated (Kahan) summation for `f32` costs.
+//!
+//! Every cell of a weight matrix accumulates one addition or subtraction per
+//! step 6 that touches it. With plain `f32` arithmetic the error of a cell
+//! after `k` updates `x_1, ..., x_k` is bounded by about `k * eps * sum |x_i|`
+//! (with `eps = 2^-24`), which on long solves is enough to turn near-ties
+//! into wrong comparisons. Kahan summation keeps the rounding error of each
+//! update in a compensation term and bounds the error by about
+//! `2 * eps * sum |x_i|`, independent of `k`.
+//!
+//! `CompensatedMatrix` applies this to the reductions, at the cost of a
+//! second `f32` per cell, and `total_cost` to the cost of a matching.
+use crate::{Position, WeightNum, Weights};
+use alloc::vec::Vec;
+
+/// A running compensated sum of `f32` values.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct KahanSum {
+    sum: f32,
+    compensation: f32,
+}
+
+impl KahanSum {
+    pub fn new() -> KahanSum {
+        KahanSum::default()
+    }
+
+    #[inline]
+    pub fn add(&mut self, value: f32) {
+        let y = value - self.compensation;
+        let t = self.sum + y;
+        self.compensation = (t - self.sum) - y;
+        self.sum = t;
+    }
+
+    pub fn sum(&self) -> f32 {
+        self.sum
+    }
+}
+
+/// The total cost of `matching`, summed with `KahanSum`. `weights` must be
+/// a copy of the original weights, not the ones reduced by the solve.
+pub fn total_cost<W>(weights: &W, matching: &[Position]) -> f32
+where
+    W: Weights<T = f32>,
+{
+    let mut sum = KahanSum::new();
+    for &pos in matching {
+        sum.add(weights.element_at(pos));
+    }
+    sum.sum()
+}
+
+/// An `f32` weight matrix whose cells are updated with Kahan summation.
+/// Non-finite values are invalid.
+#[derive(Debug, Clone)]
+pub struct CompensatedMatrix {
+    n: usize,
+    /// The cells in row-major order.
+    cells: Vec<KahanSum>,
+}
+
+impl CompensatedMatrix {
+    pub fn from_row_vec(n: usize, data: Vec<f32>) -> CompensatedMatrix {
+        assert!(data.len() == n * n);
+        let cells = data
+            .into_iter()
+            .map(|sum| KahanSum {
+                sum,
+                compensation: 0.0,
+            })
+            .collect();
+        CompensatedMatrix { n, cells }
+    }
+
+    pub fn from_fn<F: FnMut((usize, usize)) -> f32>(n: usize, mut f: F) -> CompensatedMatrix {
+        let data = (0..n * n).map(|i| f((i / n, i % n))).collect();
+        CompensatedMatrix::from_row_vec(n, data)
+    }
+
+    /// Adds `val` to the valid cells of `row`.
+    fn add_to_row(&mut self, row: usize, val: f32) {
+        for cell in &mut self.cells[row * self.n..(row + 1) * self.n] {
+            if cell.sum.is_valid() {
+                cell.add(val);
+            }
+        }
+    }
+}
+
+impl Weights for CompensatedMatrix {
+    type T = f32;
+
+    #[inline(always)]
+    fn n(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    fn element_at(&self, pos: Position) -> f32 {
+        self.cells[pos.row * self.n + pos.column].sum
+    }
+
+    fn sub_min_of_each_row(&mut self) {
+        for row in 0..self.n {
+            let min = self.cells[row * self.n..(row + 1) * self.n]
+                .iter()
+                .map(|cell| cell.sum)
+                .filter(|value| value.is_valid())
+                .fold(f32::INFINITY, f32::min);
+            if min.is_valid() {
+                self.add_to_row(row, -min);
+            }
+        }
+    }
+
+    fn add_row(&mut self, row: usize, val: f32) {
+        self.add_to_row(row, val);
+    }
+
+    fn sub_column(&mut self, col: usize, val: f32) {
+        for cell in self.cells[col..].iter_mut().step_by(self.n) {
+            if cell.sum.is_valid() {
+                cell.add(-val);
+            }
+        }
+    }
+
+    fn is_solvable(&self) -> bool {
+        self.cells
+            .chunks(self.n.max(1))
+            .all(|row| row.iter().any(|cell| cell.sum.is_valid()))
+    }
+}
+
+#[test]
+fn test_compensated() {
+    use crate::{generators, solve_assignment, WeightMatrix};
+
+    // a million small values vanish next to 1.0 in a plain f32 sum.
+    let mut sum = KahanSum::new();
+    let mut plain = 1.0f32;
+    sum.add(1.0);
+    for _ in 0..1_000_000 {
+        sum.add(1e-8);
+        plain += 1e-8;
+    }
+    assert_eq!(1.0, plain);
+    assert!((sum.sum() - 1.01).abs() < 1e-6);
+
+    for seed in 0..10 {
+        let mut rng = generators::Rng::new(seed);
+        let data: Vec<f64> = (0..100)
+            .map(|_| (rng.next_f64() * 1000.0) as f32 as f64)
+            .collect();
+        let original = WeightMatrix::from_row_vec(10, data.iter().map(|&x| x as f32).collect());
+        let exact = WeightMatrix::from_row_vec(10, data.clone());
+        let mut weights =
+            CompensatedMatrix::from_row_vec(10, data.iter().map(|&x| x as f32).collect());
+        let expected = solve_assignment(&mut exact.clone()).unwrap();
+        let matching = solve_assignment(&mut weights).unwrap();
+        let cost = |m: &[Position]| m.iter().map(|&pos| exact.element_at(pos)).sum::<f64>();
+        assert!((cost(&expected) - cost(&matching)).abs() < 1e-3);
+        assert!((total_cost(&original, &matching) as f64 - cost(&matching)).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,191 @@
+//! Solving at compile time.
+//!
+//! `solve_assignment_const` is a `const fn`, so small instances known at
+//! compile time, e.g. the dispatch tables of embedded firmware, can be
+//! solved into constants:
+//!
+//! ```
+//! use munkres::compile_time::solve_assignment_const;
+//!
+//! const TABLE: [usize; 3] = solve_assignment_const([[4, 1, 3], [2, 0, 5], [3, 2, 2]]);
+//! assert_eq!([1, 0, 2], TABLE);
+//! ```
+//!
+//! It runs the same steps as `solve_assignment_fixed`, with `while` loops
+//! over fixed-size arrays in place of iterators and the `Weights` trait,
+//! neither of which can be used in constant evaluation. Costs are `i64`,
+//! and all cells are valid; a large cost keeps a cell out of the optimum.
+
+const NONE: usize = usize::MAX;
+
+/// The optimal assignment of `costs`, as the column of each row.
+///
+/// # Panics
+///
+/// If the reductions overflow, which needs costs close to the limits of
+/// `i64`. In constant evaluation, this is a compile error.
+pub const fn solve_assignment_const<const N: usize>(costs: [[i64; N]; N]) -> [usize; N] {
+    let mut c = costs;
+    let mut star_in_row = [NONE; N];
+    let mut star_in_column = [NONE; N];
+    let mut prime_in_row = [NONE; N];
+    let mut row_covered = [false; N];
+    let mut column_covered = [false; N];
+
+    // step 1.
+    let mut row = 0;
+    while row < N {
+        let mut min = c[row][0];
+        let mut column = 1;
+        while column < N {
+            if c[row][column] < min {
+                min = c[row][column];
+            }
+            column += 1;
+        }
+        column = 0;
+        while column < N {
+            c[row][column] -= min;
+            column += 1;
+        }
+        row += 1;
+    }
+
+    // step 2.
+    row = 0;
+    while row < N {
+        let mut column = 0;
+        while column < N {
+            if star_in_column[column] == NONE && c[row][column] == 0 {
+                star_in_row[row] = column;
+                star_in_column[column] = row;
+                break;
+            }
+            column += 1;
+        }
+        row += 1;
+    }
+
+    loop {
+        // step 3.
+        let mut stars = 0;
+        let mut column = 0;
+        while column < N {
+            column_covered[column] = star_in_column[column] != NONE;
+            if column_covered[column] {
+                stars += 1;
+            }
+            column += 1;
+        }
+        if stars == N {
+            break;
+        }
+
+        // step 4 and 6, until an uncovered zero without a star in its row
+        // is primed.
+        let (mut row, mut column) = loop {
+            let (mut zero_row, mut zero_column) = (NONE, NONE);
+            let mut column = 0;
+            while column < N && zero_row == NONE {
+                let mut row = 0;
+                while !column_covered[column] && row < N {
+                    if !row_covered[row] && c[row][column] == 0 {
+                        zero_row = row;
+                        zero_column = column;
+                        break;
+                    }
+                    row += 1;
+                }
+                column += 1;
+            }
+
+            if zero_row != NONE {
+                prime_in_row[zero_row] = zero_column;
+                if star_in_row[zero_row] == NONE {
+                    break (zero_row, zero_column);
+                }
+                row_covered[zero_row] = true;
+                column_covered[star_in_row[zero_row]] = false;
+                continue;
+            }
+
+            // with fewer than N stars, some row and column are uncovered.
+            let mut min = i64::MAX;
+            let mut row = 0;
+            while row < N {
+                let mut column = 0;
+                while !row_covered[row] && column < N {
+                    if !column_covered[column] && c[row][column] < min {
+                        min = c[row][column];
+                    }
+                    column += 1;
+                }
+                row += 1;
+            }
+            row = 0;
+            while row < N {
+                let mut column = 0;
+                while column < N {
+                    if row_covered[row] {
+                        c[row][column] += min;
+                    }
+                    if !column_covered[column] {
+                        c[row][column] -= min;
+                    }
+                    column += 1;
+                }
+                row += 1;
+            }
+        };
+
+        // step 5: star the primes of the path and unstar its stars.
+        loop {
+            let star = star_in_column[column];
+            star_in_row[row] = column;
+            star_in_column[column] = row;
+            if star == NONE {
+                break;
+            }
+            row = star;
+            column = prime_in_row[row];
+        }
+        row = 0;
+        while row < N {
+            prime_in_row[row] = NONE;
+            row_covered[row] = false;
+            row += 1;
+        }
+    }
+
+    star_in_row
+}
+
+#[test]
+fn test_solve_assignment_const() {
+    use crate::{generators, solve_assignment, Position, WeightMatrix, Weights};
+
+    const TABLE: [usize; 3] =
+        solve_assignment_const([[250, 400, 350], [400, 600, 350], [200, 400, 250]]);
+    assert_eq!([1, 2, 0], TABLE);
+    const EMPTY: [usize; 0] = solve_assignment_const([]);
+    assert_eq!([0usize; 0], EMPTY);
+
+    for seed in 0..20 {
+        let weights = generators::uniform(6, 0..50, seed);
+        let mut costs = [[0i64; 6]; 6];
+        for (row, values) in costs.iter_mut().zip(weights.as_slice().chunks(6)) {
+            for (cost, &value) in row.iter_mut().zip(values) {
+                *cost = i64::from(value);
+            }
+        }
+        let cost = |m: &[Position]| m.iter().map(|&pos| weights.element_at(pos)).sum::<u32>();
+        let expected = cost(&solve_assignment(&mut WeightMatrix::clone(&weights)).unwrap());
+        let columns = solve_assignment_const(costs);
+        let matching: Vec<Position> = columns
+            .iter()
+            .enumerate()
+            .map(|(row, &column)| Position { row, column })
+            .collect();
+        assert_eq!(expected, cost(&matching));
+    }
+}
@@ -0,0 +1,114 @@
+//! Assignment with pairwise constraints between cells, e.g. precedences in
+//! manufacturing where a machine may only take a job if a preparatory job is
+//! done in the same schedule.
+//!
+//! `solve_assignment_constrained` solves the unconstrained problem, and as
+//! long as the matching violates a constraint, forbids a cell involved in
+//! the violation and solves again. Each round forbids a cell of the previous
+//! matching, so it terminates after at most `n * n` solves. The result
+//! satisfies all constraints, but may not be optimal among the matchings
+//! that do, as forbidding a cell can also rule out such matchings.
+use crate::restricted::Restricted;
+use crate::{solve_assignment, Error, Position, Weights};
+use alloc::{vec, vec::Vec};
+
+/// A constraint between two cells.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Constraint {
+    /// `cell` may only be assigned if `required` is assigned as well.
+    Requires { cell: Position, required: Position },
+    /// `cell` and `other` may not both be assigned.
+    Excludes { cell: Position, other: Position },
+}
+
+/// Solves the assignment problem subject to `constraints`. Returns
+/// `Error::MatrixNotSolvable` if forbidding the cells violating them leaves
+/// no complete matching. `weights` is not modified.
+pub fn solve_assignment_constrained<W>(
+    weights: &W,
+    constraints: &[Constraint],
+) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+{
+    let n = weights.n();
+    let mut restricted = Restricted::from_weights(weights);
+    let mut assigned = vec![None; n];
+    loop {
+        let matching = solve_assignment(&mut restricted.clone())?;
+        for pos in &matching {
+            assigned[pos.row] = Some(pos.column);
+        }
+        let is_assigned = |pos: Position| assigned[pos.row] == Some(pos.column);
+
+        let mut violated = false;
+        for constraint in constraints {
+            let forbidden = match *constraint {
+                Constraint::Requires { cell, required } => {
+                    if is_assigned(cell) && !is_assigned(required) {
+                        Some(cell)
+                    } else {
+                        None
+                    }
+                }
+                Constraint::Excludes { cell, other } => {
+                    if is_assigned(cell) && is_assigned(other) {
+                        // keep the cheaper of the two.
+                        if weights.element_at(other) > weights.element_at(cell) {
+                            Some(other)
+                        } else {
+                            Some(cell)
+                        }
+                    } else {
+                        None
+                    }
+                }
+            };
+            if let Some(pos) = forbidden {
+                restricted.forbid(pos);
+                violated = true;
+            }
+        }
+        if !violated {
+            return Ok(matching);
+        }
+    }
+}
+
+#[test]
+fn test_solve_assignment_constrained() {
+    use crate::WeightMatrix;
+
+    let pos = |row, column| Position { row, column };
+    let weights = WeightMatrix::from_row_vec(3, vec![1, 2, 3, 2, 1, 3, 3, 3, 1]);
+    assert_eq!(
+        Ok(vec![pos(0, 0), pos(1, 1), pos(2, 2)]),
+        solve_assignment_constrained(&weights, &[])
+    );
+
+    // (0, 0) needs (1, 2), which the identity doesn't assign.
+    let requires = Constraint::Requires {
+        cell: pos(0, 0),
+        required: pos(1, 2),
+    };
+    let matching = solve_assignment_constrained(&weights, &[requires]).unwrap();
+    assert_eq!(vec![pos(0, 1), pos(1, 0), pos(2, 2)], matching);
+
+    let excludes = Constraint::Excludes {
+        cell: pos(1, 1),
+        other: pos(2, 2),
+    };
+    let matching = solve_assignment_constrained(&weights, &[excludes]).unwrap();
+    assert!(!matching.contains(&pos(1, 1)) || !matching.contains(&pos(2, 2)));
+
+    let impossible = Constraint::Requires {
+        cell: pos(0, 0),
+        required: pos(1, 0),
+    };
+    let inf = f32::INFINITY;
+    let weights = WeightMatrix::from_row_vec(2, vec![1.0, inf, 1.0, 1.0]);
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_assignment_constrained(&weights, &[impossible])
+    );
+}
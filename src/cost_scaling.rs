@@ -0,0 +1,167 @@
+//! Cost-scaling backend for integer instances.
+//!
+//! Goldberg and Kennedy's cost-scaling push-relabel method specialized for the
+//! assignment problem: costs are scaled by `n + 1` and a sequence of
+//! `refine` passes with decreasing `epsilon` maintains epsilon-optimal column
+//! prices, using double-push operations (push a row to its best column,
+//! relabel the column and push back its previous owner). Once `epsilon`
+//! reaches 1 on the scaled costs the assignment is optimal.
+//!
+//! Only the valid cells are visited, which makes this the backend of choice
+//! for large, sparse integer instances.
+use crate::graph::Graph;
+use crate::{Assignment, AssignmentSolver, Error, Position, WeightNum, Weights};
+use alloc::{vec, vec::Vec};
+
+/// Factor by which `epsilon` shrinks between refine passes.
+const ALPHA: i64 = 5;
+
+/// Solves the assignment problem using cost scaling. Unlike
+/// `solve_assignment`, `weights` is not modified.
+///
+/// # Panics
+///
+/// If a valid cell is not an integer representable as `i64`, or if the
+/// scaled costs overflow `i64`.
+pub fn solve_assignment_cost_scaling<W>(weights: &W) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+{
+    let graph = Graph::from_weights(weights, |c| {
+        c.to_i64().expect("cost scaling requires integer weights")
+    });
+    solve_graph(&graph)
+}
+
+/// Solves the assignment problem on the valid cells in `graph`.
+pub(crate) fn solve_graph(graph: &Graph<i64>) -> Result<Vec<Position>, Error> {
+    if !graph.has_perfect_matching() {
+        return Err(Error::MatrixNotSolvable);
+    }
+
+    let n = graph.n();
+    let scale = (n + 1) as i64;
+    let max_cost = graph
+        .costs()
+        .iter()
+        .map(|c| c.checked_abs().and_then(|c| c.checked_mul(scale)))
+        .try_fold(1, |max: i64, c| c.map(|c| max.max(c)))
+        .expect("scaled costs overflow i64");
+
+    let mut state = State {
+        prices: vec![0; n],
+        row_column: vec![None; n],
+        column_row: vec![None; n],
+        active: Vec::with_capacity(n),
+    };
+
+    let mut epsilon = max_cost;
+    loop {
+        epsilon = (epsilon / ALPHA).max(1);
+        state.refine(graph, scale, epsilon);
+        if epsilon == 1 {
+            break;
+        }
+    }
+
+    Ok(state
+        .row_column
+        .iter()
+        .enumerate()
+        .map(|(row, column)| Position {
+            row,
+            column: column.unwrap(),
+        })
+        .collect())
+}
+
+struct State {
+    prices: Vec<i64>,
+    row_column: Vec<Option<usize>>,
+    column_row: Vec<Option<usize>>,
+    active: Vec<usize>,
+}
+
+impl State {
+    /// Turns the epsilon-optimal prices into an epsilon-optimal assignment.
+    fn refine(&mut self, graph: &Graph<i64>, scale: i64, epsilon: i64) {
+        let n = graph.n();
+        self.row_column.iter_mut().for_each(|c| *c = None);
+        self.column_row.iter_mut().for_each(|r| *r = None);
+        self.active.clear();
+        self.active.extend((0..n).rev());
+
+        while let Some(row) = self.active.pop() {
+            let mut best = None;
+            let mut second = i64::MAX;
+            for (column, cost) in graph.edges(row) {
+                let reduced = cost * scale + self.prices[column];
+                match best {
+                    Some((_, b)) if reduced >= b => second = second.min(reduced),
+                    Some((_, b)) => {
+                        second = b;
+                        best = Some((column, reduced));
+                    }
+                    None => best = Some((column, reduced)),
+                }
+            }
+            // rows always have an edge as a perfect matching exists.
+            let (column, best) = best.unwrap();
+            if second == i64::MAX {
+                second = best;
+            }
+
+            // double push: assign `row` to `column`, relabel `column` and
+            // push back its previous owner.
+            self.prices[column] += second - best + epsilon;
+            if let Some(previous) = self.column_row[column] {
+                self.row_column[previous] = None;
+                self.active.push(previous);
+            }
+            self.column_row[column] = Some(row);
+            self.row_column[row] = Some(column);
+        }
+    }
+}
+
+/// The cost-scaling backend.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct CostScaling;
+
+impl<W: Weights> AssignmentSolver<W> for CostScaling {
+    fn solve(&mut self, weights: &mut W) -> Result<Assignment, Error> {
+        solve_assignment_cost_scaling(weights).map(Assignment::from)
+    }
+}
+
+#[test]
+fn test_cost_scaling_matches_munkres() {
+    use crate::{generators, solve_assignment, WeightMatrix};
+
+    fn cost(weights: &WeightMatrix<u32>, matching: &[Position]) -> u32 {
+        matching.iter().map(|&pos| weights.element_at(pos)).sum()
+    }
+
+    let instances = vec![
+        generators::uniform(1, 0..10, 0),
+        generators::uniform(10, 0..1000, 1),
+        generators::uniform(30, 0..5, 2),
+        generators::machol_wien(12),
+    ];
+    for weights in instances {
+        let matching = solve_assignment_cost_scaling(&weights).unwrap();
+        let expected = solve_assignment(&mut weights.clone()).unwrap();
+        assert_eq!(cost(&weights, &expected), cost(&weights, &matching));
+    }
+
+    let c = vec![250, 400, 350, 400, 600, 350, 200, 400, 250];
+    let weights: WeightMatrix<i32> = WeightMatrix::from_row_vec(3, c);
+    assert_eq!(
+        vec![
+            Position { row: 0, column: 1 },
+            Position { row: 1, column: 2 },
+            Position { row: 2, column: 0 }
+        ],
+        solve_assignment_cost_scaling(&weights).unwrap()
+    );
+}
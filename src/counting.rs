@@ -0,0 +1,257 @@
+//! Counting-based minimum search for costs from a small integer range.
+//!
+//! With `u8` or `u16` costs, e.g. quantized similarities, the scans for the
+//! row minima in step 1 and the smallest uncovered value in step 6 dominate
+//! the running time. `solve_matrix_counting` finds both by counting instead
+//! of comparing: the valid uncovered cells are kept in a histogram indexed
+//! by value, so the smallest one is the first non-empty bucket.
+//!
+//! Step 6 subtracts the same value from all uncovered cells, which shifts
+//! the histogram as a whole. It is stored as a ring buffer with a moving
+//! origin, so the shift takes constant time. Covering a row or uncovering a
+//! column only moves the cells of that line in or out of the histogram.
+use crate::bounds::tight_permutation;
+use crate::coverage::Coverage;
+use crate::steps::{shift_uncovered, step2, step3, step4, step5, Step3, Step4, Step5, Step6};
+use crate::workspace::Workspace;
+use crate::{collect_stars, Error, Position, WeightMatrix, WeightNum, Weights};
+use alloc::{vec, vec::Vec};
+
+/// Integer costs whose values all fit into a histogram of `BUCKETS` buckets.
+pub trait SmallRange: WeightNum {
+    /// The number of distinct values, a power of two.
+    const BUCKETS: usize;
+
+    fn to_bucket(self) -> usize;
+
+    fn from_bucket(bucket: usize) -> Self;
+}
+
+impl SmallRange for u8 {
+    const BUCKETS: usize = 1 << 8;
+
+    #[inline(always)]
+    fn to_bucket(self) -> usize {
+        self as usize
+    }
+
+    #[inline(always)]
+    fn from_bucket(bucket: usize) -> u8 {
+        bucket as u8
+    }
+}
+
+impl SmallRange for u16 {
+    const BUCKETS: usize = 1 << 16;
+
+    #[inline(always)]
+    fn to_bucket(self) -> usize {
+        self as usize
+    }
+
+    #[inline(always)]
+    fn from_bucket(bucket: usize) -> u16 {
+        bucket as u16
+    }
+}
+
+/// The number of valid cells of each value among the cells of the rows and
+/// columns in `rows` and `columns`.
+#[derive(Debug, Clone)]
+struct Histogram {
+    counts: Vec<usize>,
+    /// The bucket of value 0.
+    origin: usize,
+    len: usize,
+    rows: Vec<bool>,
+    columns: Vec<bool>,
+}
+
+impl Histogram {
+    fn new<T: SmallRange>(n: usize) -> Histogram {
+        Histogram {
+            counts: vec![0; T::BUCKETS],
+            origin: 0,
+            len: 0,
+            rows: vec![false; n],
+            columns: vec![false; n],
+        }
+    }
+
+    #[inline]
+    fn bucket<T: SmallRange>(&self, value: T) -> usize {
+        (self.origin + value.to_bucket()) & (T::BUCKETS - 1)
+    }
+
+    /// The smallest value of the histogram, `None` if it is empty.
+    fn min<T: SmallRange>(&self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        (0..T::BUCKETS)
+            .find(|&value| self.counts[(self.origin + value) & (T::BUCKETS - 1)] > 0)
+            .map(T::from_bucket)
+    }
+
+    /// Adds (or with `remove` removes) the valid cells of `row` in the
+    /// columns of the histogram.
+    fn update_row<T: SmallRange>(&mut self, c: &WeightMatrix<T>, row: usize, remove: bool) {
+        for column in 0..c.n() {
+            let pos = Position { row, column };
+            if self.columns[column] && c.is_valid_at(pos) {
+                self.update(c.element_at(pos), remove);
+            }
+        }
+    }
+
+    fn update_column<T: SmallRange>(&mut self, c: &WeightMatrix<T>, column: usize, remove: bool) {
+        for row in 0..c.n() {
+            let pos = Position { row, column };
+            if self.rows[row] && c.is_valid_at(pos) {
+                self.update(c.element_at(pos), remove);
+            }
+        }
+    }
+
+    #[inline]
+    fn update<T: SmallRange>(&mut self, value: T, remove: bool) {
+        let bucket = self.bucket(value);
+        if remove {
+            self.counts[bucket] -= 1;
+            self.len -= 1;
+        } else {
+            self.counts[bucket] += 1;
+            self.len += 1;
+        }
+    }
+
+    /// Makes the histogram hold the uncovered cells of `c`, moving only the
+    /// lines whose coverage changed since the last call.
+    fn sync<T: SmallRange>(&mut self, c: &WeightMatrix<T>, cov: &Coverage) {
+        let n = c.n();
+        for row in 0..n {
+            if self.rows[row] && cov.is_row_covered(row) {
+                self.update_row(c, row, true);
+                self.rows[row] = false;
+            }
+        }
+        for column in 0..n {
+            let uncovered = !cov.is_column_covered(column);
+            if self.columns[column] != uncovered {
+                self.update_column(c, column, !uncovered);
+                self.columns[column] = uncovered;
+            }
+        }
+        for row in 0..n {
+            if !self.rows[row] && !cov.is_row_covered(row) {
+                self.rows[row] = true;
+                self.update_row(c, row, false);
+            }
+        }
+    }
+
+    /// The smallest valid value of `row`, counting its cells in the empty
+    /// histogram and removing them again.
+    fn row_min<T: SmallRange>(&mut self, c: &WeightMatrix<T>, row: usize) -> Option<T> {
+        debug_assert!(self.len == 0);
+        let cells = (0..c.n())
+            .map(|column| Position { row, column })
+            .filter(|&pos| c.is_valid_at(pos));
+        cells
+            .clone()
+            .for_each(|pos| self.update(c.element_at(pos), false));
+        let min = self.min();
+        cells.for_each(|pos| self.update(c.element_at(pos), true));
+        min
+    }
+}
+
+/// Like `solve_assignment`, finding the row minima and the smallest
+/// uncovered values by counting. Returns the same matching as
+/// `solve_assignment`. `weights` is left in a reduced state.
+pub fn solve_matrix_counting<T>(weights: &mut WeightMatrix<T>) -> Result<Vec<Position>, Error>
+where
+    T: SmallRange,
+{
+    if !weights.is_solvable() {
+        return Err(Error::MatrixNotSolvable);
+    }
+    let n = weights.n();
+    let mut histogram = Histogram::new::<T>(n);
+    for row in 0..n {
+        if let Some(min) = histogram.row_min(weights, row) {
+            weights.sub_row(row, min);
+        }
+    }
+    if let Some(matching) = tight_permutation(weights) {
+        return Ok(matching);
+    }
+
+    let mut workspace = Workspace::new(n);
+    let Workspace {
+        marks,
+        coverage,
+        path,
+        ..
+    } = &mut workspace;
+    step2(weights, marks, coverage);
+    'step3: loop {
+        match step3(weights, marks, coverage) {
+            Step3::ContinueWithStep4 { .. } => 'step4: loop {
+                match step4(weights, marks, coverage) {
+                    Step4::ContinueWithStep5 { z0_pos } => {
+                        match step5(marks, coverage, z0_pos, path)? {
+                            Step5::ContinueWithStep3 => continue 'step3,
+                        }
+                    }
+                    Step4::ContinueWithStep6 => {
+                        histogram.sync(weights, coverage);
+                        let min = histogram.min::<T>();
+                        match shift_uncovered(weights, coverage, min)? {
+                            Step6::ContinueWithStep4 => {
+                                // the uncovered cells all moved down by `min`.
+                                histogram.origin = histogram.bucket(min.unwrap());
+                                continue 'step4;
+                            }
+                        }
+                    }
+                }
+            },
+            Step3::Done => break 'step3,
+        }
+    }
+    Ok(collect_stars(n, &workspace))
+}
+
+#[test]
+fn test_solve_matrix_counting() {
+    use crate::generators::Rng;
+    use crate::solve_assignment;
+
+    for seed in 0..10 {
+        let mut rng = Rng::new(seed);
+        let data: Vec<u16> = (0..400).map(|_| rng.next_in(0..1000) as u16).collect();
+        let weights = WeightMatrix::from_row_vec(20, data);
+        assert_eq!(
+            solve_assignment(&mut weights.clone()),
+            solve_matrix_counting(&mut weights.clone())
+        );
+
+        let data: Vec<u8> = (0..64).map(|_| rng.next_in(0..16) as u8).collect();
+        let valid: Vec<bool> = (0..64)
+            .map(|i| i % 8 == i / 8 || rng.next_in(0..4) > 0)
+            .collect();
+        let weights = WeightMatrix::from_row_vec_with_validity(8, data, &valid);
+        assert_eq!(
+            solve_assignment(&mut weights.clone()),
+            solve_matrix_counting(&mut weights.clone())
+        );
+    }
+
+    let data = vec![1u8, 2, 1, 2];
+    let weights = WeightMatrix::from_row_vec_with_validity(2, data, &[true, false, true, false]);
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_matrix_counting(&mut weights.clone())
+    );
+}
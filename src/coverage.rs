@@ -1,13 +1,19 @@
+use crate::bitset::BitSet;
 use crate::Position;
-use fixedbitset::FixedBitSet;
 
-#[derive(Debug)]
+/// Below this size, `par_find_uncovered_cell_column_row_order` searches
+/// serially, as the parallel search doesn't pay off.
+#[cfg(feature = "rayon")]
+const PARALLEL_SEARCH_THRESHOLD: usize = 1024;
+
+/// The covered rows and columns of an `n x n` matrix.
+#[derive(Debug, Clone)]
 pub struct Coverage {
     n: usize,
     /// A bit is set, if the row is uncovered.
-    uncovered_rows: FixedBitSet,
+    uncovered_rows: BitSet,
     /// A bit is set, if the column is uncovered.
-    uncovered_columns: FixedBitSet,
+    uncovered_columns: BitSet,
 }
 
 impl Coverage {
@@ -20,14 +26,14 @@ impl Coverage {
         assert!(n > 0);
 
         // We start with all bits set (all rows/columns) uncovered.
-        let mut all_rows_uncovered = FixedBitSet::with_capacity(n);
-        all_rows_uncovered.set_range(.., true);
+        let mut all_rows_uncovered = BitSet::with_capacity(n);
+        all_rows_uncovered.set_all(true);
 
         // We can simply clone the rows as we work on square matrices
         let all_columns_uncovered = all_rows_uncovered.clone();
 
         Coverage {
-            n: n,
+            n,
             uncovered_rows: all_rows_uncovered,
             uncovered_columns: all_columns_uncovered,
         }
@@ -48,7 +54,53 @@ impl Coverage {
             }
         }
 
-        return None;
+        None
+    }
+
+    /// Find the first uncovered cell in row-major order, starting at row
+    /// `start` and wrapping around to the first row.
+    #[inline]
+    pub fn find_uncovered_cell_row_column_order_from<F>(
+        &self,
+        start: usize,
+        mut f: F,
+    ) -> Option<Position>
+    where
+        F: FnMut(Position) -> bool,
+    {
+        let rows = self.uncovered_rows.ones().filter(|&row| row >= start);
+        let wrapped = self.uncovered_rows.ones().take_while(|&row| row < start);
+        for row in rows.chain(wrapped) {
+            for column in self.uncovered_columns.ones() {
+                let pos = Position { row, column };
+                if f(pos) {
+                    return Some(pos);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Like `find_uncovered_cell_column_row_order`, but searches the columns
+    /// in parallel for large matrices. Returns the same cell.
+    #[cfg(feature = "rayon")]
+    pub fn par_find_uncovered_cell_column_row_order<F>(&self, f: F) -> Option<Position>
+    where
+        F: Fn(Position) -> bool + Sync,
+    {
+        use rayon::prelude::*;
+
+        if self.n < PARALLEL_SEARCH_THRESHOLD {
+            return self.find_uncovered_cell_column_row_order(f);
+        }
+        let rows: Vec<usize> = self.uncovered_rows.ones().collect();
+        let columns: Vec<usize> = self.uncovered_columns.ones().collect();
+        columns.par_iter().find_map_first(|&column| {
+            rows.iter()
+                .map(|&row| Position { row, column })
+                .find(|&pos| f(pos))
+        })
     }
 
     /// iterates over all uncovered (row, column) pairs in row, column order
@@ -116,6 +168,12 @@ impl Coverage {
         self.uncovered_columns.set(column, false);
     }
 
+    /// Covers all columns in `columns`, a word at a time.
+    #[inline]
+    pub(crate) fn cover_columns(&mut self, columns: &BitSet) {
+        self.uncovered_columns.remove_all(columns);
+    }
+
     #[inline]
     pub fn uncover_column(&mut self, column: usize) {
         debug_assert!(column < self.n());
@@ -129,12 +187,44 @@ impl Coverage {
     }
 
     pub fn clear(&mut self) {
-        self.uncovered_rows.set_range(.., true);
-        self.uncovered_columns.set_range(.., true);
+        self.uncovered_rows.set_all(true);
+        self.uncovered_columns.set_all(true);
+    }
+
+    /// The bytes of the coverage of an `n x n` matrix.
+    pub(crate) fn estimated_bytes(n: usize) -> usize {
+        2 * BitSet::estimated_bytes(n)
+    }
+
+    pub(crate) fn memory_usage(&self) -> usize {
+        self.uncovered_rows.memory_usage() + self.uncovered_columns.memory_usage()
     }
 
     pub fn all_uncovered(&self) -> bool {
-        self.uncovered_rows.count_ones(..) + self.uncovered_columns.count_ones(..)
-            == (self.n + self.n)
+        self.uncovered_rows.count_ones() + self.uncovered_columns.count_ones() == (self.n + self.n)
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_par_find_uncovered_cell() {
+    let n = PARALLEL_SEARCH_THRESHOLD + 100;
+    let mut cov = Coverage::new(n);
+    for i in (0..n).step_by(3) {
+        cov.cover_row(i);
+        cov.cover_column(i + 1);
     }
+    let cells = [(5, 900), (7, 900), (2, 1050), (8, 30)];
+    let f = |pos: Position| cells.contains(&(pos.row, pos.column));
+    let expected = cov.find_uncovered_cell_column_row_order(f);
+    assert_eq!(Some(Position { row: 8, column: 30 }), expected);
+    assert_eq!(expected, cov.par_find_uncovered_cell_column_row_order(f));
+    cov.cover_row(8);
+    assert_eq!(
+        Some(Position {
+            row: 5,
+            column: 900
+        }),
+        cov.par_find_uncovered_cell_column_row_order(f)
+    );
 }
@@ -0,0 +1,190 @@
+//! Exact solving within a time budget, with an approximate fallback.
+//!
+//! Latency-bound callers, e.g. a dispatcher replanning every second, prefer
+//! a good assignment in time over the optimal one too late. `Strategy` runs
+//! the exact algorithm until the budget is spent and then switches to a fast
+//! approximation. The result tells which path produced the matching, and for
+//! the approximate path bounds how far its cost may be above the optimum.
+//!
+//! The deadline is checked between the steps of the algorithm, so a single
+//! step, taking `O(n^2)` at most, may run past it.
+use crate::steps::step1;
+use crate::workspace::Workspace;
+use crate::{
+    collect_stars, lower_bound, run_reduced_until, solve_assignment_auction, Error, Position,
+    WeightNum, Weights,
+};
+use std::time::{Duration, Instant};
+
+/// The approximation used once the time budget is spent.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Fallback {
+    /// Assigns each row in order to its cheapest free column, falling back to
+    /// the auction if a row has no valid free column left. `O(n^2)`.
+    #[default]
+    Greedy,
+    /// The auction algorithm with default options.
+    Auction,
+}
+
+/// Which path produced a matching.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Path {
+    /// The exact algorithm finished within the budget.
+    Exact,
+    /// The budget was exceeded, and the fallback produced the matching.
+    Approximate,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategyResult {
+    /// The matching, sorted by row.
+    pub matching: Vec<Position>,
+    pub path: Path,
+    /// An upper bound on the cost of `matching` minus the optimal cost. Zero
+    /// for the exact path.
+    pub gap_bound: f64,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Strategy {
+    time_budget: Duration,
+    fallback: Fallback,
+}
+
+impl Strategy {
+    /// Tries the exact algorithm for `time_budget`, then the greedy fallback.
+    pub fn new(time_budget: Duration) -> Strategy {
+        Strategy {
+            time_budget,
+            fallback: Fallback::default(),
+        }
+    }
+
+    pub fn fallback(mut self, fallback: Fallback) -> Strategy {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Solves `weights`, which are not modified.
+    ///
+    /// # Panics
+    ///
+    /// On the approximate path, if a valid cell can't be converted to `f64`.
+    pub fn solve<W>(&self, weights: &W) -> Result<StrategyResult, Error>
+    where
+        W: Weights + Clone,
+    {
+        let n = weights.n();
+        if !weights.is_solvable() {
+            return Err(Error::MatrixNotSolvable);
+        }
+        let deadline = Instant::now().checked_add(self.time_budget);
+        let mut reduced = weights.clone();
+        step1(&mut reduced);
+        let mut workspace = Workspace::new(n.max(1));
+        let finished = run_reduced_until(&mut reduced, &mut workspace, |_| {
+            deadline.is_some_and(|deadline| Instant::now() >= deadline)
+        })?;
+        if finished || n == 0 {
+            return Ok(StrategyResult {
+                matching: collect_stars(n, &workspace),
+                path: Path::Exact,
+                gap_bound: 0.0,
+            });
+        }
+
+        let matching = match self.fallback {
+            Fallback::Greedy => match greedy(weights) {
+                Some(matching) => matching,
+                None => solve_assignment_auction(weights, &Default::default())?,
+            },
+            Fallback::Auction => solve_assignment_auction(weights, &Default::default())?,
+        };
+        let to_f64 = |value: W::T| {
+            value
+                .to_f64()
+                .expect("the fallback requires weights convertible to f64")
+        };
+        let cost: f64 = matching
+            .iter()
+            .map(|&pos| to_f64(weights.element_at(pos)))
+            .sum();
+        let gap_bound = (cost - to_f64(lower_bound(weights)?)).max(0.0);
+        Ok(StrategyResult {
+            matching,
+            path: Path::Approximate,
+            gap_bound,
+        })
+    }
+}
+
+/// Assigns each row in order to its cheapest free valid column. `None` if a
+/// row has none left.
+fn greedy<W>(weights: &W) -> Option<Vec<Position>>
+where
+    W: Weights,
+{
+    let n = weights.n();
+    let mut used = vec![false; n];
+    let mut matching = Vec::with_capacity(n);
+    for row in 0..n {
+        let pos = (0..n)
+            .map(|column| Position { row, column })
+            .filter(|&pos| !used[pos.column] && weights.is_valid_at(pos))
+            .reduce(|best, pos| {
+                if weights.element_at(pos) < weights.element_at(best) {
+                    pos
+                } else {
+                    best
+                }
+            })?;
+        used[pos.column] = true;
+        matching.push(pos);
+    }
+    Some(matching)
+}
+
+#[test]
+fn test_strategy() {
+    use crate::{generators, solve_assignment, WeightMatrix};
+
+    let weights = generators::uniform(30, 0..1000, 3);
+    let cost = |m: &[Position]| m.iter().map(|&pos| weights.element_at(pos)).sum::<u32>();
+    let optimum = cost(&solve_assignment(&mut weights.clone()).unwrap());
+
+    let exact = Strategy::new(Duration::from_secs(3600))
+        .solve(&weights)
+        .unwrap();
+    assert_eq!(Path::Exact, exact.path);
+    assert_eq!(0.0, exact.gap_bound);
+    assert_eq!(optimum, cost(&exact.matching));
+
+    for fallback in [Fallback::Greedy, Fallback::Auction] {
+        let result = Strategy::new(Duration::ZERO)
+            .fallback(fallback)
+            .solve(&weights)
+            .unwrap();
+        assert_eq!(Path::Approximate, result.path);
+        assert_eq!(30, result.matching.len());
+        assert!(result.gap_bound >= (cost(&result.matching) - optimum) as f64);
+    }
+
+    // greedy takes column 0 for row 0, leaving row 1 without a column.
+    let inf = f64::INFINITY;
+    let weights = WeightMatrix::from_row_vec(2, vec![1.0, 2.0, 1.0, inf]);
+    let result = Strategy::new(Duration::ZERO).solve(&weights).unwrap();
+    assert_eq!(
+        vec![
+            Position { row: 0, column: 1 },
+            Position { row: 1, column: 0 }
+        ],
+        result.matching
+    );
+
+    let weights = WeightMatrix::from_row_vec(2, vec![1.0, inf, 1.0, inf]);
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        Strategy::new(Duration::ZERO).solve(&weights)
+    );
+}
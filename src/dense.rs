@@ -0,0 +1,154 @@
+use alloc::vec::Vec;
+use core::ops::{Index, IndexMut};
+
+/// Lightweight dense `n x n` matrix in row-major order, the storage of
+/// `WeightMatrix`.
+#[derive(Debug, Clone)]
+pub(crate) struct DenseMatrix<T> {
+    n: usize,
+    data: Vec<T>,
+}
+
+impl<T> DenseMatrix<T> {
+    /// # Panics
+    ///
+    /// If `data` doesn't have `n * n` elements.
+    pub(crate) fn from_row_vec(n: usize, data: Vec<T>) -> DenseMatrix<T> {
+        assert!(data.len() == n * n);
+        DenseMatrix { n, data }
+    }
+
+    pub(crate) fn from_fn<F: FnMut((usize, usize)) -> T>(n: usize, mut f: F) -> DenseMatrix<T> {
+        let mut data = Vec::with_capacity(n * n);
+        for row in 0..n {
+            for column in 0..n {
+                data.push(f((row, column)));
+            }
+        }
+        DenseMatrix { n, data }
+    }
+
+    pub(crate) fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    /// Transposes the matrix in place, e.g. to turn column-major data into
+    /// row-major.
+    pub(crate) fn transpose(&mut self) {
+        let n = self.n;
+        for row in 0..n {
+            for column in row + 1..n {
+                self.data.swap(row * n + column, column * n + row);
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Like indexing, without bounds checks with the `unchecked` feature.
+    ///
+    /// # Safety
+    ///
+    /// `row` and `column` must be less than `n`.
+    #[inline(always)]
+    pub(crate) unsafe fn get_unchecked(&self, row: usize, column: usize) -> &T {
+        debug_assert!(row < self.n && column < self.n);
+        #[cfg(feature = "unchecked")]
+        return self.data.get_unchecked(row * self.n + column);
+        #[cfg(not(feature = "unchecked"))]
+        return &self.data[row * self.n + column];
+    }
+
+    #[inline]
+    pub(crate) fn row(&self, row: usize) -> &[T] {
+        &self.data[row * self.n..(row + 1) * self.n]
+    }
+
+    #[inline]
+    pub(crate) fn row_mut(&mut self, row: usize) -> &mut [T] {
+        &mut self.data[row * self.n..(row + 1) * self.n]
+    }
+
+    #[inline]
+    pub(crate) fn column_mut(&mut self, column: usize) -> impl Iterator<Item = &mut T> {
+        let n = self.n;
+        self.data[column..].iter_mut().step_by(n)
+    }
+
+    #[inline]
+    pub(crate) fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    #[cfg(feature = "rayon")]
+    #[inline]
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+}
+
+impl<T: Copy> DenseMatrix<T> {
+    /// Resizes the matrix to `n x n` in place, keeping the elements of the
+    /// block both sizes share and filling the others with `f`.
+    pub(crate) fn resize_with<F: FnMut((usize, usize)) -> T>(&mut self, n: usize, mut f: F) {
+        let old = self.n;
+        if old == 0 {
+            *self = DenseMatrix::from_fn(n, f);
+            return;
+        }
+        let kept = old.min(n);
+        if n > old {
+            // move the rows to their new offsets, the last row first, as
+            // each moves towards the end.
+            let placeholder = self.data[0];
+            self.data.resize(n * n, placeholder);
+            for row in (1..old).rev() {
+                self.data.copy_within(row * old..row * old + old, row * n);
+            }
+        } else {
+            for row in 1..n {
+                self.data.copy_within(row * old..row * old + n, row * n);
+            }
+            self.data.truncate(n * n);
+        }
+        self.n = n;
+        for row in 0..n {
+            let columns = if row < kept { kept..n } else { 0..n };
+            for column in columns {
+                self.data[row * n + column] = f((row, column));
+            }
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for DenseMatrix<T> {
+    type Output = T;
+
+    #[inline(always)]
+    fn index(&self, (row, column): (usize, usize)) -> &T {
+        debug_assert!(column < self.n);
+        &self.data[row * self.n + column]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for DenseMatrix<T> {
+    #[inline(always)]
+    fn index_mut(&mut self, (row, column): (usize, usize)) -> &mut T {
+        debug_assert!(column < self.n);
+        &mut self.data[row * self.n + column]
+    }
+}
+
+#[test]
+fn test_dense_matrix() {
+    let mut m = DenseMatrix::from_fn(3, |(row, column)| row * 3 + column);
+    assert_eq!(&[3, 4, 5], m.row(1));
+    m.column_mut(2).for_each(|x| *x = 0);
+    m[(0, 0)] = 9;
+    assert_eq!(&[9, 1, 0, 3, 4, 0, 6, 7, 0], m.as_slice());
+    m.transpose();
+    assert_eq!(&[9, 3, 6, 1, 4, 7, 0, 0, 0], m.as_slice());
+}
@@ -0,0 +1,121 @@
+//! Mutually disjoint assignments, e.g. a primary and a backup allocation of
+//! tasks that share no (row, column) pair.
+//!
+//! `solve_disjoint_assignments` finds `k` assignments that never reuse a
+//! cell, with minimum combined cost. Their union uses every row and column
+//! `k` times, so it is the cheapest `b`-matching with all degrees exactly
+//! `k`, solved as a min-cost flow by `capacities::solve_b_matching`. By
+//! König's theorem, a `k`-regular bipartite graph is the union of `k`
+//! perfect matchings; they are peeled off one at a time, each the cheapest
+//! assignment within the cells that are left.
+//!
+//! Unlike successive solves with the previous cells forbidden, which may end
+//! with no assignment left or a higher combined cost, this is exact.
+use crate::capacities::{solve_b_matching, Degree};
+use crate::restricted::Restricted;
+use crate::{solve_assignment, Assignment, Error, Position, WeightNum, Weights};
+use alloc::{vec, vec::Vec};
+
+/// Computes `k` assignments of `weights` without a common cell, minimizing
+/// the sum of their costs, in non-decreasing cost order. Returns
+/// `Error::MatrixNotSolvable` if the valid cells admit no `k` disjoint
+/// assignments, in particular if `k > n`. `n = 0` gives no assignments.
+///
+/// # Panics
+///
+/// If a valid cost can't be converted to `f64`.
+pub fn solve_disjoint_assignments<W>(
+    weights: &W,
+    k: usize,
+) -> Result<Vec<(Assignment, W::T)>, Error>
+where
+    W: Weights,
+{
+    let n = weights.n();
+    if n == 0 || k == 0 {
+        return Ok(Vec::new());
+    }
+    let costs: Vec<f64> = (0..n * n)
+        .map(|index| {
+            let pos = Position {
+                row: index / n,
+                column: index % n,
+            };
+            if weights.is_valid_at(pos) {
+                weights
+                    .element_at(pos)
+                    .to_f64()
+                    .expect("disjoint assignments require weights convertible to f64")
+            } else {
+                f64::INFINITY
+            }
+        })
+        .collect();
+    let union = solve_b_matching(
+        &costs,
+        &vec![Degree::exactly(k); n],
+        &vec![Degree::exactly(k); n],
+    )?;
+
+    let mut left = vec![false; n * n];
+    for pos in &union {
+        left[pos.row * n + pos.column] = true;
+    }
+    let mut assignments = Vec::with_capacity(k);
+    for _ in 0..k {
+        let mut restricted =
+            Restricted::from_weights_where(weights, |pos| left[pos.row * n + pos.column]);
+        let base = restricted.clone();
+        // the cells left form a regular graph, which has a perfect matching.
+        let matching = solve_assignment(&mut restricted)?;
+        for pos in &matching {
+            left[pos.row * n + pos.column] = false;
+        }
+        let cost = base.cost(&matching);
+        assignments.push((Assignment::from(matching), cost));
+    }
+    assignments.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+    Ok(assignments)
+}
+
+#[test]
+fn test_solve_disjoint_assignments() {
+    use crate::{generators, WeightMatrix};
+
+    for seed in 0..5 {
+        let weights = generators::uniform(7, 0..100, seed);
+        let assignments = solve_disjoint_assignments(&weights, 3).unwrap();
+        assert_eq!(3, assignments.len());
+        let mut cells: Vec<Position> = assignments
+            .iter()
+            .flat_map(|(assignment, _)| assignment.iter().copied())
+            .collect();
+        cells.sort_by_key(|pos| (pos.row, pos.column));
+        cells.dedup();
+        assert_eq!(21, cells.len());
+        for (assignment, cost) in &assignments {
+            assert_eq!(7, assignment.len());
+            let sum: u32 = assignment.iter().map(|&pos| weights.element_at(pos)).sum();
+            assert_eq!(*cost, sum);
+        }
+        assert!(assignments.windows(2).all(|w| w[0].1 <= w[1].1));
+        // a single assignment is the optimum.
+        let best = solve_assignment(&mut weights.clone()).unwrap();
+        let cost: u32 = best.iter().map(|&pos| weights.element_at(pos)).sum();
+        assert_eq!(cost, solve_disjoint_assignments(&weights, 1).unwrap()[0].1);
+    }
+
+    // the valid cells form a cycle, the union of exactly two assignments.
+    let inf = f64::INFINITY;
+    let weights = WeightMatrix::from_row_vec(3, vec![0.0, 1.0, inf, inf, 0.0, 1.0, 1.0, inf, 0.0]);
+    let assignments = solve_disjoint_assignments(&weights, 2).unwrap();
+    assert_eq!(
+        vec![0.0, 3.0],
+        assignments.iter().map(|a| a.1).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_disjoint_assignments(&weights, 3)
+    );
+    assert_eq!(Ok(vec![]), solve_disjoint_assignments(&weights, 0));
+}
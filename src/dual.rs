@@ -0,0 +1,331 @@
+//! Solving without modifying the costs.
+//!
+//! The reductions of the algorithm amount to a potential per row and per
+//! column, the dual variables of the assignment problem: the reduced cost of
+//! a cell is `c[i][j] - u[i] - v[j]`. `DualWeights` keeps only these
+//! potentials and reads the costs through a shared reference, so they are
+//! never written. That leaves the caller's matrix intact, allows costs in
+//! shared or read-only (e.g. memory-mapped) storage, and makes step 6 update
+//! `2n` potentials instead of writing to the matrix.
+//!
+//! The costs only need to be readable, see `Costs`. The potentials of rows
+//! can become negative, so the costs must be of a signed type.
+use crate::{solve_assignment, Error, Position, WeightMatrix, WeightNum};
+use alloc::{vec, vec::Vec};
+use core::ops::Neg;
+
+/// Read-only access to the costs of an `n x n` matrix, all `DualWeights`
+/// needs. Views that compute their values on the fly, like
+/// `SymmetricMatrix` or `MappedWeights`, implement only this and are solved
+/// through `DualWeights`. Every `Weights` is `Costs`.
+pub trait Costs {
+    type T: WeightNum;
+    fn n(&self) -> usize;
+    fn element_at(&self, pos: Position) -> Self::T;
+
+    /// Whether the cell at `pos` may be part of an assignment. Defaults to the
+    /// validity of its value.
+    #[inline]
+    fn is_valid_at(&self, pos: Position) -> bool {
+        self.element_at(pos).is_valid()
+    }
+
+    /// Defaults to whether every row has a valid cell.
+    fn is_solvable(&self) -> bool {
+        let n = self.n();
+        (0..n).all(|row| (0..n).any(|column| self.is_valid_at(Position { row, column })))
+    }
+}
+
+impl<W: crate::Weights> Costs for W {
+    type T = W::T;
+
+    #[inline(always)]
+    fn n(&self) -> usize {
+        crate::Weights::n(self)
+    }
+
+    #[inline(always)]
+    fn element_at(&self, pos: Position) -> W::T {
+        crate::Weights::element_at(self, pos)
+    }
+
+    #[inline(always)]
+    fn is_valid_at(&self, pos: Position) -> bool {
+        crate::Weights::is_valid_at(self, pos)
+    }
+
+    fn is_solvable(&self) -> bool {
+        crate::Weights::is_solvable(self)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DualWeights<'a, W>
+where
+    W: Costs,
+{
+    costs: &'a W,
+    row_duals: Vec<W::T>,
+    column_duals: Vec<W::T>,
+}
+
+impl<'a, W> DualWeights<'a, W>
+where
+    W: Costs,
+    W::T: Default + Neg<Output = W::T>,
+{
+    /// Starts with all potentials 0.
+    pub fn new(costs: &'a W) -> DualWeights<'a, W> {
+        let n = costs.n();
+        DualWeights {
+            costs,
+            row_duals: vec![W::T::default(); n],
+            column_duals: vec![W::T::default(); n],
+        }
+    }
+
+    pub fn costs(&self) -> &'a W {
+        self.costs
+    }
+
+    /// The potential `u` of each row.
+    pub fn row_duals(&self) -> &[W::T] {
+        &self.row_duals
+    }
+
+    /// The potential `v` of each column.
+    pub fn column_duals(&self) -> &[W::T] {
+        &self.column_duals
+    }
+}
+
+impl<'a, W> crate::Weights for DualWeights<'a, W>
+where
+    W: Costs,
+    W::T: Default + Neg<Output = W::T>,
+{
+    type T = W::T;
+
+    #[inline(always)]
+    fn n(&self) -> usize {
+        self.costs.n()
+    }
+
+    #[inline]
+    fn element_at(&self, pos: Position) -> W::T {
+        self.costs.element_at(pos) - self.row_duals[pos.row] - self.column_duals[pos.column]
+    }
+
+    /// Compares instead of subtracting the column potential, so that rounding
+    /// errors of floating point potentials can't hide a zero. Reduced costs
+    /// are never negative, so this is the same for exact types.
+    #[inline]
+    fn is_element_zero(&self, pos: Position) -> bool {
+        self.costs.is_valid_at(pos)
+            && self.costs.element_at(pos) - self.row_duals[pos.row] <= self.column_duals[pos.column]
+    }
+
+    #[inline]
+    fn is_valid_at(&self, pos: Position) -> bool {
+        self.costs.is_valid_at(pos)
+    }
+
+    fn sub_min_of_each_row(&mut self) {
+        let n = self.costs.n();
+        for row in 0..n {
+            let min = (0..n)
+                .map(|column| Position { row, column })
+                .filter(|&pos| self.costs.is_valid_at(pos))
+                .map(|pos| crate::Weights::element_at(self, pos))
+                .fold(None, |min: Option<W::T>, value| match min {
+                    Some(m) if m <= value => Some(m),
+                    _ => Some(value),
+                });
+            if let Some(min) = min {
+                self.row_duals[row] = self.row_duals[row] + min;
+            }
+        }
+    }
+
+    fn add_row(&mut self, row: usize, val: W::T) {
+        self.row_duals[row] = self.row_duals[row] - val;
+    }
+
+    fn sub_column(&mut self, col: usize, val: W::T) {
+        self.column_duals[col] = self.column_duals[col] + val;
+    }
+
+    fn is_solvable(&self) -> bool {
+        self.costs.is_solvable()
+    }
+}
+
+/// Like `solve_assignment`, leaving `weights` unmodified.
+pub fn solve_assignment_immutable<W>(weights: &W) -> Result<Vec<Position>, Error>
+where
+    W: Costs,
+    W::T: Default + Neg<Output = W::T>,
+{
+    solve_assignment(&mut DualWeights::new(weights))
+}
+
+/// An optimal assignment together with an optimal dual solution, e.g. for
+/// sensitivity analysis or to warm-start an LP relaxation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssignmentResult<T> {
+    /// Sorted by row.
+    pub positions: Vec<Position>,
+    /// The total of the original costs of `positions`.
+    pub cost: T,
+    /// The potential `u` of each row.
+    pub row_duals: Vec<T>,
+    /// The potential `v` of each column.
+    pub column_duals: Vec<T>,
+}
+
+impl<T: WeightNum + Default> AssignmentResult<T> {
+    /// `c[i][j] - u[i] - v[j]`: non-negative for the valid cells, and 0 for
+    /// the assigned ones. It is how much the cost of an unassigned cell may
+    /// decrease before the cell can enter an optimal assignment.
+    pub fn reduced_cost<W>(&self, weights: &W, pos: Position) -> T
+    where
+        W: Costs<T = T>,
+    {
+        weights.element_at(pos) - self.row_duals[pos.row] - self.column_duals[pos.column]
+    }
+
+    /// The sum of all potentials, which equals `cost` by strong duality.
+    pub fn dual_objective(&self) -> T {
+        self.row_duals
+            .iter()
+            .chain(&self.column_duals)
+            .fold(T::default(), |sum, &dual| sum + dual)
+    }
+}
+
+/// Like `solve_assignment_immutable`, returning the potentials of the solve
+/// and the cost along with the assignment.
+pub fn solve_assignment_with_duals<W>(weights: &W) -> Result<AssignmentResult<W::T>, Error>
+where
+    W: Costs,
+    W::T: Default + Neg<Output = W::T>,
+{
+    let mut duals = DualWeights::new(weights);
+    let positions = solve_assignment(&mut duals)?;
+    let cost = positions
+        .iter()
+        .fold(W::T::default(), |sum, &pos| sum + weights.element_at(pos));
+    Ok(AssignmentResult {
+        positions,
+        cost,
+        row_duals: duals.row_duals,
+        column_duals: duals.column_duals,
+    })
+}
+
+impl<T: WeightNum + Default> WeightMatrix<T> {
+    /// The matrix of the reduced costs of `result`, a solution of this
+    /// matrix, with the same forbidden cells. It is what `solve_assignment`
+    /// leaves in place of the costs, without modifying them.
+    ///
+    /// # Panics
+    ///
+    /// If `result` is of a different size.
+    pub fn reduced_costs(&self, result: &AssignmentResult<T>) -> WeightMatrix<T> {
+        let n = self.n();
+        assert!(result.row_duals.len() == n && result.column_duals.len() == n);
+        let validity = self.validity();
+        let data = (0..n * n)
+            .map(|index| {
+                let pos = Position {
+                    row: index / n,
+                    column: index % n,
+                };
+                if validity[index] {
+                    result.reduced_cost(self, pos)
+                } else {
+                    self.element_at(pos)
+                }
+            })
+            .collect();
+        WeightMatrix::from_row_vec_with_validity(n, data, &validity)
+    }
+}
+
+#[test]
+fn test_dual_weights() {
+    use crate::{generators, WeightMatrix};
+
+    for seed in 0..10 {
+        let base = generators::uniform(10, 0..1000, seed);
+        let weights = WeightMatrix::from_fn(10, |(row, column)| {
+            base.element_at(Position { row, column }) as i64
+        });
+        let original = weights.clone();
+        let expected = solve_assignment(&mut weights.clone()).unwrap();
+        let cost = |m: &[Position]| m.iter().map(|&pos| weights.element_at(pos)).sum::<i64>();
+        assert_eq!(
+            cost(&expected),
+            cost(&solve_assignment_immutable(&weights).unwrap())
+        );
+        assert_eq!(original.as_slice(), weights.as_slice());
+
+        // the potentials are a feasible dual solution, tight on the matching.
+        let mut duals = DualWeights::new(&weights);
+        let matching = solve_assignment(&mut duals).unwrap();
+        for row in 0..10 {
+            for column in 0..10 {
+                let pos = Position { row, column };
+                let reduced =
+                    weights.element_at(pos) - duals.row_duals()[row] - duals.column_duals()[column];
+                assert!(reduced >= 0);
+                if matching.contains(&pos) {
+                    assert_eq!(0, reduced);
+                }
+            }
+        }
+        let dual_cost: i64 =
+            duals.row_duals().iter().sum::<i64>() + duals.column_duals().iter().sum::<i64>();
+        assert_eq!(cost(&matching), dual_cost);
+    }
+}
+
+#[test]
+fn test_solve_assignment_with_duals() {
+    use crate::generators;
+
+    let base = generators::uniform(12, 0..1000, 4);
+    let weights = WeightMatrix::from_fn(12, |(row, column)| {
+        base.element_at(Position { row, column }) as i64
+    });
+    let result = solve_assignment_with_duals(&weights).unwrap();
+    let expected = solve_assignment(&mut weights.clone()).unwrap();
+    let cost = |m: &[Position]| m.iter().map(|&pos| weights.element_at(pos)).sum::<i64>();
+    assert_eq!(cost(&expected), result.cost);
+    assert_eq!(result.cost, result.dual_objective());
+
+    let reduced = weights.reduced_costs(&result);
+    for row in 0..12 {
+        for column in 0..12 {
+            let pos = Position { row, column };
+            assert!(reduced.element_at(pos) >= 0);
+            assert_eq!(result.reduced_cost(&weights, pos), reduced.element_at(pos));
+        }
+    }
+    assert!(result
+        .positions
+        .iter()
+        .all(|&pos| reduced.element_at(pos) == 0));
+
+    let inf = f64::INFINITY;
+    let weights = WeightMatrix::from_row_vec(2, vec![1.0, inf, 2.5, 1.0]);
+    let result = solve_assignment_with_duals(&weights).unwrap();
+    assert_eq!(2.0, result.cost);
+    let reduced = weights.reduced_costs(&result);
+    assert_eq!(vec![true, false, true, true], reduced.validity());
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_assignment_with_duals(&WeightMatrix::from_row_vec(2, vec![1.0, inf, 1.0, inf]))
+    );
+}
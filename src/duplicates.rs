@@ -0,0 +1,191 @@
+//! Collapsing duplicate rows and columns.
+//!
+//! Identical rows are interchangeable in any assignment, and so are
+//! identical columns. Matrices from discretized problems often have many of
+//! them, e.g. several workers of the same kind. `solve_assignment_dedup`
+//! collapses each group of identical rows (columns) into one source (target)
+//! with the size of the group as its mass, solves the much smaller transport
+//! problem with `transport::transport_exact`, and expands its integral plan
+//! back into an assignment.
+use crate::restricted::Restricted;
+use crate::transport::transport_exact;
+use crate::{solve_assignment, Error, Position, WeightNum, Weights};
+use std::collections::HashMap;
+
+/// The groups of identical rows and columns, each ascending and ordered by
+/// their first element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateGroups {
+    pub rows: Vec<Vec<usize>>,
+    pub columns: Vec<Vec<usize>>,
+}
+
+impl DuplicateGroups {
+    /// Whether any row or column has a duplicate.
+    pub fn has_duplicates(&self) -> bool {
+        self.rows.len() < self.rows.iter().map(Vec::len).sum()
+            || self.columns.len() < self.columns.iter().map(Vec::len).sum()
+    }
+}
+
+/// The value of a cell, `None` if invalid.
+fn cell<W: Weights>(weights: &W, row: usize, column: usize) -> Option<W::T> {
+    let pos = Position { row, column };
+    if weights.is_valid_at(pos) {
+        Some(weights.element_at(pos))
+    } else {
+        None
+    }
+}
+
+/// Groups the lines `0..n` for which `line(i)` is equal. Lines are bucketed
+/// by a hash of their values and compared exactly within a bucket.
+fn group<T, F>(n: usize, line: F) -> Vec<Vec<usize>>
+where
+    T: WeightNum,
+    F: Fn(usize) -> Vec<Option<T>>,
+{
+    let key = |values: &[Option<T>]| -> Vec<Option<u64>> {
+        values
+            .iter()
+            .map(|value| value.map(|v| v.to_f64().map_or(0, f64::to_bits)))
+            .collect()
+    };
+    let mut buckets: HashMap<Vec<Option<u64>>, Vec<usize>> = HashMap::new();
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for i in 0..n {
+        let values = line(i);
+        let bucket = buckets.entry(key(&values)).or_default();
+        match bucket.iter().find(|&&g| line(groups[g][0]) == values) {
+            Some(&g) => groups[g].push(i),
+            None => {
+                bucket.push(groups.len());
+                groups.push(vec![i]);
+            }
+        }
+    }
+    groups
+}
+
+/// Finds the groups of identical rows and columns, comparing the values and
+/// validity of their cells.
+pub fn find_duplicates<W>(weights: &W) -> DuplicateGroups
+where
+    W: Weights,
+{
+    let n = weights.n();
+    DuplicateGroups {
+        rows: group(n, |row| {
+            (0..n).map(|column| cell(weights, row, column)).collect()
+        }),
+        columns: group(n, |column| {
+            (0..n).map(|row| cell(weights, row, column)).collect()
+        }),
+    }
+}
+
+/// Solves the assignment problem on the collapsed duplicate rows and
+/// columns. Without duplicates, solves a copy of `weights` directly.
+/// `weights` is not modified.
+///
+/// # Panics
+///
+/// If there are duplicates and a valid cell can't be converted to `f64`.
+pub fn solve_assignment_dedup<W>(weights: &W) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+{
+    let n = weights.n();
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+    let groups = find_duplicates(weights);
+    if !groups.has_duplicates() {
+        return solve_assignment(&mut Restricted::from_weights(weights));
+    }
+
+    let supply: Vec<f64> = groups.rows.iter().map(|g| g.len() as f64).collect();
+    let demand: Vec<f64> = groups.columns.iter().map(|g| g.len() as f64).collect();
+    let mut costs = Vec::with_capacity(supply.len() * demand.len());
+    for rows in &groups.rows {
+        for columns in &groups.columns {
+            costs.push(
+                cell(weights, rows[0], columns[0]).map_or(f64::INFINITY, |c| {
+                    c.to_f64()
+                        .expect("collapsing duplicates requires weights convertible to f64")
+                }),
+            );
+        }
+    }
+    let plan = transport_exact(&supply, &demand, &costs)?;
+
+    // hand out the rows and columns of each group in ascending order.
+    let mut next_column = vec![0; groups.columns.len()];
+    let mut matching = Vec::with_capacity(n);
+    for (g, rows) in groups.rows.iter().enumerate() {
+        let mut rows = rows.iter();
+        for (h, columns) in groups.columns.iter().enumerate() {
+            let flow = plan.flow(g, h).round() as usize;
+            for _ in 0..flow {
+                matching.push(Position {
+                    row: *rows.next().unwrap(),
+                    column: columns[next_column[h]],
+                });
+                next_column[h] += 1;
+            }
+        }
+    }
+    matching.sort_by_key(|pos| pos.row);
+    Ok(matching)
+}
+
+#[test]
+fn test_solve_assignment_dedup() {
+    use crate::{generators, WeightMatrix};
+
+    let inf = f64::INFINITY;
+    // rows 0 and 2 are equal, and so are columns 1 and 2.
+    let weights = WeightMatrix::from_row_vec(
+        3,
+        vec![
+            4.0, 1.0, 1.0, //
+            2.0, inf, inf, //
+            4.0, 1.0, 1.0, //
+        ],
+    );
+    let groups = find_duplicates(&weights);
+    assert_eq!(vec![vec![0, 2], vec![1]], groups.rows);
+    assert_eq!(vec![vec![0], vec![1, 2]], groups.columns);
+    assert_eq!(
+        Ok(vec![
+            Position { row: 0, column: 1 },
+            Position { row: 1, column: 0 },
+            Position { row: 2, column: 2 }
+        ]),
+        solve_assignment_dedup(&weights)
+    );
+
+    let cost = |w: &WeightMatrix<u32>, m: &[Position]| -> u32 {
+        m.iter().map(|&pos| w.element_at(pos)).sum()
+    };
+    for seed in 0..10 {
+        // few distinct rows and columns.
+        let base = generators::uniform(3, 0..20, seed);
+        let weights = WeightMatrix::from_fn(9, |(row, column)| {
+            base.element_at(Position {
+                row: row % 3,
+                column: column / 3,
+            })
+        });
+        assert!(find_duplicates(&weights).has_duplicates());
+        let expected = solve_assignment(&mut weights.clone()).unwrap();
+        let matching = solve_assignment_dedup(&weights).unwrap();
+        assert_eq!(cost(&weights, &expected), cost(&weights, &matching));
+    }
+
+    let weights = WeightMatrix::from_row_vec(2, vec![1.0, inf, 1.0, inf]);
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_assignment_dedup(&weights)
+    );
+}
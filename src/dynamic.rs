@@ -0,0 +1,89 @@
+//! `Weights` behind pointers, in particular trait objects.
+//!
+//! `Weights` is object safe, so cost providers loaded at runtime can be
+//! passed around as `Box<dyn Weights<T = f64>>` or `&mut dyn Weights<T =
+//! f64>`. The solvers take their weights by a generic type, which these
+//! forwarding implementations for `&mut W` and `Box<W>` fill with the trait
+//! object, e.g. `solve_assignment(&mut boxed)`. They also let slices be
+//! solved through `&mut [Vec<T>]`.
+use crate::{Position, Weights};
+use alloc::boxed::Box;
+
+macro_rules! impl_forwarding_weights {
+    ($pointer:ty) => {
+        impl<W: Weights + ?Sized> Weights for $pointer {
+            type T = W::T;
+
+            #[inline(always)]
+            fn n(&self) -> usize {
+                (**self).n()
+            }
+
+            #[inline(always)]
+            fn element_at(&self, pos: Position) -> W::T {
+                (**self).element_at(pos)
+            }
+
+            fn sub_min_of_each_row(&mut self) {
+                (**self).sub_min_of_each_row()
+            }
+
+            fn add_row(&mut self, row: usize, val: W::T) {
+                (**self).add_row(row, val)
+            }
+
+            fn sub_column(&mut self, col: usize, val: W::T) {
+                (**self).sub_column(col, val)
+            }
+
+            #[inline(always)]
+            fn is_element_zero(&self, pos: Position) -> bool {
+                (**self).is_element_zero(pos)
+            }
+
+            #[inline(always)]
+            fn is_valid_at(&self, pos: Position) -> bool {
+                (**self).is_valid_at(pos)
+            }
+
+            fn is_solvable(&self) -> bool {
+                (**self).is_solvable()
+            }
+
+            #[inline(always)]
+            unsafe fn element_at_unchecked(&self, pos: Position) -> W::T {
+                (**self).element_at_unchecked(pos)
+            }
+
+            #[inline(always)]
+            unsafe fn is_element_zero_unchecked(&self, pos: Position) -> bool {
+                (**self).is_element_zero_unchecked(pos)
+            }
+
+            #[inline(always)]
+            unsafe fn is_valid_at_unchecked(&self, pos: Position) -> bool {
+                (**self).is_valid_at_unchecked(pos)
+            }
+        }
+    };
+}
+
+impl_forwarding_weights!(&mut W);
+impl_forwarding_weights!(Box<W>);
+
+#[test]
+fn test_dyn_weights() {
+    use crate::{solve_assignment, WeightMatrix};
+
+    let c = vec![
+        250.0, 400.0, 350.0, 400.0, 600.0, 350.0, 200.0, 400.0, 250.0,
+    ];
+    let expected = solve_assignment(&mut WeightMatrix::from_row_vec(3, c.clone()));
+
+    let mut boxed: Box<dyn Weights<T = f64>> = Box::new(WeightMatrix::from_row_vec(3, c.clone()));
+    assert_eq!(expected, solve_assignment(&mut boxed));
+
+    let mut rows = vec![c[0..3].to_vec(), c[3..6].to_vec(), c[6..9].to_vec()];
+    let mut borrowed: &mut dyn Weights<T = f64> = &mut rows;
+    assert_eq!(expected, solve_assignment(&mut borrowed));
+}
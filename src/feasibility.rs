@@ -0,0 +1,96 @@
+use crate::restricted::Restricted;
+use crate::{solve_assignment, Error, Position, Weights};
+use alloc::vec::Vec;
+
+/// Solves the assignment problem, only allowing the cells for which
+/// `feasible` returns true, in addition to the valid cells of `weights`.
+///
+/// This allows feasibility rules which are cheap to evaluate but awkward to
+/// encode in the costs, and works for integer weights without an invalid
+/// value. `feasible` is called once per valid cell before solving.
+/// `weights` is not modified.
+pub fn solve_assignment_feasible<W, F>(weights: &W, feasible: F) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+    F: FnMut(Position) -> bool,
+{
+    let mut restricted = Restricted::from_weights_where(weights, feasible);
+    solve_assignment(&mut restricted)
+}
+
+/// Solves the assignment problem, forbidding the cells above the maximum
+/// cost of their row, e.g. a gate per track from its uncertainty.
+/// `weights` is not modified.
+///
+/// # Panics
+///
+/// If `row_gates` doesn't have one entry per row.
+pub fn solve_assignment_with_row_gates<W>(
+    weights: &W,
+    row_gates: &[W::T],
+) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+{
+    assert!(
+        row_gates.len() == weights.n(),
+        "one gate per row is required"
+    );
+    solve_assignment_feasible(weights, |pos| weights.element_at(pos) <= row_gates[pos.row])
+}
+
+#[test]
+fn test_solve_assignment_feasible() {
+    use crate::WeightMatrix;
+
+    let c = vec![250, 400, 350, 400, 600, 350, 200, 400, 250];
+    let weights: WeightMatrix<u32> = WeightMatrix::from_row_vec(3, c);
+
+    // the unconstrained optimum is (0, 1), (1, 2), (2, 0).
+    let matching =
+        solve_assignment_feasible(&weights, |pos| pos != Position { row: 0, column: 0 }).unwrap();
+    assert_eq!(
+        vec![
+            Position { row: 0, column: 1 },
+            Position { row: 1, column: 2 },
+            Position { row: 2, column: 0 }
+        ],
+        matching
+    );
+
+    let err = solve_assignment_feasible(&weights, |pos| pos.column != 2).unwrap_err();
+    assert_eq!(Error::MatrixNotSolvable, err);
+
+    let matching =
+        solve_assignment_feasible(&weights, |pos| pos != Position { row: 2, column: 0 }).unwrap();
+    assert_eq!(
+        vec![
+            Position { row: 0, column: 0 },
+            Position { row: 1, column: 2 },
+            Position { row: 2, column: 1 }
+        ],
+        matching
+    );
+}
+
+#[test]
+fn test_solve_assignment_with_row_gates() {
+    use crate::WeightMatrix;
+
+    let c = vec![250, 400, 350, 400, 600, 350, 200, 400, 250];
+    let weights: WeightMatrix<u32> = WeightMatrix::from_row_vec(3, c);
+
+    // row 0 only accepts (0, 0), which is outside of the optimum.
+    let matching = solve_assignment_with_row_gates(&weights, &[300, 600, 400]).unwrap();
+    assert_eq!(
+        vec![
+            Position { row: 0, column: 0 },
+            Position { row: 1, column: 2 },
+            Position { row: 2, column: 1 }
+        ],
+        matching
+    );
+
+    let err = solve_assignment_with_row_gates(&weights, &[u32::MAX, 300, u32::MAX]).unwrap_err();
+    assert_eq!(Error::MatrixNotSolvable, err);
+}
@@ -0,0 +1,165 @@
+//! Solving without a heap, for embedded targets without an allocator.
+//!
+//! `solve_assignment_fixed` keeps all of its state in arrays of a capacity
+//! `MAX_N` fixed at compile time, and solves any size `n <= MAX_N` chosen at
+//! runtime. It runs the same steps as `solve_assignment`, but augments along
+//! the alternating path while following it, so no path is stored. The
+//! weights mustn't allocate either, e.g. `[[T; N]; N]`.
+use crate::{Error, Position, Weights};
+
+const NONE: usize = usize::MAX;
+
+/// Like `solve_assignment`, returning the matching in a `heapless::Vec`.
+///
+/// # Panics
+///
+/// If `weights` is larger than `MAX_N`.
+pub fn solve_assignment_fixed<W, const MAX_N: usize>(
+    weights: &mut W,
+) -> Result<heapless::Vec<Position, MAX_N>, Error>
+where
+    W: Weights,
+{
+    let n = weights.n();
+    assert!(n <= MAX_N, "the weights are larger than MAX_N");
+    if !weights.is_solvable() {
+        return Err(Error::MatrixNotSolvable);
+    }
+
+    let mut star_in_row = [NONE; MAX_N];
+    let mut star_in_column = [NONE; MAX_N];
+    let mut prime_in_row = [NONE; MAX_N];
+    let mut row_covered = [false; MAX_N];
+    let mut column_covered = [false; MAX_N];
+    let is_zero = |weights: &W, row, column| {
+        let pos = Position { row, column };
+        weights.is_valid_at(pos) && weights.is_element_zero(pos)
+    };
+
+    // step 1 and 2.
+    weights.sub_min_of_each_row();
+    for (row, star) in star_in_row[..n].iter_mut().enumerate() {
+        let free_zero =
+            (0..n).find(|&column| star_in_column[column] == NONE && is_zero(weights, row, column));
+        if let Some(column) = free_zero {
+            *star = column;
+            star_in_column[column] = row;
+        }
+    }
+
+    loop {
+        // step 3.
+        let mut stars = 0;
+        for column in 0..n {
+            column_covered[column] = star_in_column[column] != NONE;
+            stars += column_covered[column] as usize;
+        }
+        if stars == n {
+            break;
+        }
+
+        // step 4 and 6, until an uncovered zero without a star in its row
+        // is primed.
+        let (mut row, mut column) = loop {
+            let found = (0..n)
+                .filter(|&column| !column_covered[column])
+                .flat_map(|column| (0..n).map(move |row| (row, column)))
+                .find(|&(row, column)| !row_covered[row] && is_zero(weights, row, column));
+            match found {
+                Some((row, column)) => {
+                    prime_in_row[row] = column;
+                    match star_in_row[row] {
+                        NONE => break (row, column),
+                        star => {
+                            row_covered[row] = true;
+                            column_covered[star] = false;
+                        }
+                    }
+                }
+                None => {
+                    let mut min = None;
+                    for row in (0..n).filter(|&row| !row_covered[row]) {
+                        for column in (0..n).filter(|&column| !column_covered[column]) {
+                            let pos = Position { row, column };
+                            if weights.is_valid_at(pos) {
+                                let elm = weights.element_at(pos);
+                                min = Some(match min {
+                                    Some(m) if m < elm => m,
+                                    _ => elm,
+                                });
+                            }
+                        }
+                    }
+                    let min = min.ok_or(Error::MatrixNotSolvable)?;
+                    for row in (0..n).filter(|&row| row_covered[row]) {
+                        weights.add_row(row, min);
+                    }
+                    for column in (0..n).filter(|&column| !column_covered[column]) {
+                        weights.sub_column(column, min);
+                    }
+                }
+            }
+        };
+
+        // step 5: star the primes of the path and unstar its stars.
+        loop {
+            let star = star_in_column[column];
+            star_in_row[row] = column;
+            star_in_column[column] = row;
+            if star == NONE {
+                break;
+            }
+            row = star;
+            column = prime_in_row[row];
+        }
+        prime_in_row[..n].iter_mut().for_each(|prime| *prime = NONE);
+        row_covered[..n]
+            .iter_mut()
+            .for_each(|covered| *covered = false);
+    }
+
+    Ok(star_in_row[..n]
+        .iter()
+        .enumerate()
+        .map(|(row, &column)| Position { row, column })
+        .collect())
+}
+
+#[test]
+fn test_solve_assignment_fixed() {
+    use crate::{generators, solve_assignment, WeightMatrix};
+
+    for n in 1..=8 {
+        for seed in 0..5 {
+            let weights = generators::uniform(n, 0..100, seed);
+            let cost = |m: &[Position]| m.iter().map(|&pos| weights.element_at(pos)).sum::<u32>();
+            let expected = cost(&solve_assignment(&mut weights.clone()).unwrap());
+            let mut fixed = weights.clone();
+            let found = solve_assignment_fixed::<_, 8>(&mut fixed).unwrap();
+            assert_eq!(expected, cost(&found));
+            if n == 8 {
+                let mut array = [[0u32; 8]; 8];
+                for (row, values) in array.iter_mut().zip(weights.as_slice().chunks(8)) {
+                    row.copy_from_slice(values);
+                }
+                let found = solve_assignment_fixed::<_, 8>(&mut array).unwrap();
+                assert_eq!(expected, cost(&found));
+            }
+        }
+    }
+
+    let inf = f64::INFINITY;
+    let mut weights = [[1.0, inf], [2.0, inf]];
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_assignment_fixed::<_, 4>(&mut weights)
+    );
+    let mut weights = WeightMatrix::from_row_vec(2, vec![1.0, inf, 2.0, 3.0]);
+    assert_eq!(
+        &[
+            Position { row: 0, column: 0 },
+            Position { row: 1, column: 1 }
+        ],
+        &solve_assignment_fixed::<_, 4>(&mut weights).unwrap()[..]
+    );
+}
@@ -0,0 +1,227 @@
+use alloc::collections::BinaryHeap;
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+
+/// Min-cost flow network solved by successive shortest paths with Dijkstra
+/// and node potentials. Capacities and costs are `f64`; integral capacities
+/// yield integral flows as long as they stay below 2^53.
+#[derive(Debug, Clone)]
+pub(crate) struct FlowNetwork {
+    edges: Vec<Edge>,
+    adjacency: Vec<Vec<usize>>,
+    tolerance: f64,
+}
+
+#[derive(Debug, Clone)]
+struct Edge {
+    to: usize,
+    capacity: f64,
+    cost: f64,
+    flow: f64,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Entry {
+    distance: f64,
+    node: usize,
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Entry) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    // reversed, so that the `BinaryHeap` pops the smallest distance first.
+    fn cmp(&self, other: &Entry) -> Ordering {
+        other
+            .distance
+            .partial_cmp(&self.distance)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+impl FlowNetwork {
+    /// A network of `nodes` nodes. Residual capacities below `tolerance` are
+    /// treated as zero.
+    pub(crate) fn new(nodes: usize, tolerance: f64) -> FlowNetwork {
+        FlowNetwork {
+            edges: Vec::new(),
+            adjacency: vec![Vec::new(); nodes],
+            tolerance,
+        }
+    }
+
+    /// Adds an edge and returns its index, for use with `flow`.
+    pub(crate) fn add_edge(&mut self, from: usize, to: usize, capacity: f64, cost: f64) -> usize {
+        let index = self.edges.len();
+        self.adjacency[from].push(index);
+        self.edges.push(Edge {
+            to,
+            capacity,
+            cost,
+            flow: 0.0,
+        });
+        self.adjacency[to].push(index + 1);
+        self.edges.push(Edge {
+            to: from,
+            capacity: 0.0,
+            cost: -cost,
+            flow: 0.0,
+        });
+        index
+    }
+
+    #[inline]
+    pub(crate) fn flow(&self, edge: usize) -> f64 {
+        self.edges[edge].flow
+    }
+
+    #[inline]
+    fn residual(&self, edge: usize) -> f64 {
+        self.edges[edge].capacity - self.edges[edge].flow
+    }
+
+    fn push(&mut self, edge: usize, amount: f64) {
+        self.edges[edge].flow += amount;
+        self.edges[edge ^ 1].flow -= amount;
+    }
+
+    /// Sends up to `amount` units from `source` to `sink` at minimum cost and
+    /// returns the amount actually sent together with its cost.
+    pub(crate) fn min_cost_flow(&mut self, source: usize, sink: usize, amount: f64) -> (f64, f64) {
+        self.successive_shortest_paths(source, sink, amount, false)
+    }
+
+    /// Sends flow from `source` to `sink` as long as that lowers the cost,
+    /// i.e. finds the flow of any amount with the minimum cost.
+    pub(crate) fn min_cost_flow_any_amount(&mut self, source: usize, sink: usize) -> (f64, f64) {
+        self.successive_shortest_paths(source, sink, f64::INFINITY, true)
+    }
+
+    fn successive_shortest_paths(
+        &mut self,
+        source: usize,
+        sink: usize,
+        amount: f64,
+        negative_only: bool,
+    ) -> (f64, f64) {
+        let nodes = self.adjacency.len();
+        let mut potential = self.initial_potentials(source);
+        let mut distance = vec![f64::INFINITY; nodes];
+        let mut parent: Vec<Option<usize>> = vec![None; nodes];
+        let mut heap = BinaryHeap::new();
+        let (mut sent, mut cost) = (0.0, 0.0);
+
+        while amount - sent > self.tolerance {
+            distance.iter_mut().for_each(|d| *d = f64::INFINITY);
+            parent.iter_mut().for_each(|p| *p = None);
+            distance[source] = 0.0;
+            heap.push(Entry {
+                distance: 0.0,
+                node: source,
+            });
+            while let Some(Entry { distance: d, node }) = heap.pop() {
+                if d > distance[node] {
+                    continue;
+                }
+                for &edge in &self.adjacency[node] {
+                    if self.residual(edge) <= self.tolerance {
+                        continue;
+                    }
+                    let to = self.edges[edge].to;
+                    let reduced = self.edges[edge].cost + potential[node] - potential[to];
+                    // clamp rounding noise, reduced costs are non-negative.
+                    let next = d + reduced.max(0.0);
+                    if next < distance[to] {
+                        distance[to] = next;
+                        parent[to] = Some(edge);
+                        heap.push(Entry {
+                            distance: next,
+                            node: to,
+                        });
+                    }
+                }
+            }
+            if distance[sink].is_infinite() {
+                break;
+            }
+            for (p, &d) in potential.iter_mut().zip(distance.iter()) {
+                if d.is_finite() {
+                    *p += d;
+                }
+            }
+            // the cost of the path, which never decreases from path to path.
+            if negative_only && potential[sink] - potential[source] >= 0.0 {
+                break;
+            }
+
+            let mut bottleneck = amount - sent;
+            let mut node = sink;
+            while let Some(edge) = parent[node] {
+                bottleneck = bottleneck.min(self.residual(edge));
+                node = self.edges[edge ^ 1].to;
+            }
+            let mut node = sink;
+            while let Some(edge) = parent[node] {
+                self.push(edge, bottleneck);
+                cost += bottleneck * self.edges[edge].cost;
+                node = self.edges[edge ^ 1].to;
+            }
+            sent += bottleneck;
+        }
+        (sent, cost)
+    }
+
+    /// Shortest path distances from `source` over edges with residual
+    /// capacity (Bellman-Ford), so that negative costs are supported.
+    fn initial_potentials(&self, source: usize) -> Vec<f64> {
+        let nodes = self.adjacency.len();
+        let mut potential = vec![f64::INFINITY; nodes];
+        potential[source] = 0.0;
+        for _ in 0..nodes {
+            let mut changed = false;
+            for node in 0..nodes {
+                if potential[node].is_infinite() {
+                    continue;
+                }
+                for &edge in &self.adjacency[node] {
+                    if self.residual(edge) <= self.tolerance {
+                        continue;
+                    }
+                    let to = self.edges[edge].to;
+                    let next = potential[node] + self.edges[edge].cost;
+                    if next < potential[to] {
+                        potential[to] = next;
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        potential
+            .into_iter()
+            .map(|p| if p.is_finite() { p } else { 0.0 })
+            .collect()
+    }
+}
+
+#[test]
+fn test_min_cost_flow() {
+    // two parallel paths from 0 to 3, the cheaper one with capacity 1.
+    let mut network = FlowNetwork::new(4, 1e-9);
+    let cheap = network.add_edge(0, 1, 1.0, 1.0);
+    network.add_edge(1, 3, 1.0, -2.0);
+    let expensive = network.add_edge(0, 2, 5.0, 3.0);
+    network.add_edge(2, 3, 5.0, 0.0);
+
+    assert_eq!((3.0, -1.0 + 2.0 * 3.0), network.min_cost_flow(0, 3, 3.0));
+    assert_eq!(1.0, network.flow(cheap));
+    assert_eq!(2.0, network.flow(expensive));
+}
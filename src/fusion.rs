@@ -0,0 +1,153 @@
+//! Gated fusion of several cost cues, as in DeepSORT.
+//!
+//! Trackers score each pair of a track and a detection by several cues,
+//! e.g. motion (Mahalanobis distance) and appearance (embedding distance).
+//! The fused cost is a weighted sum of the cues, and each cue has a gate: a
+//! pair whose cost in any cue is above that cue's gate, or is invalid in any
+//! cue, is forbidden, whatever its weight. A cue with weight 0 only gates.
+//!
+//! `Fusion` collects the cues and either materializes the fused matrix or,
+//! as it is `Costs`, is solved lazily through `DualWeights`, e.g. with
+//! `solve_assignment_immutable`.
+use crate::dual::Costs;
+use crate::{Position, WeightMatrix};
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone)]
+struct Cue<'a, W> {
+    costs: &'a W,
+    weight: f64,
+    gate: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Fusion<'a, W> {
+    n: usize,
+    cues: Vec<Cue<'a, W>>,
+}
+
+impl<'a, W> Fusion<'a, W>
+where
+    W: Costs<T = f64>,
+{
+    /// Fuses `n x n` cues. Without cues every pair costs 0.
+    pub fn new(n: usize) -> Fusion<'a, W> {
+        Fusion {
+            n,
+            cues: Vec::new(),
+        }
+    }
+
+    /// Adds `weight * costs` to the fused cost, forbidding the pairs above
+    /// `gate`. Use `f64::INFINITY` for no gate.
+    ///
+    /// # Panics
+    ///
+    /// If `costs` isn't `n x n`, or `weight` is negative or not finite.
+    pub fn cue(mut self, costs: &'a W, weight: f64, gate: f64) -> Fusion<'a, W> {
+        assert!(costs.n() == self.n, "all cues must be n x n");
+        assert!(
+            weight >= 0.0 && weight.is_finite(),
+            "the weight must be finite and not negative"
+        );
+        self.cues.push(Cue {
+            costs,
+            weight,
+            gate,
+        });
+        self
+    }
+
+    /// The fused cost of a pair, `f64::INFINITY` if it's forbidden.
+    pub fn fused_at(&self, pos: Position) -> f64 {
+        let mut sum = 0.0;
+        for cue in &self.cues {
+            if !cue.costs.is_valid_at(pos) {
+                return f64::INFINITY;
+            }
+            let cost = cue.costs.element_at(pos);
+            if cost.is_nan() || cost > cue.gate {
+                return f64::INFINITY;
+            }
+            sum += cue.weight * cost;
+        }
+        sum
+    }
+
+    /// The fused matrix, with forbidden pairs infinite.
+    pub fn materialize(&self) -> WeightMatrix<f64> {
+        WeightMatrix::from_fn(self.n, |(row, column)| {
+            self.fused_at(Position { row, column })
+        })
+    }
+}
+
+impl<'a, W> Costs for Fusion<'a, W>
+where
+    W: Costs<T = f64>,
+{
+    type T = f64;
+
+    #[inline(always)]
+    fn n(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    fn element_at(&self, pos: Position) -> f64 {
+        self.fused_at(pos)
+    }
+
+    #[inline]
+    fn is_valid_at(&self, pos: Position) -> bool {
+        self.fused_at(pos).is_finite()
+    }
+}
+
+#[test]
+fn test_fusion() {
+    use crate::{generators, solve_assignment, solve_assignment_immutable};
+
+    let to_f64 = |w: &WeightMatrix<u32>| {
+        WeightMatrix::from_fn(w.n(), |(row, column)| {
+            w.element_at(Position { row, column }) as f64
+        })
+    };
+    for seed in 0..10 {
+        let motion = to_f64(&generators::uniform(8, 0..100, seed));
+        let appearance = to_f64(&generators::uniform(8, 0..100, seed + 100));
+        let fusion = Fusion::new(8)
+            .cue(&motion, 0.98, 90.0)
+            .cue(&appearance, 0.02, f64::INFINITY);
+        let fused = fusion.materialize();
+        for row in 0..8 {
+            for column in 0..8 {
+                let pos = Position { row, column };
+                let expected = if motion.element_at(pos) > 90.0 {
+                    f64::INFINITY
+                } else {
+                    0.98 * motion.element_at(pos) + 0.02 * appearance.element_at(pos)
+                };
+                assert_eq!(expected, fused.element_at(pos));
+            }
+        }
+
+        let cost = |m: &[Position]| m.iter().map(|&pos| fused.element_at(pos)).sum::<f64>();
+        let expected = solve_assignment(&mut fused.clone()).map(|m| cost(&m));
+        let found = solve_assignment_immutable(&fusion).map(|m| cost(&m));
+        match (expected, found) {
+            (Ok(expected), Ok(found)) => assert!((expected - found).abs() < 1e-9),
+            (expected, found) => assert_eq!(expected.is_err(), found.is_err()),
+        }
+    }
+
+    // a cue with weight 0 only gates.
+    let inf = f64::INFINITY;
+    let gate = WeightMatrix::from_row_vec(2, vec![1.0, inf, 1.0, 1.0]);
+    let costs = WeightMatrix::from_row_vec(2, vec![3.0, 1.0, 2.0, 5.0]);
+    let fused = Fusion::new(2)
+        .cue(&gate, 0.0, inf)
+        .cue(&costs, 1.0, 4.0)
+        .materialize();
+    assert_eq!(&[3.0, inf, 2.0, inf], fused.as_slice());
+}
@@ -0,0 +1,226 @@
+//! Generalized assignment problem (GAP).
+//!
+//! Assigns each task to exactly one agent, minimizing the total cost, such
+//! that the sizes of the tasks of an agent don't exceed its capacity. Unlike
+//! the assignment problem, an agent may take several tasks.
+//!
+//! GAP is NP-hard, so `solve_gap` is a heuristic: a Lagrangian relaxation
+//! of the capacities prices each agent, and in each iteration the tasks are
+//! handed out in rounds, solving an assignment problem of the agents against
+//! the remaining tasks at the current prices per round. The relaxation also
+//! yields a lower bound, which tells how far the result is from optimal at
+//! most.
+//!
+//! Costs are given in row-major order, one row per agent. Infinite (or NaN)
+//! costs forbid assigning a task to an agent.
+use crate::{solve_assignment, Error, WeightMatrix};
+use alloc::{vec, vec::Vec};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GapOptions {
+    /// Number of subgradient iterations on the agent prices.
+    pub iterations: usize,
+    /// Initial subgradient step, relative to the ratio of the average cost
+    /// to the average size.
+    pub step: f64,
+}
+
+impl Default for GapOptions {
+    fn default() -> Self {
+        GapOptions {
+            iterations: 50,
+            step: 1.0,
+        }
+    }
+}
+
+/// A feasible solution of a GAP instance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GapSolution {
+    /// The agent of each task.
+    pub agents: Vec<usize>,
+    pub cost: f64,
+    /// A lower bound on the cost of any feasible solution.
+    pub lower_bound: f64,
+}
+
+/// Solves the GAP instance heuristically. Returns the best solution found
+/// within `options.iterations` iterations, or `Error::MatrixNotSolvable` if
+/// none was.
+///
+/// # Panics
+///
+/// If the dimensions don't match or a size or capacity is negative.
+pub fn solve_gap(
+    costs: &[f64],
+    capacities: &[f64],
+    sizes: &[f64],
+    options: &GapOptions,
+) -> Result<GapSolution, Error> {
+    let (agents, tasks) = (capacities.len(), sizes.len());
+    assert!(costs.len() == agents * tasks);
+    assert!(capacities.iter().chain(sizes.iter()).all(|&x| x >= 0.0));
+    let cost = |agent: usize, task: usize| -> Option<f64> {
+        let c = costs[agent * tasks + task];
+        if c.is_finite() && sizes[task] <= capacities[agent] {
+            Some(c)
+        } else {
+            None
+        }
+    };
+
+    let finite = costs.iter().filter(|c| c.is_finite());
+    let mean_cost = finite.clone().map(|c| c.abs()).sum::<f64>() / finite.count().max(1) as f64;
+    let mean_size = sizes.iter().sum::<f64>() / tasks.max(1) as f64;
+    let scale = if mean_size > 0.0 {
+        mean_cost.max(1.0) / mean_size
+    } else {
+        0.0
+    };
+
+    let mut prices = vec![0.0; agents];
+    let mut best: Option<GapSolution> = None;
+    let mut lower_bound = f64::NEG_INFINITY;
+    for iteration in 0..options.iterations.max(1) {
+        // the relaxed problem: each task goes to its cheapest agent at the
+        // current prices, ignoring the capacities.
+        let mut bound = -prices
+            .iter()
+            .zip(capacities.iter())
+            .map(|(p, c)| p * c)
+            .sum::<f64>();
+        let mut loads = vec![0.0; agents];
+        for (task, &size) in sizes.iter().enumerate() {
+            let cheapest = (0..agents)
+                .filter_map(|agent| cost(agent, task).map(|c| (agent, c + prices[agent] * size)))
+                .fold(None, |min: Option<(usize, f64)>, (agent, c)| match min {
+                    Some((_, m)) if m <= c => min,
+                    _ => Some((agent, c)),
+                });
+            match cheapest {
+                Some((agent, c)) => {
+                    bound += c;
+                    loads[agent] += size;
+                }
+                None => return Err(Error::MatrixNotSolvable),
+            }
+        }
+        lower_bound = lower_bound.max(bound);
+
+        if let Some(agents_of_tasks) = hand_out(&cost, &prices, capacities, sizes) {
+            let total = agents_of_tasks
+                .iter()
+                .enumerate()
+                .map(|(task, &agent)| costs[agent * tasks + task])
+                .sum();
+            if best.as_ref().is_none_or(|b| total < b.cost) {
+                best = Some(GapSolution {
+                    agents: agents_of_tasks,
+                    cost: total,
+                    lower_bound,
+                });
+            }
+        }
+        if let Some(ref b) = best {
+            if b.cost - lower_bound <= 1e-9 * b.cost.abs().max(1.0) {
+                break;
+            }
+        }
+
+        let step = options.step * scale / (iteration + 1) as f64;
+        for ((price, load), capacity) in prices.iter_mut().zip(loads).zip(capacities) {
+            *price = (*price + step * (load - capacity)).max(0.0);
+        }
+    }
+    best.map(|b| GapSolution { lower_bound, ..b })
+        .ok_or(Error::MatrixNotSolvable)
+}
+
+/// Hands out the tasks in rounds, in each of which every agent takes at
+/// most one of the remaining tasks that still fits, maximizing the number
+/// of tasks handed out and then minimizing their priced cost. Returns `None`
+/// if a round makes no progress.
+fn hand_out<F>(cost: &F, prices: &[f64], capacities: &[f64], sizes: &[f64]) -> Option<Vec<usize>>
+where
+    F: Fn(usize, usize) -> Option<f64>,
+{
+    let agents = capacities.len();
+    let mut remaining = capacities.to_vec();
+    let mut agent_of: Vec<Option<usize>> = vec![None; sizes.len()];
+    let mut open: Vec<usize> = (0..sizes.len()).collect();
+    while !open.is_empty() {
+        let priced = |agent: usize, task: usize| {
+            cost(agent, task)
+                .filter(|_| sizes[task] <= remaining[agent])
+                .map(|c| c + prices[agent] * sizes[task])
+        };
+        let (mut lo, mut hi) = (f64::INFINITY, f64::NEG_INFINITY);
+        for agent in 0..agents {
+            for &task in &open {
+                if let Some(c) = priced(agent, task) {
+                    lo = lo.min(c);
+                    hi = hi.max(c);
+                }
+            }
+        }
+        if lo > hi {
+            return None;
+        }
+        // a dummy column per agent lets it idle, a dummy row per task leaves
+        // it open. Leaving a task open costs more than any difference in the
+        // priced costs.
+        let m = agents + open.len();
+        let penalty = m as f64 * (hi - lo) + 1.0;
+        let mut weights = WeightMatrix::from_fn(m, |(row, column)| {
+            match (row < agents, column < open.len()) {
+                (true, true) => priced(row, open[column]).map_or(f64::INFINITY, |c| c - lo),
+                (false, true) => penalty,
+                _ => 0.0,
+            }
+        });
+        let matching = solve_assignment(&mut weights).ok()?;
+
+        let handed: Vec<(usize, usize)> = matching
+            .iter()
+            .filter(|pos| pos.row < agents && pos.column < open.len())
+            .map(|pos| (pos.row, open[pos.column]))
+            .filter(|&(agent, task)| priced(agent, task).is_some())
+            .collect();
+        if handed.is_empty() {
+            return None;
+        }
+        for (agent, task) in handed {
+            agent_of[task] = Some(agent);
+            remaining[agent] -= sizes[task];
+        }
+        open.retain(|&task| agent_of[task].is_none());
+    }
+    agent_of.into_iter().collect()
+}
+
+#[test]
+fn test_solve_gap() {
+    // agent 0 is cheaper for everything, but can only take the two small
+    // tasks or the big one.
+    let costs = [1.0, 1.0, 1.0, 5.0, 5.0, 5.0];
+    let solution = solve_gap(
+        &costs,
+        &[2.0, 2.0],
+        &[1.0, 1.0, 2.0],
+        &GapOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(vec![0, 0, 1], solution.agents);
+    assert_eq!(7.0, solution.cost);
+    assert!(solution.lower_bound <= solution.cost + 1e-9);
+
+    // each agent takes one task, like the assignment problem.
+    let costs = [4.0, 1.0, 2.0, 3.0];
+    let solution = solve_gap(&costs, &[1.0, 1.0], &[1.0, 1.0], &GapOptions::default()).unwrap();
+    assert_eq!(vec![1, 0], solution.agents);
+
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_gap(&costs, &[1.0, 1.0], &[1.5, 1.0], &GapOptions::default())
+    );
+}
@@ -0,0 +1,105 @@
+//! Instance generators for benchmarking, fuzzing and regression tests.
+//!
+//! All random generators are deterministic for a given `seed`, on every
+//! platform.
+#[cfg(any(feature = "std", feature = "libm"))]
+use crate::float;
+use crate::WeightMatrix;
+#[cfg(any(feature = "std", feature = "libm"))]
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// SplitMix64 pseudo random number generator.
+#[derive(Debug, Clone)]
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `[0, 1)`.
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform value in `range`. The range must not be empty.
+    pub(crate) fn next_in(&mut self, range: Range<u32>) -> u32 {
+        assert!(range.start < range.end);
+        let span = u64::from(range.end - range.start);
+        range.start + (self.next_u64() % span) as u32
+    }
+}
+
+/// Uniformly distributed costs drawn from `range`.
+pub fn uniform(n: usize, range: Range<u32>, seed: u64) -> WeightMatrix<u32> {
+    let mut rng = Rng::new(seed);
+    WeightMatrix::from_fn(n, |_| rng.next_in(range.clone()))
+}
+
+/// Euclidean distances between two sets of `n` points drawn uniformly from
+/// the unit square. Row `i` corresponds to the `i`-th point of the first set,
+/// column `j` to the `j`-th point of the second set.
+#[cfg(any(feature = "std", feature = "libm"))]
+pub fn geometric(n: usize, seed: u64) -> WeightMatrix<f64> {
+    let mut rng = Rng::new(seed);
+    let mut points =
+        || -> Vec<(f64, f64)> { (0..n).map(|_| (rng.next_f64(), rng.next_f64())).collect() };
+    let sources = points();
+    let targets = points();
+    WeightMatrix::from_fn(n, |(row, column)| {
+        let (dx, dy) = (
+            sources[row].0 - targets[column].0,
+            sources[row].1 - targets[column].1,
+        );
+        float::sqrt(dx * dx + dy * dy)
+    })
+}
+
+/// The Machol–Wien matrix `c[i][j] = i * j`, a classical worst case for the
+/// Hungarian algorithm. Its optimal assignment is the anti-diagonal.
+pub fn machol_wien(n: usize) -> WeightMatrix<u32> {
+    WeightMatrix::from_fn(n, |(row, column)| (row * column) as u32)
+}
+
+/// Highly degenerate costs: every cell takes one of `distinct` values, so the
+/// matrix contains massive ties and many optimal assignments.
+pub fn degenerate(n: usize, distinct: u32, seed: u64) -> WeightMatrix<u32> {
+    uniform(n, 0..distinct.max(1), seed)
+}
+
+#[test]
+fn test_generators_are_deterministic() {
+    assert_eq!(
+        uniform(8, 0..100, 42).as_slice(),
+        uniform(8, 0..100, 42).as_slice()
+    );
+    assert_ne!(
+        uniform(8, 0..100, 42).as_slice(),
+        uniform(8, 0..100, 43).as_slice()
+    );
+    #[cfg(any(feature = "std", feature = "libm"))]
+    assert_eq!(geometric(8, 1).as_slice(), geometric(8, 1).as_slice());
+    assert!(degenerate(8, 2, 7).as_slice().iter().all(|&c| c < 2));
+}
+
+#[test]
+fn test_machol_wien_optimum() {
+    use crate::solve_assignment;
+
+    const N: usize = 6;
+    let mut weights = machol_wien(N);
+    let matching = solve_assignment(&mut weights).unwrap();
+    let cost: usize = matching.iter().map(|p| p.row * p.column).sum();
+    assert_eq!((0..N).map(|i| i * (N - 1 - i)).sum::<usize>(), cost);
+}
@@ -0,0 +1,135 @@
+//! Assignment of two point sets by Euclidean distance.
+//!
+//! Building the dense distance matrix of `n` points takes `O(n^2)` time and
+//! memory, although the optimal matching of spatial data almost always pairs
+//! points with one of their nearest neighbours. `solve_nearest_assignment`
+//! only considers the `k` nearest neighbours of each point on both sides,
+//! found with a kd-tree in `O(n log n)`, and solves the resulting sparse
+//! instance. If the candidates admit no complete matching, it falls back to
+//! the dense matrix.
+//!
+//! The result is optimal among the candidate pairs. It is optimal overall if
+//! the optimum only uses candidate pairs, which is almost always the case for
+//! a `k` of 5 to 10 unless the point sets are far apart.
+use crate::sparse::solve_rows;
+use crate::{solve_assignment, Error, Position, WeightMatrix};
+use kdtree::distance::squared_euclidean;
+use kdtree::KdTree;
+
+fn distance(a: &[f64], b: &[f64]) -> f64 {
+    squared_euclidean(a, b).sqrt()
+}
+
+/// The `k` nearest points of `to` for each point of `from`, as indices.
+fn nearest<P: AsRef<[f64]>>(from: &[P], to: &[P], k: usize) -> Vec<Vec<usize>> {
+    let dimensions = to.first().map_or(0, |point| point.as_ref().len());
+    let mut tree = KdTree::new(dimensions);
+    for (i, point) in to.iter().enumerate() {
+        tree.add(point.as_ref(), i)
+            .expect("points must be finite and of the same dimension");
+    }
+    from.iter()
+        .map(|point| {
+            tree.nearest(point.as_ref(), k, &squared_euclidean)
+                .expect("points must be finite and of the same dimension")
+                .into_iter()
+                .map(|(_, &i)| i)
+                .collect()
+        })
+        .collect()
+}
+
+/// Matches each point of `rows` to a point of `columns`, minimizing the sum
+/// of the Euclidean distances, considering only the pairs where one point is
+/// among the `k` nearest neighbours of the other.
+///
+/// # Panics
+///
+/// If `rows` and `columns` have different lengths, `k` is 0, or a point
+/// isn't finite or has a different dimension than the others.
+pub fn solve_nearest_assignment<P>(
+    rows: &[P],
+    columns: &[P],
+    k: usize,
+) -> Result<Vec<Position>, Error>
+where
+    P: AsRef<[f64]>,
+{
+    let n = rows.len();
+    assert!(
+        columns.len() == n,
+        "both point sets must have the same size"
+    );
+    assert!(k > 0, "at least one neighbour is required");
+
+    let mut candidates = nearest(rows, columns, k);
+    for (column, near_rows) in nearest(columns, rows, k).into_iter().enumerate() {
+        for row in near_rows {
+            candidates[row].push(column);
+        }
+    }
+    let cells: Vec<Vec<(usize, f64)>> = candidates
+        .into_iter()
+        .enumerate()
+        .map(|(row, mut near_columns)| {
+            near_columns.sort_unstable();
+            near_columns.dedup();
+            near_columns
+                .into_iter()
+                .map(|column| {
+                    let cost = distance(rows[row].as_ref(), columns[column].as_ref());
+                    (column, cost)
+                })
+                .collect()
+        })
+        .collect();
+
+    match solve_rows(n, &cells) {
+        Err(Error::MatrixNotSolvable) => {
+            solve_assignment(&mut WeightMatrix::from_fn(n, |(row, column)| {
+                distance(rows[row].as_ref(), columns[column].as_ref())
+            }))
+        }
+        result => result,
+    }
+}
+
+#[test]
+fn test_solve_nearest_assignment() {
+    use crate::generators::Rng;
+
+    let cost = |rows: &[[f64; 2]], columns: &[[f64; 2]], m: &[Position]| {
+        m.iter()
+            .map(|pos| distance(&rows[pos.row], &columns[pos.column]))
+            .sum::<f64>()
+    };
+    for seed in 0..5 {
+        let mut rng = Rng::new(seed);
+        let rows: Vec<[f64; 2]> = (0..50).map(|_| [rng.next_f64(), rng.next_f64()]).collect();
+        // the columns are the rows, moved a little.
+        let columns: Vec<[f64; 2]> = rows
+            .iter()
+            .map(|p| [p[0] + 0.01 * rng.next_f64(), p[1] + 0.01 * rng.next_f64()])
+            .collect();
+        let dense = solve_assignment(&mut WeightMatrix::from_fn(50, |(row, column)| {
+            distance(&rows[row], &columns[column])
+        }))
+        .unwrap();
+        let expected = cost(&rows, &columns, &dense);
+        for &k in &[5, 50] {
+            let found = solve_nearest_assignment(&rows, &columns, k).unwrap();
+            assert!((expected - cost(&rows, &columns, &found)).abs() < 1e-9);
+        }
+    }
+
+    // the first two rows are only candidates for the first column, so the
+    // candidates admit no matching and the dense matrix is solved.
+    let rows = [[0.0, 0.0], [0.1, 0.0], [5.0, 0.0], [5.1, 0.0]];
+    let columns = [[0.05, 0.0], [5.05, 0.0], [100.0, 0.0], [101.0, 0.0]];
+    let dense = solve_assignment(&mut WeightMatrix::from_fn(4, |(row, column)| {
+        distance(&rows[row], &columns[column])
+    }))
+    .unwrap();
+    let found = solve_nearest_assignment(&rows, &columns, 1).unwrap();
+    assert_eq!(cost(&rows, &columns, &dense), cost(&rows, &columns, &found));
+}
@@ -0,0 +1,161 @@
+use crate::{Position, Weights};
+use alloc::{vec, vec::Vec};
+
+/// Bipartite graph of the valid cells of a weight matrix in compressed row
+/// format, shared by the backends working on sparse instances.
+#[derive(Debug, Clone)]
+pub(crate) struct Graph<C> {
+    n: usize,
+    offsets: Vec<usize>,
+    columns: Vec<usize>,
+    costs: Vec<C>,
+}
+
+impl<C: Copy> Graph<C> {
+    /// Builds the graph of all valid cells of `weights`, converting their
+    /// values with `convert`.
+    pub(crate) fn from_weights<W, F>(weights: &W, mut convert: F) -> Graph<C>
+    where
+        W: Weights,
+        F: FnMut(W::T) -> C,
+    {
+        let n = weights.n();
+        let mut offsets = Vec::with_capacity(n + 1);
+        let mut columns = Vec::new();
+        let mut costs = Vec::new();
+        offsets.push(0);
+        for row in 0..n {
+            for column in 0..n {
+                let pos = Position { row, column };
+                if weights.is_valid_at(pos) {
+                    let elm = weights.element_at(pos);
+                    columns.push(column);
+                    costs.push(convert(elm));
+                }
+            }
+            offsets.push(columns.len());
+        }
+        Graph {
+            n,
+            offsets,
+            columns,
+            costs,
+        }
+    }
+
+    /// Builds the graph of `n` rows from the `(column, cost)` pairs of each
+    /// row, which must be sorted by column.
+    pub(crate) fn from_rows<I, R>(n: usize, rows: I) -> Graph<C>
+    where
+        I: IntoIterator<Item = R>,
+        R: IntoIterator<Item = (usize, C)>,
+    {
+        let mut offsets = Vec::with_capacity(n + 1);
+        let mut columns = Vec::new();
+        let mut costs = Vec::new();
+        offsets.push(0);
+        for row in rows {
+            for (column, cost) in row {
+                columns.push(column);
+                costs.push(cost);
+            }
+            offsets.push(columns.len());
+        }
+        assert!(offsets.len() == n + 1);
+        Graph {
+            n,
+            offsets,
+            columns,
+            costs,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn n(&self) -> usize {
+        self.n
+    }
+
+    /// The `(column, cost)` pairs of `row`.
+    #[inline]
+    pub(crate) fn edges(&self, row: usize) -> impl Iterator<Item = (usize, C)> + '_ {
+        let range = self.offsets[row]..self.offsets[row + 1];
+        self.columns[range.clone()]
+            .iter()
+            .cloned()
+            .zip(self.costs[range].iter().cloned())
+    }
+
+    pub(crate) fn costs(&self) -> &[C] {
+        &self.costs
+    }
+
+    /// Whether a perfect matching exists. Uses simple augmenting path search,
+    /// which is dominated by the cost of the actual solve.
+    pub(crate) fn has_perfect_matching(&self) -> bool {
+        self.perfect_matching().is_some()
+    }
+
+    /// Some perfect matching, as the column of each row, if one exists.
+    pub(crate) fn perfect_matching(&self) -> Option<Vec<usize>> {
+        let n = self.n;
+        let mut column_owner: Vec<Option<usize>> = vec![None; n];
+        let mut row_match = vec![0usize; n];
+        let mut visited = vec![usize::MAX; n];
+        let mut stack = Vec::new();
+        let mut parent = vec![0usize; n];
+
+        for root in 0..n {
+            // iterative DFS for an augmenting path starting at `root`.
+            stack.clear();
+            stack.push((root, self.offsets[root]));
+            let mut found = None;
+            while let Some(&mut (row, ref mut next)) = stack.last_mut() {
+                if *next == self.offsets[row + 1] {
+                    stack.pop();
+                    continue;
+                }
+                let column = self.columns[*next];
+                *next += 1;
+                if visited[column] == root {
+                    continue;
+                }
+                visited[column] = root;
+                parent[column] = row;
+                match column_owner[column] {
+                    None => {
+                        found = Some(column);
+                        break;
+                    }
+                    Some(owner) => stack.push((owner, self.offsets[owner])),
+                }
+            }
+            match found {
+                None => return None,
+                Some(mut column) => loop {
+                    let row = parent[column];
+                    let previous = row_match[row];
+                    row_match[row] = column;
+                    column_owner[column] = Some(row);
+                    if row == root {
+                        break;
+                    }
+                    column = previous;
+                },
+            }
+        }
+        Some(row_match)
+    }
+}
+
+#[test]
+fn test_has_perfect_matching() {
+    use crate::WeightMatrix;
+    let inf = f32::INFINITY;
+
+    let feasible = WeightMatrix::from_row_vec(3, vec![inf, 1.0, 1.0, inf, 1.0, inf, 1.0, 1.0, inf]);
+    assert!(Graph::from_weights(&feasible, |c| c).has_perfect_matching());
+
+    let infeasible =
+        WeightMatrix::from_row_vec(3, vec![inf, 1.0, inf, inf, 1.0, inf, 1.0, 1.0, 1.0]);
+    assert!(!Graph::from_weights(&infeasible, |c| c).has_perfect_matching());
+}
@@ -0,0 +1,170 @@
+//! Two-stage assignment of groups, e.g. teams to sites and then workers to
+//! shifts within each pair.
+//!
+//! The rows and columns are partitioned into groups. The first stage assigns
+//! row groups to column groups of the same size using aggregated costs, the
+//! second solves the assignment problem within each assigned pair of groups.
+//! This is much cheaper than solving the whole instance, but only optimal if
+//! the optimal assignment respects the groups.
+use crate::restricted::Restricted;
+use crate::{solve_assignment, Error, Position, WeightMatrix, WeightNum, Weights};
+use alloc::{vec, vec::Vec};
+
+/// How the cost of assigning a row group to a column group is aggregated
+/// from the cells between them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GroupCost {
+    /// The mean of the valid cells, times the group size.
+    Mean,
+    /// The minimum of the valid cells, times the group size. A lower bound
+    /// on the optimal cost within the groups.
+    Min,
+    /// The optimal cost within the groups, which takes one solve per pair of
+    /// groups of the same size.
+    Optimal,
+}
+
+/// Solves the assignment problem in two stages, first assigning each of
+/// `row_groups` to one of `column_groups` of the same size by their
+/// `group_cost`, then rows to columns within the assigned groups. Pairs of
+/// groups without a complete assignment between them are never assigned.
+/// `weights` is not modified.
+///
+/// # Panics
+///
+/// If the numbers of groups differ, the groups don't partition the rows and
+/// columns, or a valid cell can't be converted to `f64`.
+pub fn solve_assignment_hierarchical<W>(
+    weights: &W,
+    row_groups: &[Vec<usize>],
+    column_groups: &[Vec<usize>],
+    group_cost: GroupCost,
+) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+{
+    let n = weights.n();
+    assert!(row_groups.len() == column_groups.len());
+    assert!(is_partition(row_groups, n) && is_partition(column_groups, n));
+    let groups = row_groups.len();
+    if groups == 0 {
+        return Ok(Vec::new());
+    }
+    let to_f64 = |value: W::T| {
+        value
+            .to_f64()
+            .expect("solve_assignment_hierarchical requires weights convertible to f64")
+    };
+
+    // the solutions within the groups, where the group costs needed them.
+    let mut solved: Vec<Option<Vec<Position>>> = vec![None; groups * groups];
+    let mut aggregated = WeightMatrix::from_fn(groups, |(a, b)| {
+        let (rows, columns) = (&row_groups[a], &column_groups[b]);
+        if rows.len() != columns.len() {
+            return f64::INFINITY;
+        }
+        let cells = rows
+            .iter()
+            .flat_map(|&row| columns.iter().map(move |&column| Position { row, column }));
+        let valid = cells.filter(|&pos| weights.is_valid_at(pos));
+        let size = rows.len() as f64;
+        match group_cost {
+            GroupCost::Mean => {
+                let (sum, count) = valid.fold((0.0, 0), |(sum, count), pos| {
+                    (sum + to_f64(weights.element_at(pos)), count + 1)
+                });
+                if count == 0 {
+                    f64::INFINITY
+                } else {
+                    size * sum / count as f64
+                }
+            }
+            GroupCost::Min => {
+                size * valid
+                    .map(|pos| to_f64(weights.element_at(pos)))
+                    .fold(f64::INFINITY, f64::min)
+            }
+            GroupCost::Optimal => match solve_within(weights, rows, columns) {
+                Ok(matching) => {
+                    let cost = matching
+                        .iter()
+                        .map(|&pos| to_f64(weights.element_at(pos)))
+                        .sum();
+                    solved[a * groups + b] = Some(matching);
+                    cost
+                }
+                Err(_) => f64::INFINITY,
+            },
+        }
+    });
+
+    let mut matching = Vec::with_capacity(n);
+    for pair in solve_assignment(&mut aggregated)? {
+        let (a, b) = (pair.row, pair.column);
+        match solved[a * groups + b].take() {
+            Some(within) => matching.extend(within),
+            None => matching.extend(solve_within(weights, &row_groups[a], &column_groups[b])?),
+        }
+    }
+    matching.sort_by_key(|pos| pos.row);
+    Ok(matching)
+}
+
+fn is_partition(groups: &[Vec<usize>], n: usize) -> bool {
+    let mut seen = vec![false; n];
+    for &i in groups.iter().flatten() {
+        if i >= n || seen[i] {
+            return false;
+        }
+        seen[i] = true;
+    }
+    seen.into_iter().all(|s| s)
+}
+
+/// Solves the assignment of `rows` to `columns`, in global positions.
+fn solve_within<W>(weights: &W, rows: &[usize], columns: &[usize]) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+{
+    let mut sub = Restricted::submatrix(weights, rows, columns);
+    Ok(solve_assignment(&mut sub)?
+        .into_iter()
+        .map(|pos| Position {
+            row: rows[pos.row],
+            column: columns[pos.column],
+        })
+        .collect())
+}
+
+#[test]
+fn test_solve_assignment_hierarchical() {
+    // rows {0, 1} and {2, 3} are close to columns {2, 3} and {0, 1}.
+    let weights = WeightMatrix::from_row_vec(
+        4,
+        vec![
+            9, 9, 2, 1, //
+            9, 9, 1, 3, //
+            1, 5, 9, 9, //
+            4, 2, 9, 9,
+        ],
+    );
+    let row_groups = [vec![0, 1], vec![2, 3]];
+    let column_groups = [vec![0, 1], vec![2, 3]];
+    let expected = solve_assignment(&mut weights.clone()).unwrap();
+    for &group_cost in &[GroupCost::Mean, GroupCost::Min, GroupCost::Optimal] {
+        let matching =
+            solve_assignment_hierarchical(&weights, &row_groups, &column_groups, group_cost)
+                .unwrap();
+        assert_eq!(expected, matching);
+    }
+
+    // groups of different sizes are never assigned to each other.
+    let matching = solve_assignment_hierarchical(
+        &weights,
+        &[vec![0], vec![1, 2, 3]],
+        &[vec![0, 1, 2], vec![3]],
+        GroupCost::Mean,
+    )
+    .unwrap();
+    assert_eq!(3, matching[0].column);
+}
@@ -0,0 +1,55 @@
+//! The building blocks of the Kuhn-Munkres implementation, for implementing
+//! variants of it.
+//!
+//! This module is exempt from semver: its contents may change in any
+//! release. The steps expect to be driven in the order `solve_assignment`
+//! uses:
+//!
+//! * `step1` reduces each row by its minimum.
+//! * `step2` stars an independent set of zeros.
+//! * `step3` covers the columns of starred zeros, and is done once all `n`
+//!   columns are covered.
+//! * `step4` primes uncovered zeros, until it either finds one to start an
+//!   augmenting path from (go to `step5`) or none is left (go to `step6`).
+//! * `step5` augments along the alternating path of primes and stars.
+//! * `step6` shifts the smallest uncovered value to create new zeros.
+pub use crate::coverage::Coverage;
+pub use crate::mark_matrix::MarkMatrix;
+#[cfg(feature = "rayon")]
+pub use crate::steps::par_step4;
+pub use crate::steps::{
+    step1, step2, step3, step4, step4_round_robin, step4_row_major, step5, step6, step6_with,
+    ColumnMinima, Path, Step3, Step4, Step5, Step6,
+};
+
+#[test]
+fn test_drive_steps() {
+    use crate::{Position, WeightMatrix};
+
+    let mut weights: WeightMatrix<u32> = WeightMatrix::from_row_vec(2, vec![1, 2, 1, 3]);
+    let mut marks = MarkMatrix::new(2);
+    let mut cov = Coverage::new(2);
+    step1(&mut weights);
+    step2(&weights, &mut marks, &mut cov);
+    assert_eq!(
+        Step3::ContinueWithStep4 { star_count: 1 },
+        step3(&weights, &marks, &mut cov)
+    );
+    assert_eq!(
+        Step4::ContinueWithStep6,
+        step4(&weights, &mut marks, &mut cov)
+    );
+    assert_eq!(Ok(Step6::ContinueWithStep4), step6(&mut weights, &cov));
+    let z0_pos = match step4(&weights, &mut marks, &mut cov) {
+        Step4::ContinueWithStep5 { z0_pos } => z0_pos,
+        step => panic!("unexpected {:?}", step),
+    };
+    let mut path = Path::new();
+    assert_eq!(
+        Ok(Step5::ContinueWithStep3),
+        step5(&mut marks, &mut cov, z0_pos, &mut path)
+    );
+    assert_eq!(Step3::Done, step3(&weights, &marks, &mut cov));
+    assert!(marks.is_star(Position { row: 0, column: 1 }));
+    assert!(marks.is_star(Position { row: 1, column: 0 }));
+}
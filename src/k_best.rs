@@ -0,0 +1,334 @@
+//! Ranked assignments using Murty's algorithm.
+//!
+//! Murty's algorithm partitions the solution space of the best assignment
+//! found so far into subproblems, each pinning a prefix of its pairs and
+//! forbidding the next one, and solves every subproblem with the Hungarian
+//! algorithm. The subproblems are kept in a priority queue ordered by cost, so
+//! solutions come out in non-decreasing cost order. Ties are broken by the
+//! order in which subproblems were created, which makes the ranking
+//! deterministic.
+use crate::restricted::Restricted;
+use crate::{solve_assignment, Assignment, Error, Position, WeightNum, Weights};
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// A solved subproblem.
+#[derive(Debug, Clone)]
+struct Node<T> {
+    cost: T,
+    sequence: usize,
+    assignment: Assignment,
+    pinned: Vec<Position>,
+    forbidden: Vec<Position>,
+}
+
+impl<T: WeightNum> PartialEq for Node<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: WeightNum> Eq for Node<T> {}
+
+impl<T: WeightNum> PartialOrd for Node<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: WeightNum> Ord for Node<T> {
+    // reversed, so that the `BinaryHeap` pops the cheapest, oldest node first.
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .partial_cmp(&self.cost)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+/// A subproblem that still needs to be solved.
+type Subproblem = (Vec<Position>, Vec<Position>);
+
+/// The state of Murty's algorithm.
+#[derive(Debug, Clone)]
+pub(crate) struct Murty<T> {
+    base: Restricted<T>,
+    queue: BinaryHeap<Node<T>>,
+    sequence: usize,
+}
+
+impl<T: WeightNum> Murty<T> {
+    /// Solves the unconstrained problem. Fails if `weights` has no
+    /// assignment at all.
+    pub(crate) fn new<W>(weights: &W) -> Result<Murty<T>, Error>
+    where
+        W: Weights<T = T>,
+    {
+        let mut murty = Murty {
+            base: Restricted::from_weights(weights),
+            queue: BinaryHeap::new(),
+            sequence: 0,
+        };
+        let (assignment, cost) = solve_subproblem(&murty.base, &[], &[])?;
+        murty.push(cost, assignment, Vec::new(), Vec::new());
+        Ok(murty)
+    }
+
+    fn push(
+        &mut self,
+        cost: T,
+        assignment: Assignment,
+        pinned: Vec<Position>,
+        forbidden: Vec<Position>,
+    ) {
+        self.queue.push(Node {
+            cost,
+            sequence: self.sequence,
+            assignment,
+            pinned,
+            forbidden,
+        });
+        self.sequence += 1;
+    }
+
+    /// Removes the next best solution from the queue, returning it together
+    /// with the subproblems partitioning the rest of its solution space.
+    fn pop(&mut self) -> Option<(Assignment, T, Vec<Subproblem>)> {
+        let node = self.queue.pop()?;
+        let mut pinned = node.pinned.clone();
+        let mut subproblems = Vec::new();
+        for &pos in node.assignment.iter() {
+            if node.pinned.contains(&pos) {
+                continue;
+            }
+            let mut forbidden = node.forbidden.clone();
+            forbidden.push(pos);
+            subproblems.push((pinned.clone(), forbidden));
+            pinned.push(pos);
+        }
+        Some((node.assignment, node.cost, subproblems))
+    }
+
+    fn push_solved(&mut self, solved: Vec<Option<(Assignment, T, Subproblem)>>) {
+        for (assignment, cost, (pinned, forbidden)) in solved.into_iter().flatten() {
+            self.push(cost, assignment, pinned, forbidden);
+        }
+    }
+
+    /// The next best assignment and its cost.
+    pub(crate) fn next_best(&mut self) -> Option<(Assignment, T)> {
+        let (assignment, cost, subproblems) = self.pop()?;
+        let solved = subproblems
+            .into_iter()
+            .map(|subproblem| self.solve(subproblem))
+            .collect();
+        self.push_solved(solved);
+        Some((assignment, cost))
+    }
+
+    fn solve(&self, (pinned, forbidden): Subproblem) -> Option<(Assignment, T, Subproblem)> {
+        solve_subproblem(&self.base, &pinned, &forbidden)
+            .ok()
+            .map(|(assignment, cost)| (assignment, cost, (pinned, forbidden)))
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T: WeightNum + Send + Sync> Murty<T> {
+    /// Like `next_best`, but solves the subproblems in parallel.
+    pub(crate) fn next_best_parallel(&mut self) -> Option<(Assignment, T)> {
+        use rayon::prelude::*;
+
+        let (assignment, cost, subproblems) = self.pop()?;
+        let solved = subproblems
+            .into_par_iter()
+            .map(|subproblem| self.solve(subproblem))
+            .collect();
+        self.push_solved(solved);
+        Some((assignment, cost))
+    }
+}
+
+fn solve_subproblem<T: WeightNum>(
+    base: &Restricted<T>,
+    pinned: &[Position],
+    forbidden: &[Position],
+) -> Result<(Assignment, T), Error> {
+    let mut weights = base.clone();
+    for &pos in pinned {
+        weights.pin(pos);
+    }
+    for &pos in forbidden {
+        weights.forbid(pos);
+    }
+    let positions = solve_assignment(&mut weights)?;
+    let cost = base.cost(&positions);
+    Ok((Assignment::from(positions), cost))
+}
+
+/// Lazily ranked assignments, cheapest first.
+///
+/// Each call to `next_best` (or `next`) pops the best remaining solution and
+/// solves the subproblems partitioning the rest of its solution space, so
+/// callers can stop as soon as a solution passes their own validation. The
+/// iterator ends once all assignments have been enumerated.
+#[derive(Debug, Clone)]
+pub struct RankedAssignments<T> {
+    murty: Murty<T>,
+}
+
+impl<T: WeightNum> RankedAssignments<T> {
+    /// The next best assignment and its cost.
+    pub fn next_best(&mut self) -> Option<(Assignment, T)> {
+        self.murty.next_best()
+    }
+}
+
+impl<T: WeightNum> Iterator for RankedAssignments<T> {
+    type Item = (Assignment, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_best()
+    }
+}
+
+/// Starts ranking the assignments of `weights`, solving the unconstrained
+/// problem right away. `weights` is not modified.
+pub fn ranked_assignments<W>(weights: &W) -> Result<RankedAssignments<W::T>, Error>
+where
+    W: Weights,
+{
+    Murty::new(weights).map(|murty| RankedAssignments { murty })
+}
+
+/// Computes up to `k` best assignments with their costs, in non-decreasing
+/// cost order. Fewer are returned if `weights` has fewer than `k`
+/// assignments. `weights` is not modified.
+pub fn solve_k_best<W>(weights: &W, k: usize) -> Result<Vec<(Assignment, W::T)>, Error>
+where
+    W: Weights,
+{
+    Ok(ranked_assignments(weights)?.take(k).collect())
+}
+
+/// Computes up to `k` best assignments in non-decreasing cost order, solving
+/// the subproblems of each partition in parallel. Returns the same ranking as
+/// the sequential algorithm.
+#[cfg(feature = "rayon")]
+pub fn solve_k_best_parallel<W>(weights: &W, k: usize) -> Result<Vec<(Assignment, W::T)>, Error>
+where
+    W: Weights,
+    W::T: Send + Sync,
+{
+    let mut murty = Murty::new(weights)?;
+    let mut ranked = Vec::with_capacity(k);
+    while ranked.len() < k {
+        match murty.next_best_parallel() {
+            Some(solution) => ranked.push(solution),
+            None => break,
+        }
+    }
+    Ok(ranked)
+}
+
+#[cfg(test)]
+fn brute_force_costs(c: &[i32], n: usize) -> Vec<i32> {
+    fn permute(
+        c: &[i32],
+        n: usize,
+        row: usize,
+        used: &mut Vec<bool>,
+        cost: i32,
+        out: &mut Vec<i32>,
+    ) {
+        if row == n {
+            out.push(cost);
+            return;
+        }
+        for column in 0..n {
+            if !used[column] {
+                used[column] = true;
+                permute(c, n, row + 1, used, cost + c[row * n + column], out);
+                used[column] = false;
+            }
+        }
+    }
+    let mut out = Vec::new();
+    permute(c, n, 0, &mut vec![false; n], 0, &mut out);
+    out.sort();
+    out
+}
+
+#[test]
+fn test_murty_ranks_all_permutations() {
+    use crate::WeightMatrix;
+
+    const N: usize = 4;
+    let c = vec![
+        7, 53, 183, 439, 497, 383, 563, 79, 627, 343, 773, 959, 447, 283, 463, 29,
+    ];
+    let weights = WeightMatrix::from_row_vec(N, c.clone());
+    let mut murty = Murty::new(&weights).unwrap();
+    let mut costs = Vec::new();
+    let mut seen = Vec::new();
+    while let Some((assignment, cost)) = murty.next_best() {
+        assert!(!seen.contains(&assignment));
+        seen.push(assignment);
+        costs.push(cost);
+    }
+    assert_eq!(brute_force_costs(&c, N), costs);
+}
+
+#[test]
+fn test_ranked_assignments_stop_early() {
+    use crate::WeightMatrix;
+
+    let inf = f32::INFINITY;
+    let weights = WeightMatrix::from_row_vec(3, vec![1.0, 2.0, inf, 2.0, 1.0, 3.0, inf, 5.0, 1.0]);
+    let mut ranked = ranked_assignments(&weights).unwrap();
+    let (best, cost) = ranked.next_best().unwrap();
+    assert_eq!(3.0, cost);
+    assert_eq!(Some(0), best.column_of(0));
+
+    // the first solution not assigning row 0 to column 0.
+    let (other, cost) = ranked.find(|(a, _)| a.column_of(0) != Some(0)).unwrap();
+    assert_eq!(Some(1), other.column_of(0));
+    assert_eq!(5.0, cost);
+
+    // only 3 of the 6 permutations avoid the forbidden cells.
+    assert_eq!(3, ranked_assignments(&weights).unwrap().count());
+}
+
+#[test]
+fn test_solve_k_best() {
+    use crate::{generators, solve_assignment};
+
+    let weights = generators::uniform(5, 0..50, 9);
+    let ranked = solve_k_best(&weights, 10).unwrap();
+    assert_eq!(10, ranked.len());
+    assert!(ranked.windows(2).all(|w| w[0].1 <= w[1].1));
+    let best = solve_assignment(&mut weights.clone()).unwrap();
+    let cost: u32 = best.iter().map(|&pos| weights.element_at(pos)).sum();
+    assert_eq!(cost, ranked[0].1);
+    assert_eq!(120, solve_k_best(&weights, 1000).unwrap().len());
+    assert_eq!(
+        Vec::<(Assignment, u32)>::new(),
+        solve_k_best(&weights, 0).unwrap()
+    );
+
+    let inf = f64::INFINITY;
+    let weights = crate::WeightMatrix::from_row_vec(2, vec![1.0, inf, 2.0, inf]);
+    assert_eq!(Err(Error::MatrixNotSolvable), solve_k_best(&weights, 2));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_solve_k_best_parallel() {
+    use crate::generators;
+
+    let weights = generators::uniform(6, 0..20, 3);
+    let parallel = solve_k_best_parallel(&weights, 30).unwrap();
+    assert_eq!(solve_k_best(&weights, 30).unwrap(), parallel);
+}
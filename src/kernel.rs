@@ -0,0 +1,204 @@
+//! Kernelization: removing forced assignments before solving.
+//!
+//! A row with a single valid cell must be assigned to that cell's column in
+//! every complete assignment, and likewise for columns. Assigning it removes
+//! its row and column, which invalidates the remaining cells of both and can
+//! force further assignments. Gated matrices, where most cells are invalid,
+//! often reduce to a small kernel this way, which is then solved on its own.
+//!
+//! Only reductions that preserve all optimal solutions are applied. Note
+//! that an entry being the minimum of both its row and its column does not
+//! make it part of an optimal assignment, so such entries are not fixed.
+use crate::restricted::Restricted;
+use crate::{solve_assignment, Error, Position, Weights};
+use alloc::{vec, vec::Vec};
+
+/// The result of `kernelize`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Kernel {
+    forced: Vec<Position>,
+    rows: Vec<usize>,
+    columns: Vec<usize>,
+}
+
+impl Kernel {
+    /// The assignments forced by the reduction, in the order they were found.
+    pub fn forced(&self) -> &[Position] {
+        &self.forced
+    }
+
+    /// The rows of the remaining problem, ascending.
+    pub fn rows(&self) -> &[usize] {
+        &self.rows
+    }
+
+    /// The columns of the remaining problem, ascending.
+    pub fn columns(&self) -> &[usize] {
+        &self.columns
+    }
+}
+
+/// Repeatedly fixes the rows and columns with a single valid cell. Fails if
+/// a row or column loses all its valid cells.
+pub fn kernelize<W>(weights: &W) -> Result<Kernel, Error>
+where
+    W: Weights,
+{
+    let n = weights.n();
+    let valid = |row, column| weights.is_valid_at(Position { row, column });
+    let mut row_count = vec![0usize; n];
+    let mut column_count = vec![0usize; n];
+    for (row, count) in row_count.iter_mut().enumerate() {
+        for (column, column_count) in column_count.iter_mut().enumerate() {
+            if valid(row, column) {
+                *count += 1;
+                *column_count += 1;
+            }
+        }
+    }
+    let mut row_active = vec![true; n];
+    let mut column_active = vec![true; n];
+    let mut forced = Vec::new();
+
+    // lines with a single valid cell; rows are `(true, row)`.
+    let mut queue: Vec<(bool, usize)> = Vec::new();
+    for i in 0..n {
+        if row_count[i] == 0 || column_count[i] == 0 {
+            return Err(Error::MatrixNotSolvable);
+        }
+        if row_count[i] == 1 {
+            queue.push((true, i));
+        }
+        if column_count[i] == 1 {
+            queue.push((false, i));
+        }
+    }
+
+    while let Some((is_row, line)) = queue.pop() {
+        let pos = if is_row {
+            if !row_active[line] {
+                continue;
+            }
+            match (0..n).find(|&column| column_active[column] && valid(line, column)) {
+                Some(column) => Position { row: line, column },
+                None => return Err(Error::MatrixNotSolvable),
+            }
+        } else {
+            if !column_active[line] {
+                continue;
+            }
+            match (0..n).find(|&row| row_active[row] && valid(row, line)) {
+                Some(row) => Position { row, column: line },
+                None => return Err(Error::MatrixNotSolvable),
+            }
+        };
+        forced.push(pos);
+        row_active[pos.row] = false;
+        column_active[pos.column] = false;
+
+        // the other cells of the row and column are gone.
+        for column in 0..n {
+            if column_active[column] && valid(pos.row, column) {
+                column_count[column] -= 1;
+                match column_count[column] {
+                    0 => return Err(Error::MatrixNotSolvable),
+                    1 => queue.push((false, column)),
+                    _ => {}
+                }
+            }
+        }
+        for row in 0..n {
+            if row_active[row] && valid(row, pos.column) {
+                row_count[row] -= 1;
+                match row_count[row] {
+                    0 => return Err(Error::MatrixNotSolvable),
+                    1 => queue.push((true, row)),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Ok(Kernel {
+        forced,
+        rows: (0..n).filter(|&row| row_active[row]).collect(),
+        columns: (0..n).filter(|&column| column_active[column]).collect(),
+    })
+}
+
+/// Solves the assignment problem after removing forced assignments, solving
+/// only the remaining kernel. `weights` is not modified.
+pub fn solve_assignment_kernelized<W>(weights: &W) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+{
+    let kernel = kernelize(weights)?;
+    let mut matching = kernel.forced.clone();
+    if !kernel.rows.is_empty() {
+        let mut sub = Restricted::submatrix(weights, &kernel.rows, &kernel.columns);
+        for pos in solve_assignment(&mut sub)? {
+            matching.push(Position {
+                row: kernel.rows[pos.row],
+                column: kernel.columns[pos.column],
+            });
+        }
+    }
+    matching.sort_by_key(|pos| pos.row);
+    Ok(matching)
+}
+
+#[test]
+fn test_kernelize() {
+    use crate::WeightMatrix;
+
+    let inf = f64::INFINITY;
+    // row 0 only allows column 2, which leaves row 1 with column 0.
+    let weights = WeightMatrix::from_row_vec(
+        4,
+        vec![
+            inf, inf, 1.0, inf, //
+            5.0, inf, 2.0, inf, //
+            1.0, 2.0, 3.0, 4.0, //
+            2.0, 1.0, 3.0, 1.0, //
+        ],
+    );
+    let kernel = kernelize(&weights).unwrap();
+    assert_eq!(
+        &[
+            Position { row: 0, column: 2 },
+            Position { row: 1, column: 0 }
+        ],
+        kernel.forced()
+    );
+    assert_eq!(&[2, 3], kernel.rows());
+    assert_eq!(&[1, 3], kernel.columns());
+
+    let matching = solve_assignment_kernelized(&weights).unwrap();
+    assert_eq!(matching, solve_assignment(&mut weights.clone()).unwrap());
+
+    let weights = WeightMatrix::from_row_vec(2, vec![1.0, inf, 2.0, inf]);
+    assert_eq!(Err(Error::MatrixNotSolvable), kernelize(&weights));
+}
+
+#[test]
+fn test_kernelized_matches_solve() {
+    use crate::{generators, WeightMatrix};
+
+    for seed in 0..20 {
+        let n = 6;
+        let costs = generators::uniform(n, 0..100, seed);
+        // gate most cells, keeping the diagonal so there is a solution.
+        let mut rng = generators::Rng::new(seed);
+        let weights = WeightMatrix::from_fn(n, |(row, column)| {
+            if row == column || rng.next_f64() < 0.3 {
+                costs.element_at(Position { row, column }) as f64
+            } else {
+                f64::INFINITY
+            }
+        });
+        let expected = solve_assignment(&mut weights.clone()).unwrap();
+        let matching = solve_assignment_kernelized(&weights).unwrap();
+        let cost = |m: &[Position]| m.iter().map(|&pos| weights.element_at(pos)).sum::<f64>();
+        assert_eq!(cost(&expected), cost(&matching));
+    }
+}
@@ -0,0 +1,227 @@
+//! Jonker-Volgenant backend.
+//!
+//! Rows are assigned one at a time along shortest augmenting paths, found by
+//! Dijkstra's algorithm on the costs reduced by the row and column
+//! potentials, as in Jonker and Volgenant's LAPJV and the variant of Crouse
+//! (2016). Each augmentation takes O(n^2), so the whole solve runs in O(n^3)
+//! regardless of the structure of the costs, while the steps of
+//! `solve_assignment` can take far longer on large instances.
+//!
+//! Integer weights are solved exactly in `i64`, other weights in `f64`.
+use crate::{Assignment, AssignmentSolver, Error, Position, WeightNum, Weights};
+use alloc::{vec, vec::Vec};
+use core::ops::{Add, Sub};
+
+const NONE: usize = usize::MAX;
+
+/// Solves the assignment problem by shortest augmenting paths. Returns an
+/// optimal matching, sorted by row, which may differ from the one of
+/// `solve_assignment` among several optimal ones. Unlike
+/// `solve_assignment`, `weights` is not modified.
+///
+/// # Panics
+///
+/// If a valid cell that isn't an integer can't be converted to `f64`.
+pub fn solve_assignment_lapjv<W>(weights: &W) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+{
+    let n = weights.n();
+    let positions = || {
+        (0..n * n).map(|index| Position {
+            row: index / n,
+            column: index % n,
+        })
+    };
+    let valid = |pos| weights.is_valid_at(pos);
+    let integral: Option<Vec<Option<i64>>> = positions()
+        .map(|pos| {
+            if valid(pos) {
+                weights.element_at(pos).to_i64().map(Some)
+            } else {
+                Some(None)
+            }
+        })
+        .collect();
+    match integral {
+        Some(costs) => solve_dense(n, &costs),
+        None => {
+            let costs: Vec<Option<f64>> = positions()
+                .map(|pos| {
+                    valid(pos).then(|| {
+                        weights
+                            .element_at(pos)
+                            .to_f64()
+                            .expect("LAPJV requires weights convertible to f64")
+                    })
+                })
+                .collect();
+            solve_dense(n, &costs)
+        }
+    }
+}
+
+/// Solves the `n x n` matrix `costs`, in row-major order, with `None` for
+/// the forbidden cells.
+fn solve_dense<C>(n: usize, costs: &[Option<C>]) -> Result<Vec<Position>, Error>
+where
+    C: Copy + PartialOrd + Default + Add<Output = C> + Sub<Output = C>,
+{
+    let zero = C::default();
+    let mut row_duals = vec![zero; n];
+    let mut column_duals = vec![zero; n];
+    let mut column_of_row = vec![NONE; n];
+    let mut row_of_column = vec![NONE; n];
+    // the length of the shortest path to each column, and the row it is
+    // reached from.
+    let mut shortest: Vec<Option<C>> = vec![None; n];
+    let mut previous = vec![NONE; n];
+    let mut visited_rows = Vec::with_capacity(n);
+    let mut visited_columns = Vec::with_capacity(n);
+    let mut remaining = Vec::with_capacity(n);
+
+    for start in 0..n {
+        shortest.iter_mut().for_each(|length| *length = None);
+        remaining.clear();
+        remaining.extend(0..n);
+        visited_rows.clear();
+        visited_columns.clear();
+
+        let mut length = zero;
+        let mut row = start;
+        let sink = loop {
+            visited_rows.push(row);
+            // the closest remaining column, preferring unassigned ones.
+            let mut closest: Option<(C, usize)> = None;
+            for (index, &column) in remaining.iter().enumerate() {
+                if let Some(cost) = costs[row * n + column] {
+                    let reduced = length + cost - row_duals[row] - column_duals[column];
+                    if shortest[column].is_none_or(|shortest| reduced < shortest) {
+                        previous[column] = row;
+                        shortest[column] = Some(reduced);
+                    }
+                }
+                if let Some(candidate) = shortest[column] {
+                    let closer = match closest {
+                        None => true,
+                        Some((best, _)) => {
+                            candidate < best || (candidate == best && row_of_column[column] == NONE)
+                        }
+                    };
+                    if closer {
+                        closest = Some((candidate, index));
+                    }
+                }
+            }
+            let (distance, index) = closest.ok_or(Error::MatrixNotSolvable)?;
+            length = distance;
+            let column = remaining.swap_remove(index);
+            visited_columns.push(column);
+            if row_of_column[column] == NONE {
+                break column;
+            }
+            row = row_of_column[column];
+        };
+
+        // keeps the reduced costs non-negative, and zero along the path.
+        row_duals[start] = row_duals[start] + length;
+        for &row in &visited_rows[1..] {
+            let reached = shortest[column_of_row[row]].unwrap();
+            row_duals[row] = row_duals[row] + length - reached;
+        }
+        for &column in &visited_columns {
+            column_duals[column] = column_duals[column] - (length - shortest[column].unwrap());
+        }
+
+        let mut column = sink;
+        loop {
+            let row = previous[column];
+            row_of_column[column] = row;
+            let next = column_of_row[row];
+            column_of_row[row] = column;
+            if row == start {
+                break;
+            }
+            column = next;
+        }
+    }
+
+    Ok(column_of_row
+        .into_iter()
+        .enumerate()
+        .map(|(row, column)| Position { row, column })
+        .collect())
+}
+
+/// The Jonker-Volgenant backend.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Lapjv;
+
+impl<W: Weights> AssignmentSolver<W> for Lapjv {
+    fn solve(&mut self, weights: &mut W) -> Result<Assignment, Error> {
+        solve_assignment_lapjv(weights).map(Assignment::from)
+    }
+}
+
+#[test]
+fn test_lapjv_matches_munkres() {
+    use crate::{generators, solve_assignment, WeightMatrix};
+
+    fn cost<W: Weights>(weights: &W, matching: &[Position]) -> f64 {
+        matching
+            .iter()
+            .map(|&pos| weights.element_at(pos).to_f64().unwrap())
+            .sum()
+    }
+
+    let instances = vec![
+        generators::uniform(1, 0..10, 0),
+        generators::uniform(40, 0..1000, 1),
+        generators::uniform(30, 0..5, 2),
+        generators::degenerate(25, 2, 3),
+        generators::machol_wien(12),
+    ];
+    for weights in instances {
+        let matching = solve_assignment_lapjv(&weights).unwrap();
+        let expected = solve_assignment(&mut weights.clone()).unwrap();
+        assert_eq!(cost(&weights, &expected), cost(&weights, &matching));
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    for seed in 0..5 {
+        let weights = generators::geometric(20, seed);
+        let matching = solve_assignment_lapjv(&weights).unwrap();
+        let expected = solve_assignment(&mut weights.clone()).unwrap();
+        assert!((cost(&weights, &expected) - cost(&weights, &matching)).abs() < 1e-9);
+    }
+
+    let c = vec![250, 400, 350, 400, 600, 350, 200, 400, 250];
+    let weights: WeightMatrix<i32> = WeightMatrix::from_row_vec(3, c);
+    assert_eq!(
+        vec![
+            Position { row: 0, column: 1 },
+            Position { row: 1, column: 2 },
+            Position { row: 2, column: 0 }
+        ],
+        solve_assignment_lapjv(&weights).unwrap()
+    );
+
+    let inf = f64::INFINITY;
+    let weights = WeightMatrix::from_row_vec(3, vec![1.0, inf, inf, 2.0, inf, inf, 0.5, 1.0, 3.0]);
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_assignment_lapjv(&weights)
+    );
+    let weights = WeightMatrix::from_row_vec(2, vec![inf, 1.5, 2.0, inf]);
+    assert_eq!(
+        Ok(vec![
+            Position { row: 0, column: 1 },
+            Position { row: 1, column: 0 }
+        ]),
+        solve_assignment_lapjv(&weights)
+    );
+    assert_eq!(
+        Ok(vec![]),
+        solve_assignment_lapjv(&WeightMatrix::<u32>::from_row_vec(0, vec![]))
+    );
+}
@@ -1,25 +1,165 @@
-/// Kuhn-Munkres Algorithm (also called Hungarian algorithm) for solving the
-/// Assignment Problem.
-///
-/// Copyright (c) 2015-2019 by Michael Neumann (mneumann@ntecs.de).
-///
-/// This code is derived from a port of the Python version found here:
-/// https://github.com/bmc/munkres/blob/master/munkres.py
-/// which is Copyright (c) 2008 Brian M. Clapper.
+//! Kuhn-Munkres Algorithm (also called Hungarian algorithm) for solving the
+//! Assignment Problem.
+//!
+//! Copyright (c) 2015-2019 by Michael Neumann (mneumann@ntecs.de).
+//!
+//! This code is derived from a port of the Python version found here:
+//! <https://github.com/bmc/munkres/blob/master/munkres.py>
+//! which is Copyright (c) 2008 Brian M. Clapper.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![cfg_attr(test, allow(clippy::bool_assert_comparison))]
+
+extern crate alloc;
+
+pub use crate::analyze::{analyze, InstanceStats};
+pub use crate::assignment::{Assignment, AssignmentSolver, Munkres};
+pub use crate::auction::{solve_assignment_auction, Auction};
+pub use crate::backend::{solve_assignment_with_backend, Backend, Stats};
+pub use crate::bounds::lower_bound;
+pub use crate::builder::{ConfiguredSolver, SolverBuilder};
+pub use crate::capacities::{
+    solve_assignment_with_capacities, solve_assignment_with_group_bounds, solve_b_matching, Degree,
+};
+pub use crate::cost_scaling::{solve_assignment_cost_scaling, CostScaling};
+pub use crate::counting::solve_matrix_counting;
 use crate::coverage::Coverage;
+pub use crate::dual::{solve_assignment_immutable, solve_assignment_with_duals, AssignmentResult};
+pub use crate::feasibility::{solve_assignment_feasible, solve_assignment_with_row_gates};
+pub use crate::kernel::solve_assignment_kernelized;
+pub use crate::lapjv::{solve_assignment_lapjv, Lapjv};
 pub use crate::mark_matrix::MarkMatrix;
+#[cfg(feature = "profile")]
+pub use crate::profile::Timings;
+pub use crate::solver::Solver;
+pub use crate::sparse_matrix::SparseWeightMatrix;
+pub use crate::steps::ScanOrder;
+use crate::steps::*;
 pub use crate::weight_matrix::WeightMatrix;
-pub use crate::weight_num::WeightNum;
+pub use crate::weight_num::{Reverse, Total, WeightNum};
+use crate::workspace::Workspace;
+use alloc::vec::Vec;
+#[cfg(feature = "ndarray")]
 use ndarray::Array2;
 
+#[cfg(feature = "ndarray")]
 pub type SquareMatrix<T> = Array2<T>;
 
+pub mod analyze;
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
+#[cfg(feature = "std")]
+pub mod archive;
+pub mod arena;
+mod assignment;
+pub mod auction;
+pub mod backend;
+pub mod balance;
+pub mod banded;
+pub mod batch;
+mod bitset;
+pub mod blocks;
+pub mod bottleneck;
+mod bounds;
+pub mod budget;
+pub mod builder;
+pub mod capacities;
+pub mod column_generation;
+pub mod compensated;
+pub mod compile_time;
+pub mod constraints;
+pub mod cost_scaling;
+pub mod counting;
 mod coverage;
+#[cfg(feature = "std")]
+pub mod deadline;
+mod dense;
+pub mod disjoint;
+pub mod dual;
+#[cfg(feature = "std")]
+pub mod duplicates;
+mod dynamic;
+mod feasibility;
+#[cfg(feature = "heapless")]
+pub mod fixed;
+#[cfg(any(feature = "std", feature = "libm"))]
+mod float;
+mod flow;
+pub mod fusion;
+pub mod gap;
+pub mod generators;
+#[cfg(feature = "kdtree")]
+pub mod geometric;
+mod graph;
+pub mod hierarchical;
+#[cfg(feature = "internals")]
+pub mod internals;
+pub mod k_best;
+pub mod kernel;
+mod lapjv;
+pub mod limits;
+#[cfg(any(feature = "ndarray", feature = "nalgebra"))]
+mod linalg;
+#[cfg(feature = "good_lp")]
+pub mod lp;
+pub mod mapped;
 mod mark_matrix;
+#[cfg(feature = "std")]
+pub mod memo;
+pub mod memory;
+#[cfg(feature = "std")]
+pub mod metrics;
+pub mod monge;
+mod nested;
+pub mod pareto;
+pub mod perturbation;
+pub mod pipeline;
+#[cfg(feature = "std")]
+pub mod pool;
+#[cfg(any(feature = "std", feature = "libm"))]
+pub mod product;
+#[cfg(feature = "profile")]
+mod profile;
+pub mod pruning;
+pub mod quantized;
+pub mod regret;
+pub mod report;
+mod restricted;
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+mod simd;
+#[cfg(any(feature = "std", feature = "libm"))]
+pub mod sinkhorn;
+#[cfg(any(feature = "std", feature = "libm"))]
+pub mod slack;
+mod solver;
+pub mod sparse;
+mod sparse_matrix;
+pub mod state;
+mod steps;
+pub mod stochastic;
+#[cfg(feature = "proptest")]
+pub mod strategies;
+pub mod symmetric;
+pub mod temporal;
+pub mod tiered;
+pub mod tiled;
+#[cfg(any(feature = "std", feature = "libm"))]
+pub mod tracking;
+pub mod transport;
+#[cfg(feature = "rational")]
+pub mod verify;
+pub mod visualize;
 pub mod weight_matrix;
 pub mod weight_num;
+mod workspace;
+
+/// Used by `cost_matrix!`, which can't name `alloc` in crates without it.
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::vec;
+    pub use alloc::vec::Vec;
+}
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct Position {
     pub row: usize,
     pub column: usize,
@@ -39,264 +179,542 @@ pub trait Weights {
         self.element_at(pos).is_zero()
     }
 
+    /// Whether the cell at `pos` may be part of an assignment. Defaults to the
+    /// validity of its value.
+    #[inline]
+    fn is_valid_at(&self, pos: Position) -> bool {
+        self.element_at(pos).is_valid()
+    }
+
     fn is_solvable(&self) -> bool;
+
+    /// Like `element_at`, used by the inner loops of the algorithm.
+    /// Implementations may skip bounds checks, as `WeightMatrix` does with
+    /// the `unchecked` feature.
+    ///
+    /// # Safety
+    ///
+    /// `pos.row` and `pos.column` must be less than `n()`.
+    #[inline]
+    unsafe fn element_at_unchecked(&self, pos: Position) -> Self::T {
+        self.element_at(pos)
+    }
+
+    /// Like `is_element_zero`, see `element_at_unchecked`.
+    ///
+    /// # Safety
+    ///
+    /// `pos.row` and `pos.column` must be less than `n()`.
+    #[inline]
+    unsafe fn is_element_zero_unchecked(&self, pos: Position) -> bool {
+        self.is_element_zero(pos)
+    }
+
+    /// Like `is_valid_at`, see `element_at_unchecked`.
+    ///
+    /// # Safety
+    ///
+    /// `pos.row` and `pos.column` must be less than `n()`.
+    #[inline]
+    unsafe fn is_valid_at_unchecked(&self, pos: Position) -> bool {
+        self.is_valid_at(pos)
+    }
 }
 
-#[derive(Debug, Eq, PartialEq)]
+/// New variants may be added in minor releases.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
 pub enum Error {
     NoPrimeInRow,
     MatrixNotSolvable,
+    /// A limit of `limits::Limits` was reached, or the solve was cancelled.
+    LimitExceeded,
 }
 
-/// For each row of the matrix, find the smallest element and
-/// subtract it from every element in its row. Go to Step 2.
-fn step1<W>(c: &mut W)
+/// Solves the assignment problem, minimizing the sum of the assigned
+/// weights. `weights` is left in a reduced state.
+///
+/// The matching is sorted by row. It only depends on the input, not on the
+/// platform: the algorithm uses nothing but comparisons, additions and
+/// subtractions of the weights, in a fixed order. Among several optimal
+/// matchings, the one returned is determined by these scan orders:
+///
+/// * Rows are reduced by their minimum. If the greedy assignment of each row,
+///   in row order, to the first free column in which it attains the column
+///   minimum is complete, it is returned.
+/// * Otherwise, zeros are starred in row-major order (step 2), uncovered
+///   zeros are searched in column-major order (step 4), and alternating
+///   paths follow the first star in a column and the first prime in a row
+///   (step 5).
+///
+/// The other exact solvers in this crate (`solve_assignment_cost_scaling`,
+/// `solve_assignment_auction`, ...) also return matchings sorted by row and
+/// are deterministic, but may resolve ties differently.
+pub fn solve_assignment<W>(weights: &mut W) -> Result<Vec<Position>, Error>
 where
     W: Weights,
 {
-    c.sub_min_of_each_row();
+    if !weights.is_solvable() {
+        return Err(Error::MatrixNotSolvable);
+    }
+    step1(weights);
+    // if a greedy assignment meets the dual lower bound it is optimal, and
+    // the workspace is never allocated. If the lower bound comes from the
+    // row minima alone, this is also the matching step 2 would find.
+    if let Some(matching) = bounds::tight_permutation(weights) {
+        return Ok(matching);
+    }
+    let mut workspace = Workspace::new(weights.n());
+    run_reduced_in(weights, &mut workspace)?;
+    Ok(collect_stars(weights.n(), &workspace))
 }
 
-/// Find a zero (Z) in the resulting matrix. If there is no starred
-/// zero in its row or column, star Z. Repeat for each element in the
-/// matrix. Go to Step 3.
-fn step2<W>(c: &W, marks: &mut MarkMatrix, cov: &mut Coverage)
+/// Solves the assignment problem, maximizing the sum of the assigned
+/// weights, e.g. profits or bids. `weights` is not modified.
+///
+/// Solves a copy in which each valid value `x` becomes `max - x`, with `max`
+/// the largest valid value, instead of negating the weights, so unsigned
+/// types work too. `Munkres::builder().maximize()` does the same for the
+/// other options and backends.
+pub fn solve_max_assignment<W>(weights: &W) -> Result<Vec<Position>, Error>
 where
     W: Weights,
 {
-    let n = c.n();
-
-    assert!(marks.n() == n);
-    assert!(cov.n() == n);
-    debug_assert!(cov.all_uncovered());
-
-    cov.iter_uncovered_row_column_and_cover(|pos| {
-        if c.is_element_zero(pos) {
-            marks.star(pos);
-            true
-        } else {
-            false
-        }
-    });
-
-    // clear covers
-    cov.clear();
-}
-
-#[derive(Debug, Eq, PartialEq)]
-enum Step3 {
-    Done,
-    ContinueWithStep4 { star_count: usize },
+    solve_assignment(&mut builder::maximization(weights))
 }
 
-/// Cover each column containing a starred zero. If K columns are
-/// covered, the starred zeros describe a complete set of unique
-/// assignments. In this case, Go to DONE, otherwise, Go to Step 4.
-fn step3<W>(c: &W, marks: &MarkMatrix, cov: &mut Coverage) -> Step3
+/// Like `solve_assignment`, leaving `weights` unmodified, and also returns
+/// the total of the original weights of the assignment. Solves a copy of
+/// `weights`, which works for any weight type, unlike
+/// `solve_assignment_immutable`.
+pub fn solve_assignment_owned<W>(weights: &W) -> Result<(Assignment, W::T), Error>
 where
     W: Weights,
+    W::T: Default,
 {
-    let n = c.n();
-
-    assert!(marks.n() == n);
-    assert!(cov.n() == n);
+    let matching = solve_assignment(&mut restricted::Restricted::from_weights(weights))?;
+    let cost = matching
+        .iter()
+        .fold(W::T::default(), |sum, &pos| sum + weights.element_at(pos));
+    Ok((Assignment::from(matching), cost))
+}
 
-    let mut star_count: usize = 0;
+/// Like `solve_assignment`, but searches for uncovered zeros in parallel on
+/// large matrices, which dominates the running time once they exceed a few
+/// thousand rows. Returns the same matching as `solve_assignment`.
+#[cfg(feature = "rayon")]
+pub fn solve_assignment_parallel<W>(weights: &mut W) -> Result<Vec<Position>, Error>
+where
+    W: Weights + Sync,
+{
+    if !weights.is_solvable() {
+        return Err(Error::MatrixNotSolvable);
+    }
+    step1(weights);
+    if let Some(matching) = bounds::tight_permutation(weights) {
+        return Ok(matching);
+    }
+    let mut workspace = Workspace::new(weights.n());
+    run_reduced_with(weights, &mut workspace, par_step4::<W>)?;
+    Ok(collect_stars(weights.n(), &workspace))
+}
 
-    marks.each_star(|Position { column, .. }| {
-        cov.cover_column(column);
-        star_count += 1;
-    });
+/// Like `solve_assignment_parallel`, but also runs the solvability check and
+/// step 1, two passes over the whole matrix, in parallel.
+#[cfg(feature = "rayon")]
+pub fn solve_matrix_parallel<T>(weights: &mut WeightMatrix<T>) -> Result<Vec<Position>, Error>
+where
+    T: WeightNum + Send + Sync,
+{
+    if !weights.par_is_solvable() {
+        return Err(Error::MatrixNotSolvable);
+    }
+    weights.par_sub_min_of_each_row();
+    if let Some(matching) = bounds::tight_permutation(weights) {
+        return Ok(matching);
+    }
+    let mut workspace = Workspace::new(weights.n());
+    run_reduced_with(weights, &mut workspace, par_step4::<WeightMatrix<T>>)?;
+    Ok(collect_stars(weights.n(), &workspace))
+}
 
-    if star_count >= n {
-        assert!(star_count == n);
-        Step3::Done
-    } else {
-        Step3::ContinueWithStep4 { star_count }
+/// Like `solve_assignment`, but step 4 searches for uncovered zeros in
+/// `order`. `ScanOrder::ColumnMajor` returns the same matching as
+/// `solve_assignment`.
+pub fn solve_assignment_with_scan_order<W>(
+    weights: &mut W,
+    order: ScanOrder,
+) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+{
+    if !weights.is_solvable() {
+        return Err(Error::MatrixNotSolvable);
+    }
+    step1(weights);
+    if let Some(matching) = bounds::tight_permutation(weights) {
+        return Ok(matching);
     }
+    let mut workspace = Workspace::new(weights.n());
+    match order {
+        ScanOrder::ColumnMajor => run_reduced_in(weights, &mut workspace)?,
+        ScanOrder::RowMajor => run_reduced_with(weights, &mut workspace, step4_row_major::<W>)?,
+        ScanOrder::RoundRobin => {
+            let mut start = 0;
+            run_reduced_with(weights, &mut workspace, |c, marks, cov| {
+                step4_round_robin(c, marks, cov, &mut start)
+            })?
+        }
+    }
+    Ok(collect_stars(weights.n(), &workspace))
 }
 
-#[derive(Debug, Eq, PartialEq)]
-enum Step4 {
-    ContinueWithStep5 { z0_pos: Position },
-    ContinueWithStep6,
+/// Like `solve_assignment`, but starts from the column potentials
+/// `column_potentials`, e.g. the duals of the previous solve of a nearly
+/// identical problem, so that fewer steps are needed to create zeros.
+///
+/// Each column potential is first lowered to the minimum of its column if
+/// it exceeds it, which keeps the reduced matrix non-negative for any input.
+/// The row potentials follow as the row minima of the reduced matrix, which
+/// is at least as tight as any row potentials feasible with these columns,
+/// so they don't need to be given. Returns an optimal matching whatever the
+/// potentials, only the time to find it depends on them.
+///
+/// # Panics
+///
+/// If `column_potentials` doesn't have `n` elements.
+pub fn solve_assignment_warm<W>(
+    weights: &mut W,
+    column_potentials: &[W::T],
+) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+{
+    solve_assignment_warm_in(weights, column_potentials, &mut Workspace::new(weights.n()))
 }
 
-/// Find a noncovered zero and prime it. If there is no starred zero
-/// in the row containing this primed zero, Go to Step 5. Otherwise,
-/// cover this row and uncover the column containing the starred
-/// zero. Continue in this manner until there are no uncovered zeros
-/// left. Save the smallest uncovered value and Go to Step 6.
-fn step4<W>(c: &W, marks: &mut MarkMatrix, cov: &mut Coverage) -> Step4
+/// Like `solve_assignment_warm`, using the buffers of `workspace`.
+pub(crate) fn solve_assignment_warm_in<W>(
+    weights: &mut W,
+    column_potentials: &[W::T],
+    workspace: &mut Workspace,
+) -> Result<Vec<Position>, Error>
 where
     W: Weights,
 {
-    let n = c.n();
-
-    assert!(marks.n() == n);
-    assert!(cov.n() == n);
-
-    loop {
-        // find uncovered zero element
-        match cov.find_uncovered_cell_column_row_order(|pos| c.is_element_zero(pos)) {
-            Some(pos) => {
-                marks.prime(pos);
-                match marks.find_first_star_in_row(pos.row) {
-                    Some(star_col) => {
-                        cov.cover_row(pos.row);
-                        cov.uncover_column(star_col);
-                    }
-                    None => {
-                        // in Python: self.Z0_r, self.Z0_c
-                        return Step4::ContinueWithStep5 { z0_pos: pos };
-                    }
-                }
-            }
-            None => {
-                return Step4::ContinueWithStep6;
-            }
-        }
+    let n = weights.n();
+    assert!(column_potentials.len() == n);
+    if !weights.is_solvable() {
+        return Err(Error::MatrixNotSolvable);
+    }
+    for (column, &potential) in column_potentials.iter().enumerate() {
+        let min = (0..n)
+            .map(|row| Position { row, column })
+            .filter(|&pos| weights.is_valid_at(pos))
+            .map(|pos| weights.element_at(pos))
+            .fold(
+                potential,
+                |min, value| if value < min { value } else { min },
+            );
+        weights.sub_column(column, min);
+    }
+    step1(weights);
+    if let Some(matching) = bounds::tight_permutation(weights) {
+        return Ok(matching);
+    }
+    run_reduced_in(weights, workspace)?;
+    Ok(collect_stars(n, workspace))
+}
+
+/// Like `solve_assignment`, but returns an iterator over the matching in row
+/// order instead of collecting it, for callers that fold or filter it right
+/// away.
+pub fn solve_assignment_iter<W>(weights: &mut W) -> Result<AssignmentIter, Error>
+where
+    W: Weights,
+{
+    if !weights.is_solvable() {
+        return Err(Error::MatrixNotSolvable);
     }
+    step1(weights);
+    if let Some(matching) = bounds::tight_permutation(weights) {
+        return Ok(AssignmentIter(Stars::Matching(matching.into_iter())));
+    }
+    let mut workspace = Workspace::new(weights.n());
+    run_reduced_in(weights, &mut workspace)?;
+    Ok(AssignmentIter(Stars::Marks {
+        marks: workspace.marks,
+        row: 0,
+    }))
 }
 
-#[derive(Debug, Eq, PartialEq)]
-enum Step5 {
-    ContinueWithStep3,
-}
-
-/// Construct a series of alternating primed and starred zeros as
-/// follows. Let Z0 represent the uncovered primed zero found in Step 4.
-/// Let Z1 denote the starred zero in the column of Z0 (if any).
-/// Let Z2 denote the primed zero in the row of Z1 (there will always
-/// be one). Continue until the series terminates at a primed zero
-/// that has no starred zero in its column. Unstar each starred zero
-/// of the series, star each primed zero of the series, erase all
-/// primes and uncover every line in the matrix. Return to Step 3
-fn step5(
-    marks: &mut MarkMatrix,
-    cov: &mut Coverage,
-    z0_pos: Position,
-    path: &mut Vec<Position>,
-) -> Result<Step5, Error> {
-    let n = cov.n();
-
-    assert!(marks.n() == n);
-
-    path.clear();
-    path.push(z0_pos);
-
-    let mut prev_col = z0_pos.column;
-
-    loop {
-        match marks.find_first_star_in_column(prev_col) {
-            Some(row) => {
-                path.push(Position {
-                    row,
-                    column: prev_col,
-                });
-
-                if let Some(column) = marks.find_first_prime_in_row(row) {
-                    path.push(Position { row, column });
-                    prev_col = column;
-                } else {
-                    // XXX: Can this really happen?
-                    return Err(Error::NoPrimeInRow);
+/// The iterator returned by `solve_assignment_iter`.
+#[derive(Debug)]
+pub struct AssignmentIter(Stars);
+
+#[derive(Debug)]
+enum Stars {
+    /// The greedy matching of the reduced matrix.
+    Matching(alloc::vec::IntoIter<Position>),
+    /// The stars of the remaining rows, from `row` on.
+    Marks { marks: MarkMatrix, row: usize },
+}
+
+impl Iterator for AssignmentIter {
+    type Item = Position;
+
+    fn next(&mut self) -> Option<Position> {
+        match &mut self.0 {
+            Stars::Matching(positions) => positions.next(),
+            Stars::Marks { marks, row } => {
+                if *row == marks.n() {
+                    return None;
                 }
-            }
-            None => {
-                break;
+                let pos = Position {
+                    row: *row,
+                    column: marks
+                        .find_first_star_in_row(*row)
+                        .expect("every row has a star"),
+                };
+                *row += 1;
+                Some(pos)
             }
         }
     }
 
-    // convert_path
-    for &pos in path.iter() {
-        marks.toggle_star(pos);
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = match &self.0 {
+            Stars::Matching(positions) => positions.len(),
+            Stars::Marks { marks, row } => marks.n() - row,
+        };
+        (len, Some(len))
     }
+}
+
+impl ExactSizeIterator for AssignmentIter {}
+
+pub(crate) fn collect_stars(n: usize, workspace: &Workspace) -> Vec<Position> {
+    let mut matching = Vec::with_capacity(n);
+    workspace.marks.each_star(|pos| matching.push(pos));
+    assert!(matching.len() == n);
+    matching
+}
 
-    cov.clear();
-    marks.clear_primes();
-    Ok(Step5::ContinueWithStep3)
+/// Like `solve_assignment`, but reuses the buffers of earlier solves of the
+/// same size on the current thread. Workspaces for the few most recently
+/// used sizes are kept until the thread exits. Without the `std` feature,
+/// each solve allocates its own.
+pub fn solve_assignment_cached<W>(weights: &mut W) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+{
+    workspace::with_cached_workspace(weights.n(), |workspace| {
+        solve_assignment_in(weights, workspace)
+    })
 }
 
-#[derive(Debug, Eq, PartialEq)]
-enum Step6 {
-    ContinueWithStep4,
+/// Solves the `n x n` matrix `data`, in row-major order, in place, and
+/// hands `data` back with the result. Its elements are reduced, but the
+/// buffer can be refilled for the next solve instead of allocating a new
+/// one. Reuses thread-local workspaces like `solve_assignment_cached`.
+///
+/// # Panics
+///
+/// If `data` doesn't have `n * n` elements.
+pub fn solve_from_vec<T: WeightNum>(n: usize, data: Vec<T>) -> (Result<Assignment, Error>, Vec<T>) {
+    let mut weights = WeightMatrix::from_row_vec(n, data);
+    let result = solve_assignment_cached(&mut weights).map(Assignment::from_positions);
+    (result, weights.into_vec())
 }
 
-/// Add the value found in Step 4 to every element of each covered
-/// row, and subtract it from every element of each uncovered column.
-/// Return to Step 4 without altering any stars, primes, or covered
-/// lines.
-fn step6<W>(c: &mut W, cov: &Coverage) -> Result<Step6, Error>
+/// Like `solve_assignment`, but appends the matching to `matching` after
+/// clearing it, and reuses thread-local workspaces like
+/// `solve_assignment_cached`. Solving equally sized instances repeatedly with
+/// the same `matching` doesn't allocate.
+pub fn solve_assignment_into<W>(weights: &mut W, matching: &mut Vec<Position>) -> Result<(), Error>
 where
     W: Weights,
 {
-    let n = c.n();
-    assert!(cov.n() == n);
-
-    // Find the smallest, valid uncovered value in the matrix
-    let mut min = None;
-    cov.iter_uncovered_row_column_order(|pos| {
-        let elm = c.element_at(pos);
-        if elm.is_valid() {
-            min = Some(match min {
-                Some(m) if m < elm => m,
-                _ => elm,
-            });
-        }
-    });
+    matching.clear();
+    workspace::with_cached_workspace(weights.n(), |workspace| {
+        run_in(weights, workspace)?;
+        workspace.marks.each_star(|pos| matching.push(pos));
+        Ok(())
+    })
+}
 
-    if let Some(minval) = min {
-        for row in 0..n {
-            if cov.is_row_covered(row) {
-                c.add_row(row, minval);
-            }
-        }
-        for column in 0..n {
-            if !cov.is_column_covered(column) {
-                c.sub_column(column, minval);
-            }
-        }
+/// Like `solve_assignment_into`, but stores the matching as a permutation:
+/// `columns[row]` is the column assigned to `row`. `columns` is unspecified
+/// on error.
+///
+/// # Panics
+///
+/// If `columns.len()` isn't `weights.n()`.
+pub fn solve_assignment_permutation_into<W>(
+    weights: &mut W,
+    columns: &mut [usize],
+) -> Result<(), Error>
+where
+    W: Weights,
+{
+    assert!(columns.len() == weights.n());
+    workspace::with_cached_workspace(weights.n(), |workspace| {
+        run_in(weights, workspace)?;
+        workspace
+            .marks
+            .each_star(|Position { row, column }| columns[row] = column);
+        Ok(())
+    })
+}
 
-        Ok(Step6::ContinueWithStep4)
-    } else {
-        Err(Error::MatrixNotSolvable)
-    }
+/// Like `solve_assignment`, but reuses the buffers of `workspace`.
+pub(crate) fn solve_assignment_in<W>(
+    weights: &mut W,
+    workspace: &mut Workspace,
+) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+{
+    run_in(weights, workspace)?;
+    Ok(collect_stars(weights.n(), workspace))
 }
 
-pub fn solve_assignment<W>(weights: &mut W) -> Result<Vec<Position>, Error>
+/// Evaluates `$e`, adding the time it took to `$timer` with the `profile`
+/// feature. `$timer` isn't evaluated otherwise.
+macro_rules! timed {
+    ($timer:expr, $e:expr) => {{
+        #[cfg(feature = "profile")]
+        let start = std::time::Instant::now();
+        let result = $e;
+        #[cfg(feature = "profile")]
+        {
+            $timer += start.elapsed();
+        }
+        result
+    }};
+}
+
+/// Runs the algorithm, leaving the stars of the matching in
+/// `workspace.marks`.
+pub(crate) fn run_in<W>(weights: &mut W, workspace: &mut Workspace) -> Result<(), Error>
 where
     W: Weights,
 {
     if !weights.is_solvable() {
         return Err(Error::MatrixNotSolvable);
     }
+    #[cfg(feature = "profile")]
+    {
+        workspace.timings = Timings::default();
+    }
+    timed!(workspace.timings.reduction, step1(weights));
+    run_reduced_in(weights, workspace)
+}
 
-    let n = weights.n();
+/// Runs the algorithm from step 2 on, after step 1 reduced `weights`.
+fn run_reduced_in<W>(weights: &mut W, workspace: &mut Workspace) -> Result<(), Error>
+where
+    W: Weights,
+{
+    run_reduced_with(weights, workspace, step4::<W>)
+}
 
-    let mut marks = MarkMatrix::new(n);
-    let mut coverage = Coverage::new(n);
-    let mut path = Vec::with_capacity(n);
+/// Like `run_reduced_in`, using `step4` for step 4.
+fn run_reduced_with<W, S>(weights: &mut W, workspace: &mut Workspace, step4: S) -> Result<(), Error>
+where
+    W: Weights,
+    S: FnMut(&W, &mut MarkMatrix, &mut Coverage) -> Step4,
+{
+    workspace.prepare(weights.n());
+    timed!(
+        workspace.timings.zero_search,
+        step2(weights, &mut workspace.marks, &mut workspace.coverage)
+    );
+    run_marked_with(weights, workspace, step4)
+}
 
-    step1(weights);
-    step2(weights, &mut marks, &mut coverage);
+/// Like `run_reduced_in`, but stops early like `run_marked_until`.
+pub(crate) fn run_reduced_until<W, F>(
+    weights: &mut W,
+    workspace: &mut Workspace,
+    stop: F,
+) -> Result<bool, Error>
+where
+    W: Weights,
+    F: FnMut(&MarkMatrix) -> bool,
+{
+    workspace.prepare(weights.n());
+    timed!(
+        workspace.timings.zero_search,
+        step2(weights, &mut workspace.marks, &mut workspace.coverage)
+    );
+    run_marked_until(weights, workspace, step4::<W>, stop)
+}
+
+/// Runs the algorithm from step 3 on, continuing from the stars in
+/// `workspace.marks`, which must be zeros of `weights`. The coverage and
+/// primes must be clear.
+pub(crate) fn run_marked_with<W, S>(
+    weights: &mut W,
+    workspace: &mut Workspace,
+    step4: S,
+) -> Result<(), Error>
+where
+    W: Weights,
+    S: FnMut(&W, &mut MarkMatrix, &mut Coverage) -> Step4,
+{
+    run_marked_until(weights, workspace, step4, |_| false).map(|_| ())
+}
+
+/// Like `run_marked_with`, but calls `stop` with the marks before each
+/// augmentation and each step 6, and returns `Ok(false)` as soon as it returns true, leaving
+/// the solve unfinished.
+pub(crate) fn run_marked_until<W, S, F>(
+    weights: &mut W,
+    workspace: &mut Workspace,
+    mut step4: S,
+    mut stop: F,
+) -> Result<bool, Error>
+where
+    W: Weights,
+    S: FnMut(&W, &mut MarkMatrix, &mut Coverage) -> Step4,
+    F: FnMut(&MarkMatrix) -> bool,
+{
+    let Workspace {
+        marks,
+        coverage,
+        path,
+        minima,
+        #[cfg(feature = "profile")]
+        timings,
+    } = workspace;
+
+    minima.clear();
     'step3: loop {
-        match step3(weights, &marks, &mut coverage) {
+        if stop(marks) {
+            return Ok(false);
+        }
+        match timed!(timings.zero_search, step3(weights, marks, coverage)) {
             Step3::ContinueWithStep4 { .. } => 'step4: loop {
-                match step4(weights, &mut marks, &mut coverage) {
+                match timed!(timings.zero_search, step4(weights, marks, coverage)) {
                     Step4::ContinueWithStep5 { z0_pos } => {
-                        match step5(&mut marks, &mut coverage, z0_pos, &mut path)? {
+                        match timed!(timings.augmentation, step5(marks, coverage, z0_pos, path))? {
                             Step5::ContinueWithStep3 => {
+                                minima.clear();
                                 continue 'step3;
                             }
                         }
                     }
-                    Step4::ContinueWithStep6 => match step6(weights, &coverage)? {
-                        Step6::ContinueWithStep4 => {
-                            continue 'step4;
+                    Step4::ContinueWithStep6 => {
+                        if stop(marks) {
+                            return Ok(false);
                         }
-                    },
+                        match timed!(timings.dual_update, step6_with(weights, coverage, minima))? {
+                            Step6::ContinueWithStep4 => {
+                                continue 'step4;
+                            }
+                        }
+                    }
                 }
             },
             Step3::Done => {
@@ -305,11 +723,7 @@ where
         }
     }
 
-    // now look for the starred elements
-    let mut matching = Vec::with_capacity(n);
-    marks.each_star(|pos| matching.push(pos));
-    assert!(matching.len() == n);
-    return Ok(matching);
+    Ok(true)
 }
 
 #[cfg(test)]
@@ -448,6 +862,30 @@ fn test_step6() {
     assert_eq!(exp, weights.as_slice());
 }
 
+#[test]
+fn test_step6_with() {
+    const N: usize = 3;
+    let c = vec![0, 150, 100, 50, 250, 0, 0, 200, 50];
+
+    let mut weights: WeightMatrix<i32> = WeightMatrix::from_row_vec(N, c);
+    let mut minima = ColumnMinima::new();
+    let mut coverage = Coverage::new(N);
+    coverage.cover_column(0);
+    coverage.cover_column(2);
+
+    let mut expected = weights.clone();
+    step6(&mut expected, &coverage).unwrap();
+    step6_with(&mut weights, &coverage, &mut minima).unwrap();
+    assert_eq!(expected.as_slice(), weights.as_slice());
+
+    // covering the row of the minimum of column 1 rescans the column.
+    coverage.cover_row(0);
+    step6(&mut expected, &coverage).unwrap();
+    step6_with(&mut weights, &coverage, &mut minima).unwrap();
+    assert_eq!(&[50, 0, 150, 50, 50, 0, 0, 0, 50], weights.as_slice());
+    assert_eq!(expected.as_slice(), weights.as_slice());
+}
+
 #[test]
 fn test_step4_case2() {
     const N: usize = 3;
@@ -500,7 +938,7 @@ fn test_step5() {
     coverage.cover_column(2);
     coverage.cover_row(0);
 
-    let mut path = Vec::new();
+    let mut path = Path::new();
     let next_step = step5(&mut marks, &mut coverage, pos(2, 0), &mut path);
     assert_eq!(Ok(Step5::ContinueWithStep3), next_step);
 
@@ -541,6 +979,21 @@ fn test_solve() {
     assert_eq!(vec![pos(0, 1), pos(1, 2), pos(2, 0)], matching);
 }
 
+#[test]
+fn test_solve_from_vec() {
+    let c = vec![250, 400, 350, 400, 600, 350, 200, 400, 250];
+    let (result, mut buffer) = solve_from_vec(3, c.clone());
+    let expected = solve_assignment(&mut WeightMatrix::from_row_vec(3, c.clone())).unwrap();
+    assert_eq!(Ok(Assignment::from_positions(expected.clone())), result);
+
+    // the buffer is reused for the next solve.
+    let capacity = buffer.capacity();
+    buffer.copy_from_slice(&c);
+    let (result, buffer) = solve_from_vec(3, buffer);
+    assert_eq!(Ok(Assignment::from_positions(expected)), result);
+    assert_eq!(capacity, buffer.capacity());
+}
+
 #[test]
 fn test_solve_equal_rows_stepwise() {
     const N: usize = 2;
@@ -571,7 +1024,7 @@ fn test_solve_equal_rows_stepwise() {
     assert!(marks.is_none(pos(1, 0)));
 
     // step 3
-    let next_step = step3(&weights, &mut marks, &mut coverage);
+    let next_step = step3(&weights, &marks, &mut coverage);
     assert_eq!(Step3::Done, next_step);
 }
 
@@ -678,7 +1131,7 @@ fn test_solve_random10() {
 
 #[test]
 fn test_invalid() {
-    use std::f32;
+    use core::f32;
     const N: usize = 3;
     let c = vec![
         // row 0
@@ -704,7 +1157,7 @@ fn test_invalid() {
 
 #[test]
 fn test_should_be_unsolvable_if_first_cell_of_each_row_is_invalid() {
-    use std::f32;
+    use core::f32;
     const N: usize = 3;
 
     let c = vec![
@@ -729,7 +1182,7 @@ fn test_should_be_unsolvable_if_first_cell_of_each_row_is_invalid() {
 
 #[test]
 fn test_invalid2() {
-    use std::f32;
+    use core::f32;
     const N: usize = 3;
 
     let c = vec![
@@ -756,7 +1209,7 @@ fn test_invalid2() {
 
 #[test]
 fn test_unsolvable() {
-    use std::f32;
+    use core::f32;
 
     const N: usize = 3;
     let c = vec![
@@ -778,7 +1231,7 @@ fn test_unsolvable() {
 
 #[test]
 fn test_unsolvable2() {
-    use std::f32;
+    use core::f32;
     const N: usize = 3;
 
     let c = vec![
@@ -800,3 +1253,213 @@ fn test_unsolvable2() {
     let res = solve_assignment(&mut weights);
     assert_eq!(Err(Error::MatrixNotSolvable), res);
 }
+
+#[test]
+fn test_solve_cached() {
+    for seed in 0..6 {
+        let n = 2 + seed as usize % 3;
+        let weights = generators::uniform(n, 0..50, seed);
+        assert_eq!(
+            solve_assignment(&mut weights.clone()),
+            solve_assignment_cached(&mut weights.clone())
+        );
+    }
+}
+
+#[test]
+fn test_solve_into() {
+    let mut matching = vec![pos(5, 5)];
+    let mut columns = [0; 3];
+    for seed in 0..4 {
+        let weights = generators::uniform(3, 0..50, seed);
+        let expected = solve_assignment(&mut weights.clone()).unwrap();
+        solve_assignment_into(&mut weights.clone(), &mut matching).unwrap();
+        assert_eq!(expected, matching);
+        solve_assignment_permutation_into(&mut weights.clone(), &mut columns).unwrap();
+        for p in &expected {
+            assert_eq!(p.column, columns[p.row]);
+        }
+    }
+}
+
+#[test]
+fn test_solve_near_permutation() {
+    // each row has its unique minimum in a distinct column.
+    let mut weights = WeightMatrix::from_row_vec(3, vec![5, 1, 9, 2, 7, 7, 8, 8, 3]);
+    assert_eq!(
+        vec![pos(0, 1), pos(1, 0), pos(2, 2)],
+        solve_assignment(&mut weights).unwrap()
+    );
+}
+
+#[test]
+fn test_solve_greedy_meets_lower_bound() {
+    // row reduction leaves column 2 without a zero, but the greedy
+    // assignment of column minima is optimal.
+    let c = vec![1, 5, 4, 3, 1, 5, 2, 9, 4];
+    let mut weights = WeightMatrix::from_row_vec(3, c.clone());
+    step1(&mut weights);
+    assert_eq!(
+        Some(vec![pos(0, 0), pos(1, 1), pos(2, 2)]),
+        bounds::tight_permutation(&weights)
+    );
+    let matching = solve_assignment(&mut WeightMatrix::from_row_vec(3, c.clone())).unwrap();
+    assert_eq!(6, calc_cost(0, &c, &matching, 3));
+}
+
+#[test]
+fn test_solve_deterministic_ties() {
+    // every permutation is optimal, the scan orders pick the identity.
+    let mut weights = WeightMatrix::from_row_vec(3, vec![7; 9]);
+    assert_eq!(
+        vec![pos(0, 0), pos(1, 1), pos(2, 2)],
+        solve_assignment(&mut weights).unwrap()
+    );
+
+    // four optimal matchings of cost 1, the scan orders decide.
+    let c = vec![1, 1, 0, 1, 1, 0, 0, 0, 9];
+    let expected = vec![pos(0, 2), pos(1, 0), pos(2, 1)];
+    for _ in 0..3 {
+        let matching = solve_assignment(&mut WeightMatrix::from_row_vec(3, c.clone())).unwrap();
+        assert_eq!(expected, matching);
+    }
+
+    for seed in 0..10 {
+        let weights = generators::degenerate(8, 3, seed);
+        let matching = solve_assignment(&mut weights.clone()).unwrap();
+        assert!(matching.windows(2).all(|w| w[0].row < w[1].row));
+        assert_eq!(matching, solve_assignment(&mut weights.clone()).unwrap());
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_solve_parallel() {
+    for seed in 0..5 {
+        let weights = generators::uniform(50, 0..1000, seed);
+        assert_eq!(
+            solve_assignment(&mut weights.clone()),
+            solve_assignment_parallel(&mut weights.clone())
+        );
+    }
+    let weights = generators::uniform(300, 0..1000, 0);
+    assert_eq!(
+        solve_assignment(&mut weights.clone()),
+        solve_matrix_parallel(&mut weights.clone())
+    );
+}
+
+#[test]
+fn test_solve_with_scan_order() {
+    let cost = |weights: &WeightMatrix<u32>, matching: &[Position]| -> u32 {
+        matching.iter().map(|&pos| weights.element_at(pos)).sum()
+    };
+    let orders = [ScanOrder::RowMajor, ScanOrder::RoundRobin];
+    let mut differ = false;
+    for seed in 0..10 {
+        for weights in &[
+            generators::degenerate(12, 3, seed),
+            generators::uniform(20, 0..100, seed),
+        ] {
+            let expected = solve_assignment(&mut weights.clone()).unwrap();
+            assert_eq!(
+                Ok(expected.clone()),
+                solve_assignment_with_scan_order(&mut weights.clone(), ScanOrder::ColumnMajor)
+            );
+            for &order in &orders {
+                let matching =
+                    solve_assignment_with_scan_order(&mut weights.clone(), order).unwrap();
+                assert_eq!(cost(weights, &expected), cost(weights, &matching));
+                differ |= matching != expected;
+            }
+        }
+    }
+    // the order decides among several optima.
+    assert!(differ);
+}
+
+#[test]
+fn test_solve_warm() {
+    let cost = |weights: &WeightMatrix<i32>, matching: &[Position]| -> i32 {
+        matching.iter().map(|&pos| weights.element_at(pos)).sum()
+    };
+    for seed in 0..10 {
+        let weights = WeightMatrix::from_fn(12, {
+            let base = generators::uniform(12, 0..100, seed);
+            move |(row, column)| base.element_at(pos(row, column)) as i32
+        });
+        let expected = cost(&weights, &solve_assignment(&mut weights.clone()).unwrap());
+        // too large, negative and arbitrary potentials all give the optimum.
+        for potentials in &[vec![1000; 12], vec![-5; 12], (0..12).collect()] {
+            let matching = solve_assignment_warm(&mut weights.clone(), potentials).unwrap();
+            assert_eq!(expected, cost(&weights, &matching));
+        }
+    }
+}
+
+#[test]
+fn test_solve_iter() {
+    for seed in 0..10 {
+        let weights = generators::uniform(10, 0..100, seed);
+        let expected = solve_assignment(&mut weights.clone()).unwrap();
+        let iter = solve_assignment_iter(&mut weights.clone()).unwrap();
+        assert_eq!(10, iter.len());
+        assert_eq!(expected, iter.collect::<Vec<_>>());
+    }
+    // the greedy matching of the reduced matrix is optimal.
+    let weights = WeightMatrix::from_row_vec(2, vec![1, 2, 2, 1]);
+    let cost: i32 = solve_assignment_iter(&mut weights.clone())
+        .unwrap()
+        .map(|pos| weights.element_at(pos))
+        .sum();
+    assert_eq!(2, cost);
+    let mut unsolvable = WeightMatrix::from_row_vec(1, vec![f32::INFINITY]);
+    assert!(solve_assignment_iter(&mut unsolvable).is_err());
+}
+
+#[test]
+fn test_solve_max_assignment() {
+    let weights = WeightMatrix::from_row_vec(3, vec![7u8, 250, 1, 255, 3, 0, 2, 2, 9]);
+    let matching = solve_max_assignment(&weights).unwrap();
+    assert_eq!(vec![pos(0, 1), pos(1, 0), pos(2, 2)], matching);
+    assert_eq!(250, weights.element_at(pos(0, 1)));
+
+    let negated = WeightMatrix::from_fn(3, |(row, column)| {
+        -f64::from(weights.element_at(pos(row, column)))
+    });
+    assert_eq!(solve_assignment(&mut negated.clone()), Ok(matching));
+
+    let inf = f64::INFINITY;
+    let weights = WeightMatrix::from_row_vec(2, vec![5.0, inf, 1.0, 2.0]);
+    assert_eq!(
+        Ok(vec![pos(0, 0), pos(1, 1)]),
+        solve_max_assignment(&weights)
+    );
+    let weights = WeightMatrix::from_row_vec(2, vec![5.0, inf, 1.0, inf]);
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_max_assignment(&weights)
+    );
+}
+
+#[test]
+fn test_solve_assignment_owned() {
+    let c = vec![250u32, 400, 350, 400, 600, 350, 200, 400, 250];
+    let weights = WeightMatrix::from_row_vec(3, c.clone());
+    let (assignment, cost) = solve_assignment_owned(&weights).unwrap();
+    assert_eq!(&[pos(0, 1), pos(1, 2), pos(2, 0)], assignment.positions());
+    assert_eq!(calc_cost(0, &c, assignment.positions(), 3), cost);
+    assert_eq!(&c[..], weights.as_slice());
+
+    let inf = f64::INFINITY;
+    let weights = WeightMatrix::from_row_vec(2, vec![1.0, inf, 2.0, inf]);
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_assignment_owned(&weights)
+    );
+    let empty = WeightMatrix::from_row_vec(0, Vec::<i32>::new());
+    assert_eq!(
+        Ok((Assignment::default(), 0)),
+        solve_assignment_owned(&empty)
+    );
+}
@@ -0,0 +1,193 @@
+//! Solving with bounded work, for callers that need a bounded latency.
+//!
+//! On large instances, in particular float matrices with many ties, the
+//! steps of `solve_assignment` may take a long time. `Limits` caps the
+//! number of iterations, each an augmentation or a dual update (step 6), and
+//! the time spent. `solve_assignment_with_limits` returns
+//! `Error::LimitExceeded` once either is reached, or once its progress
+//! callback returns false, e.g. because a cancellation flag was set.
+//! `solve_assignment_with_limits_into` leaves the partial matching found so
+//! far in its output. The time limit needs the clock of `std`.
+//!
+//! Like the deadline of `deadline::Strategy`, the limits are checked between
+//! the steps of the algorithm, so a single step, taking `O(n^2)` at most, may
+//! run past them.
+use crate::steps::step1;
+use crate::workspace::Workspace;
+use crate::{bounds, run_reduced_until, Error, Position, Weights};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Limits {
+    max_iterations: Option<usize>,
+    #[cfg(feature = "std")]
+    time_limit: Option<Duration>,
+}
+
+impl Limits {
+    /// No limits.
+    pub fn new() -> Limits {
+        Limits::default()
+    }
+
+    /// Stops after `max_iterations` augmentations and dual updates.
+    pub fn max_iterations(mut self, max_iterations: usize) -> Limits {
+        self.max_iterations = Some(max_iterations);
+        self
+    }
+
+    /// Stops once `time_limit` has passed since the start of the solve.
+    #[cfg(feature = "std")]
+    pub fn time_limit(mut self, time_limit: Duration) -> Limits {
+        self.time_limit = Some(time_limit);
+        self
+    }
+
+    fn is_reached(&self, progress: &Progress) -> bool {
+        let iterations = self
+            .max_iterations
+            .is_some_and(|max| progress.iterations >= max);
+        #[cfg(feature = "std")]
+        let time = self
+            .time_limit
+            .is_some_and(|limit| progress.elapsed >= limit);
+        #[cfg(not(feature = "std"))]
+        let time = false;
+        iterations || time
+    }
+}
+
+/// The state of a solve, passed to the progress callback before each
+/// iteration.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Progress {
+    /// The augmentations and dual updates done so far.
+    pub iterations: usize,
+    /// The size of the partial matching, which reaches `n` at the end.
+    pub matched: usize,
+    pub n: usize,
+    #[cfg(feature = "std")]
+    pub elapsed: Duration,
+}
+
+/// Like `solve_assignment`, but returns `Error::LimitExceeded` once a limit
+/// is reached or `progress` returns false.
+pub fn solve_assignment_with_limits<W, F>(
+    weights: &mut W,
+    limits: &Limits,
+    progress: F,
+) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+    F: FnMut(&Progress) -> bool,
+{
+    let mut matching = Vec::new();
+    solve_assignment_with_limits_into(weights, limits, progress, &mut matching)?;
+    Ok(matching)
+}
+
+/// Like `solve_assignment_with_limits`, but appends the matching to
+/// `matching` after clearing it. On `Error::LimitExceeded`, `matching` holds
+/// the partial matching found so far, sorted by row: cells that are part of
+/// an optimal matching of the rows and columns they cover, though not
+/// necessarily of the whole instance.
+pub fn solve_assignment_with_limits_into<W, F>(
+    weights: &mut W,
+    limits: &Limits,
+    mut progress: F,
+    matching: &mut Vec<Position>,
+) -> Result<(), Error>
+where
+    W: Weights,
+    F: FnMut(&Progress) -> bool,
+{
+    matching.clear();
+    let n = weights.n();
+    if !weights.is_solvable() {
+        return Err(Error::MatrixNotSolvable);
+    }
+    step1(weights);
+    if let Some(tight) = bounds::tight_permutation(weights) {
+        matching.extend(tight);
+        return Ok(());
+    }
+
+    #[cfg(feature = "std")]
+    let start = Instant::now();
+    let mut iterations = 0;
+    let mut workspace = Workspace::new(n);
+    let finished = run_reduced_until(weights, &mut workspace, |marks| {
+        let matched = marks.star_count();
+        if matched == n {
+            // only the final check is left.
+            return false;
+        }
+        let state = Progress {
+            iterations,
+            matched,
+            n,
+            #[cfg(feature = "std")]
+            elapsed: start.elapsed(),
+        };
+        iterations += 1;
+        !progress(&state) || limits.is_reached(&state)
+    })?;
+    workspace.marks.each_star(|pos| matching.push(pos));
+    if finished {
+        Ok(())
+    } else {
+        Err(Error::LimitExceeded)
+    }
+}
+
+#[test]
+fn test_solve_assignment_with_limits() {
+    use crate::{generators, solve_assignment};
+
+    let weights = generators::uniform(40, 0..1000, 1);
+    let expected = solve_assignment(&mut weights.clone());
+    let mut last = None;
+    let matching = solve_assignment_with_limits(&mut weights.clone(), &Limits::new(), |p| {
+        assert_eq!(40, p.n);
+        assert!(last.is_none_or(|last: Progress| last.iterations + 1 == p.iterations));
+        last = Some(*p);
+        true
+    });
+    assert_eq!(expected, matching);
+    let iterations = last.unwrap().iterations;
+    assert!(iterations > 2);
+
+    // enough iterations finish the solve.
+    let limits = Limits::new().max_iterations(iterations + 1);
+    let matching = solve_assignment_with_limits(&mut weights.clone(), &limits, |_| true);
+    assert_eq!(expected, matching);
+
+    let limits = Limits::new().max_iterations(2);
+    let mut partial = Vec::new();
+    assert_eq!(
+        Err(Error::LimitExceeded),
+        solve_assignment_with_limits_into(&mut weights.clone(), &limits, |_| true, &mut partial)
+    );
+    assert!(!partial.is_empty() && partial.len() < 40);
+    assert!(partial.windows(2).all(|w| w[0].row < w[1].row));
+
+    // cancelled by the callback.
+    let result =
+        solve_assignment_with_limits(&mut weights.clone(), &Limits::new(), |p| p.iterations < 1);
+    assert_eq!(Err(Error::LimitExceeded), result);
+    #[cfg(feature = "std")]
+    {
+        let limits = Limits::new().time_limit(Duration::ZERO);
+        let result = solve_assignment_with_limits(&mut weights.clone(), &limits, |_| true);
+        assert_eq!(Err(Error::LimitExceeded), result);
+    }
+
+    let inf = f64::INFINITY;
+    let mut weights = crate::WeightMatrix::from_row_vec(2, vec![1.0, inf, 2.0, inf]);
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_assignment_with_limits(&mut weights, &Limits::new(), |_| true)
+    );
+}
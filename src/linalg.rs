@@ -0,0 +1,170 @@
+//! `Weights` for the matrices of ndarray and nalgebra, so they are solved in
+//! place, without copying them into a `WeightMatrix`.
+//!
+//! With the `ndarray` feature, any mutable two-dimensional `ArrayBase` is
+//! `Weights`: an owned `Array2`, or an `ArrayViewMut2` of a larger array,
+//! in any memory layout. With the `nalgebra` feature, so is a dynamically
+//! sized `Matrix` with mutable storage, e.g. a `DMatrix` or a
+//! `DMatrixViewMut`.
+//!
+//! As with `WeightMatrix::from_row_vec`, the validity of a cell is that of
+//! its value. The matrix must be square, which is checked by `is_solvable`
+//! at the start of a solve.
+use crate::{Position, WeightNum, Weights};
+
+/// The minimum of the valid values, if any.
+fn min_of_valid<'a, T, I>(values: I) -> Option<T>
+where
+    T: WeightNum + 'a,
+    I: IntoIterator<Item = &'a T>,
+{
+    values
+        .into_iter()
+        .copied()
+        .filter(WeightNum::is_valid)
+        .reduce(|min, value| if value < min { value } else { min })
+}
+
+#[cfg(feature = "ndarray")]
+impl<S, T> Weights for ndarray::ArrayBase<S, ndarray::Ix2>
+where
+    S: ndarray::DataMut<Elem = T>,
+    T: WeightNum,
+{
+    type T = T;
+
+    #[inline(always)]
+    fn n(&self) -> usize {
+        self.nrows()
+    }
+
+    #[inline]
+    fn element_at(&self, pos: Position) -> T {
+        self[[pos.row, pos.column]]
+    }
+
+    fn sub_min_of_each_row(&mut self) {
+        for mut row in self.genrows_mut() {
+            if let Some(min) = min_of_valid(row.iter()) {
+                row.iter_mut().for_each(|cur| *cur = cur.sub_if_valid(min));
+            }
+        }
+    }
+
+    fn add_row(&mut self, row: usize, val: T) {
+        self.row_mut(row)
+            .iter_mut()
+            .for_each(|cur| *cur = cur.add_if_valid(val));
+    }
+
+    fn sub_column(&mut self, col: usize, val: T) {
+        self.column_mut(col)
+            .iter_mut()
+            .for_each(|cur| *cur = cur.sub_if_valid(val));
+    }
+
+    /// # Panics
+    ///
+    /// If the matrix isn't square.
+    fn is_solvable(&self) -> bool {
+        assert!(self.is_square(), "expected a square matrix");
+        self.genrows()
+            .into_iter()
+            .all(|row| row.iter().any(WeightNum::is_valid))
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+impl<S, T> Weights for nalgebra::Matrix<T, nalgebra::Dyn, nalgebra::Dyn, S>
+where
+    S: nalgebra::StorageMut<T, nalgebra::Dyn, nalgebra::Dyn>,
+    T: WeightNum + nalgebra::Scalar,
+{
+    type T = T;
+
+    #[inline(always)]
+    fn n(&self) -> usize {
+        self.nrows()
+    }
+
+    #[inline]
+    fn element_at(&self, pos: Position) -> T {
+        self[(pos.row, pos.column)]
+    }
+
+    fn sub_min_of_each_row(&mut self) {
+        for mut row in self.row_iter_mut() {
+            if let Some(min) = min_of_valid(row.iter()) {
+                row.iter_mut().for_each(|cur| *cur = cur.sub_if_valid(min));
+            }
+        }
+    }
+
+    fn add_row(&mut self, row: usize, val: T) {
+        self.row_mut(row)
+            .iter_mut()
+            .for_each(|cur| *cur = cur.add_if_valid(val));
+    }
+
+    fn sub_column(&mut self, col: usize, val: T) {
+        self.column_mut(col)
+            .iter_mut()
+            .for_each(|cur| *cur = cur.sub_if_valid(val));
+    }
+
+    /// # Panics
+    ///
+    /// If the matrix isn't square.
+    fn is_solvable(&self) -> bool {
+        assert!(self.is_square(), "expected a square matrix");
+        self.row_iter()
+            .all(|row| row.iter().any(WeightNum::is_valid))
+    }
+}
+
+#[test]
+fn test_linalg_weights() {
+    use crate::{generators, solve_assignment};
+
+    for seed in 0..5 {
+        let weights = generators::uniform(8, 0..100, seed);
+        let expected = solve_assignment(&mut weights.clone());
+
+        #[cfg(feature = "ndarray")]
+        {
+            use ndarray::{s, Array2, ShapeBuilder};
+
+            let mut array = Array2::from_shape_vec((8, 8), weights.as_slice().to_vec()).unwrap();
+            assert_eq!(expected, solve_assignment(&mut array.clone()));
+            assert_eq!(expected, solve_assignment(&mut array.view_mut()));
+            // a view into a larger, column-major array.
+            let mut larger = Array2::zeros((10, 10).f());
+            larger.slice_mut(s![1..9, 2..10]).assign(&array);
+            assert_eq!(
+                expected,
+                solve_assignment(&mut larger.slice_mut(s![1..9, 2..10]))
+            );
+            assert_eq!(expected, solve_assignment(&mut array));
+        }
+
+        #[cfg(feature = "nalgebra")]
+        {
+            let mut matrix = nalgebra::DMatrix::from_row_slice(8, 8, weights.as_slice());
+            assert_eq!(expected, solve_assignment(&mut matrix.clone()));
+            assert_eq!(
+                expected,
+                solve_assignment(&mut matrix.view_mut((0, 0), (8, 8)))
+            );
+        }
+    }
+
+    #[cfg(feature = "ndarray")]
+    {
+        let inf = f64::INFINITY;
+        let mut array = ndarray::arr2(&[[1.0, inf], [2.0, inf]]);
+        assert_eq!(
+            Err(crate::Error::MatrixNotSolvable),
+            solve_assignment(&mut array)
+        );
+    }
+}
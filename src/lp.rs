@@ -0,0 +1,200 @@
+//! Formulating instances as linear programs for the solvers of `good_lp`.
+//!
+//! The assignment problem is the LP `min sum c_ij x_ij` subject to each row
+//! and column summing to 1, with `x_ij >= 0` for the valid cells. Its
+//! constraint matrix is totally unimodular, so an LP solver finds an integral
+//! optimum without integrality constraints. `LpFormulation` builds this LP,
+//! lets the caller add side constraints the combinatorial solvers don't
+//! support, e.g. on the total of some cells, and extracts the matching.
+//! Solving the plain formulation cross-checks the other solvers of the
+//! crate.
+//!
+//! Side constraints can break integrality. Use `LpFormulation::binary` and a
+//! solver supporting integer variables for them, or check the result with
+//! `LpSolution::is_integral`.
+use crate::{Position, WeightNum, Weights};
+use good_lp::{
+    variable, Constraint, Expression, ProblemVariables, Solution, Solver, SolverModel, Variable,
+};
+
+pub struct LpFormulation {
+    n: usize,
+    variables: ProblemVariables,
+    /// The variable of each cell in row-major order, `None` if invalid.
+    cells: Vec<Option<Variable>>,
+    objective: Expression,
+    constraints: Vec<Constraint>,
+}
+
+impl LpFormulation {
+    /// The LP relaxation of `weights`, with variables in `[0, 1]`.
+    ///
+    /// # Panics
+    ///
+    /// If a valid value can't be converted to `f64`.
+    pub fn new<W: Weights>(weights: &W) -> LpFormulation {
+        LpFormulation::with_variables(weights, false)
+    }
+
+    /// Like `new`, with binary variables, for solvers supporting integer
+    /// programs.
+    pub fn binary<W: Weights>(weights: &W) -> LpFormulation {
+        LpFormulation::with_variables(weights, true)
+    }
+
+    fn with_variables<W: Weights>(weights: &W, binary: bool) -> LpFormulation {
+        let n = weights.n();
+        let mut variables = ProblemVariables::new();
+        let mut cells = Vec::with_capacity(n * n);
+        let mut objective = Expression::from(0.0);
+        for row in 0..n {
+            for column in 0..n {
+                let pos = Position { row, column };
+                if !weights.is_valid_at(pos) {
+                    cells.push(None);
+                    continue;
+                }
+                let cost = weights
+                    .element_at(pos)
+                    .to_f64()
+                    .expect("LP formulations require weights convertible to f64");
+                let definition = if binary {
+                    variable().binary()
+                } else {
+                    variable().min(0.0).max(1.0)
+                };
+                let x = variables.add(definition);
+                objective += cost * x;
+                cells.push(Some(x));
+            }
+        }
+
+        let sum = |cells: &mut dyn Iterator<Item = &Option<Variable>>| -> Expression {
+            cells.flatten().map(|&x| Expression::from(x)).sum()
+        };
+        let mut constraints = Vec::with_capacity(2 * n);
+        for row in 0..n {
+            let mut row_cells = cells[row * n..(row + 1) * n].iter();
+            constraints.push(sum(&mut row_cells).eq(1.0));
+        }
+        for column in 0..n {
+            let mut column_cells = cells.iter().skip(column).step_by(n.max(1));
+            constraints.push(sum(&mut column_cells).eq(1.0));
+        }
+        LpFormulation {
+            n,
+            variables,
+            cells,
+            objective,
+            constraints,
+        }
+    }
+
+    #[inline]
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// The variable of the cell at `pos`, `None` if the cell is invalid.
+    pub fn variable(&self, pos: Position) -> Option<Variable> {
+        self.cells[pos.row * self.n + pos.column]
+    }
+
+    /// Adds a side constraint over the variables of the cells.
+    pub fn add_constraint(&mut self, constraint: Constraint) {
+        self.constraints.push(constraint);
+    }
+
+    /// Solves the program with `solver`, e.g. `good_lp::default_solver`.
+    /// Instances without a complete matching are infeasible, e.g.
+    /// `ResolutionError::Infeasible` for most solvers.
+    pub fn solve<S: Solver>(
+        self,
+        solver: S,
+    ) -> Result<LpSolution, <S::Model as SolverModel>::Error> {
+        let mut model = self.variables.minimise(self.objective).using(solver);
+        for constraint in self.constraints {
+            model = model.with(constraint);
+        }
+        let solution = model.solve()?;
+        let values = self
+            .cells
+            .iter()
+            .map(|x| x.map_or(0.0, |x| solution.value(x)))
+            .collect();
+        Ok(LpSolution { n: self.n, values })
+    }
+}
+
+/// The values of the variables of a solved `LpFormulation`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LpSolution {
+    n: usize,
+    values: Vec<f64>,
+}
+
+impl LpSolution {
+    /// The value of the variable of the cell at `pos`, 0 for invalid cells.
+    pub fn value(&self, pos: Position) -> f64 {
+        self.values[pos.row * self.n + pos.column]
+    }
+
+    /// Whether all values are within `1e-6` of 0 or 1.
+    pub fn is_integral(&self) -> bool {
+        self.values
+            .iter()
+            .all(|&x| x.abs() < 1e-6 || (x - 1.0).abs() < 1e-6)
+    }
+
+    /// The cells with a value above one half, sorted by row. A matching if
+    /// the solution is integral.
+    pub fn matching(&self) -> Vec<Position> {
+        let n = self.n;
+        (0..n * n)
+            .filter(|&index| self.values[index] > 0.5)
+            .map(|index| Position {
+                row: index / n,
+                column: index % n,
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_lp_formulation() {
+    use crate::{generators, solve_assignment, WeightMatrix};
+
+    for seed in 0..5 {
+        let weights = generators::uniform(6, 0..100, seed);
+        let cost = |m: &[Position]| m.iter().map(|&pos| weights.element_at(pos)).sum::<u32>();
+        let expected = cost(&solve_assignment(&mut weights.clone()).unwrap());
+        let solution = LpFormulation::new(&weights)
+            .solve(good_lp::default_solver)
+            .unwrap();
+        assert!(solution.is_integral());
+        assert_eq!(expected, cost(&solution.matching()));
+    }
+
+    // a side constraint: cells (0, 0) and (1, 1) can't both be used.
+    let pos = |row, column| Position { row, column };
+    let weights = WeightMatrix::from_row_vec(3, vec![1.0, 5.0, 9.0, 5.0, 1.0, 9.0, 9.0, 9.0, 1.0]);
+    let mut lp = LpFormulation::new(&weights);
+    let (a, b) = (
+        lp.variable(pos(0, 0)).unwrap(),
+        lp.variable(pos(1, 1)).unwrap(),
+    );
+    lp.add_constraint((a + b).leq(1.0));
+    let solution = lp.solve(good_lp::default_solver).unwrap();
+    // the relaxation mixes the identity, of cost 3, with the best matching
+    // avoiding both cells, of cost 11.
+    assert!(!solution.is_integral());
+    assert!((solution.value(pos(0, 0)) - 0.5).abs() < 1e-6);
+    assert!((solution.value(pos(0, 1)) - 0.5).abs() < 1e-6);
+
+    let inf = f64::INFINITY;
+    let weights = WeightMatrix::from_row_vec(2, vec![1.0, inf, 1.0, inf]);
+    assert_eq!(
+        Err(good_lp::ResolutionError::Infeasible),
+        LpFormulation::new(&weights).solve(good_lp::default_solver)
+    );
+}
@@ -0,0 +1,138 @@
+//! Lazily transformed weights.
+//!
+//! `MappedWeights` applies a transform to the values of another `Costs`
+//! when they are read, without copying the matrix. It is read-only: solve it
+//! with `solve_assignment_immutable` or through `DualWeights`, which keep the
+//! reductions as row and column potentials, so the transformed values must
+//! be of a signed type.
+//!
+//! Whether a transform preserves the optimum depends on the objective. Any
+//! strictly increasing transform (`ln`, `sqrt`, ...) preserves the order of
+//! the values and so the optimum of the bottleneck objective (see
+//! `bottleneck`). For the sum objective of `solve_assignment`, a nonlinear
+//! transform generally changes the optimum: that is the point of solving on
+//! `ln p` instead of `p`, but not what a rescaling should do. `affine`
+//! transforms with a positive scale are always safe: they change every sum
+//! by the same factor and offset.
+use crate::dual::Costs;
+use crate::{Position, WeightNum};
+use core::ops::Mul;
+
+#[derive(Debug, Clone)]
+pub struct MappedWeights<W, F> {
+    inner: W,
+    f: F,
+}
+
+impl<W, F> MappedWeights<W, F>
+where
+    W: Costs,
+    F: Fn(W::T) -> W::T,
+{
+    /// Reads the values of `inner` through `f`. The cells of `inner` that
+    /// are invalid, or whose transformed value is invalid, are invalid.
+    pub fn new(inner: W, f: F) -> MappedWeights<W, F> {
+        MappedWeights { inner, f }
+    }
+
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// Maps the values `x` of `inner` to `scale * x + offset`, which preserves
+/// the optimum of every objective.
+///
+/// # Panics
+///
+/// If `scale` isn't positive.
+pub fn affine<W>(inner: W, scale: W::T, offset: W::T) -> MappedWeights<W, impl Fn(W::T) -> W::T>
+where
+    W: Costs,
+    W::T: Default + Mul<Output = W::T>,
+{
+    assert!(scale > W::T::default(), "the scale must be positive");
+    MappedWeights::new(inner, move |x| scale * x + offset)
+}
+
+impl<W, F> Costs for MappedWeights<W, F>
+where
+    W: Costs,
+    F: Fn(W::T) -> W::T,
+{
+    type T = W::T;
+
+    #[inline(always)]
+    fn n(&self) -> usize {
+        self.inner.n()
+    }
+
+    #[inline]
+    fn element_at(&self, pos: Position) -> W::T {
+        (self.f)(self.inner.element_at(pos))
+    }
+
+    #[inline]
+    fn is_valid_at(&self, pos: Position) -> bool {
+        self.inner.is_valid_at(pos) && self.element_at(pos).is_valid()
+    }
+}
+
+#[test]
+fn test_mapped_weights() {
+    use crate::{generators, solve_assignment, solve_assignment_immutable, WeightMatrix};
+
+    for seed in 0..10 {
+        let base = generators::uniform(8, 1..100, seed);
+        let weights = WeightMatrix::from_fn(8, |(row, column)| {
+            base.element_at(Position { row, column }) as f64
+        });
+        let cost = |m: &[Position]| m.iter().map(|&pos| weights.element_at(pos)).sum::<f64>();
+        let expected = cost(&solve_assignment(&mut weights.clone()).unwrap());
+        let scaled = affine(weights.clone(), 2.5, -100.0);
+        assert_eq!(
+            expected,
+            cost(&solve_assignment_immutable(&scaled).unwrap())
+        );
+        assert_eq!(
+            base.element_at(Position { row: 0, column: 0 }) as f64,
+            scaled.inner().element_at(Position { row: 0, column: 0 })
+        );
+
+        // the logarithm minimizes the product instead of the sum.
+        let logs = MappedWeights::new(weights.clone(), f64::ln);
+        let log_cost = |m: &[Position]| {
+            m.iter()
+                .map(|&pos| weights.element_at(pos).ln())
+                .sum::<f64>()
+        };
+        let found = solve_assignment_immutable(&logs).unwrap();
+        let expected = solve_assignment(&mut WeightMatrix::from_fn(8, |(row, column)| {
+            weights.element_at(Position { row, column }).ln()
+        }))
+        .unwrap();
+        assert!((log_cost(&expected) - log_cost(&found)).abs() < 1e-9);
+    }
+
+    for seed in 0..50 {
+        let base = generators::uniform(8, 0..100, seed);
+        let weights = WeightMatrix::from_fn(8, |(row, column)| {
+            i64::from(base.element_at(Position { row, column }))
+        });
+        let cost = |m: &[Position]| m.iter().map(|&pos| weights.element_at(pos)).sum::<i64>();
+        let expected = cost(&solve_assignment(&mut weights.clone()).unwrap());
+        let scaled = affine(weights.clone(), 2, 3);
+        assert_eq!(
+            expected,
+            cost(&solve_assignment_immutable(&scaled).unwrap())
+        );
+    }
+
+    // cells mapped to an invalid value are invalid.
+    let weights = MappedWeights::new(WeightMatrix::from_row_vec(1, vec![0.0]), f64::ln);
+    assert!(!weights.is_valid_at(Position { row: 0, column: 0 }));
+}
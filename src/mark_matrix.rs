@@ -1,128 +1,233 @@
-use crate::{Position, SquareMatrix};
-
-#[derive(Debug)]
+use crate::bitset::BitSet;
+use crate::Position;
+use alloc::{vec, vec::Vec};
+use core::mem;
+
+/// Marks no row or column.
+const NONE: usize = usize::MAX;
+
+/// The starred and primed zeros of an `n x n` matrix.
+///
+/// There is at most one star per row and per column, and at most one prime
+/// per row, so instead of an `n x n` matrix of marks, the stars are stored
+/// as a pair of partial permutations (row to column and column to row) and
+/// the primes as one. All lookups are O(1) and the memory is O(n). The
+/// columns with a star and the number of stars are kept up to date as well,
+/// so that step 3 covers the columns a word at a time.
+#[derive(Debug, Clone)]
 pub struct MarkMatrix {
-    marks: SquareMatrix<Mark>,
+    star_column_of_row: Vec<usize>,
+    star_row_of_column: Vec<usize>,
+    prime_column_of_row: Vec<usize>,
+    starred_columns: BitSet,
+    star_count: usize,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-#[repr(u8)]
-enum Mark {
-    None,
-    Star,
-    Prime,
+#[inline]
+fn to_option(index: usize) -> Option<usize> {
+    if index == NONE {
+        None
+    } else {
+        Some(index)
+    }
 }
 
 impl MarkMatrix {
     pub fn new(n: usize) -> Self {
         Self {
-            marks: SquareMatrix::from_shape_fn((n, n), |_| Mark::None),
+            star_column_of_row: vec![NONE; n],
+            star_row_of_column: vec![NONE; n],
+            prime_column_of_row: vec![NONE; n],
+            starred_columns: BitSet::with_capacity(n),
+            star_count: 0,
         }
     }
 
     #[inline]
     pub fn n(&self) -> usize {
-        self.marks.shape()[0]
-    }
-
-    #[inline]
-    fn get_mark(&self, pos: Position) -> Mark {
-        self.marks[(pos.row, pos.column)]
+        self.star_column_of_row.len()
     }
 
-    #[inline]
-    fn set_mark(&mut self, pos: Position, mark: Mark) {
-        self.marks[(pos.row, pos.column)] = mark;
+    fn unstar(&mut self, pos: Position) {
+        debug_assert!(self.is_star(pos));
+        self.star_column_of_row[pos.row] = NONE;
+        self.star_row_of_column[pos.column] = NONE;
+        self.starred_columns.set(pos.column, false);
+        self.star_count -= 1;
     }
 
     pub fn toggle_star(&mut self, pos: Position) {
-        let cell = self.marks.get_mut((pos.row, pos.column)).unwrap();
-        if *cell == Mark::Star {
-            *cell = Mark::None;
+        if self.is_star(pos) {
+            self.unstar(pos);
         } else {
-            *cell = Mark::Star;
+            self.star(pos);
         }
     }
 
+    /// Stars `pos`, replacing a prime. The row and column of `pos` must not
+    /// have other stars, which is checked in debug builds.
     pub fn star(&mut self, pos: Position) {
-        self.set_mark(pos, Mark::Star);
+        debug_assert!(
+            self.star_column_of_row[pos.row] == NONE || self.is_star(pos),
+            "row {} already has a star",
+            pos.row
+        );
+        debug_assert!(
+            self.star_row_of_column[pos.column] == NONE
+                || self.star_row_of_column[pos.column] == pos.row,
+            "column {} already has a star",
+            pos.column
+        );
+        if self.is_prime(pos) {
+            self.prime_column_of_row[pos.row] = NONE;
+        }
+        if self.star_column_of_row[pos.row] == NONE {
+            self.star_count += 1;
+        }
+        self.star_column_of_row[pos.row] = pos.column;
+        self.star_row_of_column[pos.column] = pos.row;
+        self.starred_columns.set(pos.column, true);
     }
 
+    /// Primes `pos`, replacing a star. The row of `pos` must not have other
+    /// primes.
     pub fn prime(&mut self, pos: Position) {
-        self.set_mark(pos, Mark::Prime);
+        if self.is_star(pos) {
+            self.unstar(pos);
+        }
+        self.prime_column_of_row[pos.row] = pos.column;
     }
 
+    #[inline]
     pub fn is_star(&self, pos: Position) -> bool {
-        match self.get_mark(pos) {
-            Mark::Star => true,
-            _ => false,
-        }
+        self.star_column_of_row[pos.row] == pos.column
     }
 
+    #[inline]
     pub fn is_prime(&self, pos: Position) -> bool {
-        match self.get_mark(pos) {
-            Mark::Prime => true,
-            _ => false,
-        }
+        self.prime_column_of_row[pos.row] == pos.column
     }
 
     #[cfg(test)]
     pub fn is_none(&self, pos: Position) -> bool {
-        match self.get_mark(pos) {
-            Mark::None => true,
-            _ => false,
-        }
+        !self.is_star(pos) && !self.is_prime(pos)
     }
 
+    /// Calls `f` for each star, in row order.
     #[inline]
     pub fn each_star<F>(&self, mut f: F)
     where
         F: FnMut(Position),
     {
-        for (row, row_data) in self.marks.genrows().into_iter().enumerate() {
-            for (column, &cell) in row_data.iter().enumerate() {
-                if cell == Mark::Star {
-                    f(Position { row, column });
-                }
+        for (row, &column) in self.star_column_of_row.iter().enumerate() {
+            if column != NONE {
+                f(Position { row, column });
             }
         }
     }
 
-    fn find_first_mark_in_row(&self, row: usize, mark: Mark) -> Option<usize> {
-        self.marks
-            .row(row)
-            .iter()
-            .enumerate()
-            .find(|(_, &cell)| cell == mark)
-            .map(|(column, _)| column)
+    /// The number of stars.
+    #[inline]
+    pub fn star_count(&self) -> usize {
+        self.star_count
     }
 
-    fn find_first_mark_in_column(&self, column: usize, mark: Mark) -> Option<usize> {
-        self.marks
-            .column(column)
-            .iter()
-            .enumerate()
-            .find(|(_, &cell)| cell == mark)
-            .map(|(row, _)| row)
+    /// The columns containing a star.
+    #[inline]
+    pub(crate) fn starred_columns(&self) -> &BitSet {
+        &self.starred_columns
     }
 
+    #[inline]
     pub fn find_first_star_in_row(&self, row: usize) -> Option<usize> {
-        self.find_first_mark_in_row(row, Mark::Star)
+        to_option(self.star_column_of_row[row])
     }
 
+    #[inline]
     pub fn find_first_prime_in_row(&self, row: usize) -> Option<usize> {
-        self.find_first_mark_in_row(row, Mark::Prime)
+        to_option(self.prime_column_of_row[row])
     }
 
+    #[inline]
     pub fn find_first_star_in_column(&self, column: usize) -> Option<usize> {
-        self.find_first_mark_in_column(column, Mark::Star)
+        to_option(self.star_row_of_column[column])
+    }
+
+    /// Removes all marks.
+    pub fn clear(&mut self) {
+        self.star_column_of_row.iter_mut().for_each(|c| *c = NONE);
+        self.star_row_of_column.iter_mut().for_each(|r| *r = NONE);
+        self.starred_columns.set_all(false);
+        self.star_count = 0;
+        self.clear_primes();
     }
 
     pub fn clear_primes(&mut self) {
-        for cell in self.marks.iter_mut() {
-            if *cell == Mark::Prime {
-                *cell = Mark::None
-            }
-        }
+        self.prime_column_of_row.iter_mut().for_each(|c| *c = NONE);
     }
+
+    /// The bytes of the marks of an `n x n` matrix.
+    pub(crate) fn estimated_bytes(n: usize) -> usize {
+        3 * n * mem::size_of::<usize>() + BitSet::estimated_bytes(n)
+    }
+}
+
+#[test]
+fn test_mark_matrix() {
+    let pos = |row, column| Position { row, column };
+    let mut marks = MarkMatrix::new(3);
+    marks.star(pos(0, 1));
+    marks.star(pos(1, 0));
+    marks.prime(pos(2, 1));
+    assert_eq!(Some(1), marks.find_first_star_in_row(0));
+    assert_eq!(Some(0), marks.find_first_star_in_column(1));
+    assert_eq!(Some(1), marks.find_first_prime_in_row(2));
+    assert_eq!(None, marks.find_first_star_in_column(2));
+
+    // converting the path (2, 1) -> (0, 1) -> (0, 2) of step 5, the star
+    // first.
+    marks.prime(pos(0, 2));
+    for &p in &[pos(0, 1), pos(2, 1), pos(0, 2)] {
+        marks.toggle_star(p);
+    }
+    marks.clear_primes();
+    let mut stars = Vec::new();
+    marks.each_star(|p| stars.push(p));
+    assert_eq!(vec![pos(0, 2), pos(1, 0), pos(2, 1)], stars);
+    assert_eq!(3, marks.star_count());
+    assert_eq!(
+        vec![0, 1, 2],
+        marks.starred_columns().ones().collect::<Vec<_>>()
+    );
+    assert_eq!(Some(2), marks.find_first_star_in_column(1));
+    assert!(marks.is_none(pos(0, 1)));
+
+    marks.prime(pos(1, 0));
+    assert_eq!(2, marks.star_count());
+    assert_eq!(
+        vec![1, 2],
+        marks.starred_columns().ones().collect::<Vec<_>>()
+    );
+
+    marks.clear();
+    assert_eq!(0, marks.star_count());
+    assert!((0..3).all(|column| marks.find_first_star_in_column(column).is_none()));
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "row 0 already has a star")]
+fn test_star_twice_in_row() {
+    let mut marks = MarkMatrix::new(2);
+    marks.star(Position { row: 0, column: 0 });
+    marks.star(Position { row: 0, column: 1 });
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "column 1 already has a star")]
+fn test_star_twice_in_column() {
+    let mut marks = MarkMatrix::new(2);
+    marks.star(Position { row: 0, column: 1 });
+    marks.star(Position { row: 1, column: 1 });
 }
@@ -0,0 +1,192 @@
+//! Memoizing solver for workloads that solve identical instances again and
+//! again, e.g. simulations replaying the same situations across episodes.
+//!
+//! `MemoCache` hashes the valid cells of the input together with a context,
+//! such as the constraints passed to a solver, and returns the stored result
+//! for repeats. Entries keep a copy of the input, so a hash collision never
+//! returns a wrong result. Once full, the oldest entry is evicted.
+use crate::{solve_assignment, Error, Position, WeightNum, Weights};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+
+#[derive(Debug, Clone)]
+struct Entry<T, K> {
+    /// The cells in row-major order, `None` if invalid.
+    cells: Vec<Option<T>>,
+    context: K,
+    result: Result<Vec<Position>, Error>,
+}
+
+/// A cache of up to `capacity` solved instances.
+#[derive(Debug, Clone)]
+pub struct MemoCache<T, K = ()> {
+    capacity: usize,
+    entries: HashMap<u64, Vec<Entry<T, K>>>,
+    /// Hashes in insertion order, for eviction.
+    order: VecDeque<u64>,
+    hits: usize,
+    misses: usize,
+}
+
+impl<T, K> MemoCache<T, K>
+where
+    T: WeightNum,
+    K: Hash + PartialEq + Clone,
+{
+    pub fn new(capacity: usize) -> MemoCache<T, K> {
+        assert!(capacity > 0);
+        MemoCache {
+            capacity,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Returns the stored result for `weights` and `context`, or solves with
+    /// `solve` and stores its result. On a hit, `weights` is not modified.
+    pub fn solve_with<W, F>(
+        &mut self,
+        weights: &mut W,
+        context: &K,
+        solve: F,
+    ) -> Result<Vec<Position>, Error>
+    where
+        W: Weights<T = T>,
+        F: FnOnce(&mut W) -> Result<Vec<Position>, Error>,
+    {
+        let n = weights.n();
+        let cells: Vec<Option<T>> = (0..n)
+            .flat_map(|row| (0..n).map(move |column| Position { row, column }))
+            .map(|pos| {
+                if weights.is_valid_at(pos) {
+                    Some(weights.element_at(pos))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        let hash = hash(n, &cells, context);
+        let found = self.entries.get(&hash).and_then(|bucket| {
+            bucket
+                .iter()
+                .find(|entry| entry.context == *context && entry.cells == cells)
+        });
+        if let Some(entry) = found {
+            self.hits += 1;
+            return entry.result.clone();
+        }
+
+        self.misses += 1;
+        let result = solve(weights);
+        if self.order.len() >= self.capacity {
+            let oldest = self.order.pop_front().unwrap();
+            let bucket = self.entries.get_mut(&oldest).unwrap();
+            bucket.remove(0);
+            if bucket.is_empty() {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.entry(hash).or_default().push(Entry {
+            cells,
+            context: context.clone(),
+            result: result.clone(),
+        });
+        self.order.push_back(hash);
+        result
+    }
+
+    /// Like `solve_with` using `solve_assignment` and the default context.
+    pub fn solve<W>(&mut self, weights: &mut W) -> Result<Vec<Position>, Error>
+    where
+        W: Weights<T = T>,
+        K: Default,
+    {
+        self.solve_with(weights, &K::default(), solve_assignment)
+    }
+
+    /// Number of stored results.
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+}
+
+/// Hashes what `WeightNum` exposes of the cells. Values without an integer
+/// or `f64` representation are left out, which only causes collisions.
+fn hash<T: WeightNum, K: Hash>(n: usize, cells: &[Option<T>], context: &K) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    n.hash(&mut hasher);
+    for cell in cells {
+        match cell {
+            Some(value) => match value.to_i64() {
+                Some(value) => value.hash(&mut hasher),
+                None => value.to_f64().map(f64::to_bits).hash(&mut hasher),
+            },
+            None => 0xff_u8.hash(&mut hasher),
+        }
+    }
+    context.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn test_memo_cache() {
+    use crate::constraints::{solve_assignment_constrained, Constraint};
+    use crate::WeightMatrix;
+
+    let c = vec![250, 400, 350, 400, 600, 350, 200, 400, 250];
+    let expected = solve_assignment(&mut WeightMatrix::from_row_vec(3, c.clone()));
+    let mut cache: MemoCache<i32> = MemoCache::new(2);
+    for _ in 0..3 {
+        let mut weights = WeightMatrix::from_row_vec(3, c.clone());
+        assert_eq!(expected, cache.solve(&mut weights));
+    }
+    assert_eq!((2, 1, 1), (cache.hits(), cache.misses(), cache.len()));
+
+    // the oldest entry is evicted.
+    for &x in &[1, 2] {
+        assert!(cache
+            .solve(&mut WeightMatrix::from_row_vec(1, vec![x]))
+            .is_ok());
+    }
+    assert_eq!(2, cache.len());
+    assert_eq!(
+        expected,
+        cache.solve(&mut WeightMatrix::from_row_vec(3, c.clone()))
+    );
+    assert_eq!((2, 4), (cache.hits(), cache.misses()));
+
+    // the constraints are part of the key.
+    let mut cache = MemoCache::new(4);
+    let weights = WeightMatrix::from_row_vec(3, c);
+    let excludes = vec![Constraint::Excludes {
+        cell: Position { row: 0, column: 1 },
+        other: Position { row: 1, column: 2 },
+    }];
+    for constraints in &[vec![], excludes.clone(), excludes] {
+        let result = cache.solve_with(&mut weights.clone(), constraints, |w| {
+            solve_assignment_constrained(w, constraints)
+        });
+        assert_eq!(solve_assignment_constrained(&weights, constraints), result);
+    }
+    assert_eq!((1, 2), (cache.hits(), cache.misses()));
+}
@@ -0,0 +1,72 @@
+//! Memory used by the solver, for capacity planning.
+//!
+//! A solve of size `n` holds the weights, `n^2` values for a dense matrix,
+//! and a workspace of `O(n)`: the marks, the coverage, and the buffers of
+//! steps 5 and 6. The workspace is negligible next to a dense matrix, but
+//! not next to sparse or implicit weights. The figures are the bytes of the
+//! buffers, not counting the few fixed-size fields of each structure.
+use crate::workspace::Workspace;
+use crate::{WeightMatrix, WeightNum};
+use core::ops::Add;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    pub weights: usize,
+    /// The starred and primed zeros.
+    pub marks: usize,
+    /// The covered rows and columns.
+    pub coverage: usize,
+    /// The other buffers of the algorithm.
+    pub workspace: usize,
+}
+
+impl MemoryUsage {
+    pub fn total(&self) -> usize {
+        self.weights + self.marks + self.coverage + self.workspace
+    }
+}
+
+impl Add for MemoryUsage {
+    type Output = MemoryUsage;
+
+    fn add(self, other: MemoryUsage) -> MemoryUsage {
+        MemoryUsage {
+            weights: self.weights + other.weights,
+            marks: self.marks + other.marks,
+            coverage: self.coverage + other.coverage,
+            workspace: self.workspace + other.workspace,
+        }
+    }
+}
+
+/// The memory needed to solve a `WeightMatrix<T>` of size `n`, see
+/// `WeightMatrix::estimated_bytes`.
+pub fn estimate<T: WeightNum>(n: usize, with_invalid_cells: bool) -> MemoryUsage {
+    MemoryUsage {
+        weights: WeightMatrix::<T>::estimated_bytes(n, with_invalid_cells),
+        ..Workspace::estimated_memory_usage(n)
+    }
+}
+
+#[test]
+fn test_memory_usage() {
+    use crate::arena::SolverArena;
+    use crate::generators;
+
+    // 100k x 100k `f32` take 40 GB, the workspace a few MB.
+    let usage = estimate::<f32>(100_000, false);
+    assert_eq!(40_000_000_000, usage.weights);
+    assert!(usage.total() - usage.weights < 10_000_000);
+
+    let mut weights = generators::uniform(100, 0..1000, 0);
+    assert_eq!(estimate::<u32>(100, false).weights, weights.memory_usage());
+    let mut arena = SolverArena::new();
+    arena.solve(&mut weights).unwrap();
+    let used = arena.memory_usage();
+    let estimated = Workspace::estimated_memory_usage(100);
+    assert_eq!(
+        (estimated.marks, estimated.coverage),
+        (used.marks, used.coverage)
+    );
+    assert!(used.workspace <= estimated.workspace);
+}
@@ -0,0 +1,111 @@
+//! Evaluation metrics for tracking, in the style of CLEAR MOT.
+//!
+//! Each frame has a ground truth, pairs of an object (the row) and the
+//! detection (the column) that belongs to it, and the assignment computed
+//! by the tracker, pairs of a track (the row) and a detection. Object and
+//! track indices are independent; they are related through the detections.
+//!
+//! * A ground-truth pair whose detection isn't assigned to any track is a
+//!   miss.
+//! * An assigned detection without ground truth is a false match.
+//! * An object whose detection is assigned to a different track than in the
+//!   last frame it was matched in is an ID switch.
+//!
+//! MOTA is `1 - (misses + false matches + ID switches) / ground truth pairs`.
+use crate::Position;
+use std::collections::HashMap;
+
+/// The counts accumulated over a sequence of frames.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrackingMetrics {
+    pub frames: usize,
+    /// Number of ground-truth pairs.
+    pub ground_truth: usize,
+    /// Ground-truth pairs whose detection was assigned to a track.
+    pub matches: usize,
+    pub misses: usize,
+    pub false_matches: usize,
+    pub id_switches: usize,
+    /// The track each object was last matched to.
+    last_track: HashMap<usize, usize>,
+}
+
+impl TrackingMetrics {
+    pub fn new() -> TrackingMetrics {
+        TrackingMetrics::default()
+    }
+
+    /// Adds a frame with its ground truth and the computed assignment.
+    pub fn add_frame(&mut self, truth: &[Position], computed: &[Position]) {
+        let track_of_detection: HashMap<usize, usize> =
+            computed.iter().map(|pos| (pos.column, pos.row)).collect();
+        let object_of_detection: HashMap<usize, usize> =
+            truth.iter().map(|pos| (pos.column, pos.row)).collect();
+
+        self.frames += 1;
+        self.ground_truth += truth.len();
+        for pos in truth {
+            match track_of_detection.get(&pos.column) {
+                Some(&track) => {
+                    self.matches += 1;
+                    if let Some(last) = self.last_track.insert(pos.row, track) {
+                        if last != track {
+                            self.id_switches += 1;
+                        }
+                    }
+                }
+                None => self.misses += 1,
+            }
+        }
+        self.false_matches += computed
+            .iter()
+            .filter(|pos| !object_of_detection.contains_key(&pos.column))
+            .count();
+    }
+
+    /// Multiple object tracking accuracy, at most 1 and negative if there
+    /// are more errors than ground-truth pairs. `None` without ground truth.
+    pub fn mota(&self) -> Option<f64> {
+        if self.ground_truth == 0 {
+            return None;
+        }
+        let errors = self.misses + self.false_matches + self.id_switches;
+        Some(1.0 - errors as f64 / self.ground_truth as f64)
+    }
+}
+
+/// Accumulates the metrics of a sequence of `(truth, computed)` frames.
+pub fn evaluate<'a, I>(frames: I) -> TrackingMetrics
+where
+    I: IntoIterator<Item = (&'a [Position], &'a [Position])>,
+{
+    let mut metrics = TrackingMetrics::new();
+    for (truth, computed) in frames {
+        metrics.add_frame(truth, computed);
+    }
+    metrics
+}
+
+#[test]
+fn test_tracking_metrics() {
+    let pos = |row, column| Position { row, column };
+    // objects 0 and 1 are detections 0 and 1 in every frame.
+    let truth = [pos(0, 0), pos(1, 1)];
+    let frames = [
+        // tracks 5 and 6 follow the objects.
+        [pos(5, 0), pos(6, 1)],
+        // object 1 is missed, detection 2 is a false match.
+        [pos(5, 0), pos(6, 2)],
+        // object 1 is picked up again by the same track, object 0 switches.
+        [pos(7, 0), pos(6, 1)],
+    ];
+    let metrics = evaluate(frames.iter().map(|computed| (&truth[..], &computed[..])));
+    assert_eq!(3, metrics.frames);
+    assert_eq!(6, metrics.ground_truth);
+    assert_eq!(5, metrics.matches);
+    assert_eq!(1, metrics.misses);
+    assert_eq!(1, metrics.false_matches);
+    assert_eq!(1, metrics.id_switches);
+    assert_eq!(Some(0.5), metrics.mota());
+    assert_eq!(None, TrackingMetrics::new().mota());
+}
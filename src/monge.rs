@@ -0,0 +1,123 @@
+//! Monge matrices, for which the identity is an optimal assignment.
+//!
+//! A matrix is Monge if `c[i][j] + c[i+1][j+1] <= c[i][j+1] + c[i+1][j]` for
+//! all adjacent rows and columns. Costs that are convex functions of the
+//! distance between sorted points on a line, e.g. matching two sorted
+//! sequences by squared difference, are Monge. The property is checked in
+//! O(n^2), and if it holds the identity is returned without running the
+//! algorithm.
+use crate::{solve_assignment, Error, Position, Weights};
+use alloc::vec::Vec;
+
+/// Whether all cells are valid and each 2x2 block of adjacent rows and
+/// columns satisfies the Monge inequality.
+pub fn is_monge<W>(weights: &W) -> bool
+where
+    W: Weights,
+{
+    let n = weights.n();
+    let at = |row, column| weights.element_at(Position { row, column });
+    let all_valid =
+        (0..n).all(|row| (0..n).all(|column| weights.is_valid_at(Position { row, column })));
+    all_valid
+        && (1..n).all(|row| {
+            (1..n).all(|column| {
+                at(row - 1, column - 1) + at(row, column)
+                    <= at(row - 1, column) + at(row, column - 1)
+            })
+        })
+}
+
+/// Returns the identity if `weights` is Monge, and solves with
+/// `solve_assignment` otherwise. The identity leaves `weights` unmodified.
+pub fn solve_assignment_monge<W>(weights: &mut W) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+{
+    if is_monge(weights) {
+        Ok((0..weights.n())
+            .map(|i| Position { row: i, column: i })
+            .collect())
+    } else {
+        solve_assignment(weights)
+    }
+}
+
+#[test]
+fn test_monge() {
+    use crate::{generators, WeightMatrix};
+
+    let cost = |w: &WeightMatrix<i64>, m: &[Position]| -> i64 {
+        m.iter().map(|&pos| w.element_at(pos)).sum()
+    };
+    for seed in 0..10 {
+        let mut rng = generators::Rng::new(seed);
+        let mut xs: Vec<i64> = (0..8).map(|_| rng.next_in(0..100) as i64).collect();
+        let mut ys: Vec<i64> = (0..8).map(|_| rng.next_in(0..100) as i64).collect();
+        xs.sort_unstable();
+        ys.sort_unstable();
+        let weights = WeightMatrix::from_fn(8, |(row, column)| (xs[row] - ys[column]).pow(2));
+        assert!(is_monge(&weights));
+        let expected = solve_assignment(&mut weights.clone()).unwrap();
+        let matching = solve_assignment_monge(&mut weights.clone()).unwrap();
+        assert_eq!(cost(&weights, &expected), cost(&weights, &matching));
+    }
+
+    let mut weights = WeightMatrix::from_row_vec(2, vec![2, 1, 1, 2]);
+    assert!(!is_monge(&weights));
+    assert_eq!(
+        Ok(vec![
+            Position { row: 0, column: 1 },
+            Position { row: 1, column: 0 }
+        ]),
+        solve_assignment_monge(&mut weights)
+    );
+    let inf = f64::INFINITY;
+    assert!(!is_monge(&WeightMatrix::from_row_vec(
+        2,
+        vec![0.0, 1.0, inf, 0.0]
+    )));
+}
+
+#[test]
+fn test_monge_edge_cases() {
+    use crate::WeightMatrix;
+
+    let pos = |row, column| Position { row, column };
+
+    // empty and single-cell matrices are Monge.
+    let mut weights = WeightMatrix::<i32>::from_row_vec(0, vec![]);
+    assert!(is_monge(&weights));
+    assert_eq!(Ok(vec![]), solve_assignment_monge(&mut weights));
+    let mut weights = WeightMatrix::from_row_vec(1, vec![5]);
+    assert!(is_monge(&weights));
+    assert_eq!(Ok(vec![pos(0, 0)]), solve_assignment_monge(&mut weights));
+
+    // the early exit leaves the weights unreduced.
+    let c = vec![0, 1, 4, 1, 0, 1, 4, 1, 0];
+    let mut weights = WeightMatrix::from_row_vec(3, c.clone());
+    assert_eq!(
+        Ok(vec![pos(0, 0), pos(1, 1), pos(2, 2)]),
+        solve_assignment_monge(&mut weights)
+    );
+    assert_eq!(&c[..], weights.as_slice());
+
+    // only the last 2x2 block violates the inequality, 0 + 5 > 1 + 3, and
+    // the identity isn't optimal.
+    let c = vec![0, 1, 2, 1, 0, 1, 5, 3, 5];
+    let mut weights = WeightMatrix::from_row_vec(3, c.clone());
+    assert!(!is_monge(&weights));
+    assert_eq!(
+        Ok(vec![pos(0, 0), pos(1, 2), pos(2, 1)]),
+        solve_assignment_monge(&mut weights)
+    );
+    assert_ne!(&c[..], weights.as_slice());
+
+    // an invalid cell on the diagonal of an otherwise Monge matrix.
+    let weights = WeightMatrix::from_row_options(2, vec![Some(0.0), Some(1.0), Some(1.0), None]);
+    assert!(!is_monge(&weights));
+    assert_eq!(
+        Ok(vec![pos(0, 1), pos(1, 0)]),
+        solve_assignment_monge(&mut weights.clone())
+    );
+}
@@ -0,0 +1,107 @@
+//! `Weights` for matrices stored as rows: `Vec<Vec<T>>`, `[Vec<T>]` and
+//! `[[T; N]; N]`, so data already in one of these shapes is solved in place,
+//! e.g. with `solve_assignment(&mut &mut rows[..])`.
+//!
+//! As with `WeightMatrix::from_row_vec`, the validity of a cell is that of
+//! its value. The rows of `Vec`s and slices must all have `n` elements, which
+//! is checked by `is_solvable` at the start of a solve.
+use crate::{Position, WeightNum, Weights};
+use alloc::vec::Vec;
+
+macro_rules! impl_rows_weights {
+    ([$($generics:tt)*] $rows:ty) => {
+        impl<$($generics)*> Weights for $rows {
+            type T = T;
+
+            #[inline(always)]
+            fn n(&self) -> usize {
+                self.len()
+            }
+
+            #[inline]
+            fn element_at(&self, pos: Position) -> T {
+                self[pos.row][pos.column]
+            }
+
+            fn sub_min_of_each_row(&mut self) {
+                for row in self.iter_mut() {
+                    let min = row
+                        .iter()
+                        .filter(|value| value.is_valid())
+                        .fold(None, |min: Option<T>, &value| match min {
+                            Some(m) if m <= value => Some(m),
+                            _ => Some(value),
+                        });
+                    if let Some(min) = min {
+                        row.iter_mut().for_each(|cur| *cur = cur.sub_if_valid(min));
+                    }
+                }
+            }
+
+            fn add_row(&mut self, row: usize, val: T) {
+                self[row]
+                    .iter_mut()
+                    .for_each(|cur| *cur = cur.add_if_valid(val));
+            }
+
+            fn sub_column(&mut self, col: usize, val: T) {
+                for row in self.iter_mut() {
+                    row[col] = row[col].sub_if_valid(val);
+                }
+            }
+
+            /// # Panics
+            ///
+            /// If a row doesn't have `n` elements.
+            fn is_solvable(&self) -> bool {
+                let n = self.n();
+                assert!(
+                    self.iter().all(|row| row.len() == n),
+                    "all rows must have n elements"
+                );
+                self.iter().all(|row| row.iter().any(|value| value.is_valid()))
+            }
+        }
+    };
+}
+
+impl_rows_weights!([T: WeightNum] Vec<Vec<T>>);
+impl_rows_weights!([T: WeightNum] [Vec<T>]);
+impl_rows_weights!([T: WeightNum, const N: usize] [[T; N]; N]);
+
+#[test]
+fn test_nested_weights() {
+    use crate::{generators, solve_assignment, Error, WeightMatrix};
+
+    for seed in 0..10 {
+        let weights = generators::uniform(8, 0..100, seed);
+        let expected = solve_assignment(&mut weights.clone());
+
+        let mut rows: Vec<Vec<u32>> = weights
+            .as_slice()
+            .chunks(8)
+            .map(|row| row.to_vec())
+            .collect();
+        assert_eq!(expected, solve_assignment(&mut rows.clone()));
+        assert_eq!(expected, solve_assignment(&mut &mut rows[..]));
+
+        let mut array = [[0u32; 8]; 8];
+        for (row, values) in array.iter_mut().zip(weights.as_slice().chunks(8)) {
+            row.copy_from_slice(values);
+        }
+        assert_eq!(expected, solve_assignment(&mut &mut array));
+    }
+
+    let inf = f64::INFINITY;
+    let mut rows = vec![vec![1.0, inf], vec![2.0, 3.0]];
+    let expected = solve_assignment(&mut WeightMatrix::from_row_vec(2, vec![1.0, inf, 2.0, 3.0]));
+    assert_eq!(expected, solve_assignment(&mut rows));
+    let mut rows = [[1.0, inf], [2.0, inf]];
+    assert_eq!(Err(Error::MatrixNotSolvable), solve_assignment(&mut rows));
+}
+
+#[test]
+#[should_panic(expected = "all rows must have n elements")]
+fn test_nested_weights_ragged() {
+    let _ = crate::solve_assignment(&mut vec![vec![1, 2], vec![3]]);
+}
@@ -0,0 +1,155 @@
+//! Bi-objective assignment: the tradeoff between two cost matrices.
+//!
+//! `pareto_front` enumerates the supported Pareto-optimal assignments, those
+//! minimizing some positive weighted sum of the two costs, by dichotomic
+//! search: starting from the lexicographic optima of both objectives, it
+//! solves the weighted sum whose level lines pass through two adjacent
+//! points, and recurses if that finds a point below the line. Each point
+//! takes one solve. Pareto-optimal assignments inside the convex hull of the
+//! front ("unsupported" ones) are not found.
+use crate::bounds::column_minima;
+use crate::{
+    solve_assignment, solve_assignment_feasible, Assignment, Error, Position, WeightMatrix,
+    WeightNum, Weights,
+};
+use alloc::{vec, vec::Vec};
+
+/// An assignment together with its costs under both objectives.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParetoPoint {
+    pub assignment: Assignment,
+    pub first: f64,
+    pub second: f64,
+}
+
+/// Computes up to `max_points` supported Pareto-optimal assignments of the
+/// costs `first` and `second`, ordered by increasing `first` cost (and
+/// decreasing `second` cost). A cell is valid if it is valid in both.
+///
+/// # Panics
+///
+/// If the sizes differ, or a valid cell can't be converted to `f64`.
+pub fn pareto_front<W1, W2>(
+    first: &W1,
+    second: &W2,
+    max_points: usize,
+) -> Result<Vec<ParetoPoint>, Error>
+where
+    W1: Weights,
+    W2: Weights,
+{
+    assert!(first.n() == second.n());
+    let first = to_f64(first, second);
+    let second = to_f64(second, &first);
+    if max_points == 0 {
+        return Ok(Vec::new());
+    }
+
+    let a = point(&first, &second, lexicographic(&first, &second)?);
+    let b = point(&first, &second, lexicographic(&second, &first)?);
+    if a.first >= b.first || max_points == 1 {
+        return Ok(vec![a]);
+    }
+    let mut front = vec![a];
+    let last = b.clone();
+    let mut pending = vec![b];
+    // depth-first between the last point of the front and the next pending.
+    while let Some(next) = pending.pop() {
+        if front.len() + pending.len() + 1 >= max_points {
+            pending.push(next);
+            break;
+        }
+        let prev = front.last().unwrap();
+        let (l1, l2) = (prev.second - next.second, next.first - prev.first);
+        let mut weighted = WeightMatrix::from_fn(first.n(), |(row, column)| {
+            let pos = Position { row, column };
+            l1 * first.element_at(pos) + l2 * second.element_at(pos)
+        });
+        let candidate = point(&first, &second, solve_assignment(&mut weighted)?);
+        let level = l1 * prev.first + l2 * prev.second;
+        let value = l1 * candidate.first + l2 * candidate.second;
+        if value < level - 1e-9 * level.abs().max(1.0) {
+            pending.push(next);
+            pending.push(candidate);
+        } else {
+            front.push(next);
+        }
+    }
+    front.extend(pending.into_iter().rev());
+    debug_assert!(front.last() == Some(&last));
+    Ok(front)
+}
+
+/// `weights` as `f64`, with the cells invalid in `other` made infinite.
+pub(crate) fn to_f64<W, V>(weights: &W, other: &V) -> WeightMatrix<f64>
+where
+    W: Weights,
+    V: Weights,
+{
+    WeightMatrix::from_fn(weights.n(), |(row, column)| {
+        let pos = Position { row, column };
+        if weights.is_valid_at(pos) && other.is_valid_at(pos) {
+            weights
+                .element_at(pos)
+                .to_f64()
+                .expect("requires weights convertible to f64")
+        } else {
+            f64::INFINITY
+        }
+    })
+}
+
+/// Minimizes `primary`, breaking ties by `secondary`. The optimal
+/// assignments of `primary` are exactly the perfect matchings of the cells
+/// with zero reduced cost, so the ties are broken by minimizing `secondary`
+/// over those cells.
+fn lexicographic(
+    primary: &WeightMatrix<f64>,
+    secondary: &WeightMatrix<f64>,
+) -> Result<Vec<Position>, Error> {
+    let mut reduced = primary.clone();
+    solve_assignment(&mut reduced)?;
+    // the solve may stop after the row reduction, leaving column minima.
+    let minima = column_minima(&reduced);
+    let scale = primary
+        .as_slice()
+        .iter()
+        .filter(|c| c.is_finite())
+        .fold(1.0f64, |max, c| max.max(c.abs()));
+    solve_assignment_feasible(secondary, |pos| {
+        let min = minima[pos.column].unwrap_or(f64::INFINITY);
+        (reduced.element_at(pos) - min).abs() <= 1e-9 * scale
+    })
+}
+
+pub(crate) fn point(
+    first: &WeightMatrix<f64>,
+    second: &WeightMatrix<f64>,
+    positions: Vec<Position>,
+) -> ParetoPoint {
+    let cost = |w: &WeightMatrix<f64>| positions.iter().map(|&pos| w.element_at(pos)).sum();
+    ParetoPoint {
+        first: cost(first),
+        second: cost(second),
+        assignment: Assignment::from(positions),
+    }
+}
+
+#[test]
+fn test_pareto_front() {
+    // the identity is cheapest in the first, the reverse permutation in the
+    // second objective, the third point is a compromise.
+    let first = WeightMatrix::from_row_vec(3, vec![1, 5, 9, 5, 1, 9, 9, 9, 1]);
+    let second = WeightMatrix::from_row_vec(3, vec![9, 2, 1, 2, 6, 9, 1, 9, 9]);
+    let front = pareto_front(&first, &second, 10).unwrap();
+    let costs: Vec<_> = front.iter().map(|p| (p.first, p.second)).collect();
+    assert_eq!(vec![(3.0, 24.0), (11.0, 13.0), (19.0, 8.0)], costs);
+    assert!(front
+        .windows(2)
+        .all(|w| w[0].first < w[1].first && w[0].second > w[1].second));
+
+    assert_eq!(1, pareto_front(&first, &second, 1).unwrap().len());
+    let extremes = pareto_front(&first, &second, 2).unwrap();
+    assert_eq!((3.0, 8.0), (extremes[0].first, extremes[1].second));
+    assert_eq!(1, pareto_front(&first, &first, 10).unwrap().len());
+}
@@ -0,0 +1,136 @@
+//! Breaking ties by a deterministic perturbation.
+//!
+//! Highly degenerate instances, with many equal costs, make the algorithm
+//! take far more steps than usual: step 6 then creates many zeros at once,
+//! and most of the augmenting paths found through them are redundant.
+//! `solve_assignment_perturbed` adds a tiny pseudo random value, drawn from
+//! a seed, to every cell of integer costs, which makes the optimum unique
+//! with probability 1 and breaks the ties.
+//!
+//! The perturbations of a matching sum up to less than 1/4, so the optimum
+//! of the perturbed costs is also optimal for integer costs. The solve runs
+//! in floating point, though, so the result is verified on the original
+//! costs with the dual potentials of the perturbed solve, and solved again
+//! without perturbation in the unlikely case that the verification fails.
+use crate::dual::DualWeights;
+use crate::generators::Rng;
+use crate::restricted::Restricted;
+use crate::{solve_assignment, Error, Position, WeightMatrix, WeightNum, Weights};
+use alloc::vec::Vec;
+
+/// Integer costs up to `MAX_MAGNITUDE / n` in absolute value are perturbed.
+/// Beyond, `f64` can't resolve the perturbations reliably.
+const MAX_MAGNITUDE: f64 = (1u64 << 32) as f64;
+
+/// Solves the assignment problem on the costs perturbed with the
+/// perturbations drawn from `seed`, returning a matching optimal for the
+/// original costs. Weights that aren't all integers are solved without
+/// perturbation. `weights` is not modified.
+pub fn solve_assignment_perturbed<W>(weights: &W, seed: u64) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+{
+    let n = weights.n();
+    if !weights.is_solvable() {
+        return Err(Error::MatrixNotSolvable);
+    }
+    let exact = || solve_assignment(&mut Restricted::from_weights(weights));
+    let costs = match integral_costs(weights) {
+        Some(costs) if n > 0 => costs,
+        _ => return exact(),
+    };
+
+    let epsilon = 1.0 / (4 * n) as f64;
+    let mut rng = Rng::new(seed);
+    let valid: Vec<bool> = costs.iter().map(Option::is_some).collect();
+    let perturbed = costs
+        .iter()
+        .map(|cost| cost.unwrap_or(0.0) + epsilon * rng.next_f64())
+        .collect();
+    let perturbed = WeightMatrix::from_row_vec_with_validity(n, perturbed, &valid);
+    let mut duals = DualWeights::new(&perturbed);
+    let matching = solve_assignment(&mut duals)?;
+
+    // the column potentials are feasible for the original costs with the
+    // row potentials the row minima of the reduced costs, which bounds the
+    // optimum from below. Both the optimum and the cost of `matching` are
+    // integers, so a gap below 1 proves `matching` optimal.
+    let column_duals = duals.column_duals();
+    let mut lower_bound: f64 = column_duals.iter().sum();
+    for row in 0..n {
+        lower_bound += (0..n)
+            .filter_map(|column| Some(costs[row * n + column]? - column_duals[column]))
+            .fold(f64::INFINITY, f64::min);
+    }
+    let cost: f64 = matching
+        .iter()
+        .map(|pos| costs[pos.row * n + pos.column].unwrap())
+        .sum();
+    if cost - lower_bound < 0.5 {
+        Ok(matching)
+    } else {
+        exact()
+    }
+}
+
+/// The valid costs as `f64` in row-major order, `None` for invalid cells,
+/// if all are integers small enough to be perturbed.
+fn integral_costs<W>(weights: &W) -> Option<Vec<Option<f64>>>
+where
+    W: Weights,
+{
+    let n = weights.n();
+    let max = MAX_MAGNITUDE / n.max(1) as f64;
+    let mut costs = Vec::with_capacity(n * n);
+    for row in 0..n {
+        for column in 0..n {
+            let pos = Position { row, column };
+            if !weights.is_valid_at(pos) {
+                costs.push(None);
+                continue;
+            }
+            let cost = weights.element_at(pos).to_i64()? as f64;
+            if cost.abs() > max {
+                return None;
+            }
+            costs.push(Some(cost));
+        }
+    }
+    Some(costs)
+}
+
+#[test]
+fn test_solve_assignment_perturbed() {
+    use crate::generators;
+
+    for seed in 0..10 {
+        let weights = generators::degenerate(12, 3, seed);
+        let cost = |m: &[Position]| m.iter().map(|&pos| weights.element_at(pos)).sum::<u32>();
+        let expected = cost(&solve_assignment(&mut weights.clone()).unwrap());
+        let matching = solve_assignment_perturbed(&weights, seed).unwrap();
+        assert_eq!(12, matching.len());
+        assert_eq!(expected, cost(&matching));
+        // deterministic for a given seed.
+        assert_eq!(
+            matching,
+            solve_assignment_perturbed(&weights, seed).unwrap()
+        );
+    }
+
+    // all rows equal: every permutation is optimal.
+    let weights = WeightMatrix::from_fn(6, |(_, column)| column as i32);
+    let matching = solve_assignment_perturbed(&weights, 1).unwrap();
+    assert_eq!(6, matching.len());
+
+    let weights = WeightMatrix::from_row_vec(2, vec![0.5, 1.5, 1.0, 1.0]);
+    assert_eq!(
+        solve_assignment(&mut weights.clone()),
+        solve_assignment_perturbed(&weights, 1)
+    );
+    let inf = f64::INFINITY;
+    let weights = WeightMatrix::from_row_vec(2, vec![1.0, inf, 1.0, inf]);
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_assignment_perturbed(&weights, 1)
+    );
+}
@@ -0,0 +1,129 @@
+//! Solving while the cost matrix is still being built.
+//!
+//! Cost rows often become available one at a time, e.g. per track as a
+//! camera pipeline scores the detections of a frame. `PipelinedSolver`
+//! takes the rows as they arrive and runs the first two steps on each right
+//! away: the row is reduced by its minimum, and its first zero in a column
+//! without a star is starred. Both steps only depend on the rows received
+//! so far, and give the same result as running them on the complete matrix,
+//! so by the time the last row arrives, only the remaining steps are left.
+//!
+//! Feeding the solver from another thread, e.g. through a channel, overlaps
+//! computing the costs with the early steps.
+use crate::steps::step4;
+use crate::workspace::Workspace;
+use crate::{
+    bounds, collect_stars, run_marked_with, Error, Position, WeightMatrix, WeightNum, Weights,
+};
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone)]
+pub struct PipelinedSolver<T> {
+    n: usize,
+    /// The reduced rows received so far, in row-major order.
+    data: Vec<T>,
+    workspace: Workspace,
+}
+
+impl<T: WeightNum> PipelinedSolver<T> {
+    /// A solver for an `n x n` matrix, expecting `n` rows.
+    pub fn new(n: usize) -> PipelinedSolver<T> {
+        PipelinedSolver {
+            n,
+            data: Vec::with_capacity(n * n),
+            workspace: Workspace::new(n.max(1)),
+        }
+    }
+
+    #[inline]
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// The number of rows received so far.
+    pub fn rows(&self) -> usize {
+        self.data.len() / self.n.max(1)
+    }
+
+    /// Takes the next row, reduces it and stars one of its zeros. Returns
+    /// `Error::MatrixNotSolvable` right away if the row has no valid cell,
+    /// as the matrix can't be solved then.
+    ///
+    /// # Panics
+    ///
+    /// If `row` doesn't have `n` elements, or all rows were received.
+    pub fn push_row(&mut self, row: &[T]) -> Result<(), Error> {
+        assert!(row.len() == self.n, "the row must have n elements");
+        assert!(self.rows() < self.n, "all rows were received");
+        let index = self.rows();
+        let min = row
+            .iter()
+            .copied()
+            .filter(WeightNum::is_valid)
+            .reduce(|min, val| if val < min { val } else { min })
+            .ok_or(Error::MatrixNotSolvable)?;
+        self.data
+            .extend(row.iter().map(|&cost| cost.sub_if_valid(min)));
+
+        let marks = &mut self.workspace.marks;
+        let reduced = &self.data[index * self.n..];
+        let zero = (0..self.n).find(|&column| {
+            reduced[column].is_valid()
+                && reduced[column].is_zero()
+                && marks.find_first_star_in_column(column).is_none()
+        });
+        if let Some(column) = zero {
+            marks.star(Position { row: index, column });
+        }
+        Ok(())
+    }
+
+    /// Solves the matrix once all rows are received. Returns the same
+    /// matching as `solve_assignment` on the complete matrix.
+    ///
+    /// # Panics
+    ///
+    /// If fewer than `n` rows were received.
+    pub fn finish(mut self) -> Result<Vec<Position>, Error> {
+        assert!(self.rows() == self.n, "not all rows were received");
+        if self.n == 0 {
+            return Ok(Vec::new());
+        }
+        let mut weights = WeightMatrix::from_row_vec(self.n, self.data);
+        if !weights.is_solvable() {
+            return Err(Error::MatrixNotSolvable);
+        }
+        if let Some(matching) = bounds::tight_permutation(&weights) {
+            return Ok(matching);
+        }
+        run_marked_with(&mut weights, &mut self.workspace, step4)?;
+        Ok(collect_stars(self.n, &self.workspace))
+    }
+}
+
+#[test]
+fn test_pipelined_solver() {
+    use crate::{generators, solve_assignment};
+
+    for seed in 0..10 {
+        let weights = generators::uniform(15, 0..100, seed);
+        let mut solver = PipelinedSolver::new(15);
+        for row in weights.as_slice().chunks(15) {
+            solver.push_row(row).unwrap();
+        }
+        assert_eq!(15, solver.rows());
+        assert_eq!(solve_assignment(&mut weights.clone()), solver.finish());
+    }
+
+    let inf = f64::INFINITY;
+    let mut solver = PipelinedSolver::new(2);
+    solver.push_row(&[1.0, inf]).unwrap();
+    assert_eq!(Err(Error::MatrixNotSolvable), solver.push_row(&[inf, inf]));
+
+    let mut solver = PipelinedSolver::new(2);
+    solver.push_row(&[1.0, inf]).unwrap();
+    solver.push_row(&[2.0, inf]).unwrap();
+    assert_eq!(Err(Error::MatrixNotSolvable), solver.finish());
+
+    assert_eq!(Ok(vec![]), PipelinedSolver::<u32>::new(0).finish());
+}
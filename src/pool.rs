@@ -0,0 +1,108 @@
+//! A pool of worker threads solving assignment problems.
+//!
+//! Each worker owns a workspace that is reused across the jobs it solves, so
+//! a steady stream of equally sized instances doesn't allocate besides the
+//! returned matchings.
+use crate::workspace::Workspace;
+use crate::{solve_assignment_in, Error, Position, Weights};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+type Job = Box<dyn FnOnce(&mut Workspace) + Send>;
+
+#[derive(Debug)]
+pub struct SolverPool {
+    jobs: Option<Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl SolverPool {
+    /// Starts `threads` workers, with workspaces preallocated for instances
+    /// of size `n`.
+    pub fn new(threads: usize, n: usize) -> SolverPool {
+        assert!(threads > 0);
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let workers = (0..threads)
+            .map(|_| {
+                let receiver = receiver.clone();
+                thread::spawn(move || {
+                    let mut workspace = Workspace::new(n.max(1));
+                    loop {
+                        let job = match receiver.lock().unwrap().recv() {
+                            Ok(job) => job,
+                            Err(_) => break,
+                        };
+                        // a panicking job only drops its result sender.
+                        let _ = panic::catch_unwind(AssertUnwindSafe(|| job(&mut workspace)));
+                    }
+                })
+            })
+            .collect();
+        SolverPool {
+            jobs: Some(sender),
+            workers,
+        }
+    }
+
+    /// Number of worker threads.
+    pub fn threads(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Queues `weights` for solving. The result can be received from the
+    /// returned receiver; receiving fails if the solve panicked.
+    pub fn submit<W>(&self, mut weights: W) -> Receiver<Result<Vec<Position>, Error>>
+    where
+        W: Weights + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let job: Job = Box::new(move |workspace| {
+            let _ = sender.send(solve_assignment_in(&mut weights, workspace));
+        });
+        self.jobs
+            .as_ref()
+            .unwrap()
+            .send(job)
+            .expect("solver pool workers have stopped");
+        receiver
+    }
+}
+
+impl Drop for SolverPool {
+    /// Finishes the queued jobs and stops the workers.
+    fn drop(&mut self) {
+        self.jobs = None;
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[test]
+fn test_solver_pool() {
+    use crate::{generators, solve_assignment};
+
+    let pool = SolverPool::new(3, 8);
+    assert_eq!(3, pool.threads());
+    let instances: Vec<_> = (0..10)
+        .map(|seed| generators::uniform(8, 0..100, seed))
+        .collect();
+    let receivers: Vec<_> = instances
+        .iter()
+        .map(|weights| pool.submit(weights.clone()))
+        .collect();
+    for (weights, receiver) in instances.into_iter().zip(receivers) {
+        let expected = solve_assignment(&mut weights.clone()).unwrap();
+        assert_eq!(expected, receiver.recv().unwrap().unwrap());
+    }
+
+    let inf = f32::INFINITY;
+    let weights = crate::WeightMatrix::from_row_vec(2, vec![inf, 1.0, inf, 2.0]);
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        pool.submit(weights).recv().unwrap()
+    );
+}
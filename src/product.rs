@@ -0,0 +1,156 @@
+//! Multiplicative objectives, e.g. the likelihood of a matching given
+//! independent match probabilities.
+//!
+//! The product of the assigned factors is optimized as the sum of their
+//! logarithms. Zero factors have no logarithm; they are replaced by a cost
+//! that outweighs any sum of logarithms of the positive factors, so that a
+//! matching with a zero factor is preferred (when minimizing) or avoided
+//! (when maximizing) whenever possible. Products of many small factors
+//! underflow, so the result is reported as a logarithm as well.
+use crate::{
+    float, solve_assignment, Assignment, Error, Position, WeightMatrix, WeightNum, Weights,
+};
+
+/// An assignment together with the product of its factors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProductAssignment {
+    pub assignment: Assignment,
+    /// The natural logarithm of the product, `-inf` if a factor is zero.
+    pub log_product: f64,
+}
+
+impl ProductAssignment {
+    /// The product of the assigned factors. May underflow to zero (or
+    /// overflow to infinity) where `log_product` is still finite.
+    pub fn product(&self) -> f64 {
+        float::exp(self.log_product)
+    }
+}
+
+/// Finds the assignment minimizing the product of the assigned factors.
+/// `weights` is not modified. Invalid cells are forbidden.
+///
+/// # Panics
+///
+/// If a valid cell is negative or can't be converted to `f64`.
+pub fn solve_assignment_min_product<W>(weights: &W) -> Result<ProductAssignment, Error>
+where
+    W: Weights,
+{
+    solve_product(weights, 1.0)
+}
+
+/// Finds the assignment maximizing the product of the assigned factors,
+/// e.g. the most likely matching. `weights` is not modified. Invalid cells
+/// are forbidden, zero factors are only used if every assignment needs one.
+///
+/// # Panics
+///
+/// Like `solve_assignment_min_product`.
+pub fn solve_assignment_max_product<W>(weights: &W) -> Result<ProductAssignment, Error>
+where
+    W: Weights,
+{
+    solve_product(weights, -1.0)
+}
+
+/// Minimizes the sum of `sign * ln(x)`.
+fn solve_product<W>(weights: &W, sign: f64) -> Result<ProductAssignment, Error>
+where
+    W: Weights,
+{
+    let n = weights.n();
+    if n == 0 {
+        return Ok(ProductAssignment {
+            assignment: Assignment::default(),
+            log_product: 0.0,
+        });
+    }
+    let factor = |pos: Position| -> Option<f64> {
+        if !weights.is_valid_at(pos) {
+            return None;
+        }
+        let x = weights
+            .element_at(pos)
+            .to_f64()
+            .expect("product objectives require weights convertible to f64");
+        assert!(x >= 0.0, "product objectives require non-negative weights");
+        Some(x)
+    };
+
+    let (mut lo, mut hi) = (f64::INFINITY, f64::NEG_INFINITY);
+    for row in 0..n {
+        for column in 0..n {
+            if let Some(x) = factor(Position { row, column }).filter(|&x| x > 0.0) {
+                let cost = sign * float::ln(x);
+                lo = lo.min(cost);
+                hi = hi.max(cost);
+            }
+        }
+    }
+    if lo > hi {
+        // only zero factors.
+        lo = 0.0;
+        hi = 0.0;
+    }
+    // exceeds the spread of any two sums of `n` logarithms.
+    let penalty = n as f64 * (hi - lo) + 1.0;
+    let zero_cost = if sign > 0.0 { -penalty } else { penalty };
+
+    let mut costs =
+        WeightMatrix::from_fn(n, |(row, column)| match factor(Position { row, column }) {
+            Some(x) if x > 0.0 => sign * float::ln(x) - lo,
+            Some(_) => zero_cost,
+            None => f64::INFINITY,
+        });
+    let positions = solve_assignment(&mut costs)?;
+    let log_product = positions
+        .iter()
+        .map(|&pos| float::ln(factor(pos).unwrap()))
+        .sum();
+    Ok(ProductAssignment {
+        assignment: Assignment::from(positions),
+        log_product,
+    })
+}
+
+#[test]
+fn test_product_objectives() {
+    let columns = |p: &ProductAssignment| -> Vec<usize> {
+        p.assignment.iter().map(|pos| pos.column).collect()
+    };
+
+    let probabilities = WeightMatrix::from_row_vec(2, vec![0.5, 0.1, 0.2, 0.9]);
+    let best = solve_assignment_max_product(&probabilities).unwrap();
+    assert_eq!(vec![0, 1], columns(&best));
+    assert!((best.product() - 0.45).abs() < 1e-12);
+    let worst = solve_assignment_min_product(&probabilities).unwrap();
+    assert_eq!(vec![1, 0], columns(&worst));
+    assert!((worst.product() - 0.02).abs() < 1e-12);
+
+    // a zero factor beats any positive product, however small.
+    let weights = WeightMatrix::from_row_vec(2, vec![0.0, 1.0, 1e-300, 1e-300]);
+    let min = solve_assignment_min_product(&weights).unwrap();
+    assert_eq!(vec![0, 1], columns(&min));
+    assert_eq!(f64::NEG_INFINITY, min.log_product);
+    assert_eq!(0.0, min.product());
+    assert_eq!(
+        vec![1, 0],
+        columns(&solve_assignment_max_product(&weights).unwrap())
+    );
+
+    // when every assignment needs a zero, the product is zero.
+    let weights = WeightMatrix::from_row_vec(2, vec![0.0, 0.5, 0.0, 0.5]);
+    let max = solve_assignment_max_product(&weights).unwrap();
+    assert_eq!(0.0, max.product());
+
+    // the product underflows, its logarithm doesn't.
+    let weights = WeightMatrix::from_fn(
+        3,
+        |(row, column)| if row == column { 1e-200 } else { 1e-250 },
+    );
+    let max = solve_assignment_max_product(&weights).unwrap();
+    assert_eq!(vec![0, 1, 2], columns(&max));
+    assert_eq!(0.0, max.product());
+    assert!((max.log_product - 3.0 * 1e-200f64.ln()).abs() < 1e-9);
+}
@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+/// Wall time spent in the phases of the Kuhn-Munkres algorithm.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Timings {
+    /// Step 1, subtracting the row minima.
+    pub reduction: Duration,
+    /// Steps 2 to 4, starring, covering and priming zeros.
+    pub zero_search: Duration,
+    /// Step 5, augmenting along alternating paths.
+    pub augmentation: Duration,
+    /// Step 6, updating the duals to create new zeros.
+    pub dual_update: Duration,
+}
+
+impl Timings {
+    pub fn total(&self) -> Duration {
+        self.reduction + self.zero_search + self.augmentation + self.dual_update
+    }
+}
@@ -0,0 +1,199 @@
+//! Pruning a stream of candidate pairs to the best few per row.
+//!
+//! Matching pipelines generate far more candidates than can be stored, most
+//! of which are never part of a good assignment. `TopKBuilder` keeps only
+//! the `k` cheapest candidates of each row, and optionally also those of each
+//! column, in `O((rows + columns) k)` memory, and builds a `TripletBuilder`
+//! from them. Keeping the best of the columns too makes it much less likely
+//! that some column ends up without candidates.
+//!
+//! `Diagnostics` tells whether the pruned instance still has a complete
+//! assignment, and if not, which rows and columns lost all their candidates.
+use crate::graph::Graph;
+use crate::sparse::TripletBuilder;
+use alloc::collections::BinaryHeap;
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    cost: f64,
+    row: usize,
+    column: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Candidate) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Candidate) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// By cost, so that the top of a heap is the worst candidate kept.
+impl Ord for Candidate {
+    fn cmp(&self, other: &Candidate) -> Ordering {
+        self.cost
+            .total_cmp(&other.cost)
+            .then(self.row.cmp(&other.row))
+            .then(self.column.cmp(&other.column))
+    }
+}
+
+/// Keeps `candidate` in `heaps[line]` if it's among the `k` best.
+fn keep_best(heaps: &mut Vec<BinaryHeap<Candidate>>, line: usize, k: usize, candidate: Candidate) {
+    if line >= heaps.len() {
+        heaps.resize_with(line + 1, BinaryHeap::new);
+    }
+    let heap = &mut heaps[line];
+    if heap.len() < k {
+        heap.push(candidate);
+    } else if heap.peek().is_some_and(|worst| candidate < *worst) {
+        heap.pop();
+        heap.push(candidate);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TopKBuilder {
+    k: usize,
+    column_k: usize,
+    rows: Vec<BinaryHeap<Candidate>>,
+    columns: Vec<BinaryHeap<Candidate>>,
+    candidates: usize,
+}
+
+impl TopKBuilder {
+    /// Keeps the `k` cheapest candidates of each row.
+    pub fn new(k: usize) -> TopKBuilder {
+        TopKBuilder {
+            k,
+            column_k: 0,
+            rows: Vec::new(),
+            columns: Vec::new(),
+            candidates: 0,
+        }
+    }
+
+    /// Also keeps the `k` cheapest candidates of each column.
+    pub fn per_column(mut self, k: usize) -> TopKBuilder {
+        self.column_k = k;
+        self
+    }
+
+    /// Offers a candidate. Non-finite costs are skipped, as by
+    /// `TripletBuilder::push`.
+    pub fn push(&mut self, row: usize, column: usize, cost: f64) {
+        if !cost.is_finite() {
+            return;
+        }
+        self.candidates += 1;
+        let candidate = Candidate { cost, row, column };
+        keep_best(&mut self.rows, row, self.k, candidate);
+        if self.column_k > 0 {
+            keep_best(&mut self.columns, column, self.column_k, candidate);
+        } else if column >= self.columns.len() {
+            // only track the size of the instance.
+            self.columns.resize_with(column + 1, BinaryHeap::new);
+        }
+    }
+
+    /// The kept candidates as a sparse instance, and whether it can be
+    /// solved.
+    pub fn build(self) -> (TripletBuilder, Diagnostics) {
+        let n = self.rows.len().max(self.columns.len());
+        let mut kept: Vec<Candidate> = self
+            .rows
+            .into_iter()
+            .chain(self.columns)
+            .flatten()
+            .collect();
+        kept.sort_unstable();
+        kept.dedup();
+
+        let mut builder = TripletBuilder::new();
+        let mut cells = vec![Vec::new(); n];
+        let mut column_used = vec![false; n];
+        for candidate in &kept {
+            builder.push(candidate.row, candidate.column, candidate.cost);
+            cells[candidate.row].push((candidate.column, ()));
+            column_used[candidate.column] = true;
+        }
+        for row in &mut cells {
+            row.sort_unstable_by_key(|&(column, _)| column);
+            row.dedup();
+        }
+        let diagnostics = Diagnostics {
+            candidates: self.candidates,
+            kept: kept.len(),
+            empty_rows: (0..n).filter(|&row| cells[row].is_empty()).collect(),
+            empty_columns: (0..n).filter(|&column| !column_used[column]).collect(),
+            has_perfect_matching: Graph::from_rows(n, cells).has_perfect_matching(),
+        };
+        (builder, diagnostics)
+    }
+}
+
+/// What `TopKBuilder::build` kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostics {
+    /// The number of candidates with a finite cost pushed.
+    pub candidates: usize,
+    pub kept: usize,
+    /// The rows without any candidate kept, ascending.
+    pub empty_rows: Vec<usize>,
+    /// The columns without any candidate kept, ascending.
+    pub empty_columns: Vec<usize>,
+    /// Whether the kept candidates admit a complete assignment, that is,
+    /// whether solving the instance can succeed.
+    pub has_perfect_matching: bool,
+}
+
+#[test]
+fn test_top_k_builder() {
+    use crate::{generators, solve_assignment, Error, Position, Weights};
+
+    for seed in 0..10 {
+        let weights = generators::uniform(10, 0..1000, seed);
+        let mut builder = TopKBuilder::new(4).per_column(4);
+        for row in 0..10 {
+            for column in 0..10 {
+                let pos = Position { row, column };
+                builder.push(row, column, weights.element_at(pos) as f64);
+            }
+        }
+        let (mut instance, diagnostics) = builder.build();
+        assert_eq!(100, diagnostics.candidates);
+        assert!(diagnostics.kept >= 40 && diagnostics.kept <= 80);
+        assert!(diagnostics.has_perfect_matching);
+
+        // pruning only increases the optimal cost.
+        let cost = |m: &[Position]| m.iter().map(|&pos| weights.element_at(pos)).sum::<u32>();
+        let expected = cost(&solve_assignment(&mut weights.clone()).unwrap());
+        assert!(cost(&instance.solve().unwrap()) >= expected);
+    }
+
+    // both rows prefer column 0, and nothing is kept for column 1.
+    let mut builder = TopKBuilder::new(1);
+    for &(row, column, cost) in &[(0, 0, 1.0), (0, 1, 2.0), (1, 0, 1.0), (1, 1, 3.0)] {
+        builder.push(row, column, cost);
+    }
+    let (mut instance, diagnostics) = builder.build();
+    assert_eq!(
+        Diagnostics {
+            candidates: 4,
+            kept: 2,
+            empty_rows: vec![],
+            empty_columns: vec![1],
+            has_perfect_matching: false,
+        },
+        diagnostics
+    );
+    assert_eq!(Err(Error::MatrixNotSolvable), instance.solve());
+}
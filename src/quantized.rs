@@ -0,0 +1,98 @@
+//! Quantized `u8` and `u16` cost matrices.
+//!
+//! Quantized embedding similarities come as small integers `q` with a scale
+//! and zero point, standing for `scale * (q - zero_point)`. Storing them as
+//! such takes a quarter (or half) of the memory of `f32`, enough to keep
+//! 30k x 30k instances in memory. With a positive scale, the optimal
+//! assignment of the quantized values is optimal in original units too, so
+//! `QuantizedMatrix` solves on `q` directly. The reductions can't be stored
+//! in `u8`, so it is read-only, with `q` read as `i64`: solve it with
+//! `solve_assignment_immutable` or through `DualWeights`, which keep the
+//! reductions as row and column potentials.
+use crate::dual::Costs;
+use crate::Position;
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone)]
+pub struct QuantizedMatrix<Q> {
+    n: usize,
+    /// The quantized values in row-major order.
+    data: Vec<Q>,
+    scale: f64,
+    zero_point: i64,
+}
+
+impl<Q: Copy + Into<i64>> QuantizedMatrix<Q> {
+    /// # Panics
+    ///
+    /// If `data` doesn't have `n * n` elements, or `scale` isn't positive.
+    pub fn from_row_vec(n: usize, data: Vec<Q>, scale: f64, zero_point: i64) -> QuantizedMatrix<Q> {
+        assert!(data.len() == n * n);
+        assert!(scale > 0.0, "the scale must be positive");
+        QuantizedMatrix {
+            n,
+            data,
+            scale,
+            zero_point,
+        }
+    }
+
+    #[inline]
+    fn quantized_at(&self, pos: Position) -> i64 {
+        self.data[pos.row * self.n + pos.column].into()
+    }
+
+    /// The value of a cell in original units.
+    pub fn dequantize(&self, pos: Position) -> f64 {
+        self.scale * (self.quantized_at(pos) - self.zero_point) as f64
+    }
+
+    /// The total cost of `matching` in original units.
+    pub fn cost(&self, matching: &[Position]) -> f64 {
+        let sum: i64 = matching
+            .iter()
+            .map(|&pos| self.quantized_at(pos) - self.zero_point)
+            .sum();
+        self.scale * sum as f64
+    }
+}
+
+impl<Q: Copy + Into<i64>> Costs for QuantizedMatrix<Q> {
+    type T = i64;
+
+    #[inline(always)]
+    fn n(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    fn element_at(&self, pos: Position) -> i64 {
+        self.quantized_at(pos)
+    }
+
+    fn is_solvable(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn test_quantized_matrix() {
+    use crate::{generators, solve_assignment, solve_assignment_immutable, WeightMatrix};
+
+    for seed in 0..10 {
+        let mut rng = generators::Rng::new(seed);
+        let data: Vec<u8> = (0..64).map(|_| rng.next_in(0..256) as u8).collect();
+        let weights = QuantizedMatrix::from_row_vec(8, data.clone(), 0.5, 128);
+        let original = WeightMatrix::from_fn(8, |(row, column)| {
+            weights.dequantize(Position { row, column })
+        });
+        let expected = solve_assignment(&mut original.clone()).unwrap();
+        let matching = solve_assignment_immutable(&weights).unwrap();
+        let cost = |m: &[Position]| m.iter().map(|&pos| original.element_at(pos)).sum::<f64>();
+        assert_eq!(cost(&expected), cost(&matching));
+        assert_eq!(cost(&matching), weights.cost(&matching));
+    }
+
+    let weights = QuantizedMatrix::from_row_vec(1, vec![1000u16], 0.25, 0);
+    assert_eq!(250.0, weights.dequantize(Position { row: 0, column: 0 }));
+}
@@ -0,0 +1,175 @@
+//! Min-max regret assignment with interval costs.
+//!
+//! Each cell cost is only known to lie in an interval `[lower, upper]`. The
+//! regret of an assignment in a scenario (a choice of costs within the
+//! intervals) is its cost minus the optimal cost in that scenario. The
+//! worst scenario for an assignment sets its own cells to their upper and
+//! all other cells to their lower bounds, so its maximum regret takes one
+//! solve to evaluate.
+//!
+//! Minimizing the maximum regret is NP-hard. Instances up to
+//! `EXACT_MAX_N` are solved exactly by enumeration. Larger ones use the
+//! assignment optimal for the interval midpoints, whose maximum regret is
+//! at most twice the optimum, or the one optimal for the upper bounds if its
+//! regret is smaller.
+use crate::pareto::to_f64;
+use crate::{solve_assignment, Assignment, Error, Position, WeightMatrix, Weights};
+use alloc::{vec, vec::Vec};
+
+/// Up to this size, `solve_assignment_min_max_regret` enumerates all
+/// assignments.
+pub const EXACT_MAX_N: usize = 7;
+
+/// An assignment with its maximum regret.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegretSolution {
+    pub assignment: Assignment,
+    pub max_regret: f64,
+    /// A lower bound on the maximum regret of any assignment. Equal to
+    /// `max_regret` if the instance was solved exactly.
+    pub lower_bound: f64,
+}
+
+/// Finds the assignment with the minimum maximum regret, where the cost of
+/// each cell lies between `lower` and `upper`. A cell is valid if it is
+/// valid in both.
+///
+/// # Panics
+///
+/// If the sizes differ, a lower bound exceeds its upper bound, or a valid
+/// cell can't be converted to `f64`.
+pub fn solve_assignment_min_max_regret<W1, W2>(
+    lower: &W1,
+    upper: &W2,
+) -> Result<RegretSolution, Error>
+where
+    W1: Weights,
+    W2: Weights,
+{
+    assert!(lower.n() == upper.n());
+    let lower = to_f64(lower, upper);
+    let upper = to_f64(upper, &lower);
+    assert!(lower
+        .as_slice()
+        .iter()
+        .zip(upper.as_slice())
+        .all(|(lo, hi)| !lo.is_finite() || lo <= hi));
+    if lower.n() <= EXACT_MAX_N {
+        solve_exact(&lower, &upper)
+    } else {
+        solve_midpoint(&lower, &upper)
+    }
+}
+
+/// The maximum regret of the assignment `positions`.
+fn max_regret(
+    lower: &WeightMatrix<f64>,
+    upper: &WeightMatrix<f64>,
+    positions: &[Position],
+) -> Result<f64, Error> {
+    let cost = |w: &WeightMatrix<f64>, positions: &[Position]| -> f64 {
+        positions.iter().map(|&pos| w.element_at(pos)).sum()
+    };
+    let mut assigned = vec![None; lower.n()];
+    for pos in positions {
+        assigned[pos.row] = Some(pos.column);
+    }
+    let worst = WeightMatrix::from_fn(lower.n(), |(row, column)| {
+        let pos = Position { row, column };
+        if assigned[row] == Some(column) {
+            upper.element_at(pos)
+        } else {
+            lower.element_at(pos)
+        }
+    });
+    let best = solve_assignment(&mut worst.clone())?;
+    Ok(cost(upper, positions) - cost(&worst, &best))
+}
+
+fn solve_exact(
+    lower: &WeightMatrix<f64>,
+    upper: &WeightMatrix<f64>,
+) -> Result<RegretSolution, Error> {
+    let n = lower.n();
+    let mut columns: Vec<usize> = (0..n).collect();
+    let mut best: Option<(Vec<Position>, f64)> = None;
+    // Heap's algorithm.
+    let mut counters = vec![0; n];
+    let mut i = 0;
+    loop {
+        let positions: Vec<Position> = columns
+            .iter()
+            .enumerate()
+            .map(|(row, &column)| Position { row, column })
+            .collect();
+        if positions.iter().all(|&pos| lower.is_valid_at(pos)) {
+            let regret = max_regret(lower, upper, &positions)?;
+            if best.as_ref().is_none_or(|&(_, r)| regret < r) {
+                best = Some((positions, regret));
+            }
+        }
+        while i < n && counters[i] >= i {
+            counters[i] = 0;
+            i += 1;
+        }
+        if i >= n {
+            break;
+        }
+        let j = if i % 2 == 0 { 0 } else { counters[i] };
+        columns.swap(j, i);
+        counters[i] += 1;
+        i = 0;
+    }
+    let (positions, max_regret) = best.ok_or(Error::MatrixNotSolvable)?;
+    Ok(RegretSolution {
+        assignment: Assignment::from(positions),
+        max_regret,
+        lower_bound: max_regret,
+    })
+}
+
+fn solve_midpoint(
+    lower: &WeightMatrix<f64>,
+    upper: &WeightMatrix<f64>,
+) -> Result<RegretSolution, Error> {
+    let mut midpoint = WeightMatrix::from_fn(lower.n(), |(row, column)| {
+        let pos = Position { row, column };
+        (lower.element_at(pos) + upper.element_at(pos)) / 2.0
+    });
+    let central = solve_assignment(&mut midpoint)?;
+    let central_regret = max_regret(lower, upper, &central)?;
+    let pessimistic = solve_assignment(&mut upper.clone())?;
+    let pessimistic_regret = max_regret(lower, upper, &pessimistic)?;
+    let (positions, max_regret) = if pessimistic_regret < central_regret {
+        (pessimistic, pessimistic_regret)
+    } else {
+        (central, central_regret)
+    };
+    Ok(RegretSolution {
+        assignment: Assignment::from(positions),
+        max_regret,
+        lower_bound: central_regret / 2.0,
+    })
+}
+
+#[test]
+fn test_min_max_regret() {
+    // the diagonal may be cheap, but risks a regret of 20.
+    let lower = WeightMatrix::from_row_vec(2, vec![0.0, 0.0, 0.0, 0.0]);
+    let upper = WeightMatrix::from_row_vec(2, vec![10.0, 1.0, 1.0, 10.0]);
+    let solution = solve_assignment_min_max_regret(&lower, &upper).unwrap();
+    assert_eq!(Some(1), solution.assignment.column_of(0));
+    assert_eq!(2.0, solution.max_regret);
+    assert_eq!(2.0, solution.lower_bound);
+
+    // the heuristic stays within a factor of two of the optimum.
+    let lower = crate::generators::uniform(6, 0..50, 7);
+    let upper = WeightMatrix::from_fn(6, |(row, column)| {
+        lower.element_at(Position { row, column }) + ((row * 7 + column * 3) % 40) as u32
+    });
+    let exact = solve_assignment_min_max_regret(&lower, &upper).unwrap();
+    let heuristic = solve_midpoint(&to_f64(&lower, &upper), &to_f64(&upper, &lower)).unwrap();
+    assert!(exact.max_regret <= heuristic.max_regret);
+    assert!(heuristic.lower_bound <= exact.max_regret);
+    assert!(heuristic.max_regret <= 2.0 * exact.max_regret);
+}
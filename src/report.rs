@@ -0,0 +1,200 @@
+//! Human-readable reports of a matching, with the rows and columns named by
+//! labels (e.g. worker and task names) instead of indices, exportable as
+//! JSON or CSV without further dependencies.
+use crate::{Position, WeightNum, Weights};
+use alloc::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::Write;
+
+/// An assigned pair of a report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportPair {
+    pub row: usize,
+    pub column: usize,
+    pub row_label: String,
+    pub column_label: String,
+    pub cost: f64,
+}
+
+/// A matching with labels, per-pair costs, the total cost and the rows and
+/// columns left unassigned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub pairs: Vec<ReportPair>,
+    pub total: f64,
+    pub unassigned_rows: Vec<String>,
+    pub unassigned_columns: Vec<String>,
+}
+
+impl Report {
+    /// Creates the report of `matching` with the costs of `weights`, which
+    /// must not be the weights reduced by the solve, but a copy of the
+    /// original ones.
+    ///
+    /// # Panics
+    ///
+    /// If the numbers of labels don't match the size of `weights`, or an
+    /// assigned cost can't be converted to `f64`.
+    pub fn new<W, S>(
+        weights: &W,
+        matching: &[Position],
+        row_labels: &[S],
+        column_labels: &[S],
+    ) -> Report
+    where
+        W: Weights,
+        S: AsRef<str>,
+    {
+        let n = weights.n();
+        assert!(row_labels.len() == n && column_labels.len() == n);
+        let mut row_assigned = vec![false; n];
+        let mut column_assigned = vec![false; n];
+        let pairs: Vec<ReportPair> = matching
+            .iter()
+            .map(|&pos| {
+                row_assigned[pos.row] = true;
+                column_assigned[pos.column] = true;
+                ReportPair {
+                    row: pos.row,
+                    column: pos.column,
+                    row_label: row_labels[pos.row].as_ref().to_owned(),
+                    column_label: column_labels[pos.column].as_ref().to_owned(),
+                    cost: weights
+                        .element_at(pos)
+                        .to_f64()
+                        .expect("reports require weights convertible to f64"),
+                }
+            })
+            .collect();
+        let unassigned = |labels: &[S], assigned: &[bool]| -> Vec<String> {
+            labels
+                .iter()
+                .zip(assigned)
+                .filter(|&(_, &assigned)| !assigned)
+                .map(|(label, _)| label.as_ref().to_owned())
+                .collect()
+        };
+        Report {
+            total: pairs.iter().map(|pair| pair.cost).sum(),
+            unassigned_rows: unassigned(row_labels, &row_assigned),
+            unassigned_columns: unassigned(column_labels, &column_assigned),
+            pairs,
+        }
+    }
+
+    /// The report as a JSON object with the fields of `Report`.
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\"pairs\":[");
+        for (i, pair) in self.pairs.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            write!(
+                json,
+                "{{\"row\":{},\"column\":{},\"row_label\":{},\"column_label\":{},\"cost\":{}}}",
+                pair.row,
+                pair.column,
+                json_string(&pair.row_label),
+                json_string(&pair.column_label),
+                json_number(pair.cost)
+            )
+            .unwrap();
+        }
+        write!(json, "],\"total\":{}", json_number(self.total)).unwrap();
+        for (name, labels) in &[
+            ("unassigned_rows", &self.unassigned_rows),
+            ("unassigned_columns", &self.unassigned_columns),
+        ] {
+            write!(json, ",\"{}\":[", name).unwrap();
+            let strings: Vec<String> = labels.iter().map(|label| json_string(label)).collect();
+            json.push_str(&strings.join(","));
+            json.push(']');
+        }
+        json.push('}');
+        json
+    }
+
+    /// The pairs of the report as CSV with the header
+    /// `row_label,column_label,cost`.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("row_label,column_label,cost\n");
+        for pair in &self.pairs {
+            writeln!(
+                csv,
+                "{},{},{}",
+                csv_field(&pair.row_label),
+                csv_field(&pair.column_label),
+                pair.cost
+            )
+            .unwrap();
+        }
+        csv
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// JSON has no infinities or NaN.
+fn json_number(x: f64) -> String {
+    if x.is_finite() {
+        x.to_string()
+    } else {
+        "null".to_owned()
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
+
+#[test]
+fn test_report() {
+    use crate::{solve_assignment, WeightMatrix};
+
+    let weights = WeightMatrix::from_row_vec(2, vec![1.5, 4.0, 3.0, 2.0]);
+    let matching = solve_assignment(&mut weights.clone()).unwrap();
+    let report = Report::new(&weights, &matching, &["ann", "bob"], &["fix \"A\"", "b,c"]);
+    assert_eq!(3.5, report.total);
+    assert_eq!("bob", report.pairs[1].row_label);
+    assert_eq!(
+        "{\"pairs\":[{\"row\":0,\"column\":0,\"row_label\":\"ann\",\"column_label\":\"fix \\\"A\\\"\",\"cost\":1.5},\
+         {\"row\":1,\"column\":1,\"row_label\":\"bob\",\"column_label\":\"b,c\",\"cost\":2}],\
+         \"total\":3.5,\"unassigned_rows\":[],\"unassigned_columns\":[]}",
+        report.to_json()
+    );
+    assert_eq!(
+        "row_label,column_label,cost\nann,\"fix \"\"A\"\"\",1.5\nbob,\"b,c\",2\n",
+        report.to_csv()
+    );
+
+    let partial = Report::new(&weights, &matching[..1], &["ann", "bob"], &["x", "y"]);
+    assert_eq!(vec!["bob".to_owned()], partial.unassigned_rows);
+    assert!(partial
+        .to_json()
+        .ends_with("\"unassigned_rows\":[\"bob\"],\"unassigned_columns\":[\"y\"]}"));
+}
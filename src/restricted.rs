@@ -0,0 +1,192 @@
+use crate::bitset::BitSet;
+use crate::{Position, WeightNum, Weights};
+use alloc::vec::Vec;
+use core::mem;
+
+/// Dense copy of a weight matrix with an explicit set of allowed cells, so
+/// that cells can be forbidden or pinned independently of their values and
+/// for any `WeightNum`. Forbidden cells are never modified.
+#[derive(Debug, Clone)]
+pub(crate) struct Restricted<T> {
+    n: usize,
+    values: Vec<T>,
+    allowed: BitSet,
+}
+
+impl<T: WeightNum> Restricted<T> {
+    pub(crate) fn from_weights<W>(weights: &W) -> Restricted<T>
+    where
+        W: Weights<T = T>,
+    {
+        Restricted::from_weights_where(weights, |_| true)
+    }
+
+    /// Like `from_weights`, additionally forbidding all cells for which
+    /// `allowed` returns false.
+    pub(crate) fn from_weights_where<W, F>(weights: &W, mut allowed_at: F) -> Restricted<T>
+    where
+        W: Weights<T = T>,
+        F: FnMut(Position) -> bool,
+    {
+        let n = weights.n();
+        let mut values = Vec::with_capacity(n * n);
+        let mut allowed = BitSet::with_capacity(n * n);
+        for row in 0..n {
+            for column in 0..n {
+                let pos = Position { row, column };
+                values.push(weights.element_at(pos));
+                allowed.set(
+                    row * n + column,
+                    weights.is_valid_at(pos) && allowed_at(pos),
+                );
+            }
+        }
+        Restricted { n, values, allowed }
+    }
+
+    pub(crate) fn memory_usage(&self) -> usize {
+        mem::size_of_val(&self.values[..]) + self.allowed.memory_usage()
+    }
+
+    /// The submatrix of `weights` made of `rows` and `columns`, which must
+    /// have the same length.
+    pub(crate) fn submatrix<W>(weights: &W, rows: &[usize], columns: &[usize]) -> Restricted<T>
+    where
+        W: Weights<T = T>,
+    {
+        assert!(rows.len() == columns.len());
+        let n = rows.len();
+        let mut values = Vec::with_capacity(n * n);
+        let mut allowed = BitSet::with_capacity(n * n);
+        for (i, &row) in rows.iter().enumerate() {
+            for (j, &column) in columns.iter().enumerate() {
+                let pos = Position { row, column };
+                values.push(weights.element_at(pos));
+                allowed.set(i * n + j, weights.is_valid_at(pos));
+            }
+        }
+        Restricted { n, values, allowed }
+    }
+
+    #[inline]
+    fn index(&self, pos: Position) -> usize {
+        pos.row * self.n + pos.column
+    }
+
+    pub(crate) fn forbid(&mut self, pos: Position) {
+        let index = self.index(pos);
+        self.allowed.set(index, false);
+    }
+
+    /// Sets the value of the cell at `pos`, keeping whether it's allowed.
+    pub(crate) fn set(&mut self, pos: Position, value: T) {
+        let index = self.index(pos);
+        self.values[index] = value;
+    }
+
+    /// Adds `val` to the valid cells of `column`.
+    pub(crate) fn add_column(&mut self, column: usize, val: T) {
+        for row in 0..self.n {
+            let pos = Position { row, column };
+            if self.is_valid_at(pos) {
+                let index = self.index(pos);
+                self.values[index] = self.values[index] + val;
+            }
+        }
+    }
+
+    /// Forbids all other cells in the row and column of `pos`.
+    pub(crate) fn pin(&mut self, pos: Position) {
+        for i in 0..self.n {
+            if i != pos.column {
+                self.forbid(Position {
+                    row: pos.row,
+                    column: i,
+                });
+            }
+            if i != pos.row {
+                self.forbid(Position {
+                    row: i,
+                    column: pos.column,
+                });
+            }
+        }
+    }
+
+    /// The sum of the values at `positions`.
+    pub(crate) fn cost(&self, positions: &[Position]) -> T {
+        let mut values = positions.iter().map(|&pos| self.values[self.index(pos)]);
+        let first = values.next().expect("empty assignment");
+        values.fold(first, |sum, value| sum + value)
+    }
+}
+
+impl<T: WeightNum> Weights for Restricted<T> {
+    type T = T;
+
+    #[inline(always)]
+    fn n(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    fn element_at(&self, pos: Position) -> T {
+        self.values[self.index(pos)]
+    }
+
+    #[inline]
+    fn is_element_zero(&self, pos: Position) -> bool {
+        self.is_valid_at(pos) && self.element_at(pos).is_zero()
+    }
+
+    #[inline]
+    fn is_valid_at(&self, pos: Position) -> bool {
+        self.allowed.contains(self.index(pos)) && self.element_at(pos).is_valid()
+    }
+
+    fn sub_min_of_each_row(&mut self) {
+        for row in 0..self.n {
+            let min = (0..self.n)
+                .map(|column| Position { row, column })
+                .filter(|&pos| self.is_valid_at(pos))
+                .map(|pos| self.element_at(pos))
+                .fold(None, |min: Option<T>, value| match min {
+                    Some(m) if m < value => Some(m),
+                    _ => Some(value),
+                });
+            if let Some(min) = min {
+                for column in 0..self.n {
+                    let pos = Position { row, column };
+                    if self.is_valid_at(pos) {
+                        let index = self.index(pos);
+                        self.values[index] = self.values[index] - min;
+                    }
+                }
+            }
+        }
+    }
+
+    fn add_row(&mut self, row: usize, val: T) {
+        for column in 0..self.n {
+            let pos = Position { row, column };
+            if self.is_valid_at(pos) {
+                let index = self.index(pos);
+                self.values[index] = self.values[index] + val;
+            }
+        }
+    }
+
+    fn sub_column(&mut self, column: usize, val: T) {
+        for row in 0..self.n {
+            let pos = Position { row, column };
+            if self.is_valid_at(pos) {
+                let index = self.index(pos);
+                self.values[index] = self.values[index] - val;
+            }
+        }
+    }
+
+    fn is_solvable(&self) -> bool {
+        (0..self.n).all(|row| (0..self.n).any(|column| self.is_valid_at(Position { row, column })))
+    }
+}
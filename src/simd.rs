@@ -0,0 +1,157 @@
+//! SIMD128 versions of the row loops of `WeightMatrix`, for wasm32 builds
+//! with the `simd128` target feature. Each function gives the same result as
+//! the scalar loop it replaces: the minimum is taken with `pmin`, which like
+//! the scalar comparison keeps the current minimum unless the new value is
+//! strictly smaller.
+use core::arch::wasm32::*;
+
+pub(crate) fn min_f32(values: &[f32]) -> Option<f32> {
+    let (&first, _) = values.split_first()?;
+    let chunks = values.chunks_exact(4);
+    let rest = chunks.remainder();
+    let mut min = f32x4_splat(first);
+    for chunk in chunks {
+        // safe, as the chunk has 4 elements, and loads needn't be aligned.
+        let v = unsafe { v128_load(chunk.as_ptr() as *const v128) };
+        min = f32x4_pmin(min, v);
+    }
+    let lanes = [
+        f32x4_extract_lane::<0>(min),
+        f32x4_extract_lane::<1>(min),
+        f32x4_extract_lane::<2>(min),
+        f32x4_extract_lane::<3>(min),
+    ];
+    Some(
+        lanes
+            .iter()
+            .chain(rest)
+            .fold(first, |min, &val| if val < min { val } else { min }),
+    )
+}
+
+pub(crate) fn add_f32(values: &mut [f32], val: f32) {
+    let splat = f32x4_splat(val);
+    let mut chunks = values.chunks_exact_mut(4);
+    for chunk in &mut chunks {
+        let ptr = chunk.as_mut_ptr() as *mut v128;
+        // safe, as the chunk has 4 elements, and accesses needn't be aligned.
+        unsafe { v128_store(ptr, f32x4_add(v128_load(ptr), splat)) };
+    }
+    chunks
+        .into_remainder()
+        .iter_mut()
+        .for_each(|cur| *cur += val);
+}
+
+pub(crate) fn sub_f32(values: &mut [f32], val: f32) {
+    let splat = f32x4_splat(val);
+    let mut chunks = values.chunks_exact_mut(4);
+    for chunk in &mut chunks {
+        let ptr = chunk.as_mut_ptr() as *mut v128;
+        // safe, as the chunk has 4 elements, and accesses needn't be aligned.
+        unsafe { v128_store(ptr, f32x4_sub(v128_load(ptr), splat)) };
+    }
+    chunks
+        .into_remainder()
+        .iter_mut()
+        .for_each(|cur| *cur -= val);
+}
+
+pub(crate) fn min_f64(values: &[f64]) -> Option<f64> {
+    let (&first, _) = values.split_first()?;
+    let chunks = values.chunks_exact(2);
+    let rest = chunks.remainder();
+    let mut min = f64x2_splat(first);
+    for chunk in chunks {
+        // safe, as the chunk has 2 elements, and loads needn't be aligned.
+        let v = unsafe { v128_load(chunk.as_ptr() as *const v128) };
+        min = f64x2_pmin(min, v);
+    }
+    let lanes = [f64x2_extract_lane::<0>(min), f64x2_extract_lane::<1>(min)];
+    Some(
+        lanes
+            .iter()
+            .chain(rest)
+            .fold(first, |min, &val| if val < min { val } else { min }),
+    )
+}
+
+pub(crate) fn add_f64(values: &mut [f64], val: f64) {
+    let splat = f64x2_splat(val);
+    let mut chunks = values.chunks_exact_mut(2);
+    for chunk in &mut chunks {
+        let ptr = chunk.as_mut_ptr() as *mut v128;
+        // safe, as the chunk has 2 elements, and accesses needn't be aligned.
+        unsafe { v128_store(ptr, f64x2_add(v128_load(ptr), splat)) };
+    }
+    chunks
+        .into_remainder()
+        .iter_mut()
+        .for_each(|cur| *cur += val);
+}
+
+pub(crate) fn sub_f64(values: &mut [f64], val: f64) {
+    let splat = f64x2_splat(val);
+    let mut chunks = values.chunks_exact_mut(2);
+    for chunk in &mut chunks {
+        let ptr = chunk.as_mut_ptr() as *mut v128;
+        // safe, as the chunk has 2 elements, and accesses needn't be aligned.
+        unsafe { v128_store(ptr, f64x2_sub(v128_load(ptr), splat)) };
+    }
+    chunks
+        .into_remainder()
+        .iter_mut()
+        .for_each(|cur| *cur -= val);
+}
+
+#[cfg(test)]
+fn scalar_min<T: Copy + PartialOrd>(values: &[T]) -> Option<T> {
+    let (&first, rest) = values.split_first()?;
+    Some(
+        rest.iter()
+            .fold(first, |min, &val| if val < min { val } else { min }),
+    )
+}
+
+#[test]
+fn test_min_matches_scalar() {
+    // every length up to two full f32 chunks plus a tail, with the minimum
+    // moved through every lane and every tail position.
+    for len in 0..12 {
+        for at in 0..len {
+            let values: Vec<f64> = (0..len)
+                .map(|i| if i == at { -1.5 } else { (i * 7 % 5) as f64 })
+                .collect();
+            assert_eq!(scalar_min(&values), min_f64(&values));
+            let values: Vec<f32> = values.iter().map(|&val| val as f32).collect();
+            assert_eq!(scalar_min(&values), min_f32(&values));
+        }
+        let values = vec![f64::INFINITY; len];
+        assert_eq!(scalar_min(&values), min_f64(&values));
+        let values = vec![f32::INFINITY; len];
+        assert_eq!(scalar_min(&values), min_f32(&values));
+    }
+}
+
+#[test]
+fn test_add_sub_match_scalar() {
+    for len in 0..12 {
+        let values: Vec<f64> = (0..len).map(|i| i as f64 * 0.25 - 1.0).collect();
+        let mut simd = values.clone();
+        add_f64(&mut simd, 0.5);
+        let expected: Vec<f64> = values.iter().map(|&val| val + 0.5).collect();
+        assert_eq!(expected, simd);
+        sub_f64(&mut simd, 0.75);
+        let expected: Vec<f64> = expected.iter().map(|&val| val - 0.75).collect();
+        assert_eq!(expected, simd);
+
+        let values: Vec<f32> = values.iter().map(|&val| val as f32).collect();
+        let mut simd = values.clone();
+        add_f32(&mut simd, 0.5);
+        let expected: Vec<f32> = values.iter().map(|&val| val + 0.5).collect();
+        assert_eq!(expected, simd);
+        sub_f32(&mut simd, 0.75);
+        let expected: Vec<f32> = expected.iter().map(|&val| val - 0.75).collect();
+        assert_eq!(expected, simd);
+    }
+}
@@ -0,0 +1,237 @@
+//! Entropic-regularized (Sinkhorn / softassign) approximate solver.
+//!
+//! Computes the doubly-stochastic matrix `P` minimizing
+//! `sum(P * C) - regularization * H(P)`, which approaches the optimal
+//! permutation matrix as `regularization` goes to zero. The iteration runs in
+//! the log domain, so small regularizations don't underflow.
+use crate::{float, solve_assignment, Error, Position, WeightMatrix, WeightNum, Weights};
+use alloc::{vec, vec::Vec};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SinkhornOptions {
+    /// Entropic regularization strength, in units of the costs. Smaller
+    /// values give sharper plans but converge more slowly.
+    pub regularization: f64,
+    pub max_iterations: usize,
+    /// Stop once no row marginal deviates from its target by more than this.
+    pub tolerance: f64,
+}
+
+impl Default for SinkhornOptions {
+    fn default() -> Self {
+        SinkhornOptions {
+            regularization: 0.01,
+            max_iterations: 1000,
+            tolerance: 1e-6,
+        }
+    }
+}
+
+/// A doubly-stochastic "soft" assignment.
+#[derive(Debug, Clone)]
+pub struct SoftAssignment {
+    n: usize,
+    plan: Vec<f64>,
+    iterations: usize,
+    converged: bool,
+}
+
+impl SoftAssignment {
+    #[inline]
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// The weight of `pos` in the plan, in `[0, 1]`.
+    #[inline]
+    pub fn probability(&self, pos: Position) -> f64 {
+        self.plan[pos.row * self.n + pos.column]
+    }
+
+    /// The plan in row-major order.
+    pub fn as_slice(&self) -> &[f64] {
+        &self.plan
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Whether the tolerance was reached within `max_iterations`.
+    pub fn converged(&self) -> bool {
+        self.converged
+    }
+
+    /// Rounds the plan to the permutation of maximal total weight. Cells
+    /// with zero weight are never chosen.
+    pub fn round(&self) -> Result<Vec<Position>, Error> {
+        let mut weights = WeightMatrix::from_fn(self.n, |(row, column)| {
+            let p = self.plan[row * self.n + column];
+            if p > 0.0 {
+                -p
+            } else {
+                f64::INFINITY
+            }
+        });
+        solve_assignment(&mut weights)
+    }
+}
+
+/// Computes the entropic-regularized soft assignment of `weights`.
+///
+/// # Panics
+///
+/// If a valid cell can't be converted to `f64`.
+pub fn sinkhorn<W>(weights: &W, options: &SinkhornOptions) -> Result<SoftAssignment, Error>
+where
+    W: Weights,
+{
+    let n = weights.n();
+    let mut costs = Vec::with_capacity(n * n);
+    for row in 0..n {
+        for column in 0..n {
+            let pos = Position { row, column };
+            costs.push(if weights.is_valid_at(pos) {
+                weights
+                    .element_at(pos)
+                    .to_f64()
+                    .expect("sinkhorn requires weights convertible to f64")
+            } else {
+                f64::INFINITY
+            });
+        }
+    }
+    let marginals = vec![1.0; n];
+    let result = sinkhorn_log(&costs, &marginals, &marginals, options)?;
+    Ok(SoftAssignment {
+        n,
+        plan: result.plan,
+        iterations: result.iterations,
+        converged: result.converged,
+    })
+}
+
+pub(crate) struct SinkhornResult {
+    pub(crate) plan: Vec<f64>,
+    pub(crate) iterations: usize,
+    pub(crate) converged: bool,
+}
+
+/// Log-domain Sinkhorn iteration on the row-major `a.len() x b.len()` cost
+/// matrix `costs` with row marginals `a` and column marginals `b`. Infinite
+/// costs are forbidden cells.
+pub(crate) fn sinkhorn_log(
+    costs: &[f64],
+    a: &[f64],
+    b: &[f64],
+    options: &SinkhornOptions,
+) -> Result<SinkhornResult, Error> {
+    let (rows, columns) = (a.len(), b.len());
+    assert!(costs.len() == rows * columns);
+    assert!(options.regularization > 0.0);
+
+    let row_ok = |row: usize| {
+        costs[row * columns..(row + 1) * columns]
+            .iter()
+            .any(|c| c.is_finite())
+    };
+    let column_ok = |column: usize| (0..rows).any(|row| costs[row * columns + column].is_finite());
+    if !(0..rows).all(row_ok) || !(0..columns).all(column_ok) {
+        return Err(Error::MatrixNotSolvable);
+    }
+
+    let eps = options.regularization;
+    let log_a: Vec<f64> = a.iter().map(|&x| float::ln(x)).collect();
+    let log_b: Vec<f64> = b.iter().map(|&x| float::ln(x)).collect();
+    let mut f = vec![0.0; rows];
+    let mut g = vec![0.0; columns];
+    let mut iterations = 0;
+    let mut converged = false;
+
+    while iterations < options.max_iterations {
+        iterations += 1;
+        for (row, f_row) in f.iter_mut().enumerate() {
+            let lse = log_sum_exp((0..columns).map(|c| (g[c] - costs[row * columns + c]) / eps));
+            *f_row = eps * (log_a[row] - lse);
+        }
+        for (column, g_column) in g.iter_mut().enumerate() {
+            let lse = log_sum_exp((0..rows).map(|r| (f[r] - costs[r * columns + column]) / eps));
+            *g_column = eps * (log_b[column] - lse);
+        }
+
+        // columns are exact after the g update, so check the rows.
+        let error = (0..rows)
+            .map(|row| {
+                let sum: f64 = (0..columns)
+                    .map(|c| float::exp((f[row] + g[c] - costs[row * columns + c]) / eps))
+                    .sum();
+                (sum - a[row]).abs()
+            })
+            .fold(0.0, f64::max);
+        if error <= options.tolerance {
+            converged = true;
+            break;
+        }
+    }
+
+    let plan = (0..rows * columns)
+        .map(|i| float::exp((f[i / columns] + g[i % columns] - costs[i]) / eps))
+        .collect();
+    Ok(SinkhornResult {
+        plan,
+        iterations,
+        converged,
+    })
+}
+
+fn log_sum_exp<I: Iterator<Item = f64> + Clone>(values: I) -> f64 {
+    let max = values.clone().fold(f64::NEG_INFINITY, f64::max);
+    if max == f64::NEG_INFINITY {
+        return max;
+    }
+    max + float::ln(values.map(|v| float::exp(v - max)).sum::<f64>())
+}
+
+#[test]
+fn test_sinkhorn() {
+    let c = vec![
+        250.0, 400.0, 350.0, 400.0, 600.0, 350.0, 200.0, 400.0, 250.0,
+    ];
+    let weights: WeightMatrix<f64> = WeightMatrix::from_row_vec(3, c);
+    let options = SinkhornOptions {
+        regularization: 20.0,
+        ..SinkhornOptions::default()
+    };
+    let soft = sinkhorn(&weights, &options).unwrap();
+    assert!(soft.converged());
+    for i in 0..3 {
+        let row: f64 = (0..3)
+            .map(|j| soft.probability(Position { row: i, column: j }))
+            .sum();
+        let column: f64 = (0..3)
+            .map(|j| soft.probability(Position { row: j, column: i }))
+            .sum();
+        assert!((row - 1.0).abs() < 1e-5);
+        assert!((column - 1.0).abs() < 1e-5);
+    }
+    assert_eq!(
+        vec![
+            Position { row: 0, column: 1 },
+            Position { row: 1, column: 2 },
+            Position { row: 2, column: 0 }
+        ],
+        soft.round().unwrap()
+    );
+}
+
+#[test]
+fn test_sinkhorn_forbidden() {
+    let inf = f32::INFINITY;
+    let weights = WeightMatrix::from_row_vec(2, vec![1.0, inf, 2.0, 3.0]);
+    let soft = sinkhorn(&weights, &SinkhornOptions::default()).unwrap();
+    assert_eq!(0.0, soft.probability(Position { row: 0, column: 1 }));
+    assert!(soft.probability(Position { row: 0, column: 0 }) > 0.999);
+
+    let weights = WeightMatrix::from_row_vec(2, vec![inf, inf, 2.0, 3.0]);
+    assert!(sinkhorn(&weights, &SinkhornOptions::default()).is_err());
+}
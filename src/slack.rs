@@ -0,0 +1,190 @@
+//! Per-pair slack, a cheap confidence measure for each assignment.
+//!
+//! After a solve, the weights are reduced: all valid values are at least 0
+//! and the assigned cells are 0. The slack of an assigned pair is the
+//! smallest reduced value among the other valid cells of its row and its
+//! column, i.e. how much cheaper the closest alternative would have to get
+//! before it ties with the pair under the final duals. A pair with a large
+//! slack is a clear choice; a slack of 0 means an alternative is about as
+//! good. It is a heuristic, not a full sensitivity analysis: changing one
+//! cost can also shift the duals.
+//!
+//! `row_probabilities` turns the reduced values of each row into soft
+//! association probabilities by a softmax with a temperature, as used by
+//! probabilistic data association (JPDA-style) filters. The assigned cell,
+//! with a reduced value of 0, always has the highest probability of its row.
+use crate::{float, solve_assignment, Error, Position, WeightNum, Weights};
+use alloc::{vec, vec::Vec};
+
+/// The slack of each pair of `matching`, read from the `reduced` weights
+/// left by the solve. `None` if the pair has no valid alternative.
+pub fn slacks<W>(reduced: &W, matching: &[Position]) -> Vec<Option<W::T>>
+where
+    W: Weights,
+{
+    let n = reduced.n();
+    matching
+        .iter()
+        .map(|&pos| {
+            let row = (0..n)
+                .filter(|&column| column != pos.column)
+                .map(|column| Position {
+                    row: pos.row,
+                    column,
+                });
+            let column = (0..n).filter(|&row| row != pos.row).map(|row| Position {
+                row,
+                column: pos.column,
+            });
+            row.chain(column)
+                .filter(|&other| reduced.is_valid_at(other))
+                .map(|other| reduced.element_at(other))
+                .fold(None, |min, value| match min {
+                    Some(m) if m <= value => Some(m),
+                    _ => Some(value),
+                })
+        })
+        .collect()
+}
+
+/// An assigned pair with its slack, see `slacks`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PairSlack<T> {
+    pub pos: Position,
+    pub slack: Option<T>,
+}
+
+/// Like `solve_assignment`, pairing each position with its slack.
+pub fn solve_assignment_with_slack<W>(weights: &mut W) -> Result<Vec<PairSlack<W::T>>, Error>
+where
+    W: Weights,
+{
+    let matching = solve_assignment(weights)?;
+    let slacks = slacks(weights, &matching);
+    Ok(matching
+        .into_iter()
+        .zip(slacks)
+        .map(|(pos, slack)| PairSlack { pos, slack })
+        .collect())
+}
+
+/// The probabilities of each row over the columns, see `row_probabilities`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowProbabilities {
+    n: usize,
+    probabilities: Vec<f64>,
+}
+
+impl RowProbabilities {
+    #[inline]
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// The probability that the row of `pos` belongs to its column.
+    #[inline]
+    pub fn probability(&self, pos: Position) -> f64 {
+        self.probabilities[pos.row * self.n + pos.column]
+    }
+
+    /// The probabilities in row-major order.
+    pub fn as_slice(&self) -> &[f64] {
+        &self.probabilities
+    }
+}
+
+/// The softmax of `-r / temperature` over the reduced values `r` of the
+/// valid cells of each row of `reduced`, the weights left by the solve.
+/// Invalid cells have probability 0. Lower temperatures concentrate the
+/// probability on the assigned cells.
+///
+/// # Panics
+///
+/// If `temperature` isn't positive, or a valid value can't be converted to
+/// `f64`.
+pub fn row_probabilities<W>(reduced: &W, temperature: f64) -> RowProbabilities
+where
+    W: Weights,
+{
+    assert!(temperature > 0.0, "the temperature must be positive");
+    let n = reduced.n();
+    let mut probabilities = vec![0.0; n * n];
+    for (row, probabilities) in probabilities.chunks_mut(n.max(1)).enumerate() {
+        for (column, probability) in probabilities.iter_mut().enumerate() {
+            let pos = Position { row, column };
+            if reduced.is_valid_at(pos) {
+                let value = reduced
+                    .element_at(pos)
+                    .to_f64()
+                    .expect("probabilities require weights convertible to f64");
+                // the reduced values are not negative, so this can't overflow.
+                *probability = float::exp(-value / temperature);
+            }
+        }
+        let sum: f64 = probabilities.iter().sum();
+        if sum > 0.0 {
+            probabilities.iter_mut().for_each(|p| *p /= sum);
+        }
+    }
+    RowProbabilities { n, probabilities }
+}
+
+/// Like `solve_assignment`, also returning the `row_probabilities` of the
+/// reduced weights.
+pub fn solve_assignment_with_probabilities<W>(
+    weights: &mut W,
+    temperature: f64,
+) -> Result<(Vec<Position>, RowProbabilities), Error>
+where
+    W: Weights,
+{
+    let matching = solve_assignment(weights)?;
+    Ok((matching, row_probabilities(weights, temperature)))
+}
+
+#[test]
+fn test_slacks() {
+    use crate::WeightMatrix;
+
+    let pair = |row, column, slack| PairSlack {
+        pos: Position { row, column },
+        slack,
+    };
+    let mut weights = WeightMatrix::from_row_vec(2, vec![1, 10, 10, 1]);
+    assert_eq!(
+        Ok(vec![pair(0, 0, Some(9)), pair(1, 1, Some(9))]),
+        solve_assignment_with_slack(&mut weights)
+    );
+
+    // row 1 could take column 0 just as well, so neither pair is certain.
+    let mut weights = WeightMatrix::from_row_vec(2, vec![1, 10, 5, 5]);
+    assert_eq!(
+        Ok(vec![pair(0, 0, Some(0)), pair(1, 1, Some(0))]),
+        solve_assignment_with_slack(&mut weights)
+    );
+
+    // no alternatives.
+    let inf = f64::INFINITY;
+    let mut weights = WeightMatrix::from_row_vec(2, vec![1.0, inf, inf, 2.0]);
+    let pairs = solve_assignment_with_slack(&mut weights).unwrap();
+    assert!(pairs.iter().all(|pair| pair.slack.is_none()));
+}
+
+#[test]
+fn test_row_probabilities() {
+    use crate::WeightMatrix;
+
+    let inf = f64::INFINITY;
+    let mut weights = WeightMatrix::from_row_vec(2, vec![1.0, 2.0, 3.0, inf]);
+    let (matching, probabilities) = solve_assignment_with_probabilities(&mut weights, 1.0).unwrap();
+    let pos = |row, column| Position { row, column };
+    assert_eq!(vec![pos(0, 1), pos(1, 0)], matching);
+    // the reduced values of row 0 are 0 and 0, row 1 has a single choice.
+    assert_eq!(&[0.5, 0.5, 1.0, 0.0], probabilities.as_slice());
+
+    let weights = WeightMatrix::from_row_vec(2, vec![0.0, 1.0, 1.0, 0.0]);
+    let sharp = row_probabilities(&weights, 0.1);
+    let e = (-10.0f64).exp();
+    assert!((sharp.probability(pos(0, 0)) - 1.0 / (1.0 + e)).abs() < 1e-12);
+    assert!(sharp.probability(pos(0, 0)) > row_probabilities(&weights, 1.0).probability(pos(0, 0)));
+}
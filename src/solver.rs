@@ -0,0 +1,112 @@
+//! A solver owning its buffers, for solving many instances of the same size
+//! in a loop, e.g. once per frame in a tracker.
+//!
+//! `Solver` keeps the marks, the coverage, the path of step 5 and the
+//! matching between solves. Once it has solved an instance of size `n`, or
+//! was created with `Solver::with_capacity(n)`, solving further `n x n`
+//! instances doesn't allocate. Unlike `solve_assignment_cached`, the buffers
+//! belong to the caller and aren't tied to a thread; unlike `SolverArena`,
+//! only the buffers of the last size are kept.
+use crate::memory::MemoryUsage;
+use crate::workspace::Workspace;
+use crate::{run_in, Assignment, AssignmentSolver, Error, Position, Weights};
+use alloc::vec::Vec;
+use core::mem;
+
+#[derive(Debug, Clone, Default)]
+pub struct Solver {
+    /// `None` until the first solve of a non-empty instance.
+    workspace: Option<Workspace>,
+    matching: Vec<Position>,
+}
+
+impl Solver {
+    pub fn new() -> Solver {
+        Solver::default()
+    }
+
+    /// A solver with the buffers for `n x n` instances allocated up front.
+    pub fn with_capacity(n: usize) -> Solver {
+        Solver {
+            workspace: if n > 0 { Some(Workspace::new(n)) } else { None },
+            matching: Vec::with_capacity(n),
+        }
+    }
+
+    /// The size of the instances the buffers are allocated for.
+    pub fn capacity(&self) -> usize {
+        self.workspace.as_ref().map_or(0, Workspace::n)
+    }
+
+    /// Like `solve_assignment`, returning the same matching, sorted by row.
+    /// It borrows from the solver and is overwritten by the next solve.
+    /// Buffers of a different size are replaced.
+    pub fn solve<W>(&mut self, weights: &mut W) -> Result<&[Position], Error>
+    where
+        W: Weights,
+    {
+        self.matching.clear();
+        let n = weights.n();
+        if n == 0 {
+            return Ok(&self.matching);
+        }
+        let workspace = self.workspace.get_or_insert_with(|| Workspace::new(n));
+        run_in(weights, workspace)?;
+        let matching = &mut self.matching;
+        workspace.marks.each_star(|pos| matching.push(pos));
+        Ok(&self.matching)
+    }
+
+    /// The memory of the buffers.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let mut usage = self
+            .workspace
+            .as_ref()
+            .map_or(MemoryUsage::default(), Workspace::memory_usage);
+        usage.workspace += self.matching.capacity() * mem::size_of::<Position>();
+        usage
+    }
+}
+
+impl<W: Weights> AssignmentSolver<W> for Solver {
+    fn solve(&mut self, weights: &mut W) -> Result<Assignment, Error> {
+        Solver::solve(self, weights).map(|matching| Assignment::from(matching.to_vec()))
+    }
+}
+
+#[test]
+fn test_solver() {
+    use crate::{generators, solve_assignment, WeightMatrix};
+
+    let mut solver = Solver::with_capacity(10);
+    assert_eq!(10, solver.capacity());
+    let usage = solver.memory_usage();
+    for seed in 0..5 {
+        let weights = generators::uniform(10, 0..100, seed);
+        let expected = solve_assignment(&mut weights.clone()).unwrap();
+        assert_eq!(&expected[..], solver.solve(&mut weights.clone()).unwrap());
+    }
+    // the buffers are reused, not grown.
+    assert_eq!(usage.marks, solver.memory_usage().marks);
+    assert_eq!(usage.coverage, solver.memory_usage().coverage);
+
+    let weights = generators::uniform(4, 0..100, 1);
+    let expected = solve_assignment(&mut weights.clone()).unwrap();
+    assert_eq!(
+        &expected[..],
+        Solver::new().solve(&mut weights.clone()).unwrap()
+    );
+    assert_eq!(&expected[..], solver.solve(&mut weights.clone()).unwrap());
+    assert_eq!(4, solver.capacity());
+
+    let inf = f64::INFINITY;
+    let mut weights = WeightMatrix::from_row_vec(2, vec![1.0, inf, 2.0, inf]);
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        Solver::new().solve(&mut weights)
+    );
+    assert_eq!(
+        Ok(&[][..]),
+        Solver::new().solve(&mut WeightMatrix::<u32>::from_row_vec(0, vec![]))
+    );
+}
@@ -0,0 +1,239 @@
+//! Building sparse instances incrementally from `(row, column, cost)`
+//! triples.
+//!
+//! `TripletBuilder` stores only the given cells, one list per row, so that
+//! instances far too large for a dense matrix can be read from a stream and
+//! solved. Cells not given are forbidden. Integral costs are solved by cost
+//! scaling, others by min-cost flow; both only visit the given cells.
+//!
+//! With the `sprs` feature, `solve_csmat` solves a `sprs::CsMat` in the same
+//! way, with the entries not stored forbidden, and `permutation_matrix`
+//! returns a matching as a sparse matrix.
+use crate::flow::FlowNetwork;
+use crate::graph::Graph;
+use crate::{cost_scaling, Error, Position};
+use alloc::vec::Vec;
+
+/// Collects the cells of a sparse `n x n` instance.
+#[derive(Debug, Clone, Default)]
+pub struct TripletBuilder {
+    rows: Vec<Vec<(usize, f64)>>,
+    columns: usize,
+    len: usize,
+}
+
+impl TripletBuilder {
+    pub fn new() -> TripletBuilder {
+        TripletBuilder::default()
+    }
+
+    /// Adds a cell. Non-finite costs forbid the cell and are skipped. If a
+    /// cell is given more than once, its smallest cost is used.
+    pub fn push(&mut self, row: usize, column: usize, cost: f64) {
+        if !cost.is_finite() {
+            return;
+        }
+        if row >= self.rows.len() {
+            self.rows.resize_with(row + 1, Vec::new);
+        }
+        self.rows[row].push((column, cost));
+        self.columns = self.columns.max(column + 1);
+        self.len += 1;
+    }
+
+    /// The size of the instance, one more than the largest row or column
+    /// given.
+    pub fn n(&self) -> usize {
+        self.rows.len().max(self.columns)
+    }
+
+    /// The number of cells pushed, including duplicates.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The cost of a cell, `None` if it wasn't given.
+    pub fn cost(&self, pos: Position) -> Option<f64> {
+        self.rows
+            .get(pos.row)?
+            .iter()
+            .filter(|&&(column, _)| column == pos.column)
+            .map(|&(_, cost)| cost)
+            .min_by(f64::total_cmp)
+    }
+
+    /// Finds the assignment with minimum total cost using only the given
+    /// cells. Returns one position per row, sorted by row.
+    pub fn solve(&mut self) -> Result<Vec<Position>, Error> {
+        let n = self.n();
+        let rows = &mut self.rows;
+        rows.resize_with(n, Vec::new);
+        for row in rows.iter_mut() {
+            row.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+            row.dedup_by_key(|&mut (column, _)| column);
+        }
+        solve_rows(n, rows)
+    }
+}
+
+/// Solves the `n x n` instance whose cells are given as `(column, cost)`
+/// lists per row, sorted by column and without duplicates.
+pub(crate) fn solve_rows(n: usize, rows: &[Vec<(usize, f64)>]) -> Result<Vec<Position>, Error> {
+    // cost scaling multiplies the costs by about `n^2`.
+    let limit = (1u64 << 62) as f64 / ((n + 1) as f64 * (n + 1) as f64);
+    let integral = rows
+        .iter()
+        .flatten()
+        .all(|&(_, c)| c.abs() <= limit && c as i64 as f64 == c);
+    if integral {
+        let graph = Graph::from_rows(
+            n,
+            rows.iter()
+                .map(|row| row.iter().map(|&(column, cost)| (column, cost as i64))),
+        );
+        return cost_scaling::solve_graph(&graph);
+    }
+
+    let graph = Graph::from_rows(n, rows.iter().map(|row| row.iter().copied()));
+    if !graph.has_perfect_matching() {
+        return Err(Error::MatrixNotSolvable);
+    }
+    // nodes: source, sink, rows, columns.
+    let (source, sink) = (0, 1);
+    let mut network = FlowNetwork::new(2 + 2 * n, 1e-9);
+    let mut edges = Vec::with_capacity(graph.costs().len());
+    for row in 0..n {
+        network.add_edge(source, 2 + row, 1.0, 0.0);
+        network.add_edge(2 + n + row, sink, 1.0, 0.0);
+        for (column, cost) in graph.edges(row) {
+            let edge = network.add_edge(2 + row, 2 + n + column, 1.0, cost);
+            edges.push((Position { row, column }, edge));
+        }
+    }
+    network.min_cost_flow(source, sink, n as f64);
+    Ok(edges
+        .into_iter()
+        .filter(|&(_, edge)| network.flow(edge) > 0.5)
+        .map(|(pos, _)| pos)
+        .collect())
+}
+
+/// Solves the square sparse matrix `costs`, in CSR or CSC storage, using
+/// only its stored entries, like `TripletBuilder::solve`. Stored entries
+/// with invalid values are forbidden as well.
+///
+/// # Panics
+///
+/// If `costs` isn't square, or a valid value can't be converted to `f64`.
+#[cfg(feature = "sprs")]
+pub fn solve_csmat<T>(costs: &sprs::CsMat<T>) -> Result<Vec<Position>, Error>
+where
+    T: crate::WeightNum,
+{
+    let n = costs.rows();
+    assert!(costs.cols() == n, "expected a square matrix");
+    let mut rows = vec![Vec::new(); n];
+    for (value, (row, column)) in costs.iter() {
+        if value.is_valid() {
+            let cost = value
+                .to_f64()
+                .expect("sparse matrices require weights convertible to f64");
+            rows[row].push((column, cost));
+        }
+    }
+    for row in &mut rows {
+        row.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+        row.dedup_by_key(|&mut (column, _)| column);
+    }
+    solve_rows(n, &rows)
+}
+
+/// The `n x n` permutation matrix of `matching` in CSR storage, with a 1 at
+/// each assigned cell.
+///
+/// # Panics
+///
+/// If `matching` doesn't assign each row of `0..n` once.
+#[cfg(feature = "sprs")]
+pub fn permutation_matrix(n: usize, matching: &[Position]) -> sprs::CsMat<f64> {
+    assert!(matching.len() == n);
+    let mut columns = vec![usize::MAX; n];
+    for pos in matching {
+        assert!(columns[pos.row] == usize::MAX, "a row is assigned twice");
+        columns[pos.row] = pos.column;
+    }
+    sprs::CsMat::new((n, n), (0..=n).collect(), columns, vec![1.0; n])
+}
+
+#[test]
+fn test_triplet_builder() {
+    use crate::{solve_assignment, WeightMatrix};
+
+    let c = vec![250, 400, 350, 400, 600, 350, 200, 400, 250];
+    let expected = solve_assignment(&mut WeightMatrix::from_row_vec(3, c.clone()));
+    for &offset in &[0.0, 0.5] {
+        let mut builder = TripletBuilder::new();
+        for (i, &cost) in c.iter().enumerate().rev() {
+            builder.push(i / 3, i % 3, cost as f64 + offset);
+        }
+        // duplicates keep the smallest cost, non-finite costs are skipped.
+        builder.push(0, 1, 1000.0);
+        builder.push(2, 2, f64::INFINITY);
+        assert_eq!((3, 10), (builder.n(), builder.len()));
+        assert_eq!(
+            Some(400.0 + offset),
+            builder.cost(Position { row: 0, column: 1 })
+        );
+        assert_eq!(expected, builder.solve());
+    }
+
+    let mut builder = TripletBuilder::new();
+    builder.push(0, 0, 1.0);
+    builder.push(1, 0, 1.0);
+    assert_eq!(Err(Error::MatrixNotSolvable), builder.solve());
+    builder.push(0, 1, 1.5);
+    builder.push(1, 1, 2.0);
+    assert_eq!(
+        Ok(vec![
+            Position { row: 0, column: 1 },
+            Position { row: 1, column: 0 }
+        ]),
+        builder.solve()
+    );
+    assert_eq!(Ok(vec![]), TripletBuilder::new().solve());
+}
+
+#[cfg(feature = "sprs")]
+#[test]
+fn test_solve_csmat() {
+    use sprs::{CsMat, TriMat};
+
+    let pos = |row, column| Position { row, column };
+    let mut triplets = TriMat::new((3, 3));
+    for &(row, column, cost) in &[(0, 0, 4.0), (0, 1, 1.0), (1, 0, 2.0), (2, 2, 0.5)] {
+        triplets.add_triplet(row, column, cost);
+    }
+    triplets.add_triplet(1, 1, f64::INFINITY);
+    let csr: CsMat<f64> = triplets.to_csr();
+    let expected = vec![pos(0, 1), pos(1, 0), pos(2, 2)];
+    assert_eq!(Ok(expected.clone()), solve_csmat(&csr));
+    assert_eq!(Ok(expected.clone()), solve_csmat(&triplets.to_csc()));
+
+    let permutation = permutation_matrix(3, &expected);
+    assert_eq!(3, permutation.nnz());
+    assert_eq!(Some(&1.0), permutation.get(1, 0));
+    assert_eq!(None, permutation.get(1, 1));
+
+    // row 2 has no entries.
+    let mut triplets = TriMat::new((3, 3));
+    triplets.add_triplet(0, 0, 1);
+    triplets.add_triplet(1, 1, 1);
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_csmat(&triplets.to_csr::<usize>())
+    );
+}
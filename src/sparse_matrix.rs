@@ -0,0 +1,228 @@
+//! A sparse weight matrix, storing only the feasible cells.
+//!
+//! `SparseWeightMatrix` keeps its entries in compressed sparse row form,
+//! with an index of the entries of each column, and takes memory linear in
+//! their number instead of `n^2`. Cells without an entry are forbidden. The
+//! row and column operations of `Weights` only visit the entries of the row
+//! or column. The steps of the algorithm still scan the cells of uncovered
+//! rows and columns, each a binary search within its row, so for huge
+//! instances with few entries per row, `TripletBuilder::solve` is faster.
+use crate::{Position, WeightNum, Weights};
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+use core::mem;
+
+#[derive(Debug, Clone)]
+pub struct SparseWeightMatrix<T> {
+    n: usize,
+    /// The entries of `row` are at `row_starts[row]..row_starts[row + 1]`
+    /// of `columns` and `values`, sorted by column.
+    row_starts: Vec<usize>,
+    columns: Vec<usize>,
+    values: Vec<T>,
+    /// The indices of the entries of `column` are at
+    /// `column_starts[column]..column_starts[column + 1]` of
+    /// `column_entries`.
+    column_starts: Vec<usize>,
+    column_entries: Vec<usize>,
+    /// Returned by `element_at` for cells without an entry.
+    forbidden: T,
+}
+
+impl<T: WeightNum> SparseWeightMatrix<T> {
+    /// An `n x n` matrix of the `(row, column, value)` entries, with
+    /// `element_at` returning `forbidden` for the other cells, e.g.
+    /// `f64::INFINITY`. Invalid values are skipped. If a cell is given more
+    /// than once, its smallest value is used.
+    ///
+    /// # Panics
+    ///
+    /// If a row or column is out of range.
+    pub fn from_triplets<I>(n: usize, triplets: I, forbidden: T) -> SparseWeightMatrix<T>
+    where
+        I: IntoIterator<Item = (usize, usize, T)>,
+    {
+        let mut entries: Vec<(usize, usize, T)> = triplets
+            .into_iter()
+            .filter(|entry| entry.2.is_valid())
+            .collect();
+        for &(row, column, _) in &entries {
+            assert!(row < n && column < n, "cell out of range");
+        }
+        entries.sort_by(|a, b| {
+            (a.0, a.1)
+                .cmp(&(b.0, b.1))
+                .then(a.2.partial_cmp(&b.2).unwrap_or(Ordering::Equal))
+        });
+        entries.dedup_by_key(|entry| (entry.0, entry.1));
+
+        let mut row_starts = vec![0; n + 1];
+        let mut column_starts = vec![0; n + 1];
+        for &(row, column, _) in &entries {
+            row_starts[row + 1] += 1;
+            column_starts[column + 1] += 1;
+        }
+        for i in 0..n {
+            row_starts[i + 1] += row_starts[i];
+            column_starts[i + 1] += column_starts[i];
+        }
+        let mut next = column_starts.clone();
+        let mut column_entries = vec![0; entries.len()];
+        for (index, &(_, column, _)) in entries.iter().enumerate() {
+            column_entries[next[column]] = index;
+            next[column] += 1;
+        }
+        SparseWeightMatrix {
+            n,
+            row_starts,
+            columns: entries.iter().map(|entry| entry.1).collect(),
+            values: entries.iter().map(|entry| entry.2).collect(),
+            column_starts,
+            column_entries,
+            forbidden,
+        }
+    }
+
+    /// The number of entries.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The entries of `row` as `(column, value)` pairs, sorted by column.
+    pub fn row(&self, row: usize) -> impl Iterator<Item = (usize, T)> + '_ {
+        let range = self.row_starts[row]..self.row_starts[row + 1];
+        self.columns[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.values[range].iter().copied())
+    }
+
+    /// The bytes of the entries and the indices.
+    pub fn memory_usage(&self) -> usize {
+        mem::size_of_val(self.values.as_slice())
+            + mem::size_of::<usize>()
+                * (self.row_starts.len()
+                    + self.columns.len()
+                    + self.column_starts.len()
+                    + self.column_entries.len())
+    }
+
+    fn index(&self, pos: Position) -> Option<usize> {
+        let start = self.row_starts[pos.row];
+        let end = self.row_starts[pos.row + 1];
+        let offset = self.columns[start..end].binary_search(&pos.column).ok()?;
+        Some(start + offset)
+    }
+}
+
+impl<T: WeightNum> Weights for SparseWeightMatrix<T> {
+    type T = T;
+
+    #[inline(always)]
+    fn n(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    fn element_at(&self, pos: Position) -> T {
+        self.index(pos)
+            .map_or(self.forbidden, |index| self.values[index])
+    }
+
+    #[inline]
+    fn is_element_zero(&self, pos: Position) -> bool {
+        self.index(pos)
+            .is_some_and(|index| self.values[index].is_valid() && self.values[index].is_zero())
+    }
+
+    #[inline]
+    fn is_valid_at(&self, pos: Position) -> bool {
+        self.index(pos)
+            .is_some_and(|index| self.values[index].is_valid())
+    }
+
+    fn sub_min_of_each_row(&mut self) {
+        for row in 0..self.n {
+            let values = &mut self.values[self.row_starts[row]..self.row_starts[row + 1]];
+            let min = values
+                .iter()
+                .copied()
+                .filter(WeightNum::is_valid)
+                .reduce(|min, val| if val < min { val } else { min });
+            if let Some(min) = min {
+                for value in values {
+                    *value = value.sub_if_valid(min);
+                }
+            }
+        }
+    }
+
+    fn add_row(&mut self, row: usize, val: T) {
+        for value in &mut self.values[self.row_starts[row]..self.row_starts[row + 1]] {
+            *value = value.add_if_valid(val);
+        }
+    }
+
+    fn sub_column(&mut self, column: usize, val: T) {
+        let entries =
+            &self.column_entries[self.column_starts[column]..self.column_starts[column + 1]];
+        for &index in entries {
+            self.values[index] = self.values[index].sub_if_valid(val);
+        }
+    }
+
+    fn is_solvable(&self) -> bool {
+        (0..self.n).all(|row| {
+            self.values[self.row_starts[row]..self.row_starts[row + 1]]
+                .iter()
+                .any(WeightNum::is_valid)
+        })
+    }
+}
+
+#[test]
+fn test_sparse_weight_matrix() {
+    use crate::generators::Rng;
+    use crate::{solve_assignment, Error, WeightMatrix};
+
+    let n = 30;
+    let mut rng = Rng::new(7);
+    let mut triplets = Vec::new();
+    for row in 0..n {
+        // the diagonal keeps the instance solvable.
+        triplets.push((row, row, 1000));
+        for _ in 0..3 {
+            let column = rng.next_in(0..n as u32) as usize;
+            triplets.push((row, column, i64::from(rng.next_in(0..100))));
+        }
+    }
+    let sparse = SparseWeightMatrix::from_triplets(n, triplets.iter().copied(), i64::MAX);
+    assert!(sparse.len() <= 4 * n);
+    let mut options = vec![None; n * n];
+    for &(row, column, value) in &triplets {
+        let cell: &mut Option<i64> = &mut options[row * n + column];
+        *cell = Some(cell.map_or(value, |old| old.min(value)));
+    }
+    let dense = WeightMatrix::from_row_options(n, options);
+    assert_eq!(
+        solve_assignment(&mut dense.clone()),
+        solve_assignment(&mut sparse.clone())
+    );
+    let pos = |row, column| Position { row, column };
+    assert_eq!(dense.element_at(pos(3, 3)), sparse.element_at(pos(3, 3)));
+
+    // duplicates keep the smallest value, invalid values are skipped.
+    let inf = f64::INFINITY;
+    let mut sparse = SparseWeightMatrix::from_triplets(
+        2,
+        vec![(0, 1, 3.0), (0, 1, 2.0), (1, 0, inf), (1, 1, 5.0)],
+        inf,
+    );
+    assert_eq!(vec![(1, 2.0)], sparse.row(0).collect::<Vec<_>>());
+    assert!(!sparse.is_valid_at(pos(1, 0)));
+    assert_eq!(Err(Error::MatrixNotSolvable), solve_assignment(&mut sparse));
+}
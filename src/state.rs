@@ -0,0 +1,308 @@
+//! Forkable solver state for speculative searches.
+//!
+//! Murty's k-best partitioning and branch-and-bound solve many variants of
+//! one instance, each with some cells forbidden or pinned. Forbidding a cell
+//! keeps the reduced matrix dual feasible and all stars but the one in that
+//! cell, so a variant can continue from the state of a finished solve
+//! instead of starting over. `SolverState` owns the reduced weights and the
+//! marks; cloning it forks the solve.
+//!
+//! Online problems change one row at a time, e.g. a new job replacing a
+//! finished one. `update_row` replaces the costs of a row, keeping the
+//! column potentials, and lowers those that exceed a new cost, which keeps
+//! the reduced matrix non-negative. Only the stars in the changed row and in
+//! lowered columns are lost, so the next `solve` needs just a few
+//! augmentations instead of a full solve.
+use crate::memory::MemoryUsage;
+use crate::restricted::Restricted;
+use crate::steps::{step1, step2, step4};
+use crate::workspace::Workspace;
+use crate::{collect_stars, run_marked_with, Error, Position, WeightNum, Weights};
+use alloc::{vec, vec::Vec};
+
+#[derive(Debug, Clone)]
+pub struct SolverState<T> {
+    weights: Restricted<T>,
+    /// The total subtracted from each column, `None` for none.
+    column_duals: Vec<Option<T>>,
+    workspace: Workspace,
+}
+
+/// Forwards to the reduced weights, recording what is subtracted from each
+/// column.
+struct Tracked<'a, T> {
+    weights: &'a mut Restricted<T>,
+    column_duals: &'a mut [Option<T>],
+}
+
+impl<T: WeightNum> Weights for Tracked<'_, T> {
+    type T = T;
+
+    #[inline(always)]
+    fn n(&self) -> usize {
+        self.weights.n()
+    }
+
+    #[inline]
+    fn element_at(&self, pos: Position) -> T {
+        self.weights.element_at(pos)
+    }
+
+    #[inline]
+    fn is_element_zero(&self, pos: Position) -> bool {
+        self.weights.is_element_zero(pos)
+    }
+
+    #[inline]
+    fn is_valid_at(&self, pos: Position) -> bool {
+        self.weights.is_valid_at(pos)
+    }
+
+    fn sub_min_of_each_row(&mut self) {
+        self.weights.sub_min_of_each_row()
+    }
+
+    fn add_row(&mut self, row: usize, val: T) {
+        self.weights.add_row(row, val)
+    }
+
+    fn sub_column(&mut self, column: usize, val: T) {
+        self.weights.sub_column(column, val);
+        let dual = &mut self.column_duals[column];
+        *dual = Some(dual.map_or(val, |dual| dual + val));
+    }
+
+    fn is_solvable(&self) -> bool {
+        self.weights.is_solvable()
+    }
+}
+
+impl<T: WeightNum> SolverState<T> {
+    /// Copies `weights` and reduces it, ready to `solve`.
+    pub fn new<W>(weights: &W) -> SolverState<T>
+    where
+        W: Weights<T = T>,
+    {
+        let mut weights = Restricted::from_weights(weights);
+        let mut workspace = Workspace::new(weights.n());
+        step1(&mut weights);
+        step2(&weights, &mut workspace.marks, &mut workspace.coverage);
+        SolverState {
+            column_duals: vec![None; weights.n()],
+            weights,
+            workspace,
+        }
+    }
+
+    pub fn n(&self) -> usize {
+        self.weights.n()
+    }
+
+    /// The memory of the copy of the weights and of the workspace.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            weights: self.weights.memory_usage(),
+            ..self.workspace.memory_usage()
+        }
+    }
+
+    /// Finds the optimal matching of the allowed cells, continuing from the
+    /// current state.
+    pub fn solve(&mut self) -> Result<Vec<Position>, Error> {
+        if !self.weights.is_solvable() {
+            return Err(Error::MatrixNotSolvable);
+        }
+        self.workspace.marks.clear_primes();
+        self.workspace.coverage.clear();
+        let mut tracked = Tracked {
+            weights: &mut self.weights,
+            column_duals: &mut self.column_duals,
+        };
+        run_marked_with(&mut tracked, &mut self.workspace, step4)?;
+        Ok(collect_stars(self.n(), &self.workspace))
+    }
+
+    /// Replaces the costs of `row` for the following solves. Forbidden and
+    /// pinned cells of the row stay so.
+    ///
+    /// # Panics
+    ///
+    /// If `row` is out of range or `costs` doesn't have `n` elements.
+    pub fn update_row(&mut self, row: usize, costs: &[T]) {
+        let n = self.n();
+        assert!(row < n && costs.len() == n);
+        let weights = &mut self.weights;
+        let column_duals = &mut self.column_duals;
+        let marks = &mut self.workspace.marks;
+        for (column, &cost) in costs.iter().enumerate() {
+            match column_duals[column] {
+                Some(dual) if cost.is_valid() && cost < dual => {
+                    // lowering the potential of the column to the new cost
+                    // raises the reduced costs of the other rows.
+                    weights.add_column(column, dual - cost);
+                    column_duals[column] = Some(cost);
+                    if let Some(star) = marks.find_first_star_in_column(column) {
+                        let pos = Position { row: star, column };
+                        if !weights.is_element_zero(pos) {
+                            marks.toggle_star(pos);
+                        }
+                    }
+                }
+                _ => {}
+            }
+            let value = match column_duals[column] {
+                Some(dual) if cost.is_valid() => cost - dual,
+                _ => cost,
+            };
+            weights.set(Position { row, column }, value);
+        }
+
+        let positions = (0..n).map(|column| Position { row, column });
+        let min = positions
+            .clone()
+            .filter(|&pos| weights.is_valid_at(pos))
+            .map(|pos| weights.element_at(pos))
+            .reduce(|min, value| if value < min { value } else { min });
+        if let Some(min) = min {
+            for pos in positions.clone() {
+                if weights.is_valid_at(pos) {
+                    weights.set(pos, weights.element_at(pos) - min);
+                }
+            }
+        }
+
+        if let Some(column) = marks.find_first_star_in_row(row) {
+            let pos = Position { row, column };
+            if !weights.is_element_zero(pos) {
+                marks.toggle_star(pos);
+            }
+        }
+        if marks.find_first_star_in_row(row).is_none() {
+            let zero = positions.clone().find(|&pos| {
+                weights.is_element_zero(pos)
+                    && marks.find_first_star_in_column(pos.column).is_none()
+            });
+            if let Some(pos) = zero {
+                marks.star(pos);
+            }
+        }
+    }
+
+    /// Forbids the cell `pos` for the following solves.
+    pub fn forbid(&mut self, pos: Position) {
+        self.weights.forbid(pos);
+        if self.workspace.marks.is_star(pos) {
+            self.workspace.marks.toggle_star(pos);
+        }
+    }
+
+    /// Forces the following solves to assign `pos`, by forbidding all other
+    /// cells of its row and column.
+    pub fn pin(&mut self, pos: Position) {
+        self.weights.pin(pos);
+        let marks = &mut self.workspace.marks;
+        if let Some(column) = marks.find_first_star_in_row(pos.row) {
+            if column != pos.column {
+                marks.toggle_star(Position {
+                    row: pos.row,
+                    column,
+                });
+            }
+        }
+        if let Some(row) = marks.find_first_star_in_column(pos.column) {
+            if row != pos.row {
+                marks.toggle_star(Position {
+                    row,
+                    column: pos.column,
+                });
+            }
+        }
+    }
+}
+
+#[test]
+fn test_solver_state() {
+    use crate::{generators, solve_assignment, WeightMatrix};
+
+    let cost =
+        |w: &WeightMatrix<f64>, m: &[Position]| m.iter().map(|&pos| w.element_at(pos)).sum::<f64>();
+    for seed in 0..10 {
+        let base = generators::uniform(8, 0..100, seed);
+        let weights = WeightMatrix::from_fn(8, |(row, column)| {
+            base.element_at(Position { row, column }) as f64
+        });
+        let mut state = SolverState::new(&weights);
+        let matching = state.solve().unwrap();
+        assert_eq!(solve_assignment(&mut weights.clone()), Ok(matching.clone()));
+
+        // fork, forbidding a cell of the optimum.
+        let mut forbidden = state.clone();
+        forbidden.forbid(matching[0]);
+        let expected = solve_assignment(&mut WeightMatrix::from_fn(8, |(row, column)| {
+            let pos = Position { row, column };
+            if pos == matching[0] {
+                f64::INFINITY
+            } else {
+                weights.element_at(pos)
+            }
+        }))
+        .unwrap();
+        let found = forbidden.solve().unwrap();
+        assert!(!found.contains(&matching[0]));
+        assert_eq!(cost(&weights, &expected), cost(&weights, &found));
+
+        // fork again, pinning a cell outside of the optimum.
+        let pinned_cell = Position {
+            row: 0,
+            column: (matching[0].column + 1) % 8,
+        };
+        let mut pinned = state.clone();
+        pinned.pin(pinned_cell);
+        assert!(pinned.solve().unwrap().contains(&pinned_cell));
+
+        // the original state is unchanged.
+        assert_eq!(Ok(matching), state.solve());
+    }
+
+    let mut state = SolverState::new(&WeightMatrix::from_row_vec(1, vec![1.0]));
+    state.forbid(Position { row: 0, column: 0 });
+    assert_eq!(Err(Error::MatrixNotSolvable), state.solve());
+}
+
+#[test]
+fn test_solver_state_update_row() {
+    use crate::generators::{self, Rng};
+    use crate::{solve_assignment, WeightMatrix};
+
+    let mut rng = Rng::new(11);
+    for seed in 0..5 {
+        let mut weights = generators::uniform(10, 0..100, seed);
+        let mut state = SolverState::new(&weights);
+        state.solve().unwrap();
+        for _ in 0..10 {
+            let row = rng.next_in(0..10) as usize;
+            let costs: Vec<u32> = (0..10).map(|_| rng.next_in(0..100)).collect();
+            state.update_row(row, &costs);
+            weights = WeightMatrix::from_fn(10, |(r, column)| {
+                if r == row {
+                    costs[column]
+                } else {
+                    weights.element_at(Position { row: r, column })
+                }
+            });
+            let cost = |m: &[Position]| m.iter().map(|&pos| weights.element_at(pos)).sum::<u32>();
+            let expected = solve_assignment(&mut weights.clone()).unwrap();
+            assert_eq!(cost(&expected), cost(&state.solve().unwrap()));
+        }
+    }
+
+    let inf = f64::INFINITY;
+    let weights = WeightMatrix::from_row_vec(2, vec![1.0, 2.0, 1.0, 3.0]);
+    let mut state = SolverState::new(&weights);
+    let pos = |row, column| Position { row, column };
+    assert_eq!(Ok(vec![pos(0, 1), pos(1, 0)]), state.solve());
+    state.update_row(0, &[inf, 5.0]);
+    assert_eq!(Ok(vec![pos(0, 1), pos(1, 0)]), state.solve());
+    state.update_row(1, &[inf, 0.5]);
+    assert_eq!(Err(Error::MatrixNotSolvable), state.solve());
+}
@@ -0,0 +1,399 @@
+//! The individual steps of the Kuhn-Munkres algorithm, as driven by
+//! `solve_assignment`.
+use crate::coverage::Coverage;
+use crate::{Error, MarkMatrix, Position, Weights};
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::mem;
+#[cfg(feature = "smallvec")]
+use smallvec::SmallVec;
+
+/// For each row of the matrix, find the smallest element and
+/// subtract it from every element in its row. Go to Step 2.
+pub fn step1<W>(c: &mut W)
+where
+    W: Weights,
+{
+    c.sub_min_of_each_row();
+}
+
+/// Find a zero (Z) in the resulting matrix. If there is no starred
+/// zero in its row or column, star Z. Repeat for each element in the
+/// matrix. Go to Step 3.
+pub fn step2<W>(c: &W, marks: &mut MarkMatrix, cov: &mut Coverage)
+where
+    W: Weights,
+{
+    let n = c.n();
+
+    assert!(marks.n() == n);
+    assert!(cov.n() == n);
+    debug_assert!(cov.all_uncovered());
+
+    // safe, as the coverage only yields cells of the `n x n` matrix.
+    cov.iter_uncovered_row_column_and_cover(|pos| {
+        if unsafe { c.is_element_zero_unchecked(pos) } {
+            marks.star(pos);
+            true
+        } else {
+            false
+        }
+    });
+
+    // clear covers
+    cov.clear();
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Step3 {
+    Done,
+    ContinueWithStep4 { star_count: usize },
+}
+
+/// Cover each column containing a starred zero. If K columns are
+/// covered, the starred zeros describe a complete set of unique
+/// assignments. In this case, Go to DONE, otherwise, Go to Step 4.
+pub fn step3<W>(c: &W, marks: &MarkMatrix, cov: &mut Coverage) -> Step3
+where
+    W: Weights,
+{
+    let n = c.n();
+
+    assert!(marks.n() == n);
+    assert!(cov.n() == n);
+
+    cov.cover_columns(marks.starred_columns());
+    let star_count = marks.star_count();
+
+    if star_count >= n {
+        assert!(star_count == n);
+        Step3::Done
+    } else {
+        Step3::ContinueWithStep4 { star_count }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Step4 {
+    ContinueWithStep5 { z0_pos: Position },
+    ContinueWithStep6,
+}
+
+/// Find a noncovered zero and prime it. If there is no starred zero
+/// in the row containing this primed zero, Go to Step 5. Otherwise,
+/// cover this row and uncover the column containing the starred
+/// zero. Continue in this manner until there are no uncovered zeros
+/// left. Save the smallest uncovered value and Go to Step 6.
+pub fn step4<W>(c: &W, marks: &mut MarkMatrix, cov: &mut Coverage) -> Step4
+where
+    W: Weights,
+{
+    // safe, as `step4_by` asserts that the coverage is of size `n`.
+    step4_by(c, marks, cov, |cov| {
+        cov.find_uncovered_cell_column_row_order(|pos| unsafe { c.is_element_zero_unchecked(pos) })
+    })
+}
+
+/// Like `step4`, but searches for uncovered zeros in parallel. Finds the
+/// same zeros as `step4`.
+#[cfg(feature = "rayon")]
+pub fn par_step4<W>(c: &W, marks: &mut MarkMatrix, cov: &mut Coverage) -> Step4
+where
+    W: Weights + Sync,
+{
+    step4_by(c, marks, cov, |cov| {
+        cov.par_find_uncovered_cell_column_row_order(|pos| unsafe {
+            c.is_element_zero_unchecked(pos)
+        })
+    })
+}
+
+/// The order in which step 4 searches for uncovered zeros. All orders find
+/// an optimal matching, but may pick different ones among several optima.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum ScanOrder {
+    /// Column by column, as in the original description of the algorithm.
+    #[default]
+    ColumnMajor,
+    /// Row by row, following the row-major storage of `WeightMatrix`.
+    RowMajor,
+    /// Row by row, resuming at the row of the previously found zero.
+    RoundRobin,
+}
+
+/// Like `step4`, but searches for uncovered zeros in row-major order.
+pub fn step4_row_major<W>(c: &W, marks: &mut MarkMatrix, cov: &mut Coverage) -> Step4
+where
+    W: Weights,
+{
+    let mut row = 0;
+    step4_round_robin(c, marks, cov, &mut row)
+}
+
+/// Like `step4`, but searches for uncovered zeros in row-major order,
+/// starting at row `start` and wrapping around. `start` is set to the row
+/// of the last zero found.
+pub fn step4_round_robin<W>(
+    c: &W,
+    marks: &mut MarkMatrix,
+    cov: &mut Coverage,
+    start: &mut usize,
+) -> Step4
+where
+    W: Weights,
+{
+    let row = Cell::new(*start);
+    // safe, as `step4_by` asserts that the coverage is of size `n`.
+    let step = step4_by(c, marks, cov, |cov| {
+        let found = cov.find_uncovered_cell_row_column_order_from(row.get(), |pos| unsafe {
+            c.is_element_zero_unchecked(pos)
+        });
+        if let Some(pos) = found {
+            row.set(pos.row);
+        }
+        found
+    });
+    *start = row.get();
+    step
+}
+
+fn step4_by<W, F>(c: &W, marks: &mut MarkMatrix, cov: &mut Coverage, find: F) -> Step4
+where
+    W: Weights,
+    F: Fn(&Coverage) -> Option<Position>,
+{
+    let n = c.n();
+
+    assert!(marks.n() == n);
+    assert!(cov.n() == n);
+
+    loop {
+        // find uncovered zero element
+        match find(cov) {
+            Some(pos) => {
+                marks.prime(pos);
+                match marks.find_first_star_in_row(pos.row) {
+                    Some(star_col) => {
+                        cov.cover_row(pos.row);
+                        cov.uncover_column(star_col);
+                    }
+                    None => {
+                        // in Python: self.Z0_r, self.Z0_c
+                        return Step4::ContinueWithStep5 { z0_pos: pos };
+                    }
+                }
+            }
+            None => {
+                return Step4::ContinueWithStep6;
+            }
+        }
+    }
+}
+
+/// The alternating path of step 5, stored inline for small instances with
+/// the `smallvec` feature.
+#[cfg(feature = "smallvec")]
+pub type Path = SmallVec<[Position; 16]>;
+#[cfg(not(feature = "smallvec"))]
+pub type Path = Vec<Position>;
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Step5 {
+    ContinueWithStep3,
+}
+
+/// Construct a series of alternating primed and starred zeros as
+/// follows. Let Z0 represent the uncovered primed zero found in Step 4.
+/// Let Z1 denote the starred zero in the column of Z0 (if any).
+/// Let Z2 denote the primed zero in the row of Z1 (there will always
+/// be one). Continue until the series terminates at a primed zero
+/// that has no starred zero in its column. Unstar each starred zero
+/// of the series, star each primed zero of the series, erase all
+/// primes and uncover every line in the matrix. Return to Step 3
+pub fn step5(
+    marks: &mut MarkMatrix,
+    cov: &mut Coverage,
+    z0_pos: Position,
+    path: &mut Path,
+) -> Result<Step5, Error> {
+    let n = cov.n();
+
+    assert!(marks.n() == n);
+
+    path.clear();
+    path.push(z0_pos);
+
+    let mut prev_col = z0_pos.column;
+
+    while let Some(row) = marks.find_first_star_in_column(prev_col) {
+        path.push(Position {
+            row,
+            column: prev_col,
+        });
+
+        if let Some(column) = marks.find_first_prime_in_row(row) {
+            path.push(Position { row, column });
+            prev_col = column;
+        } else {
+            // XXX: Can this really happen?
+            return Err(Error::NoPrimeInRow);
+        }
+    }
+
+    // convert_path, unstarring first so that no row or column ever has two
+    // stars.
+    for &pos in path.iter().skip(1).step_by(2) {
+        marks.toggle_star(pos);
+    }
+    for &pos in path.iter().step_by(2) {
+        marks.toggle_star(pos);
+    }
+
+    cov.clear();
+    marks.clear_primes();
+    Ok(Step5::ContinueWithStep3)
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Step6 {
+    ContinueWithStep4,
+}
+
+/// Add the value found in Step 4 to every element of each covered
+/// row, and subtract it from every element of each uncovered column.
+/// Return to Step 4 without altering any stars, primes, or covered
+/// lines.
+pub fn step6<W>(c: &mut W, cov: &Coverage) -> Result<Step6, Error>
+where
+    W: Weights,
+{
+    let n = c.n();
+    assert!(cov.n() == n);
+
+    // Find the smallest, valid uncovered value in the matrix
+    let mut min = None;
+    // safe, as the coverage is of size `n`.
+    cov.iter_uncovered_row_column_order(|pos| unsafe {
+        if c.is_valid_at_unchecked(pos) {
+            let elm = c.element_at_unchecked(pos);
+            min = Some(match min {
+                Some(m) if m < elm => m,
+                _ => elm,
+            });
+        }
+    });
+    shift_uncovered(c, cov, min)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ColumnMin {
+    Unknown,
+    /// No valid cell in an uncovered row.
+    Empty,
+    Row(usize),
+}
+
+/// The row of the smallest valid value of each column among the uncovered
+/// rows, kept between the calls of `step6_with`.
+///
+/// Between two augmentations, rows are only ever covered, and step 6
+/// shifts the uncovered rows of a column all by the same value, which
+/// keeps their order. So the minimum of a column stays where it is until
+/// its row is covered, and only those columns are rescanned, instead of
+/// all uncovered cells. Must be cleared whenever rows are uncovered, i.e.
+/// after step 5.
+#[derive(Debug, Clone, Default)]
+pub struct ColumnMinima {
+    rows: Vec<ColumnMin>,
+}
+
+impl ColumnMinima {
+    pub fn new() -> ColumnMinima {
+        ColumnMinima::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.rows.clear();
+    }
+
+    pub(crate) fn memory_usage(&self) -> usize {
+        self.rows.capacity() * mem::size_of::<ColumnMin>()
+    }
+
+    /// The bytes of the minima of an `n x n` matrix.
+    pub(crate) fn estimated_bytes(n: usize) -> usize {
+        n * mem::size_of::<ColumnMin>()
+    }
+}
+
+/// Like `step6`, finding the smallest uncovered value from the column
+/// minima in `minima`, which are updated.
+pub fn step6_with<W>(c: &mut W, cov: &Coverage, minima: &mut ColumnMinima) -> Result<Step6, Error>
+where
+    W: Weights,
+{
+    let n = c.n();
+    assert!(cov.n() == n);
+    minima.rows.resize(n, ColumnMin::Unknown);
+
+    let mut min = None;
+    for column in (0..n).filter(|&column| !cov.is_column_covered(column)) {
+        let row = match minima.rows[column] {
+            ColumnMin::Row(row) if !cov.is_row_covered(row) => Some(row),
+            ColumnMin::Empty => None,
+            _ => {
+                // safe, as `row` and `column` are less than `n`.
+                let row = (0..n)
+                    .filter(|&row| {
+                        !cov.is_row_covered(row)
+                            && unsafe { c.is_valid_at_unchecked(Position { row, column }) }
+                    })
+                    .fold(None, |min: Option<(usize, W::T)>, row| {
+                        let elm = unsafe { c.element_at_unchecked(Position { row, column }) };
+                        match min {
+                            Some((_, m)) if m <= elm => min,
+                            _ => Some((row, elm)),
+                        }
+                    })
+                    .map(|(row, _)| row);
+                minima.rows[column] = row.map_or(ColumnMin::Empty, ColumnMin::Row);
+                row
+            }
+        };
+        if let Some(row) = row {
+            let elm = c.element_at(Position { row, column });
+            min = Some(match min {
+                Some(m) if m < elm => m,
+                _ => elm,
+            });
+        }
+    }
+    shift_uncovered(c, cov, min)
+}
+
+pub(crate) fn shift_uncovered<W>(
+    c: &mut W,
+    cov: &Coverage,
+    min: Option<W::T>,
+) -> Result<Step6, Error>
+where
+    W: Weights,
+{
+    let n = c.n();
+    if let Some(minval) = min {
+        for row in 0..n {
+            if cov.is_row_covered(row) {
+                c.add_row(row, minval);
+            }
+        }
+        for column in 0..n {
+            if !cov.is_column_covered(column) {
+                c.sub_column(column, minval);
+            }
+        }
+
+        Ok(Step6::ContinueWithStep4)
+    } else {
+        Err(Error::MatrixNotSolvable)
+    }
+}
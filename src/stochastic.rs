@@ -0,0 +1,168 @@
+//! Assignment with stochastic cell costs.
+//!
+//! Each cell carries a distribution of its cost, summarized by its mean and
+//! variance. The costs of different cells are assumed independent, so the
+//! mean and variance of the total cost of an assignment are the sums over
+//! its cells, and `Aggregation` says explicitly how they are combined into
+//! the objective.
+use crate::{solve_assignment, Assignment, Error, Position, WeightMatrix};
+use alloc::vec::Vec;
+
+/// Mean and variance of a cell cost.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Moments {
+    pub mean: f64,
+    pub variance: f64,
+}
+
+impl Moments {
+    /// The sample mean and (unbiased) sample variance of `samples`.
+    ///
+    /// # Panics
+    ///
+    /// If `samples` is empty.
+    pub fn from_samples(samples: &[f64]) -> Moments {
+        assert!(!samples.is_empty());
+        let count = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / count;
+        let variance = if samples.len() > 1 {
+            samples.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / (count - 1.0)
+        } else {
+            0.0
+        };
+        Moments { mean, variance }
+    }
+}
+
+/// How the distribution of the total cost is turned into the objective.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Aggregation {
+    /// Minimizes the expected total cost.
+    Expected,
+    /// Minimizes `mean + risk_aversion * variance` of the total cost, which
+    /// prefers assignments with less uncertain costs.
+    MeanVariance { risk_aversion: f64 },
+}
+
+/// An `n x n` matrix of cost distributions in row-major order. Cells with a
+/// non-finite mean are forbidden.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StochasticMatrix {
+    n: usize,
+    cells: Vec<Moments>,
+}
+
+impl StochasticMatrix {
+    /// # Panics
+    ///
+    /// If `cells` doesn't have `n * n` elements, or a valid cell has a
+    /// negative variance.
+    pub fn from_moments(n: usize, cells: Vec<Moments>) -> StochasticMatrix {
+        assert!(cells.len() == n * n);
+        assert!(cells
+            .iter()
+            .all(|m| !m.mean.is_finite() || m.variance >= 0.0));
+        StochasticMatrix { n, cells }
+    }
+
+    /// Summarizes the samples of each cell, in row-major order.
+    pub fn from_samples<S: AsRef<[f64]>>(n: usize, samples: &[S]) -> StochasticMatrix {
+        StochasticMatrix::from_moments(
+            n,
+            samples
+                .iter()
+                .map(|s| Moments::from_samples(s.as_ref()))
+                .collect(),
+        )
+    }
+
+    #[inline]
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    pub fn moments(&self, pos: Position) -> Moments {
+        self.cells[pos.row * self.n + pos.column]
+    }
+}
+
+/// An assignment with the distribution of its total cost.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StochasticSolution {
+    pub assignment: Assignment,
+    pub total: Moments,
+}
+
+/// Solves the assignment problem for the objective given by `aggregation`.
+pub fn solve_assignment_stochastic(
+    matrix: &StochasticMatrix,
+    aggregation: Aggregation,
+) -> Result<StochasticSolution, Error> {
+    if matrix.n == 0 {
+        return Ok(StochasticSolution {
+            assignment: Assignment::default(),
+            total: Moments {
+                mean: 0.0,
+                variance: 0.0,
+            },
+        });
+    }
+    let mut weights = WeightMatrix::from_fn(matrix.n, |(row, column)| {
+        let m = matrix.moments(Position { row, column });
+        match aggregation {
+            Aggregation::Expected => m.mean,
+            Aggregation::MeanVariance { risk_aversion } => m.mean + risk_aversion * m.variance,
+        }
+    });
+    let positions = solve_assignment(&mut weights)?;
+    let total = positions.iter().fold(
+        Moments {
+            mean: 0.0,
+            variance: 0.0,
+        },
+        |total, &pos| {
+            let m = matrix.moments(pos);
+            Moments {
+                mean: total.mean + m.mean,
+                variance: total.variance + m.variance,
+            }
+        },
+    );
+    Ok(StochasticSolution {
+        assignment: Assignment::from(positions),
+        total,
+    })
+}
+
+#[test]
+fn test_solve_assignment_stochastic() {
+    // the diagonal is cheaper on average, but much more uncertain.
+    let matrix = StochasticMatrix::from_samples(
+        2,
+        &[
+            vec![0.0, 10.0],
+            vec![6.0, 6.0],
+            vec![6.0, 6.0],
+            vec![0.0, 10.0],
+        ],
+    );
+    assert_eq!(
+        Moments {
+            mean: 5.0,
+            variance: 50.0
+        },
+        matrix.moments(Position { row: 0, column: 0 })
+    );
+
+    let expected = solve_assignment_stochastic(&matrix, Aggregation::Expected).unwrap();
+    assert_eq!(Some(0), expected.assignment.column_of(0));
+    assert_eq!(10.0, expected.total.mean);
+    assert_eq!(100.0, expected.total.variance);
+
+    let risk_averse = Aggregation::MeanVariance { risk_aversion: 0.1 };
+    let safe = solve_assignment_stochastic(&matrix, risk_averse).unwrap();
+    assert_eq!(Some(1), safe.assignment.column_of(0));
+    assert_eq!(12.0, safe.total.mean);
+    assert_eq!(0.0, safe.total.variance);
+}
@@ -0,0 +1,147 @@
+//! proptest strategies generating `WeightMatrix` instances.
+//!
+//! Enabled with the `proptest` feature. All strategies take the range of
+//! matrix sizes `n` to draw from, and most take a strategy for the individual
+//! cell values, which must only produce valid values.
+use crate::{WeightMatrix, WeightNum};
+use proptest::collection::vec;
+use proptest::prelude::*;
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+
+/// Matrices whose cells are all drawn from `element`.
+pub fn matrix<S>(
+    size: RangeInclusive<usize>,
+    element: S,
+) -> impl Strategy<Value = WeightMatrix<S::Value>>
+where
+    S: Strategy + Clone,
+    S::Value: WeightNum + Debug,
+{
+    size.prop_flat_map(move |n| {
+        vec(element.clone(), n * n).prop_map(move |data| WeightMatrix::from_row_vec(n, data))
+    })
+}
+
+/// Matrices that always have a complete assignment over valid cells.
+///
+/// A random permutation of cells is drawn from `element`, every other cell is
+/// either drawn from `element` or set to `invalid`. `invalid` must be a value
+/// for which `is_valid()` returns false, e.g. `f32::INFINITY`.
+pub fn solvable<S>(
+    size: RangeInclusive<usize>,
+    element: S,
+    invalid: S::Value,
+) -> impl Strategy<Value = WeightMatrix<S::Value>>
+where
+    S: Strategy + Clone,
+    S::Value: WeightNum + Debug,
+{
+    size.prop_flat_map(move |n| {
+        let permutation = Just((0..n).collect::<Vec<_>>()).prop_shuffle();
+        let assigned = vec(element.clone(), n);
+        let cells = vec(prop::option::of(element.clone()), n * n);
+        (permutation, assigned, cells).prop_map(move |(permutation, assigned, cells)| {
+            let mut data: Vec<_> = cells.into_iter().map(|c| c.unwrap_or(invalid)).collect();
+            for (row, &column) in permutation.iter().enumerate() {
+                data[row * n + column] = assigned[row];
+            }
+            WeightMatrix::from_row_vec(n, data)
+        })
+    })
+}
+
+/// Matrices without any complete assignment over valid cells.
+///
+/// A random set of `k` rows is restricted to `k - 1` columns, which violates
+/// Hall's condition, while every row still contains at least one valid cell so
+/// the infeasibility is not detected by the trivial per-row check.
+pub fn infeasible<S>(
+    size: RangeInclusive<usize>,
+    element: S,
+    invalid: S::Value,
+) -> impl Strategy<Value = WeightMatrix<S::Value>>
+where
+    S: Strategy + Clone,
+    S::Value: WeightNum + Debug,
+{
+    let start = (*size.start()).max(2);
+    (start..=*size.end()).prop_flat_map(move |n| {
+        let rows = Just((0..n).collect::<Vec<_>>()).prop_shuffle();
+        let columns = Just((0..n).collect::<Vec<_>>()).prop_shuffle();
+        let cells = vec(element.clone(), n * n);
+        (2..=n, rows, columns, cells).prop_map(move |(k, rows, columns, mut data)| {
+            for &row in &rows[..k] {
+                for &column in &columns[k - 1..] {
+                    data[row * n + column] = invalid;
+                }
+            }
+            WeightMatrix::from_row_vec(n, data)
+        })
+    })
+}
+
+/// Matrices with massive ties: every cell takes one of two values drawn from
+/// `element`.
+pub fn degenerate<S>(
+    size: RangeInclusive<usize>,
+    element: S,
+) -> impl Strategy<Value = WeightMatrix<S::Value>>
+where
+    S: Strategy + Clone,
+    S::Value: WeightNum + Debug,
+{
+    size.prop_flat_map(move |n| {
+        (element.clone(), element.clone(), vec(any::<bool>(), n * n)).prop_map(
+            move |(a, b, choices)| {
+                let data = choices.into_iter().map(|c| if c { a } else { b }).collect();
+                WeightMatrix::from_row_vec(n, data)
+            },
+        )
+    })
+}
+
+/// Row- and column-shuffled Machol–Wien matrices (`c[i][j] = i * j`), which
+/// force a large number of augmentations.
+///
+/// Sizes are capped at 256 so that every cell fits into an `u16`.
+pub fn adversarial<T>(size: RangeInclusive<usize>) -> impl Strategy<Value = WeightMatrix<T>>
+where
+    T: WeightNum + Debug + From<u16>,
+{
+    let end = (*size.end()).min(256);
+    (*size.start()..=end).prop_flat_map(|n| {
+        let rows = Just((0..n).collect::<Vec<_>>()).prop_shuffle();
+        let columns = Just((0..n).collect::<Vec<_>>()).prop_shuffle();
+        (rows, columns).prop_map(move |(rows, columns)| {
+            WeightMatrix::from_fn(n, |(row, column)| {
+                T::from((rows[row] * columns[column]) as u16)
+            })
+        })
+    })
+}
+
+#[cfg(test)]
+use crate::solve_assignment;
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn test_solvable_strategy(mut m in solvable(1..=8, 0.0f32..100.0, f32::INFINITY)) {
+        prop_assert!(solve_assignment(&mut m).is_ok());
+    }
+
+    #[test]
+    fn test_infeasible_strategy(mut m in infeasible(1..=8, 0.0f32..100.0, f32::INFINITY)) {
+        prop_assert!(solve_assignment(&mut m).is_err());
+    }
+
+    #[test]
+    fn test_degenerate_and_adversarial_strategies(
+        mut d in degenerate(1..=8, 0u32..3),
+        mut a in adversarial::<i32>(1..=8),
+    ) {
+        prop_assert!(solve_assignment(&mut d).is_ok());
+        prop_assert!(solve_assignment(&mut a).is_ok());
+    }
+}
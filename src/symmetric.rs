@@ -0,0 +1,120 @@
+//! Compact storage for symmetric cost matrices, e.g. distances between two
+//! copies of the same point set.
+//!
+//! `SymmetricMatrix` stores only the upper triangle, about half of a dense
+//! matrix. The reductions of the algorithm don't keep the matrix symmetric,
+//! so it is read-only: solve it with `solve_assignment_immutable` or through
+//! `DualWeights`, which keep the reductions as row and column potentials.
+//! The potentials of rows can become negative, so the costs must be of a
+//! signed type. Unsigned distances can be converted while building the
+//! matrix, e.g. with `i64::from`.
+use crate::dual::Costs;
+use crate::{Position, WeightNum};
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone)]
+pub struct SymmetricMatrix<T> {
+    n: usize,
+    /// The upper triangle including the diagonal, in row-major order.
+    packed: Vec<T>,
+}
+
+impl<T: WeightNum> SymmetricMatrix<T> {
+    /// Takes the upper triangle, including the diagonal, in row-major order.
+    ///
+    /// # Panics
+    ///
+    /// If `packed` doesn't have `n * (n + 1) / 2` elements.
+    pub fn from_upper_triangle(n: usize, packed: Vec<T>) -> SymmetricMatrix<T> {
+        assert!(packed.len() == n * (n + 1) / 2);
+        SymmetricMatrix { n, packed }
+    }
+
+    /// Calls `f` for the cells of the upper triangle only.
+    pub fn from_fn<F: FnMut((usize, usize)) -> T>(n: usize, mut f: F) -> SymmetricMatrix<T> {
+        let mut packed = Vec::with_capacity(n * (n + 1) / 2);
+        for row in 0..n {
+            for column in row..n {
+                packed.push(f((row, column)));
+            }
+        }
+        SymmetricMatrix::from_upper_triangle(n, packed)
+    }
+}
+
+impl<T: WeightNum> Costs for SymmetricMatrix<T> {
+    type T = T;
+
+    #[inline(always)]
+    fn n(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    fn element_at(&self, pos: Position) -> T {
+        let (row, column) = if pos.row <= pos.column {
+            (pos.row, pos.column)
+        } else {
+            (pos.column, pos.row)
+        };
+        debug_assert!(column < self.n);
+        self.packed[row * (2 * self.n - row + 1) / 2 + column - row]
+    }
+}
+
+#[test]
+fn test_symmetric_matrix() {
+    use crate::{generators, solve_assignment, solve_assignment_immutable, WeightMatrix};
+
+    let weights = SymmetricMatrix::from_upper_triangle(3, vec![1, 2, 3, 4, 5, 6]);
+    assert_eq!(5, weights.element_at(Position { row: 2, column: 1 }));
+    assert_eq!(5, weights.element_at(Position { row: 1, column: 2 }));
+
+    for seed in 0..10 {
+        let mut rng = generators::Rng::new(seed);
+        let points: Vec<(f64, f64)> = (0..12).map(|_| (rng.next_f64(), rng.next_f64())).collect();
+        let distance = |(i, j): (usize, usize)| {
+            if i == j {
+                // no point is matched with itself.
+                f64::INFINITY
+            } else {
+                let (dx, dy) = (points[i].0 - points[j].0, points[i].1 - points[j].1);
+                (dx * dx + dy * dy).sqrt()
+            }
+        };
+        let symmetric = SymmetricMatrix::from_fn(12, distance);
+        let mut dense = WeightMatrix::from_fn(12, distance);
+        let cost = |m: &[Position]| {
+            m.iter()
+                .map(|&pos| distance((pos.row, pos.column)))
+                .sum::<f64>()
+        };
+        let expected = cost(&solve_assignment(&mut dense).unwrap());
+        let found = cost(&solve_assignment_immutable(&symmetric).unwrap());
+        assert!((expected - found).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_symmetric_matrix_integers() {
+    use crate::{generators, solve_assignment, solve_assignment_immutable, WeightMatrix};
+
+    for seed in 0..20 {
+        let upper = generators::uniform(10, 0..50, seed);
+        let cost = |(i, j): (usize, usize)| {
+            let (row, column) = (i.min(j), i.max(j));
+            i64::from(upper.element_at(Position { row, column }))
+        };
+        let symmetric = SymmetricMatrix::from_fn(10, cost);
+        let mut dense = WeightMatrix::from_fn(10, cost);
+        let total = |m: &[Position]| {
+            m.iter()
+                .map(|&pos| cost((pos.row, pos.column)))
+                .sum::<i64>()
+        };
+        assert_eq!(
+            total(&solve_assignment(&mut dense).unwrap()),
+            total(&solve_assignment_immutable(&symmetric).unwrap())
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! Assignment over time with a penalty for switching.
+//!
+//! Applications like load balancing and tracking solve a new instance at
+//! every time step, and changing the column of a row between steps has a
+//! cost of its own. `TemporalAssigner` folds a switching penalty into each
+//! step: every cell except the one a row was assigned to in the previous
+//! step costs `switch_penalty` more. The buffers of the solver are reused
+//! between the steps.
+use crate::workspace::Workspace;
+use crate::{solve_assignment_in, Error, Position, WeightMatrix, WeightNum, Weights};
+use alloc::vec::Vec;
+
+/// Solves a sequence of assignment problems of the same size, penalizing
+/// rows that change their column from one step to the next.
+#[derive(Debug)]
+pub struct TemporalAssigner<T: WeightNum> {
+    switch_penalty: T,
+    /// The column of each row in the previous step, empty before the first.
+    previous: Vec<usize>,
+    workspace: Workspace,
+}
+
+impl<T: WeightNum> TemporalAssigner<T> {
+    pub fn new(switch_penalty: T) -> TemporalAssigner<T> {
+        TemporalAssigner {
+            switch_penalty,
+            previous: Vec::new(),
+            workspace: Workspace::new(1),
+        }
+    }
+
+    /// Solves the next step. The matching minimizes the weights plus the
+    /// penalties, and is sorted by row. `weights` is not modified. If the
+    /// size changed since the previous step, no penalties apply.
+    pub fn step<W>(&mut self, weights: &W) -> Result<Vec<Position>, Error>
+    where
+        W: Weights<T = T>,
+    {
+        let n = weights.n();
+        if n == 0 {
+            self.previous.clear();
+            return Ok(Vec::new());
+        }
+        let previous = if self.previous.len() == n {
+            &self.previous[..]
+        } else {
+            &[]
+        };
+        let penalty = self.switch_penalty;
+        let mut penalized = WeightMatrix::from_fn(n, |(row, column)| {
+            let cost = weights.element_at(Position { row, column });
+            match previous.get(row) {
+                Some(&kept) if kept != column => cost.add_if_valid(penalty),
+                _ => cost,
+            }
+        });
+        let matching = solve_assignment_in(&mut penalized, &mut self.workspace)?;
+        self.previous.clear();
+        self.previous.extend(matching.iter().map(|pos| pos.column));
+        Ok(matching)
+    }
+
+    /// The column of each row in the previous step.
+    pub fn previous(&self) -> Option<&[usize]> {
+        if self.previous.is_empty() {
+            None
+        } else {
+            Some(&self.previous)
+        }
+    }
+
+    /// Forgets the previous step, so that the next one has no penalties.
+    pub fn reset(&mut self) {
+        self.previous.clear();
+    }
+}
+
+#[test]
+fn test_temporal_assigner() {
+    let columns = |matching: Vec<Position>| -> Vec<usize> {
+        matching.into_iter().map(|pos| pos.column).collect()
+    };
+    let first = WeightMatrix::from_row_vec(2, vec![1, 5, 5, 1]);
+    // slightly cheaper to swap, but not worth the penalty.
+    let second = WeightMatrix::from_row_vec(2, vec![4, 3, 3, 4]);
+    // much cheaper to swap.
+    let third = WeightMatrix::from_row_vec(2, vec![9, 1, 1, 9]);
+
+    let mut assigner = TemporalAssigner::new(2);
+    assert_eq!(None, assigner.previous());
+    assert_eq!(vec![0, 1], columns(assigner.step(&first).unwrap()));
+    assert_eq!(vec![0, 1], columns(assigner.step(&second).unwrap()));
+    assert_eq!(vec![1, 0], columns(assigner.step(&third).unwrap()));
+    assert_eq!(Some(&[1, 0][..]), assigner.previous());
+
+    assigner.reset();
+    assert_eq!(vec![0, 1], columns(assigner.step(&first).unwrap()));
+    let mut unpenalized = TemporalAssigner::new(0);
+    unpenalized.step(&first).unwrap();
+    assert_eq!(vec![1, 0], columns(unpenalized.step(&second).unwrap()));
+}
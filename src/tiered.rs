@@ -0,0 +1,115 @@
+//! Assignment in priority tiers, e.g. dispatching emergencies before routine
+//! calls.
+//!
+//! A single weighted objective trades the cost of one row against another,
+//! so no choice of weights guarantees that an urgent row gets its best
+//! column regardless of the others. `solve_assignment_tiered` instead
+//! optimizes the tiers one after another: the rows of the top tier are
+//! matched at minimum cost, then the rows of the next tier over the columns
+//! left, and so on. While a tier is solved, the rows of the lower tiers take
+//! part at cost 0 on their valid cells, which keeps a complete matching
+//! possible without letting them influence the tier.
+use crate::workspace::Workspace;
+use crate::{solve_assignment_in, Error, Position, WeightMatrix, Weights};
+use alloc::{vec, vec::Vec};
+
+/// Solves the tiers of rows in order, `tiers[row]` being the tier of `row`
+/// and tier 0 the most urgent. Each tier is matched at minimum cost among the
+/// matchings that keep the tiers above it as they are and can be completed.
+/// Returns the matching sorted by row. `weights` is not modified.
+///
+/// # Panics
+///
+/// If `tiers` doesn't have `n` elements.
+pub fn solve_assignment_tiered<W>(weights: &W, tiers: &[usize]) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+    W::T: Default,
+{
+    let n = weights.n();
+    assert!(tiers.len() == n, "tiers must have n elements");
+    let mut tier_order: Vec<usize> = tiers.to_vec();
+    tier_order.sort_unstable();
+    tier_order.dedup();
+
+    let mut rows: Vec<usize> = (0..n).collect();
+    let mut columns: Vec<usize> = (0..n).collect();
+    let mut matching = Vec::with_capacity(n);
+    let mut workspace = Workspace::new(n.max(1));
+    for tier in tier_order {
+        let m = rows.len();
+        let mut data = Vec::with_capacity(m * m);
+        let mut valid = Vec::with_capacity(m * m);
+        for &row in &rows {
+            for &column in &columns {
+                let pos = Position { row, column };
+                let is_valid = weights.is_valid_at(pos);
+                data.push(if tiers[row] == tier && is_valid {
+                    weights.element_at(pos)
+                } else {
+                    W::T::default()
+                });
+                valid.push(is_valid);
+            }
+        }
+        let mut sub = WeightMatrix::from_row_vec_with_validity(m, data, &valid);
+        let solved = solve_assignment_in(&mut sub, &mut workspace)?;
+
+        let mut used = vec![false; m];
+        for pos in solved {
+            if tiers[rows[pos.row]] == tier {
+                matching.push(Position {
+                    row: rows[pos.row],
+                    column: columns[pos.column],
+                });
+                used[pos.column] = true;
+            }
+        }
+        rows.retain(|&row| tiers[row] != tier);
+        let mut index = 0;
+        columns.retain(|_| {
+            index += 1;
+            !used[index - 1]
+        });
+    }
+    matching.sort_unstable_by_key(|pos| pos.row);
+    Ok(matching)
+}
+
+#[test]
+fn test_solve_assignment_tiered() {
+    use crate::solve_assignment;
+
+    let pos = |row, column| Position { row, column };
+    let inf = f64::INFINITY;
+
+    // the optimum gives row 0 its second choice; as the only urgent row it
+    // gets its first.
+    let weights = WeightMatrix::from_row_vec(3, vec![1.0, 2.0, 9.0, 1.0, 9.0, 9.0, 9.0, 9.0, 1.0]);
+    assert_eq!(
+        Ok(vec![pos(0, 1), pos(1, 0), pos(2, 2)]),
+        solve_assignment(&mut weights.clone())
+    );
+    assert_eq!(
+        Ok(vec![pos(0, 0), pos(1, 1), pos(2, 2)]),
+        solve_assignment_tiered(&weights, &[0, 1, 1])
+    );
+    // a single tier is the plain optimum.
+    assert_eq!(
+        solve_assignment(&mut weights.clone()),
+        solve_assignment_tiered(&weights, &[3, 3, 3])
+    );
+
+    // row 0 prefers column 2, but row 2 can only take column 2.
+    let weights = WeightMatrix::from_row_vec(3, vec![5.0, 9.0, 1.0, 1.0, 1.0, 1.0, inf, inf, 1.0]);
+    assert_eq!(
+        Ok(vec![pos(0, 0), pos(1, 1), pos(2, 2)]),
+        solve_assignment_tiered(&weights, &[0, 1, 1])
+    );
+
+    let weights = WeightMatrix::from_row_vec(2, vec![1.0, inf, 1.0, inf]);
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_assignment_tiered(&weights, &[0, 1])
+    );
+}
@@ -0,0 +1,149 @@
+//! Blocked storage for very large cost matrices.
+//!
+//! In the row-major layout of `WeightMatrix`, consecutive cells of a column
+//! are a whole row apart, so subtracting from a column in step 6 touches a
+//! different cache line, and at `n` beyond about 10k a different page, for
+//! every cell. `TiledMatrix` stores the matrix in square tiles of
+//! `TILE x TILE` cells, each in row-major order. A column then runs through
+//! `n / TILE` tiles with a short stride within each, and a row through as
+//! many tiles contiguously, so both kinds of updates stay in cache-friendly
+//! blocks. The last tiles of each row and column are padded.
+use crate::{Position, WeightNum, Weights};
+use alloc::{vec, vec::Vec};
+
+/// The side of a tile. 64 `f64` cells make a tile of 32 KiB, the size of a
+/// typical L1 data cache.
+pub const TILE: usize = 64;
+
+#[derive(Debug, Clone)]
+pub struct TiledMatrix<T> {
+    n: usize,
+    /// The number of tiles in each row of tiles.
+    tiles: usize,
+    values: Vec<T>,
+}
+
+impl<T: WeightNum + Default> TiledMatrix<T> {
+    pub fn from_fn<F: FnMut((usize, usize)) -> T>(n: usize, mut f: F) -> TiledMatrix<T> {
+        let tiles = n.div_ceil(TILE);
+        let mut matrix = TiledMatrix {
+            n,
+            tiles,
+            values: vec![T::default(); tiles * tiles * TILE * TILE],
+        };
+        for row in 0..n {
+            for column in 0..n {
+                let index = matrix.index(row, column);
+                matrix.values[index] = f((row, column));
+            }
+        }
+        matrix
+    }
+
+    /// Takes the matrix in row-major order.
+    ///
+    /// # Panics
+    ///
+    /// If `data` doesn't have `n * n` elements.
+    pub fn from_row_vec(n: usize, data: Vec<T>) -> TiledMatrix<T> {
+        assert!(data.len() == n * n);
+        TiledMatrix::from_fn(n, |(row, column)| data[row * n + column])
+    }
+
+    /// The matrix in row-major order.
+    pub fn to_row_vec(&self) -> Vec<T> {
+        let mut data = Vec::with_capacity(self.n * self.n);
+        for row in 0..self.n {
+            data.extend((0..self.n).map(|column| self.values[self.index(row, column)]));
+        }
+        data
+    }
+
+    #[inline(always)]
+    fn index(&self, row: usize, column: usize) -> usize {
+        let tile = (row / TILE) * self.tiles + column / TILE;
+        tile * TILE * TILE + (row % TILE) * TILE + column % TILE
+    }
+
+    /// Applies `f` to the valid cells of `row`, tile by tile.
+    fn map_valid_in_row<F: Fn(T) -> T>(&mut self, row: usize, f: F) {
+        let start = (row / TILE) * self.tiles * TILE * TILE + (row % TILE) * TILE;
+        for tile in 0..self.tiles {
+            let begin = start + tile * TILE * TILE;
+            let len = TILE.min(self.n - tile * TILE);
+            for cur in &mut self.values[begin..begin + len] {
+                if cur.is_valid() {
+                    *cur = f(*cur);
+                }
+            }
+        }
+    }
+}
+
+impl<T: WeightNum + Default> Weights for TiledMatrix<T> {
+    type T = T;
+
+    #[inline(always)]
+    fn n(&self) -> usize {
+        self.n
+    }
+
+    #[inline]
+    fn element_at(&self, pos: Position) -> T {
+        debug_assert!(pos.row < self.n && pos.column < self.n);
+        self.values[self.index(pos.row, pos.column)]
+    }
+
+    fn sub_min_of_each_row(&mut self) {
+        for row in 0..self.n {
+            let min = (0..self.n)
+                .map(|column| self.values[self.index(row, column)])
+                .filter(WeightNum::is_valid)
+                .reduce(|min, val| if val < min { val } else { min });
+            if let Some(min) = min {
+                self.map_valid_in_row(row, |cur| cur - min);
+            }
+        }
+    }
+
+    fn add_row(&mut self, row: usize, val: T) {
+        self.map_valid_in_row(row, |cur| cur + val);
+    }
+
+    fn sub_column(&mut self, col: usize, val: T) {
+        for row in 0..self.n {
+            let index = self.index(row, col);
+            let cur = &mut self.values[index];
+            if cur.is_valid() {
+                *cur = *cur - val;
+            }
+        }
+    }
+
+    fn is_solvable(&self) -> bool {
+        (0..self.n)
+            .all(|row| (0..self.n).any(|column| self.values[self.index(row, column)].is_valid()))
+    }
+}
+
+#[test]
+fn test_tiled_matrix() {
+    use crate::{generators, solve_assignment, WeightMatrix};
+
+    // spans two tiles per row and column, the second padded.
+    let n = TILE + 6;
+    let weights = generators::uniform(n, 0..1000, 1);
+    let mut tiled = TiledMatrix::from_row_vec(n, weights.as_slice().to_vec());
+    assert_eq!(weights.as_slice(), &tiled.to_row_vec()[..]);
+    assert_eq!(
+        solve_assignment(&mut weights.clone()),
+        solve_assignment(&mut tiled)
+    );
+
+    let inf = f64::INFINITY;
+    let data = vec![1.0, inf, 2.0, 3.0];
+    let mut tiled = TiledMatrix::from_row_vec(2, data.clone());
+    let mut dense = WeightMatrix::from_row_vec(2, data);
+    assert_eq!(solve_assignment(&mut dense), solve_assignment(&mut tiled));
+    assert!(!TiledMatrix::from_row_vec(2, vec![inf, inf, 1.0, 2.0]).is_solvable());
+}
@@ -0,0 +1,163 @@
+//! Cost matrices for associating measurements with predicted tracks, the
+//! front-end of Kalman-filter style trackers.
+//!
+//! `MahalanobisCost` takes the predicted measurement of each track together
+//! with its innovation covariance `S`, and the actual measurements. The cost
+//! of a pair is the squared Mahalanobis distance `y^T S^-1 y` of the
+//! innovation `y`, which is chi-square distributed with as many degrees of
+//! freedom as the measurements have dimensions. Pairs beyond the chi-square
+//! `gate`, e.g. 9.21 for 2-D measurements at 99%, are forbidden.
+//!
+//! The numbers of tracks and measurements usually differ, so the matrix is
+//! padded to a square one. A padded cell stands for a missed detection or a
+//! new track and costs `gate`, as much as the most unlikely pair allowed.
+use crate::{float, WeightMatrix};
+use alloc::{vec, vec::Vec};
+
+/// The Cholesky factor `L` of a symmetric positive definite matrix, with
+/// `L L^T` the matrix, in row-major order.
+fn cholesky(matrix: &[f64], dims: usize) -> Option<Vec<f64>> {
+    let mut l = vec![0.0; dims * dims];
+    for i in 0..dims {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| l[i * dims + k] * l[j * dims + k]).sum();
+            let value = matrix[i * dims + j] - sum;
+            if i == j {
+                if value.is_nan() || value <= 0.0 {
+                    return None;
+                }
+                l[i * dims + i] = float::sqrt(value);
+            } else {
+                l[i * dims + j] = value / l[j * dims + j];
+            }
+        }
+    }
+    Some(l)
+}
+
+#[derive(Debug, Clone)]
+struct Prediction {
+    mean: Vec<f64>,
+    /// The Cholesky factor of the innovation covariance.
+    factor: Vec<f64>,
+}
+
+impl Prediction {
+    /// `y^T S^-1 y`, as `|L^-1 y|^2` by forward substitution.
+    fn squared_distance(&self, measurement: &[f64]) -> f64 {
+        let dims = self.mean.len();
+        let mut solved = vec![0.0; dims];
+        for i in 0..dims {
+            let sum: f64 = (0..i).map(|k| self.factor[i * dims + k] * solved[k]).sum();
+            let innovation = measurement[i] - self.mean[i];
+            solved[i] = (innovation - sum) / self.factor[i * dims + i];
+        }
+        solved.iter().map(|y| y * y).sum()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MahalanobisCost {
+    dims: usize,
+    gate: f64,
+    predictions: Vec<Prediction>,
+    measurements: Vec<Vec<f64>>,
+}
+
+impl MahalanobisCost {
+    /// A builder for measurements of `dims` dimensions, gating the squared
+    /// distance at `gate`.
+    ///
+    /// # Panics
+    ///
+    /// If `gate` isn't positive and finite.
+    pub fn new(dims: usize, gate: f64) -> MahalanobisCost {
+        assert!(
+            gate > 0.0 && gate.is_finite(),
+            "the gate must be positive and finite"
+        );
+        MahalanobisCost {
+            dims,
+            gate,
+            predictions: Vec::new(),
+            measurements: Vec::new(),
+        }
+    }
+
+    /// Adds a track with its predicted measurement `mean` and innovation
+    /// covariance `covariance`, in row-major order. Tracks are the rows.
+    ///
+    /// # Panics
+    ///
+    /// If the dimensions don't match, or `covariance` isn't symmetric
+    /// positive definite.
+    pub fn prediction(mut self, mean: &[f64], covariance: &[f64]) -> MahalanobisCost {
+        assert!(mean.len() == self.dims && covariance.len() == self.dims * self.dims);
+        let factor = cholesky(covariance, self.dims)
+            .expect("the covariance must be symmetric positive definite");
+        self.predictions.push(Prediction {
+            mean: mean.to_vec(),
+            factor,
+        });
+        self
+    }
+
+    /// Adds a measurement. Measurements are the columns.
+    ///
+    /// # Panics
+    ///
+    /// If the dimensions don't match.
+    pub fn measurement(mut self, measurement: &[f64]) -> MahalanobisCost {
+        assert!(measurement.len() == self.dims);
+        self.measurements.push(measurement.to_vec());
+        self
+    }
+
+    /// The squared distance of each pair, `INFINITY` beyond the gate, padded
+    /// with cells of cost `gate` to a square matrix.
+    ///
+    /// # Panics
+    ///
+    /// If there are neither tracks nor measurements.
+    pub fn build(&self) -> WeightMatrix<f64> {
+        let n = self.predictions.len().max(self.measurements.len());
+        WeightMatrix::from_fn(n, |(row, column)| {
+            match (self.predictions.get(row), self.measurements.get(column)) {
+                (Some(prediction), Some(measurement)) => {
+                    let distance = prediction.squared_distance(measurement);
+                    if distance <= self.gate {
+                        distance
+                    } else {
+                        f64::INFINITY
+                    }
+                }
+                _ => self.gate,
+            }
+        })
+    }
+}
+
+#[test]
+fn test_mahalanobis_cost() {
+    use crate::{solve_assignment, Position, Weights};
+
+    let pos = |row, column| Position { row, column };
+    // the first track is uncertain along x only.
+    let costs = MahalanobisCost::new(2, 9.21)
+        .prediction(&[0.0, 0.0], &[4.0, 0.0, 0.0, 0.25])
+        .prediction(&[10.0, 0.0], &[1.0, 0.5, 0.5, 1.0])
+        .measurement(&[2.0, 0.0])
+        .measurement(&[0.0, 2.0])
+        .measurement(&[10.5, 0.5])
+        .build();
+    assert_eq!(3, costs.n());
+    assert_eq!(1.0, costs.element_at(pos(0, 0)));
+    // 4 standard deviations along y, a squared distance of 16.
+    assert!(!costs.is_valid_at(pos(0, 1)));
+    // y = (0.5, 0.5), S^-1 = [[4, -2], [-2, 4]] / 3.
+    assert!((costs.element_at(pos(1, 2)) - 1.0 / 3.0).abs() < 1e-12);
+    assert_eq!(9.21, costs.element_at(pos(2, 0)));
+
+    let matching = solve_assignment(&mut costs.clone()).unwrap();
+    assert_eq!(vec![pos(0, 0), pos(1, 2), pos(2, 1)], matching);
+}
@@ -0,0 +1,192 @@
+//! Discrete optimal transport.
+//!
+//! Moves the mass `supply[i]` of each source `i` to the targets, such that
+//! target `j` receives exactly `demand[j]`, minimizing the total cost
+//! `sum(plan[i][j] * costs[i][j])`. The assignment problem is the special case
+//! of `n` sources and targets with unit masses.
+//!
+//! Costs are given in row-major order, one row per source. Infinite (or NaN)
+//! costs forbid transport between a source and a target.
+use crate::flow::FlowNetwork;
+#[cfg(any(feature = "std", feature = "libm"))]
+use crate::sinkhorn::{sinkhorn_log, SinkhornOptions};
+use crate::Error;
+use alloc::vec::Vec;
+
+/// A transport plan between `rows` sources and `columns` targets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransportPlan {
+    rows: usize,
+    columns: usize,
+    flows: Vec<f64>,
+    cost: f64,
+}
+
+impl TransportPlan {
+    #[inline]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    #[inline]
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// The mass transported from source `row` to target `column`.
+    #[inline]
+    pub fn flow(&self, row: usize, column: usize) -> f64 {
+        self.flows[row * self.columns + column]
+    }
+
+    /// The plan in row-major order.
+    pub fn as_slice(&self) -> &[f64] {
+        &self.flows
+    }
+
+    /// The total cost of the plan.
+    pub fn cost(&self) -> f64 {
+        self.cost
+    }
+}
+
+fn check_problem(supply: &[f64], demand: &[f64], costs: &[f64]) -> f64 {
+    assert!(costs.len() == supply.len() * demand.len());
+    assert!(supply.iter().chain(demand.iter()).all(|&m| m >= 0.0));
+    let total: f64 = supply.iter().sum();
+    let total_demand: f64 = demand.iter().sum();
+    assert!(
+        (total - total_demand).abs() <= 1e-9 * total.max(1.0),
+        "supply and demand must balance"
+    );
+    total
+}
+
+/// Computes an optimal transport plan exactly, using a min-cost flow
+/// formulation solved by successive shortest paths.
+///
+/// # Panics
+///
+/// If the dimensions don't match, a mass is negative or total supply and
+/// demand differ.
+pub fn transport_exact(
+    supply: &[f64],
+    demand: &[f64],
+    costs: &[f64],
+) -> Result<TransportPlan, Error> {
+    let total = check_problem(supply, demand, costs);
+    let (rows, columns) = (supply.len(), demand.len());
+    let tolerance = 1e-12 * total.max(1.0);
+
+    // nodes: source, sink, sources, targets.
+    let (source, sink) = (0, 1);
+    let mut network = FlowNetwork::new(2 + rows + columns, tolerance);
+    for (row, &mass) in supply.iter().enumerate() {
+        network.add_edge(source, 2 + row, mass, 0.0);
+    }
+    for (column, &mass) in demand.iter().enumerate() {
+        network.add_edge(2 + rows + column, sink, mass, 0.0);
+    }
+    let mut edges = Vec::with_capacity(costs.len());
+    for row in 0..rows {
+        for column in 0..columns {
+            let cost = costs[row * columns + column];
+            edges.push(if cost.is_finite() {
+                Some(network.add_edge(2 + row, 2 + rows + column, f64::INFINITY, cost))
+            } else {
+                None
+            });
+        }
+    }
+
+    let (sent, cost) = network.min_cost_flow(source, sink, total);
+    if total - sent > 1e-9 * total.max(1.0) {
+        return Err(Error::MatrixNotSolvable);
+    }
+    let flows = edges
+        .iter()
+        .map(|edge| edge.map_or(0.0, |edge| network.flow(edge)))
+        .collect();
+    Ok(TransportPlan {
+        rows,
+        columns,
+        flows,
+        cost,
+    })
+}
+
+/// Computes an approximate, entropic-regularized transport plan using
+/// Sinkhorn iterations. Much faster than `transport_exact` on large dense
+/// instances. The marginals are only met up to `options.tolerance`.
+///
+/// # Panics
+///
+/// Like `transport_exact`.
+#[cfg(any(feature = "std", feature = "libm"))]
+pub fn transport_sinkhorn(
+    supply: &[f64],
+    demand: &[f64],
+    costs: &[f64],
+    options: &SinkhornOptions,
+) -> Result<TransportPlan, Error> {
+    check_problem(supply, demand, costs);
+    let costs: Vec<f64> = costs
+        .iter()
+        .map(|&c| if c.is_finite() { c } else { f64::INFINITY })
+        .collect();
+    let flows = sinkhorn_log(&costs, supply, demand, options)?.plan;
+    let cost = flows
+        .iter()
+        .zip(costs.iter())
+        .filter(|(&f, _)| f > 0.0)
+        .map(|(f, c)| f * c)
+        .sum();
+    Ok(TransportPlan {
+        rows: supply.len(),
+        columns: demand.len(),
+        flows,
+        cost,
+    })
+}
+
+#[test]
+fn test_transport_exact() {
+    // two sources, three targets.
+    let supply = [3.0, 2.0];
+    let demand = [1.0, 2.5, 1.5];
+    let costs = [1.0, 2.0, 3.0, 4.0, 1.0, 2.0];
+    let plan = transport_exact(&supply, &demand, &costs).unwrap();
+
+    for (row, mass) in supply.iter().enumerate() {
+        let sum: f64 = (0..3).map(|column| plan.flow(row, column)).sum();
+        assert!((sum - mass).abs() < 1e-9);
+    }
+    for (column, mass) in demand.iter().enumerate() {
+        let sum: f64 = (0..2).map(|row| plan.flow(row, column)).sum();
+        assert!((sum - mass).abs() < 1e-9);
+    }
+    // source 1 serves target 1 at cost 1 and the rest goes via source 0.
+    assert!((plan.cost() - (1.0 + 0.5 * 2.0 + 1.5 * 3.0 + 2.0 * 1.0)).abs() < 1e-9);
+
+    let inf = f64::INFINITY;
+    let forbidden = [1.0, inf, inf, inf, 1.0, 1.0];
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        transport_exact(&supply, &demand, &forbidden)
+    );
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+#[test]
+fn test_transport_sinkhorn_approximates_exact() {
+    let supply = [3.0, 2.0];
+    let demand = [1.0, 2.5, 1.5];
+    let costs = [1.0, 2.0, 3.0, 4.0, 1.0, 2.0];
+    let exact = transport_exact(&supply, &demand, &costs).unwrap();
+    let options = SinkhornOptions {
+        regularization: 0.05,
+        ..SinkhornOptions::default()
+    };
+    let approx = transport_sinkhorn(&supply, &demand, &costs, &options).unwrap();
+    assert!((exact.cost() - approx.cost()).abs() < 0.1);
+}
@@ -0,0 +1,131 @@
+//! Verifying float solutions in exact arithmetic.
+//!
+//! Every finite float is a rational number, so a matching found with float
+//! costs can be checked exactly: `verify_exact` sums its cost without
+//! rounding and searches for exact duals `u`, `v` with `u[i] + v[j] <=
+//! c[i][j]` for all valid cells and equality on the matching. Such duals
+//! prove that no other matching is cheaper. If none exist, the matching only
+//! looked optimal because of rounding.
+use crate::{Position, WeightNum, Weights};
+use num_rational::BigRational;
+use num_traits::Zero;
+
+/// The result of `verify_exact`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExactVerification {
+    /// The exact total cost of the matching.
+    pub cost: BigRational,
+    /// The row and column duals proving optimality, `None` if the matching
+    /// is not optimal.
+    pub duals: Option<(Vec<BigRational>, Vec<BigRational>)>,
+}
+
+impl ExactVerification {
+    pub fn is_optimal(&self) -> bool {
+        self.duals.is_some()
+    }
+}
+
+/// Checks `matching` against the original costs `weights` in exact rational
+/// arithmetic. Takes O(n^3) rational operations.
+///
+/// # Panics
+///
+/// If `matching` doesn't assign each row to a distinct valid cell, or a
+/// valid cell isn't a finite value convertible to `f64`.
+pub fn verify_exact<W>(weights: &W, matching: &[Position]) -> ExactVerification
+where
+    W: Weights,
+{
+    let n = weights.n();
+    let cost = |pos: Position| -> Option<BigRational> {
+        if !weights.is_valid_at(pos) {
+            return None;
+        }
+        let value = weights.element_at(pos).to_f64();
+        Some(
+            value
+                .and_then(BigRational::from_float)
+                .expect("exact verification requires finite weights convertible to f64"),
+        )
+    };
+
+    let mut column_of = vec![usize::MAX; n];
+    let mut assigned = vec![false; n];
+    for &pos in matching {
+        assert!(column_of[pos.row] == usize::MAX && !assigned[pos.column]);
+        column_of[pos.row] = pos.column;
+        assigned[pos.column] = true;
+    }
+    assert!(matching.len() == n, "matching must assign every row");
+    let matched: Vec<BigRational> = (0..n)
+        .map(|row| {
+            cost(Position {
+                row,
+                column: column_of[row],
+            })
+            .expect("matching must only use valid cells")
+        })
+        .collect();
+    let total = matched.iter().fold(BigRational::zero(), |sum, c| sum + c);
+
+    // with `u[i] = c[i][m(i)] - v[m(i)]`, the constraints become
+    // `v[j] - v[m(i)] <= c[i][j] - c[i][m(i)]`, which Bellman-Ford solves
+    // unless the residual graph has a negative cycle.
+    let mut v = vec![BigRational::zero(); n];
+    let mut changed = true;
+    for _ in 0..=n {
+        if !changed {
+            break;
+        }
+        changed = false;
+        for row in 0..n {
+            for column in 0..n {
+                if let Some(c) = cost(Position { row, column }) {
+                    let candidate = &v[column_of[row]] + c - &matched[row];
+                    if candidate < v[column] {
+                        v[column] = candidate;
+                        changed = true;
+                    }
+                }
+            }
+        }
+    }
+    let duals = if changed {
+        None
+    } else {
+        let u = (0..n)
+            .map(|row| &matched[row] - &v[column_of[row]])
+            .collect();
+        Some((u, v))
+    };
+    ExactVerification { cost: total, duals }
+}
+
+#[test]
+fn test_verify_exact() {
+    use crate::{solve_assignment, WeightMatrix};
+
+    let weights =
+        WeightMatrix::from_row_vec(3, vec![0.1, 0.2, 0.3, 0.7, 0.1, 0.25, 0.3, 0.4, 0.15]);
+    let matching = solve_assignment(&mut weights.clone()).unwrap();
+    let verification = verify_exact(&weights, &matching);
+    assert!(verification.is_optimal());
+    let (u, v) = verification.duals.unwrap();
+    let sum = |d: &[BigRational]| d.iter().fold(BigRational::zero(), |s, x| s + x);
+    assert_eq!(verification.cost, sum(&u) + sum(&v));
+
+    // both matchings cost 1e16 in floats, but the diagonal is 1 more.
+    let weights = WeightMatrix::from_row_vec(2, vec![1e16, 1e16, 0.0, 1.0]);
+    let diagonal = [
+        Position { row: 0, column: 0 },
+        Position { row: 1, column: 1 },
+    ];
+    let swapped = [
+        Position { row: 0, column: 1 },
+        Position { row: 1, column: 0 },
+    ];
+    assert_eq!(1e16 + 1.0, 1e16 + 0.0);
+    assert!(!verify_exact(&weights, &diagonal).is_optimal());
+    assert!(verify_exact(&weights, &swapped).is_optimal());
+}
@@ -0,0 +1,275 @@
+//! Recording and rendering the steps of the algorithm, e.g. for teaching.
+//!
+//! `solve_assignment_recorded` solves like `solve_assignment`, but takes a
+//! `Snapshot` of the matrix, its marks and covered lines after every step.
+//! `render_svg` draws a single snapshot, and `render_html` a standalone HTML
+//! page stepping through all of them. As the number of steps grows quickly,
+//! this is meant for small instances.
+use crate::coverage::Coverage;
+use crate::steps::*;
+use crate::{Error, MarkMatrix, Position, Weights};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::{Display, Write};
+
+/// The state of the algorithm after a step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    /// The step just executed, from 1 to 6.
+    pub step: u8,
+    pub n: usize,
+    /// The formatted values of the (reduced) matrix in row-major order.
+    pub values: Vec<String>,
+    pub stars: Vec<Position>,
+    pub primes: Vec<Position>,
+    pub covered_rows: Vec<usize>,
+    pub covered_columns: Vec<usize>,
+}
+
+impl Snapshot {
+    fn take<W>(step: u8, weights: &W, marks: &MarkMatrix, coverage: &Coverage) -> Snapshot
+    where
+        W: Weights,
+        W::T: Display,
+    {
+        let n = weights.n();
+        let cells =
+            || (0..n).flat_map(move |row| (0..n).map(move |column| Position { row, column }));
+        Snapshot {
+            step,
+            n,
+            values: cells()
+                .map(|pos| weights.element_at(pos).to_string())
+                .collect(),
+            stars: cells().filter(|&pos| marks.is_star(pos)).collect(),
+            primes: cells().filter(|&pos| marks.is_prime(pos)).collect(),
+            covered_rows: (0..n).filter(|&row| coverage.is_row_covered(row)).collect(),
+            covered_columns: (0..n)
+                .filter(|&column| coverage.is_column_covered(column))
+                .collect(),
+        }
+    }
+
+    /// What the step did, in a few words.
+    pub fn description(&self) -> &'static str {
+        match self.step {
+            1 => "Step 1: subtract the minimum of each row",
+            2 => "Step 2: star independent zeros",
+            3 => "Step 3: cover the columns of starred zeros",
+            4 => "Step 4: prime uncovered zeros",
+            5 => "Step 5: augment along the alternating path",
+            _ => "Step 6: shift the smallest uncovered value",
+        }
+    }
+}
+
+/// Like `solve_assignment`, additionally returning a snapshot after each
+/// step, up to the error if the solve failed. Always runs all steps, so
+/// among several optimal matchings it may return a different one than
+/// `solve_assignment`, which skips them if the row reduction already
+/// yields a matching.
+pub fn solve_assignment_recorded<W>(
+    weights: &mut W,
+) -> (Result<Vec<Position>, Error>, Vec<Snapshot>)
+where
+    W: Weights,
+    W::T: Display,
+{
+    let mut snapshots = Vec::new();
+    let result = record(weights, &mut snapshots);
+    (result, snapshots)
+}
+
+fn record<W>(weights: &mut W, snapshots: &mut Vec<Snapshot>) -> Result<Vec<Position>, Error>
+where
+    W: Weights,
+    W::T: Display,
+{
+    let n = weights.n();
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+    if !weights.is_solvable() {
+        return Err(Error::MatrixNotSolvable);
+    }
+    let mut marks = MarkMatrix::new(n);
+    let mut coverage = Coverage::new(n);
+    let mut path = Path::new();
+
+    step1(weights);
+    snapshots.push(Snapshot::take(1, weights, &marks, &coverage));
+    step2(weights, &mut marks, &mut coverage);
+    snapshots.push(Snapshot::take(2, weights, &marks, &coverage));
+    'step3: loop {
+        let step = step3(weights, &marks, &mut coverage);
+        snapshots.push(Snapshot::take(3, weights, &marks, &coverage));
+        if step == Step3::Done {
+            break;
+        }
+        loop {
+            let step = step4(weights, &mut marks, &mut coverage);
+            snapshots.push(Snapshot::take(4, weights, &marks, &coverage));
+            match step {
+                Step4::ContinueWithStep5 { z0_pos } => {
+                    step5(&mut marks, &mut coverage, z0_pos, &mut path)?;
+                    snapshots.push(Snapshot::take(5, weights, &marks, &coverage));
+                    continue 'step3;
+                }
+                Step4::ContinueWithStep6 => {
+                    step6(weights, &coverage)?;
+                    snapshots.push(Snapshot::take(6, weights, &marks, &coverage));
+                }
+            }
+        }
+    }
+
+    let mut matching = Vec::with_capacity(n);
+    marks.each_star(|pos| matching.push(pos));
+    matching.sort_by_key(|pos| pos.row);
+    Ok(matching)
+}
+
+const CELL: usize = 48;
+
+/// Renders `snapshot` as an SVG image: the matrix with starred zeros in
+/// bold with a `*`, primed zeros with a `'`, and covered lines shaded.
+pub fn render_svg(snapshot: &Snapshot) -> String {
+    let n = snapshot.n;
+    let size = n * CELL;
+    let mut svg = String::new();
+    write!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" \
+         viewBox=\"0 0 {0} {1}\" font-family=\"monospace\" font-size=\"14\">",
+        size,
+        size + 24
+    )
+    .unwrap();
+    write!(
+        svg,
+        "<text x=\"0\" y=\"16\">{}</text><g transform=\"translate(0 24)\">",
+        escape(snapshot.description())
+    )
+    .unwrap();
+    for &row in &snapshot.covered_rows {
+        write!(
+            svg,
+            "<rect x=\"0\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#fdd\"/>",
+            row * CELL,
+            size,
+            CELL
+        )
+        .unwrap();
+    }
+    for &column in &snapshot.covered_columns {
+        write!(
+            svg,
+            "<rect x=\"{}\" y=\"0\" width=\"{}\" height=\"{}\" fill=\"#fdd\" fill-opacity=\"0.6\"/>",
+            column * CELL,
+            CELL,
+            size
+        )
+        .unwrap();
+    }
+    for row in 0..n {
+        for column in 0..n {
+            let pos = Position { row, column };
+            let (mark, weight) = if snapshot.stars.contains(&pos) {
+                ("*", "bold")
+            } else if snapshot.primes.contains(&pos) {
+                ("'", "normal")
+            } else {
+                ("", "normal")
+            };
+            write!(
+                svg,
+                "<rect x=\"{x}\" y=\"{y}\" width=\"{cell}\" height=\"{cell}\" fill=\"none\" \
+                 stroke=\"#999\"/><text x=\"{cx}\" y=\"{cy}\" text-anchor=\"middle\" \
+                 font-weight=\"{weight}\">{value}{mark}</text>",
+                x = column * CELL,
+                y = row * CELL,
+                cell = CELL,
+                cx = column * CELL + CELL / 2,
+                cy = row * CELL + CELL / 2 + 5,
+                weight = weight,
+                value = escape(&snapshot.values[row * n + column]),
+                mark = mark
+            )
+            .unwrap();
+        }
+    }
+    svg.push_str("</g></svg>");
+    svg
+}
+
+/// Renders all `snapshots` as a standalone HTML page showing one at a time,
+/// with buttons (and the arrow keys) to step through them.
+pub fn render_html(snapshots: &[Snapshot]) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\">\
+         <title>Hungarian algorithm</title></head><body>\n\
+         <p><button id=\"prev\">&larr;</button> <span id=\"index\"></span> \
+         <button id=\"next\">&rarr;</button></p>\n",
+    );
+    for snapshot in snapshots {
+        writeln!(
+            html,
+            "<div class=\"frame\" style=\"display:none\">{}</div>",
+            render_svg(snapshot)
+        )
+        .unwrap();
+    }
+    html.push_str(
+        "<script>\n\
+         var frames = document.getElementsByClassName('frame'), current = 0;\n\
+         function show(i) {\n\
+         if (frames.length == 0) return;\n\
+         current = Math.max(0, Math.min(frames.length - 1, i));\n\
+         for (var j = 0; j < frames.length; j++) frames[j].style.display = j == current ? '' : 'none';\n\
+         document.getElementById('index').textContent = (current + 1) + ' / ' + frames.length;\n\
+         }\n\
+         document.getElementById('prev').onclick = function () { show(current - 1); };\n\
+         document.getElementById('next').onclick = function () { show(current + 1); };\n\
+         document.onkeydown = function (e) {\n\
+         if (e.key == 'ArrowLeft') show(current - 1);\n\
+         if (e.key == 'ArrowRight') show(current + 1);\n\
+         };\n\
+         show(0);\n\
+         </script></body></html>\n",
+    );
+    html
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[test]
+fn test_solve_assignment_recorded() {
+    use crate::{solve_assignment, WeightMatrix};
+
+    let c = vec![250, 400, 350, 400, 600, 350, 200, 400, 250];
+    let (matching, snapshots) =
+        solve_assignment_recorded(&mut WeightMatrix::from_row_vec(3, c.clone()));
+    assert_eq!(
+        solve_assignment(&mut WeightMatrix::from_row_vec(3, c)),
+        matching
+    );
+    let steps: Vec<u8> = snapshots.iter().map(|s| s.step).collect();
+    assert_eq!(&[1, 2, 3], &steps[..3]);
+    assert_eq!(Some(&3), steps.last());
+    let last = snapshots.last().unwrap();
+    assert_eq!(matching.unwrap(), last.stars);
+    assert_eq!(vec![0, 1, 2], last.covered_columns);
+    assert_eq!(vec!["0", "150", "100"], snapshots[0].values[..3].to_vec());
+
+    let svg = render_svg(last);
+    assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+    assert_eq!(3, svg.matches("*</text>").count());
+    let html = render_html(&snapshots);
+    assert_eq!(snapshots.len(), html.matches("<svg").count());
+}
@@ -1,8 +1,23 @@
-use crate::{Position, SquareMatrix, WeightNum, Weights};
+use crate::bitset::BitSet;
+use crate::dense::DenseMatrix;
+#[cfg(any(feature = "std", feature = "libm"))]
+use crate::float;
+use crate::{Position, WeightNum, Weights};
+use alloc::vec::Vec;
+use core::mem;
 
-#[derive(Debug)]
+/// Below this size, the `par_` methods run serially, as splitting the rows
+/// doesn't pay off.
+#[cfg(feature = "rayon")]
+const PARALLEL_ROWS_THRESHOLD: usize = 256;
+
+#[derive(Clone, Debug)]
 pub struct WeightMatrix<T: WeightNum> {
-    c: SquareMatrix<T>,
+    c: DenseMatrix<T>,
+    /// Validity of each cell in row-major order, computed once at
+    /// construction so the hot loops don't call `is_valid` per element.
+    /// `None` if all cells are valid, which lets the loops vectorize.
+    valid: Option<BitSet>,
 }
 
 impl<T: WeightNum> Weights for WeightMatrix<T> {
@@ -10,7 +25,7 @@ impl<T: WeightNum> Weights for WeightMatrix<T> {
 
     #[inline(always)]
     fn n(&self) -> usize {
-        self.c.shape()[0]
+        self.c.n()
     }
 
     #[inline]
@@ -18,84 +33,545 @@ impl<T: WeightNum> Weights for WeightMatrix<T> {
         self.c[(pos.row, pos.column)]
     }
 
+    /// Masked cells hold arbitrary placeholders, so they are never zero.
+    #[inline]
+    fn is_element_zero(&self, pos: Position) -> bool {
+        self.is_valid_at(pos) && self.element_at(pos).is_zero()
+    }
+
+    #[inline]
+    fn is_valid_at(&self, pos: Position) -> bool {
+        match self.valid {
+            Some(ref valid) => valid.contains(pos.row * self.n() + pos.column),
+            None => true,
+        }
+    }
+
+    #[inline]
+    unsafe fn element_at_unchecked(&self, pos: Position) -> T {
+        *self.c.get_unchecked(pos.row, pos.column)
+    }
+
+    #[inline]
+    unsafe fn is_element_zero_unchecked(&self, pos: Position) -> bool {
+        self.is_valid_at_unchecked(pos) && self.element_at_unchecked(pos).is_zero()
+    }
+
+    #[inline]
+    unsafe fn is_valid_at_unchecked(&self, pos: Position) -> bool {
+        match self.valid {
+            Some(ref valid) => valid.contains_unchecked(pos.row * self.n() + pos.column),
+            None => true,
+        }
+    }
+
     // for each row, subtracts the minimum of that row from each other value in the
     // row.
     fn sub_min_of_each_row(&mut self) {
+        // rows without a valid cell are left to `is_solvable`.
         for row in 0..self.n() {
-            let min = self.min_of_row(row);
-            self.sub_row(row, min);
+            if let Some(min) = self.min_of_row(row) {
+                self.sub_row(row, min);
+            }
         }
     }
 
     // Add `val` to every element in row `row`.
     fn add_row(&mut self, row: usize, val: T) {
-        self.c
-            .row_mut(row)
-            .mapv_inplace(|cur| cur.add_if_valid(val));
+        match self.valid {
+            Some(_) => self.map_valid_in_row(row, |cur| cur + val),
+            None => T::add_to_valid(self.c.row_mut(row), val),
+        }
     }
 
     // Subtract `val` from every element in column `col`.
     fn sub_column(&mut self, col: usize, val: T) {
-        self.c
-            .column_mut(col)
-            .mapv_inplace(|cur| cur.sub_if_valid(val));
+        let n = self.n();
+        match self.valid {
+            Some(ref valid) => {
+                for (row, cur) in self.c.column_mut(col).enumerate() {
+                    if valid.contains(row * n + col) {
+                        *cur = *cur - val;
+                    }
+                }
+            }
+            None => self.c.column_mut(col).for_each(|cur| *cur = *cur - val),
+        }
     }
 
     fn is_solvable(&self) -> bool {
-        for row in self.c.genrows() {
-            if row.iter().all(|c| !c.is_valid()) {
-                return false;
-            }
-        }
-        true
+        let n = self.n();
+        (0..n).all(|row| (0..n).any(|column| self.is_valid_at(Position { row, column })))
     }
 }
 
 impl<T: WeightNum> WeightMatrix<T> {
     pub fn from_row_vec(n: usize, data: Vec<T>) -> WeightMatrix<T> {
-        WeightMatrix {
-            c: SquareMatrix::from_shape_vec((n, n), data).unwrap(),
-        }
+        WeightMatrix::from_dense(DenseMatrix::from_row_vec(n, data))
+    }
+
+    /// Like `from_row_vec`, but takes `data` in column-major order, as used
+    /// by Fortran-order NumPy arrays and BLAS. Transposes it in place.
+    pub fn from_column_vec(n: usize, data: Vec<T>) -> WeightMatrix<T> {
+        let mut c = DenseMatrix::from_row_vec(n, data);
+        c.transpose();
+        WeightMatrix::from_dense(c)
+    }
+
+    /// Copies a square ndarray matrix of any memory layout, e.g. a
+    /// transposed or sliced view, falling back to iteration if it isn't
+    /// contiguous in row-major order.
+    ///
+    /// # Panics
+    ///
+    /// If `array` is not square.
+    #[cfg(feature = "ndarray")]
+    pub fn from_array<S>(array: &ndarray::ArrayBase<S, ndarray::Ix2>) -> WeightMatrix<T>
+    where
+        S: ndarray::Data<Elem = T>,
+    {
+        let (rows, columns) = array.dim();
+        assert!(rows == columns, "expected a square matrix");
+        let data = match array.as_slice() {
+            Some(slice) => slice.to_vec(),
+            None => array.iter().copied().collect(),
+        };
+        WeightMatrix::from_row_vec(rows, data)
     }
 
-    pub fn from_fn<F: Fn((usize, usize)) -> T>(n: usize, f: F) -> WeightMatrix<T> {
+    pub fn from_fn<F: FnMut((usize, usize)) -> T>(n: usize, f: F) -> WeightMatrix<T> {
         assert!(n > 0);
-        WeightMatrix {
-            c: SquareMatrix::from_shape_fn((n, n), f),
+        WeightMatrix::from_dense(DenseMatrix::from_fn(n, f))
+    }
+
+    /// Like `from_fn`, but calls `f` for the rows in parallel.
+    #[cfg(feature = "rayon")]
+    pub fn par_from_fn<F>(n: usize, f: F) -> WeightMatrix<T>
+    where
+        F: Fn((usize, usize)) -> T + Sync,
+        T: Send,
+    {
+        use rayon::prelude::*;
+
+        assert!(n > 0);
+        let data = (0..n)
+            .into_par_iter()
+            .flat_map_iter(|row| (0..n).map(move |column| (row, column)).map(&f))
+            .collect();
+        WeightMatrix::from_row_vec(n, data)
+    }
+
+    /// Like `par_from_fn`, but each thread writes the rows it computes
+    /// directly into the matrix instead of collecting them first. Linux
+    /// places a page on the NUMA node of the thread that first writes to it,
+    /// so on multi-socket machines the rows end up local to the threads that
+    /// later reduce them in `par_sub_min_of_each_row`, rather than all on
+    /// the node of the thread that allocated the matrix.
+    #[cfg(feature = "numa")]
+    pub fn par_from_fn_local<F>(n: usize, f: F) -> WeightMatrix<T>
+    where
+        F: Fn((usize, usize)) -> T + Sync,
+        T: Send,
+    {
+        use rayon::prelude::*;
+
+        assert!(n > 0);
+        let mut data = Vec::with_capacity(n * n);
+        data.spare_capacity_mut()[..n * n]
+            .par_chunks_mut(n)
+            .enumerate()
+            .for_each(|(row, cells)| {
+                for (column, cell) in cells.iter_mut().enumerate() {
+                    cell.write(f((row, column)));
+                }
+            });
+        // safe, as all `n * n` cells were written above.
+        unsafe { data.set_len(n * n) };
+        WeightMatrix::from_row_vec(n, data)
+    }
+
+    /// Like `sub_min_of_each_row`, reducing the rows in parallel on large
+    /// matrices.
+    #[cfg(feature = "rayon")]
+    pub fn par_sub_min_of_each_row(&mut self)
+    where
+        T: Send + Sync,
+    {
+        use rayon::prelude::*;
+
+        let n = self.n();
+        if n < PARALLEL_ROWS_THRESHOLD {
+            return self.sub_min_of_each_row();
         }
+        let valid = self.valid.as_ref();
+        let is_valid =
+            |row: usize, column: usize| valid.is_none_or(|v| v.contains(row * n + column));
+        self.c
+            .as_mut_slice()
+            .par_chunks_mut(n)
+            .enumerate()
+            .for_each(|(row, cells)| {
+                let min = cells
+                    .iter()
+                    .enumerate()
+                    .filter(|&(column, _)| is_valid(row, column))
+                    .map(|(_, &cost)| cost)
+                    .reduce(|min, val| if val < min { val } else { min });
+                // rows without a valid cell are left to `is_solvable`.
+                let Some(min) = min else {
+                    return;
+                };
+                for (column, cur) in cells.iter_mut().enumerate() {
+                    if is_valid(row, column) {
+                        *cur = *cur - min;
+                    }
+                }
+            });
     }
 
-    /// Return the minimum element of row `row`.
-    fn min_of_row(&self, row: usize) -> T {
-        let row_iter = self.c.row(row);
-        let mut valid_iter = row_iter.iter().filter(|cost| cost.is_valid()).cloned();
-        let first_min = valid_iter.next().unwrap();
-        valid_iter.fold(
-            first_min,
-            |total_min, val| if val < total_min { val } else { total_min },
-        )
+    /// Like `is_solvable`, checking the rows in parallel on large matrices.
+    #[cfg(feature = "rayon")]
+    pub fn par_is_solvable(&self) -> bool
+    where
+        T: Send + Sync,
+    {
+        use rayon::prelude::*;
+
+        let n = self.n();
+        if n < PARALLEL_ROWS_THRESHOLD {
+            return self.is_solvable();
+        }
+        (0..n)
+            .into_par_iter()
+            .all(|row| (0..n).any(|column| self.is_valid_at(Position { row, column })))
     }
 
-    // Subtract `val` from every element in row `row`.
-    fn sub_row(&mut self, row: usize, val: T) {
+    /// Like `from_row_vec`, with `None` for the forbidden cells.
+    pub fn from_row_options(n: usize, data: Vec<Option<T>>) -> WeightMatrix<T>
+    where
+        T: Default,
+    {
+        assert!(data.len() == n * n);
+        let mut valid = BitSet::with_capacity(data.len());
+        for (index, cost) in data.iter().enumerate() {
+            valid.set(index, cost.is_some_and(|cost| cost.is_valid()));
+        }
+        let c =
+            DenseMatrix::from_row_vec(n, data.into_iter().map(Option::unwrap_or_default).collect());
+        let valid = if valid.count_ones() == c.as_slice().len() {
+            None
+        } else {
+            Some(valid)
+        };
+        WeightMatrix { c, valid }
+    }
+
+    /// Like `from_row_vec`, with the validity of the cells given separately
+    /// in `valid`, in row-major order. A cell is valid if it's valid in
+    /// `valid` and its value is valid, so integer costs need no sentinel
+    /// for forbidden cells.
+    ///
+    /// # Panics
+    ///
+    /// If `data` or `valid` doesn't have `n * n` elements.
+    pub fn from_row_vec_with_validity(n: usize, data: Vec<T>, valid: &[bool]) -> WeightMatrix<T> {
+        assert!(valid.len() == n * n);
+        let c = DenseMatrix::from_row_vec(n, data);
+        let mut validity = BitSet::with_capacity(valid.len());
+        for (index, (&cost, &valid)) in c.as_slice().iter().zip(valid).enumerate() {
+            validity.set(index, valid && cost.is_valid());
+        }
+        let valid = if validity.count_ones() == valid.len() {
+            None
+        } else {
+            Some(validity)
+        };
+        WeightMatrix { c, valid }
+    }
+
+    /// Forbids the cell at `pos`, whatever its value, so integer matrices
+    /// built by `from_fn` or `from_row_vec` need no sentinel for the cells
+    /// that must never be assigned. Its value is left alone and never
+    /// modified by the solve.
+    ///
+    /// # Panics
+    ///
+    /// If `pos` is out of range.
+    pub fn forbid(&mut self, pos: Position) {
+        let n = self.n();
+        assert!(pos.row < n && pos.column < n, "cell out of range");
+        let valid = self.valid.get_or_insert_with(|| {
+            let mut valid = BitSet::with_capacity(n * n);
+            valid.set_all(true);
+            valid
+        });
+        valid.set(pos.row * n + pos.column, false);
+    }
+
+    /// The validity of each cell in row-major order, e.g. to inspect the
+    /// structure of the allowed cells.
+    pub fn validity(&self) -> Vec<bool> {
+        let n = self.n();
+        (0..n * n)
+            .map(|index| {
+                self.is_valid_at(Position {
+                    row: index / n,
+                    column: index % n,
+                })
+            })
+            .collect()
+    }
+
+    fn from_dense(c: DenseMatrix<T>) -> WeightMatrix<T> {
+        let valid = if c.as_slice().iter().all(|cost| cost.is_valid()) {
+            None
+        } else {
+            let mut valid = BitSet::with_capacity(c.as_slice().len());
+            for (index, cost) in c.as_slice().iter().enumerate() {
+                valid.set(index, cost.is_valid());
+            }
+            Some(valid)
+        };
+        WeightMatrix { c, valid }
+    }
+
+    /// Replaces each valid element `x` of row `row` by `f(x)`.
+    #[inline]
+    fn map_valid_in_row<F: Fn(T) -> T>(&mut self, row: usize, f: F) {
+        let n = self.n();
+        let cells = self.c.row_mut(row).iter_mut();
+        match self.valid {
+            Some(ref valid) => {
+                for (column, cur) in cells.enumerate() {
+                    if valid.contains(row * n + column) {
+                        *cur = f(*cur);
+                    }
+                }
+            }
+            None => cells.for_each(|cur| *cur = f(*cur)),
+        }
+    }
+
+    /// Return the minimum valid element of row `row`, `None` if it has no
+    /// valid element.
+    fn min_of_row(&self, row: usize) -> Option<T> {
+        if self.valid.is_none() {
+            return T::min_of_valid(self.c.row(row));
+        }
         self.c
-            .row_mut(row)
-            .mapv_inplace(|cur| cur.sub_if_valid(val));
+            .row(row)
+            .iter()
+            .enumerate()
+            // safe, as `row` is checked by `row()` and `column` is within it.
+            .filter(|&(column, _)| unsafe { self.is_valid_at_unchecked(Position { row, column }) })
+            .map(|(_, &cost)| cost)
+            .reduce(|total_min, val| if val < total_min { val } else { total_min })
+    }
+
+    // Subtract `val` from every element in row `row`.
+    pub(crate) fn sub_row(&mut self, row: usize, val: T) {
+        match self.valid {
+            Some(_) => self.map_valid_in_row(row, |cur| cur - val),
+            None => T::sub_from_valid(self.c.row_mut(row), val),
+        }
     }
 
     pub fn as_slice(&self) -> &[T] {
-        self.c.as_slice().unwrap()
+        self.c.as_slice()
+    }
+
+    /// The bytes of the elements and of the validity of the cells.
+    pub fn memory_usage(&self) -> usize {
+        mem::size_of_val(self.c.as_slice()) + self.valid.as_ref().map_or(0, BitSet::memory_usage)
+    }
+
+    /// The bytes of a matrix of size `n`. The validity of the cells takes
+    /// another bit per cell if any is invalid.
+    pub fn estimated_bytes(n: usize, with_invalid_cells: bool) -> usize {
+        let validity = if with_invalid_cells {
+            BitSet::estimated_bytes(n * n)
+        } else {
+            0
+        };
+        n * n * mem::size_of::<T>() + validity
+    }
+
+    /// Resizes the matrix to `n x n` in place, reusing its allocation. The
+    /// cells of the block both sizes share keep their values and validity,
+    /// the others are set to `f((row, column))`, e.g. when the number of
+    /// tracked objects changes between frames.
+    pub fn resize_with<F: FnMut((usize, usize)) -> T>(&mut self, n: usize, f: F) {
+        let old = self.n();
+        let kept = old.min(n);
+        self.c.resize_with(n, f);
+        let mut valid = BitSet::with_capacity(n * n);
+        for row in 0..n {
+            for column in 0..n {
+                let was_valid = row >= kept
+                    || column >= kept
+                    || self
+                        .valid
+                        .as_ref()
+                        .is_none_or(|valid| valid.contains(row * old + column));
+                valid.set(
+                    row * n + column,
+                    was_valid && self.c[(row, column)].is_valid(),
+                );
+            }
+        }
+        self.valid = if valid.count_ones() == n * n {
+            None
+        } else {
+            Some(valid)
+        };
+    }
+
+    /// The elements in row-major order, reduced if the matrix was solved.
+    pub fn into_vec(self) -> Vec<T> {
+        self.c.into_vec()
+    }
+}
+
+/// Normalizations of the valid cells of float matrices, for fusing costs
+/// from sources with different units. Invalid cells are left unchanged and
+/// don't contribute to the statistics. The statistics are computed in `f64`.
+macro_rules! impl_normalization {
+    ($t:ty) => {
+        impl WeightMatrix<$t> {
+            /// The smallest and largest valid values of `row`, or of all
+            /// rows if `None`.
+            fn valid_range(&self, row: Option<usize>) -> Option<(f64, f64)> {
+                let rows = row.map_or(0..self.n(), |row| row..row + 1);
+                rows.flat_map(|row| (0..self.n()).map(move |column| Position { row, column }))
+                    .filter(|&pos| self.is_valid_at(pos))
+                    .map(|pos| self.element_at(pos) as f64)
+                    .fold(None, |range, x| match range {
+                        Some((min, max)) => Some((x.min(min), x.max(max))),
+                        None => Some((x, x)),
+                    })
+            }
+
+            /// Maps the valid values linearly to `[0, 1]`, or to 0 if they
+            /// are all equal.
+            pub fn min_max_scale(&mut self) {
+                if let Some((min, max)) = self.valid_range(None) {
+                    for row in 0..self.n() {
+                        self.map_valid_in_row(row, |x| scale(x as f64, min, max) as $t);
+                    }
+                }
+            }
+
+            /// Like `min_max_scale`, for each row separately.
+            pub fn min_max_scale_rows(&mut self) {
+                for row in 0..self.n() {
+                    if let Some((min, max)) = self.valid_range(Some(row)) {
+                        self.map_valid_in_row(row, |x| scale(x as f64, min, max) as $t);
+                    }
+                }
+            }
+
+            /// Maps the valid values to their z-score `(x - mean) / std`, or
+            /// to 0 if they are all equal.
+            #[cfg(any(feature = "std", feature = "libm"))]
+            pub fn z_score(&mut self) {
+                let n = self.n();
+                let valid: Vec<f64> = (0..n * n)
+                    .map(|i| Position {
+                        row: i / n,
+                        column: i % n,
+                    })
+                    .filter(|&pos| self.is_valid_at(pos))
+                    .map(|pos| self.element_at(pos) as f64)
+                    .collect();
+                if valid.is_empty() {
+                    return;
+                }
+                let mean = valid.iter().sum::<f64>() / valid.len() as f64;
+                let variance =
+                    valid.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / valid.len() as f64;
+                let std = float::sqrt(variance);
+                for row in 0..n {
+                    self.map_valid_in_row(row, |x| {
+                        if std > 0.0 {
+                            ((x as f64 - mean) / std) as $t
+                        } else {
+                            0.0
+                        }
+                    });
+                }
+            }
+        }
+    };
+}
+
+/// Maps `x` from `[min, max]` to `[0, 1]`.
+fn scale(x: f64, min: f64, max: f64) -> f64 {
+    if max > min {
+        (x - min) / (max - min)
+    } else {
+        0.0
     }
 }
 
+impl_normalization!(f32);
+impl_normalization!(f64);
+
+/// Builds a `WeightMatrix` from rows of cells, with `_` for forbidden cells.
+///
+/// ```
+/// let weights = munkres::cost_matrix![
+///     [1, 2, _],
+///     [4, _, 6],
+///     [7, 8, 9],
+/// ];
+/// ```
+///
+/// # Panics
+///
+/// If the matrix is not square.
+#[macro_export]
+macro_rules! cost_matrix {
+    (@row [$($cells:tt)*]) => {
+        $crate::__private::vec![$($cells)*]
+    };
+    (@row [$($cells:tt)*] _ $(, $($rest:tt)*)?) => {
+        $crate::cost_matrix!(@row [$($cells)* ::core::option::Option::None,] $($($rest)*)?)
+    };
+    (@row [$($cells:tt)*] $cell:expr $(, $($rest:tt)*)?) => {
+        $crate::cost_matrix!(@row [$($cells)* ::core::option::Option::Some($cell),] $($($rest)*)?)
+    };
+    ($([$($row:tt)*]),+ $(,)?) => {{
+        let rows: $crate::__private::Vec<$crate::__private::Vec<_>> =
+            $crate::__private::vec![$($crate::cost_matrix!(@row [] $($row)*)),+];
+        let n = rows.len();
+        assert!(
+            rows.iter().all(|row| row.len() == n),
+            "cost_matrix! requires a square matrix"
+        );
+        $crate::WeightMatrix::from_row_options(n, rows.into_iter().flatten().collect())
+    }};
+}
+
 #[test]
 fn test_weight_matrix() {
-    assert_eq!(0, WeightMatrix::from_row_vec(1, vec![0]).min_of_row(0));
-    assert_eq!(1, WeightMatrix::from_row_vec(1, vec![1]).min_of_row(0));
     assert_eq!(
-        1,
+        Some(0),
+        WeightMatrix::from_row_vec(1, vec![0]).min_of_row(0)
+    );
+    assert_eq!(
+        Some(1),
+        WeightMatrix::from_row_vec(1, vec![1]).min_of_row(0)
+    );
+    assert_eq!(
+        Some(1),
         WeightMatrix::from_row_vec(2, vec![5, 1, 0, 0]).min_of_row(0)
     );
+    let inf = f64::INFINITY;
+    let mut mat = WeightMatrix::from_row_vec(2, vec![inf, inf, 3.0, 2.0]);
+    assert_eq!(None, mat.min_of_row(0));
+    mat.sub_min_of_each_row();
+    assert_eq!(&[inf, inf, 1.0, 0.0], mat.as_slice());
+    assert!(!mat.is_solvable());
 
     let mut mat = WeightMatrix::from_row_vec(2, vec![0, 1, 2, 3]);
     mat.sub_row(1, 1);
@@ -104,4 +580,226 @@ fn test_weight_matrix() {
     let mut mat = WeightMatrix::from_row_vec(2, vec![5, 3, 2, 3]);
     mat.sub_min_of_each_row();
     assert_eq!(&[2, 0, 0, 1], mat.as_slice());
+
+    let mat = WeightMatrix::from_column_vec(3, vec![1, 4, 7, 2, 5, 8, 3, 6, 9]);
+    assert_eq!(&[1, 2, 3, 4, 5, 6, 7, 8, 9], mat.as_slice());
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_par_from_fn() {
+    let f = |(row, column)| (row * 31 + column * 7) % 13;
+    let parallel = WeightMatrix::par_from_fn(50, f);
+    assert_eq!(WeightMatrix::from_fn(50, f).as_slice(), parallel.as_slice());
+}
+
+#[cfg(feature = "numa")]
+#[test]
+fn test_par_from_fn_local() {
+    let f = |(row, column)| (row * 31 + column * 7) % 13;
+    let local = WeightMatrix::par_from_fn_local(50, f);
+    assert_eq!(WeightMatrix::from_fn(50, f).as_slice(), local.as_slice());
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_par_sub_min_of_each_row() {
+    let n = PARALLEL_ROWS_THRESHOLD + 3;
+    let data: Vec<u32> = (0..n * n).map(|i| (i * 7919 % 1009) as u32 + 5).collect();
+    let valid: Vec<bool> = (0..n * n).map(|i| i % 11 != 0).collect();
+    let mut serial = WeightMatrix::from_row_vec_with_validity(n, data, &valid);
+    let mut parallel = serial.clone();
+    assert!(parallel.par_is_solvable());
+    serial.sub_min_of_each_row();
+    parallel.par_sub_min_of_each_row();
+    assert_eq!(serial.as_slice(), parallel.as_slice());
+
+    // row 7 has no valid cell and is skipped.
+    let valid: Vec<bool> = (0..n * n).map(|i| i / n != 7).collect();
+    let data: Vec<u32> = (0..n * n).map(|i| (i % n) as u32 + 1).collect();
+    let mut serial = WeightMatrix::from_row_vec_with_validity(n, data.clone(), &valid);
+    let mut parallel = serial.clone();
+    assert!(!parallel.par_is_solvable());
+    serial.sub_min_of_each_row();
+    parallel.par_sub_min_of_each_row();
+    assert_eq!(serial.as_slice(), parallel.as_slice());
+    assert_eq!(&data[7 * n..8 * n], &parallel.as_slice()[7 * n..8 * n]);
+    assert_eq!(0, parallel.as_slice()[0]);
+}
+
+#[cfg(feature = "ndarray")]
+#[test]
+fn test_from_array() {
+    use ndarray::{s, Array2};
+
+    let array = Array2::from_shape_fn((4, 4), |(row, column)| row * 4 + column);
+    assert_eq!(
+        array.as_slice().unwrap(),
+        WeightMatrix::from_array(&array).as_slice()
+    );
+    let transposed = WeightMatrix::from_array(&array.t());
+    assert_eq!(&[0, 4, 8, 12], &transposed.as_slice()[..4]);
+    let sliced = WeightMatrix::from_array(&array.slice(s![1..3, ..;2]));
+    assert_eq!(&[4, 6, 8, 10], sliced.as_slice());
+    let owned = array.t().to_owned();
+    assert_eq!(
+        transposed.as_slice(),
+        WeightMatrix::from_array(&owned).as_slice()
+    );
+}
+
+#[test]
+fn test_weight_matrix_validity_mask() {
+    let inf = f32::INFINITY;
+    let mut mat = WeightMatrix::from_row_vec(2, vec![inf, 2.0, 3.0, inf]);
+    assert!(!mat.is_valid_at(Position { row: 0, column: 0 }));
+    assert!(mat.is_valid_at(Position { row: 0, column: 1 }));
+    mat.sub_min_of_each_row();
+    mat.add_row(0, 1.0);
+    mat.sub_column(0, 1.0);
+    assert_eq!(&[inf, 1.0, -1.0, inf], mat.as_slice());
+    assert!(mat.is_solvable());
+    assert!(!WeightMatrix::from_row_vec(2, vec![inf, inf, 1.0, 2.0]).is_solvable());
+}
+
+#[test]
+fn test_cost_matrix() {
+    let weights = cost_matrix![[1, 2, _], [-4, _, 6], [7, 8, 9],];
+    assert_eq!(&[1, 2, 0, -4, 0, 6, 7, 8, 9], weights.as_slice());
+    assert!(!weights.is_valid_at(Position { row: 1, column: 1 }));
+    assert!(weights.is_valid_at(Position { row: 1, column: 0 }));
+
+    let weights: WeightMatrix<f64> = cost_matrix![[1.5, f64::INFINITY], [2.0 * 2.0, 3.0]];
+    assert_eq!(&[1.5, f64::INFINITY, 4.0, 3.0], weights.as_slice());
+    assert!(weights.valid.is_some());
+    assert!(cost_matrix![[1]].valid.is_none());
+}
+
+#[test]
+fn test_normalization() {
+    let inf = f64::INFINITY;
+    let mut weights = WeightMatrix::from_row_vec(2, vec![2.0, 4.0, inf, 10.0]);
+    weights.min_max_scale();
+    assert_eq!(&[0.0, 0.25, inf, 1.0], weights.as_slice());
+
+    let mut weights = WeightMatrix::from_row_vec(2, vec![2.0f32, 4.0, 5.0, 5.0]);
+    weights.min_max_scale_rows();
+    assert_eq!(&[0.0, 1.0, 0.0, 0.0], weights.as_slice());
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+#[test]
+fn test_z_score() {
+    let inf = f64::INFINITY;
+    let mut weights = WeightMatrix::from_row_vec(2, vec![1.0, inf, 3.0, 2.0]);
+    weights.z_score();
+    let sqrt = (2.0f64 / 3.0).sqrt();
+    assert_eq!(&[-1.0 / sqrt, inf, 1.0 / sqrt, 0.0], weights.as_slice());
+
+    // forbidden cells keep their placeholder and don't count.
+    let mut weights: WeightMatrix<f64> = cost_matrix![[_, 1.0], [1.0, 1.0]];
+    weights.z_score();
+    assert_eq!(&[0.0, 0.0, 0.0, 0.0], weights.as_slice());
+    assert!(!weights.is_valid_at(Position { row: 0, column: 0 }));
+}
+
+#[test]
+fn test_forbid() {
+    use crate::{solve_assignment, Error};
+
+    let pos = |row, column| Position { row, column };
+    // the forbidden cells hold the largest values, which step 6 would
+    // overflow if they were adjusted.
+    let mut weights = WeightMatrix::from_fn(3, |(row, column)| {
+        if row == column {
+            u64::MAX
+        } else {
+            (row * 3 + column) as u64
+        }
+    });
+    for i in 0..3 {
+        weights.forbid(pos(i, i));
+    }
+    assert_eq!(
+        vec![false, true, true, true, false, true, true, true, false],
+        weights.validity()
+    );
+    let matching = solve_assignment(&mut weights.clone()).unwrap();
+    assert!(matching.iter().all(|pos| pos.row != pos.column));
+    assert_eq!(
+        12,
+        matching
+            .iter()
+            .map(|&pos| weights.element_at(pos))
+            .sum::<u64>()
+    );
+
+    weights.forbid(pos(0, 1));
+    weights.forbid(pos(0, 2));
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_assignment(&mut weights)
+    );
+}
+
+#[test]
+fn test_from_row_vec_with_validity() {
+    use crate::{solve_assignment, Error};
+
+    let valid = [false, true, true, true];
+    let mut weights = WeightMatrix::from_row_vec_with_validity(2, vec![0u32, 5, 1, 5], &valid);
+    assert_eq!(valid.to_vec(), weights.validity());
+    assert_eq!(
+        Ok(vec![
+            Position { row: 0, column: 1 },
+            Position { row: 1, column: 0 }
+        ]),
+        solve_assignment(&mut weights)
+    );
+
+    // masked placeholders that happen to be 0 are never assigned.
+    let valid = [true, true, true, true, true, true, false, false, true];
+    let data = vec![5, 9, 1, 0, 0, 0, 0, 0, 0];
+    let mut weights = WeightMatrix::from_row_vec_with_validity(3, data, &valid);
+    let matching = solve_assignment(&mut weights).unwrap();
+    assert!(matching.iter().all(|&pos| valid[pos.row * 3 + pos.column]));
+
+    // invalid values stay invalid.
+    let weights =
+        WeightMatrix::from_row_vec_with_validity(2, vec![f64::NAN, 1.0, 1.0, 1.0], &[true; 4]);
+    assert_eq!(vec![false, true, true, true], weights.validity());
+    let mut weights =
+        WeightMatrix::from_row_vec_with_validity(2, vec![1, 1, 1, 1], &[true, false, true, false]);
+    assert_eq!(
+        Err(Error::MatrixNotSolvable),
+        solve_assignment(&mut weights)
+    );
+}
+
+#[test]
+fn test_resize_with() {
+    let mut weights = WeightMatrix::from_row_vec(2, vec![1, 2, 3, 4]);
+    weights.resize_with(3, |(row, column)| 10 * row + column);
+    assert_eq!(&[1, 2, 2, 3, 4, 12, 20, 21, 22], weights.as_slice());
+    let ptr = weights.as_slice().as_ptr();
+    weights.resize_with(2, |_| unreachable!());
+    assert_eq!(&[1, 2, 3, 4], weights.as_slice());
+    assert_eq!(ptr, weights.as_slice().as_ptr());
+
+    // the validity of the shared block is kept, new cells get their own.
+    let inf = f64::INFINITY;
+    let mut weights = WeightMatrix::from_row_vec_with_validity(
+        2,
+        vec![1.0, 2.0, 3.0, 4.0],
+        &[true, false, true, true],
+    );
+    weights.resize_with(3, |(row, _)| if row == 2 { inf } else { 5.0 });
+    let expected = [true, false, true, true, true, true, false, false, false];
+    assert_eq!(&expected[..], &weights.validity()[..]);
+    weights.resize_with(1, |_| unreachable!());
+    assert_eq!(vec![true], weights.validity());
+
+    let mut weights = WeightMatrix::from_row_vec(0, Vec::<u32>::new());
+    weights.resize_with(2, |(row, column)| (row + column) as u32);
+    assert_eq!(&[0, 1, 1, 2], weights.as_slice());
 }
@@ -1,5 +1,7 @@
-use std::ops::{Add, Sub};
-use std::{f32, f64};
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::ops::{Add, Sub};
+use core::{f32, f64};
 
 pub trait WeightNum: PartialOrd + Copy + Sub<Output = Self> + Add<Output = Self> {
     fn is_zero(&self) -> bool;
@@ -20,6 +22,39 @@ pub trait WeightNum: PartialOrd + Copy + Sub<Output = Self> + Add<Output = Self>
             self
         }
     }
+    /// The value as `i64`, if it is an integer representable as such. Used by
+    /// the integer-only backends.
+    fn to_i64(&self) -> Option<i64> {
+        None
+    }
+    /// The value as `f64`, possibly rounded. Used by the floating point
+    /// backends.
+    fn to_f64(&self) -> Option<f64> {
+        None
+    }
+    /// The minimum of `values`, which must all be valid. The row loops of
+    /// `WeightMatrix` go through these slice functions, so that they can be
+    /// vectorized per type, e.g. for `f32` and `f64` on wasm32 with SIMD128.
+    #[doc(hidden)]
+    #[inline]
+    fn min_of_valid(values: &[Self]) -> Option<Self> {
+        values
+            .iter()
+            .copied()
+            .reduce(|min, val| if val < min { val } else { min })
+    }
+    /// Adds `val` to each of `values`, which must all be valid.
+    #[doc(hidden)]
+    #[inline]
+    fn add_to_valid(values: &mut [Self], val: Self) {
+        values.iter_mut().for_each(|cur| *cur = *cur + val);
+    }
+    /// Subtracts `val` from each of `values`, which must all be valid.
+    #[doc(hidden)]
+    #[inline]
+    fn sub_from_valid(values: &mut [Self], val: Self) {
+        values.iter_mut().for_each(|cur| *cur = *cur - val);
+    }
 }
 
 impl WeightNum for usize {
@@ -27,6 +62,15 @@ impl WeightNum for usize {
     fn is_zero(&self) -> bool {
         *self == 0
     }
+    #[inline(always)]
+    fn to_i64(&self) -> Option<i64> {
+        i64::try_from(*self).ok()
+    }
+
+    #[inline(always)]
+    fn to_f64(&self) -> Option<f64> {
+        Some(*self as f64)
+    }
 }
 
 impl WeightNum for isize {
@@ -34,6 +78,15 @@ impl WeightNum for isize {
     fn is_zero(&self) -> bool {
         *self == 0
     }
+    #[inline(always)]
+    fn to_i64(&self) -> Option<i64> {
+        i64::try_from(*self).ok()
+    }
+
+    #[inline(always)]
+    fn to_f64(&self) -> Option<f64> {
+        Some(*self as f64)
+    }
 }
 
 impl WeightNum for u64 {
@@ -41,6 +94,15 @@ impl WeightNum for u64 {
     fn is_zero(&self) -> bool {
         *self == 0
     }
+    #[inline(always)]
+    fn to_i64(&self) -> Option<i64> {
+        i64::try_from(*self).ok()
+    }
+
+    #[inline(always)]
+    fn to_f64(&self) -> Option<f64> {
+        Some(*self as f64)
+    }
 }
 
 impl WeightNum for i64 {
@@ -48,6 +110,15 @@ impl WeightNum for i64 {
     fn is_zero(&self) -> bool {
         *self == 0
     }
+    #[inline(always)]
+    fn to_i64(&self) -> Option<i64> {
+        Some(*self)
+    }
+
+    #[inline(always)]
+    fn to_f64(&self) -> Option<f64> {
+        Some(*self as f64)
+    }
 }
 
 impl WeightNum for u32 {
@@ -55,6 +126,15 @@ impl WeightNum for u32 {
     fn is_zero(&self) -> bool {
         *self == 0
     }
+    #[inline(always)]
+    fn to_i64(&self) -> Option<i64> {
+        Some(i64::from(*self))
+    }
+
+    #[inline(always)]
+    fn to_f64(&self) -> Option<f64> {
+        Some(f64::from(*self))
+    }
 }
 
 impl WeightNum for i32 {
@@ -62,6 +142,15 @@ impl WeightNum for i32 {
     fn is_zero(&self) -> bool {
         *self == 0
     }
+    #[inline(always)]
+    fn to_i64(&self) -> Option<i64> {
+        Some(i64::from(*self))
+    }
+
+    #[inline(always)]
+    fn to_f64(&self) -> Option<f64> {
+        Some(f64::from(*self))
+    }
 }
 
 impl WeightNum for u16 {
@@ -69,6 +158,15 @@ impl WeightNum for u16 {
     fn is_zero(&self) -> bool {
         *self == 0
     }
+    #[inline(always)]
+    fn to_i64(&self) -> Option<i64> {
+        Some(i64::from(*self))
+    }
+
+    #[inline(always)]
+    fn to_f64(&self) -> Option<f64> {
+        Some(f64::from(*self))
+    }
 }
 
 impl WeightNum for i16 {
@@ -76,6 +174,15 @@ impl WeightNum for i16 {
     fn is_zero(&self) -> bool {
         *self == 0
     }
+    #[inline(always)]
+    fn to_i64(&self) -> Option<i64> {
+        Some(i64::from(*self))
+    }
+
+    #[inline(always)]
+    fn to_f64(&self) -> Option<f64> {
+        Some(f64::from(*self))
+    }
 }
 
 impl WeightNum for u8 {
@@ -83,6 +190,15 @@ impl WeightNum for u8 {
     fn is_zero(&self) -> bool {
         *self == 0
     }
+    #[inline(always)]
+    fn to_i64(&self) -> Option<i64> {
+        Some(i64::from(*self))
+    }
+
+    #[inline(always)]
+    fn to_f64(&self) -> Option<f64> {
+        Some(f64::from(*self))
+    }
 }
 
 impl WeightNum for i8 {
@@ -90,6 +206,15 @@ impl WeightNum for i8 {
     fn is_zero(&self) -> bool {
         *self == 0
     }
+    #[inline(always)]
+    fn to_i64(&self) -> Option<i64> {
+        Some(i64::from(*self))
+    }
+
+    #[inline(always)]
+    fn to_f64(&self) -> Option<f64> {
+        Some(f64::from(*self))
+    }
 }
 
 impl WeightNum for f64 {
@@ -98,10 +223,33 @@ impl WeightNum for f64 {
         *self == 0.0
     }
 
+    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+    #[inline]
+    fn min_of_valid(values: &[f64]) -> Option<f64> {
+        crate::simd::min_f64(values)
+    }
+
+    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+    #[inline]
+    fn add_to_valid(values: &mut [f64], val: f64) {
+        crate::simd::add_f64(values, val)
+    }
+
+    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+    #[inline]
+    fn sub_from_valid(values: &mut [f64], val: f64) {
+        crate::simd::sub_f64(values, val)
+    }
+
     #[inline(always)]
     fn is_valid(&self) -> bool {
         self.is_finite()
     }
+
+    #[inline(always)]
+    fn to_f64(&self) -> Option<f64> {
+        Some(*self)
+    }
 }
 
 impl WeightNum for f32 {
@@ -110,8 +258,270 @@ impl WeightNum for f32 {
         *self == 0.0
     }
 
+    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+    #[inline]
+    fn min_of_valid(values: &[f32]) -> Option<f32> {
+        crate::simd::min_f32(values)
+    }
+
+    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+    #[inline]
+    fn add_to_valid(values: &mut [f32], val: f32) {
+        crate::simd::add_f32(values, val)
+    }
+
+    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+    #[inline]
+    fn sub_from_valid(values: &mut [f32], val: f32) {
+        crate::simd::sub_f32(values, val)
+    }
+
     #[inline(always)]
     fn is_valid(&self) -> bool {
         self.is_finite()
     }
+
+    #[inline(always)]
+    fn to_f64(&self) -> Option<f64> {
+        Some(f64::from(*self))
+    }
+}
+
+/// Flips the objective, like `core::cmp::Reverse`: comparisons are inverted
+/// while the arithmetic is left alone, so solving a matrix of `Reverse`
+/// weights maximizes the total of the wrapped values. `Reverse(x)` behaves
+/// like `-x`, so the reduced values are negative and `T` must be signed or
+/// floating point.
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Reverse<T>(pub T);
+
+impl<T: PartialOrd> PartialOrd for Reverse<T> {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Reverse<T>) -> Option<Ordering> {
+        other.0.partial_cmp(&self.0)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Reverse<T> {
+    type Output = Reverse<T>;
+
+    #[inline(always)]
+    fn add(self, other: Reverse<T>) -> Reverse<T> {
+        Reverse(self.0 + other.0)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Reverse<T> {
+    type Output = Reverse<T>;
+
+    #[inline(always)]
+    fn sub(self, other: Reverse<T>) -> Reverse<T> {
+        Reverse(self.0 - other.0)
+    }
+}
+
+impl<T: WeightNum> WeightNum for Reverse<T> {
+    #[inline(always)]
+    fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    #[inline(always)]
+    fn is_valid(&self) -> bool {
+        self.0.is_valid()
+    }
+
+    // the backends minimize the converted values, so these are negated.
+    #[inline(always)]
+    fn to_i64(&self) -> Option<i64> {
+        self.0.to_i64().and_then(i64::checked_neg)
+    }
+
+    #[inline(always)]
+    fn to_f64(&self) -> Option<f64> {
+        self.0.to_f64().map(|x| -x)
+    }
+}
+
+/// A float compared by `total_cmp`, so that it's `Ord`. Comparisons of
+/// plain floats are inconsistent once a NaN is involved, which can make the
+/// minimum searches of steps 4 and 6 depend on the order of the cells. Under
+/// the total order, NaN is above all other values and `-0.0` is below `0.0`.
+/// NaN and infinite values are invalid, and both zeros are zero, as for the
+/// plain floats.
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Total<T>(pub T);
+
+macro_rules! impl_total {
+    ($t:ty) => {
+        impl PartialEq for Total<$t> {
+            #[inline(always)]
+            fn eq(&self, other: &Total<$t>) -> bool {
+                self.cmp(other) == Ordering::Equal
+            }
+        }
+
+        impl Eq for Total<$t> {}
+
+        impl PartialOrd for Total<$t> {
+            #[inline(always)]
+            fn partial_cmp(&self, other: &Total<$t>) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for Total<$t> {
+            #[inline(always)]
+            fn cmp(&self, other: &Total<$t>) -> Ordering {
+                self.0.total_cmp(&other.0)
+            }
+        }
+
+        impl Add for Total<$t> {
+            type Output = Total<$t>;
+
+            #[inline(always)]
+            fn add(self, other: Total<$t>) -> Total<$t> {
+                Total(self.0 + other.0)
+            }
+        }
+
+        impl Sub for Total<$t> {
+            type Output = Total<$t>;
+
+            #[inline(always)]
+            fn sub(self, other: Total<$t>) -> Total<$t> {
+                Total(self.0 - other.0)
+            }
+        }
+
+        impl WeightNum for Total<$t> {
+            #[inline(always)]
+            fn is_zero(&self) -> bool {
+                self.0 == 0.0
+            }
+
+            #[inline(always)]
+            fn is_valid(&self) -> bool {
+                self.0.is_finite()
+            }
+
+            #[inline(always)]
+            fn to_f64(&self) -> Option<f64> {
+                Some(f64::from(self.0))
+            }
+        }
+    };
+}
+
+impl_total!(f32);
+impl_total!(f64);
+
+// `f16` and `bf16` add and subtract by widening to `f32` and rounding back,
+// so matrices stay at half the size of `f32` ones.
+#[cfg(feature = "half")]
+impl WeightNum for half::f16 {
+    #[inline(always)]
+    fn is_zero(&self) -> bool {
+        *self == half::f16::ZERO
+    }
+
+    #[inline(always)]
+    fn is_valid(&self) -> bool {
+        self.is_finite()
+    }
+
+    #[inline(always)]
+    fn to_f64(&self) -> Option<f64> {
+        Some(half::f16::to_f64(*self))
+    }
+}
+
+#[cfg(feature = "half")]
+impl WeightNum for half::bf16 {
+    #[inline(always)]
+    fn is_zero(&self) -> bool {
+        *self == half::bf16::ZERO
+    }
+
+    #[inline(always)]
+    fn is_valid(&self) -> bool {
+        self.is_finite()
+    }
+
+    #[inline(always)]
+    fn to_f64(&self) -> Option<f64> {
+        Some(half::bf16::to_f64(*self))
+    }
+}
+
+#[test]
+fn test_reverse() {
+    use crate::{solve_assignment, solve_assignment_cost_scaling, Position, WeightMatrix};
+
+    let pos = |row, column| Position { row, column };
+    let c = [1, 2, 3, 2, 4, 6, 3, 6, 9];
+    let mut weights = WeightMatrix::from_fn(3, |(row, column)| Reverse(c[row * 3 + column]));
+    let expected = vec![pos(0, 0), pos(1, 1), pos(2, 2)];
+    assert_eq!(
+        Ok(expected.clone()),
+        solve_assignment_cost_scaling(&weights)
+    );
+    assert_eq!(Ok(expected), solve_assignment(&mut weights));
+
+    let inf = f64::INFINITY;
+    let c = [0.9, 0.8, inf, 0.8, 0.1, 0.7, 0.1, 0.2, 0.3];
+    let mut weights = WeightMatrix::from_fn(3, |(row, column)| Reverse(c[row * 3 + column]));
+    assert_eq!(
+        Ok(vec![pos(0, 1), pos(1, 0), pos(2, 2)]),
+        solve_assignment(&mut weights)
+    );
+}
+
+#[test]
+fn test_total() {
+    use crate::{solve_assignment, Position, WeightMatrix};
+
+    let nan = f64::NAN;
+    let mut values = vec![Total(1.0), Total(nan), Total(-0.0), Total(0.0), Total(-nan)];
+    values.sort();
+    assert_eq!(
+        vec![Total(-nan), Total(-0.0), Total(0.0), Total(1.0), Total(nan)],
+        values
+    );
+    assert!(Total(-0.0).is_zero());
+    assert!(!Total(nan).is_valid());
+
+    let c = [nan, 1.0, 2.0, -0.0, 3.0, nan, 4.0, 0.0, 5.0];
+    let mut weights = WeightMatrix::from_fn(3, |(row, column)| Total(c[row * 3 + column]));
+    let pos = |row, column| Position { row, column };
+    assert_eq!(
+        Ok(vec![pos(0, 2), pos(1, 0), pos(2, 1)]),
+        solve_assignment(&mut weights)
+    );
+}
+
+#[cfg(feature = "half")]
+#[test]
+fn test_half_weights() {
+    use crate::{solve_assignment, Position, WeightMatrix};
+    use half::{bf16, f16};
+
+    let c = [
+        250.0, 400.0, 350.0, 400.0, 600.0, 350.0, 200.0, 400.0, 250.0,
+    ];
+    let expected = vec![
+        Position { row: 0, column: 1 },
+        Position { row: 1, column: 2 },
+        Position { row: 2, column: 0 },
+    ];
+    let mut weights = WeightMatrix::from_row_vec(3, c.iter().map(|&x| f16::from_f64(x)).collect());
+    assert_eq!(Ok(expected.clone()), solve_assignment(&mut weights));
+    let mut weights = WeightMatrix::from_row_vec(3, c.iter().map(|&x| bf16::from_f64(x)).collect());
+    assert_eq!(Ok(expected), solve_assignment(&mut weights));
+
+    assert!(f16::from_f32(-0.0).is_zero());
+    assert!(!f16::INFINITY.is_valid() && !bf16::NAN.is_valid());
 }
@@ -0,0 +1,118 @@
+use crate::coverage::Coverage;
+use crate::memory::MemoryUsage;
+use crate::steps::{ColumnMinima, Path};
+use crate::{MarkMatrix, Position};
+#[cfg(feature = "std")]
+use core::cell::RefCell;
+use core::mem;
+
+/// The buffers `solve_assignment` needs besides the weights, kept around to
+/// be reused by subsequent solves.
+#[derive(Debug, Clone)]
+pub(crate) struct Workspace {
+    pub(crate) marks: MarkMatrix,
+    pub(crate) coverage: Coverage,
+    pub(crate) path: Path,
+    pub(crate) minima: ColumnMinima,
+    /// Accumulated by each solve, see `run_in`.
+    #[cfg(feature = "profile")]
+    pub(crate) timings: crate::Timings,
+}
+
+impl Workspace {
+    pub(crate) fn new(n: usize) -> Workspace {
+        Workspace {
+            marks: MarkMatrix::new(n),
+            coverage: Coverage::new(n),
+            path: Path::new(),
+            minima: ColumnMinima::new(),
+            #[cfg(feature = "profile")]
+            timings: crate::Timings::default(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn n(&self) -> usize {
+        self.marks.n()
+    }
+
+    pub(crate) fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            weights: 0,
+            marks: MarkMatrix::estimated_bytes(self.n()),
+            coverage: self.coverage.memory_usage(),
+            workspace: self.path.capacity() * mem::size_of::<Position>()
+                + self.minima.memory_usage(),
+        }
+    }
+
+    /// The memory of a workspace of size `n` at the end of a solve, when
+    /// the path of step 5 can hold `2n` positions.
+    pub(crate) fn estimated_memory_usage(n: usize) -> MemoryUsage {
+        MemoryUsage {
+            weights: 0,
+            marks: MarkMatrix::estimated_bytes(n),
+            coverage: Coverage::estimated_bytes(n),
+            workspace: 2 * n * mem::size_of::<Position>() + ColumnMinima::estimated_bytes(n),
+        }
+    }
+
+    /// Resets the workspace for a solve of size `n`, reallocating only if
+    /// the size changed.
+    pub(crate) fn prepare(&mut self, n: usize) {
+        if n == self.n() {
+            self.marks.clear();
+            self.coverage.clear();
+            self.path.clear();
+        } else {
+            self.marks = MarkMatrix::new(n);
+            self.coverage = Coverage::new(n);
+            self.path.clear();
+        }
+    }
+}
+
+/// Number of differently sized workspaces kept per thread.
+#[cfg(feature = "std")]
+const CACHED_WORKSPACES: usize = 4;
+
+#[cfg(feature = "std")]
+thread_local! {
+    /// Least recently used first.
+    static CACHE: RefCell<Vec<Workspace>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Calls `f` with a workspace of size `n` from the cache of the current
+/// thread. The workspace is taken out of the cache for the duration of the
+/// call, so `f` may reenter.
+#[cfg(feature = "std")]
+pub(crate) fn with_cached_workspace<R, F>(n: usize, f: F) -> R
+where
+    F: FnOnce(&mut Workspace) -> R,
+{
+    let cached = CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let index = cache.iter().position(|workspace| workspace.n() == n)?;
+        Some(cache.remove(index))
+    });
+    let mut workspace = cached.unwrap_or_else(|| Workspace::new(n));
+    let result = f(&mut workspace);
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.len() >= CACHED_WORKSPACES {
+            cache.remove(0);
+        }
+        cache.push(workspace);
+    });
+    result
+}
+
+/// Without `std` there are no thread locals to cache in, so each call
+/// allocates a new workspace.
+#[cfg(not(feature = "std"))]
+pub(crate) fn with_cached_workspace<R, F>(n: usize, f: F) -> R
+where
+    F: FnOnce(&mut Workspace) -> R,
+{
+    f(&mut Workspace::new(n))
+}